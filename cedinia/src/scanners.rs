@@ -163,9 +163,9 @@ pub(crate) fn scan_duplicate_files<H: ScanResultHandler>(
 }
 
 pub(crate) fn scan_empty_folders<H: ScanResultHandler>(dirs: Vec<PathBuf>, filters: &CommonFilters, stop: &Arc<AtomicBool>, handler: &Arc<H>, scan_id: u32) -> Vec<FileItem> {
-    use czkawka_core::tools::empty_folder::EmptyFolder;
+    use czkawka_core::tools::empty_folder::{EmptyFolder, EmptyFolderParameters};
     let (ptx, fwd) = spawn_progress_forwarder(Arc::clone(handler), scan_id);
-    let mut tool = EmptyFolder::new();
+    let mut tool = EmptyFolder::new(EmptyFolderParameters::default());
     tool.set_included_paths(dirs);
     apply_filters(&mut tool, filters);
     tool.search(stop, Some(&ptx));
@@ -448,6 +448,7 @@ pub(crate) fn scan_bad_names<H: ScanResultHandler>(
         non_ascii_graphical,
         restricted_charset_allowed: if non_ascii_graphical { Some(vec!['_', '-', ' ', '.']) } else { None },
         remove_duplicated_non_alphanumeric: remove_duplicated_non_alpha,
+        ..Default::default()
     });
     let mut tool = BadNames::new(params);
     tool.set_included_paths(dirs);
@@ -523,9 +524,20 @@ pub(crate) fn scan_same_music<H: ScanResultHandler>(
     handler: &Arc<H>,
     scan_id: u32,
 ) -> Vec<FileItem> {
-    use czkawka_core::tools::same_music::{MusicSimilarity, SameMusic, SameMusicParameters};
+    use czkawka_core::tools::same_music::{MusicSimilarity, SameMusic, SameMusicParameters, TagNormalization};
     let (ptx, fwd) = spawn_progress_forwarder(Arc::clone(handler), scan_id);
-    let params = SameMusicParameters::new(MusicSimilarity::from_bits_truncate(music_similarity), approximate, check_method, 0.0, 0.0, false);
+    let params = SameMusicParameters::new(
+        MusicSimilarity::from_bits_truncate(music_similarity),
+        approximate,
+        check_method,
+        0.0,
+        0.0,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
     let mut tool = SameMusic::new(params);
     tool.set_included_paths(dirs);
     apply_filters(&mut tool, filters);
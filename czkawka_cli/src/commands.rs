@@ -8,8 +8,11 @@ use czkawka_core::CZKAWKA_VERSION;
 use czkawka_core::common::model::{CheckingMethod, HashType};
 use czkawka_core::common::tool_data::DeleteMethod;
 use czkawka_core::re_exported::{FilterType, HashAlg};
+use czkawka_core::tools::bad_extensions::{ExtensionCollisionMode, ExtensionFixMode};
 use czkawka_core::tools::broken_files::CheckedTypes;
-use czkawka_core::tools::same_music::MusicSimilarity;
+use czkawka_core::tools::build_artifacts::ArtifactKind;
+use czkawka_core::tools::image_optimizer::ImageOptimizerFormat;
+use czkawka_core::tools::same_music::{MusicSimilarity, TagNormalization};
 use czkawka_core::tools::similar_images::GeometricInvariance;
 use czkawka_core::tools::similar_videos::{
     DEFAULT_AUDIO_LENGTH_RATIO, DEFAULT_AUDIO_MAXIMUM_DIFFERENCE, DEFAULT_AUDIO_MIN_DURATION_SECONDS, DEFAULT_AUDIO_SIMILARITY_PERCENT, DEFAULT_CROP_DETECT,
@@ -20,10 +23,11 @@ use czkawka_core::tools::video_optimizer::{NoiseReductionMethod, VideoCodec};
 use log::error;
 
 use crate::parsers::{
-    parse_audio_length_ratio, parse_audio_maximum_difference, parse_audio_similarity_percent, parse_broken_files, parse_checking_method_duplicate,
-    parse_checking_method_same_music, parse_crop_mechanism, parse_delete_method, parse_duration_tolerance_pct, parse_geometric_invariance, parse_hash_type, parse_image_hash_size,
-    parse_match_fraction, parse_max_samples, parse_maximal_file_size, parse_maximum_difference, parse_min_crop_size, parse_minimal_file_size, parse_minimum_segment_duration,
-    parse_music_duplicate_type, parse_noise_reduction, parse_scan_duration, parse_similar_hash_algorithm, parse_similar_image_filter, parse_skip_forward_amount, parse_tolerance,
+    parse_artifact_kind, parse_audio_length_ratio, parse_audio_maximum_difference, parse_audio_similarity_percent, parse_broken_files, parse_checking_method_duplicate,
+    parse_checking_method_same_music, parse_crop_mechanism, parse_delete_method, parse_duration_tolerance_pct, parse_extension_collision_mode, parse_extension_fix_mode,
+    parse_fuzzy_similarity_threshold, parse_geometric_invariance, parse_hash_type, parse_image_hash_size, parse_image_optimizer_format, parse_match_fraction, parse_max_samples,
+    parse_maximal_file_size, parse_maximum_difference, parse_min_crop_size, parse_minimal_file_size, parse_minimum_segment_duration, parse_music_duplicate_type,
+    parse_noise_reduction, parse_scan_duration, parse_similar_hash_algorithm, parse_similar_image_filter, parse_skip_forward_amount, parse_tag_normalization, parse_tolerance,
     parse_video_codec, parse_window_count,
 };
 
@@ -98,6 +102,12 @@ pub enum Commands {
     SimilarImages(SimilarImagesArgs),
     #[clap(name = "music", about = "Finds same music by tags", after_help = "EXAMPLE:\n    czkawka music -d /home/rafal -f results.txt")]
     SameMusic(SameMusicArgs),
+    #[clap(
+        name = "music-snippet",
+        about = "Finds music files which contain given audio clip",
+        after_help = "EXAMPLE:\n    czkawka music-snippet -d /home/rafal/Music -s /home/rafal/clip.mp3 -f results.txt"
+    )]
+    MusicSnippet(MusicSnippetArgs),
     #[clap(
         name = "symlinks",
         about = "Finds invalid symlinks",
@@ -136,6 +146,42 @@ pub enum Commands {
         after_help = "EXAMPLE:\n    czkawka exif-remover -d /home/rafal -f results.txt"
     )]
     ExifRemover(ExifRemoverArgs),
+    #[clap(
+        name = "bit-rot",
+        about = "Finds files with changed content but unchanged size and modification date",
+        after_help = "EXAMPLE:\n    czkawka bit-rot -d /mnt/nas -f results.txt"
+    )]
+    BitRot(BitRotArgs),
+    #[clap(
+        name = "image-optimizer",
+        about = "Finds images that can be recompressed without losses or converted to modern formats",
+        after_help = "EXAMPLE:\n    czkawka image-optimizer -d /home/rafal/Photos -f results.txt"
+    )]
+    ImageOptimizer(ImageOptimizerArgs),
+    #[clap(
+        name = "stale",
+        about = "Finds files which were not modified or accessed for a long time",
+        after_help = "EXAMPLE:\n    czkawka stale -d /home/rafal --modified-days 730 -f results.txt"
+    )]
+    StaleFiles(StaleFilesArgs),
+    #[clap(
+        name = "artifacts",
+        about = "Finds build artifact and cache directories of projects, like target or node_modules",
+        after_help = "EXAMPLE:\n    czkawka artifacts -d /home/rafal/Projects -f results.txt"
+    )]
+    BuildArtifacts(BuildArtifactsArgs),
+    #[clap(
+        name = "rename",
+        about = "Renames files with names generated from template",
+        after_help = "EXAMPLE:\n    czkawka rename -t \"{parent}_{counter:3}{.ext}\" --dry-run /home/rafal/Photos/*.jpg"
+    )]
+    Rename(RenameArgs),
+    #[clap(
+        name = "rollback-renames",
+        about = "Reverts renames saved in rename log",
+        after_help = "EXAMPLE:\n    czkawka rollback-renames renames.jsonl"
+    )]
+    RollbackRenames(RollbackRenamesArgs),
 }
 
 #[derive(Debug, clap::Args)]
@@ -219,6 +265,14 @@ pub struct EmptyFoldersArgs {
     pub common_cli_items: CommonCliItems,
     #[clap(flatten)]
     pub delete_method: SDMethod,
+    #[clap(
+        short = 'I',
+        long,
+        value_delimiter = ',',
+        help = "Ignored file name(s) inside empty folders",
+        long_help = "List of file names (case insensitive, wildcards allowed, e.g. Thumbs.db,._*). Folders containing only such files are treated as empty and files are deleted together with folder. Macro DEFAULT adds Thumbs.db, ehthumbs.db, desktop.ini, .DS_Store and ._* files."
+    )]
+    pub ignored_files: Vec<String>,
 }
 
 #[derive(Debug, clap::Args)]
@@ -242,6 +296,21 @@ pub struct BiggestFilesArgs {
         long_help = "Switch mode to find smallest files instead of biggest ones"
     )]
     pub smallest_mode: bool,
+    #[clap(
+        long,
+        value_name = "NUMBER",
+        help = "Show the heaviest directories at each depth",
+        long_help = "Sums sizes of files through directory tree and shows given number of the heaviest directories at each depth, together with number of files inside them"
+    )]
+    pub directories_per_depth: Option<usize>,
+    #[clap(
+        long,
+        value_name = "FILE",
+        requires = "directories_per_depth",
+        help = "Save directory tree as treemap JSON",
+        long_help = "Saves whole directory tree with sizes to JSON file, which can be loaded by treemap viewers (nodes contain name, path, value - size of files directly inside, size, files and children)"
+    )]
+    pub treemap_json: Option<PathBuf>,
 }
 
 #[derive(Debug, clap::Args)]
@@ -282,12 +351,78 @@ Matched using ends_with on the lowercased filename."
     pub extensions: Vec<String>,
 }
 
+#[derive(Debug, clap::Args)]
+pub struct StaleFilesArgs {
+    #[clap(flatten)]
+    pub common_cli_items: CommonCliItems,
+    #[clap(flatten)]
+    pub delete_method: SDMethod,
+    #[clap(
+        short,
+        long,
+        value_parser = parse_minimal_file_size,
+        default_value = "1",
+        help = "Minimum size in bytes",
+        long_help = "Minimum size of checked files in bytes, assigning bigger value may speed up searching"
+    )]
+    pub minimal_file_size: u64,
+    #[clap(
+        short = 'i',
+        long,
+        value_parser = parse_maximal_file_size,
+        default_value = "18446744073709551615",
+        help = "Maximum size in bytes",
+        long_help = "Maximum size of checked files in bytes, assigning lower value may speed up searching"
+    )]
+    pub maximal_file_size: u64,
+    #[clap(
+        long,
+        value_name = "DAYS",
+        help = "Minimal days since last modification",
+        long_help = "Find files not modified for at least this many days. If none of the age options is given, files not modified for 365 days are found."
+    )]
+    pub modified_days: Option<u64>,
+    #[clap(
+        long,
+        value_name = "DAYS",
+        help = "Minimal days since last access",
+        long_help = "Find files not accessed for at least this many days. Access times are not reliable on filesystems mounted with noatime or relatime, a warning is shown in such case."
+    )]
+    pub accessed_days: Option<u64>,
+    #[clap(
+        long,
+        value_name = "DAYS",
+        help = "Minimal days since last status change",
+        long_help = "Find files whose inode status (e.g. permissions, owner, name) was not changed for at least this many days. On Windows creation time is used instead."
+    )]
+    pub changed_days: Option<u64>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct BuildArtifactsArgs {
+    #[clap(flatten)]
+    pub common_cli_items: CommonCliItems,
+    #[clap(flatten)]
+    pub delete_method: SDMethod,
+    #[clap(
+        short = 'k',
+        long,
+        value_delimiter = ',',
+        value_parser = parse_artifact_kind,
+        help = "Artifact kind(s) to find (RUST, NODE, PYCACHE, VENV, GRADLE, CMAKE)",
+        long_help = "Kinds of artifact directories to find, all by default.\nRUST - target next to Cargo.toml\nNODE - node_modules next to package.json\nPYCACHE - __pycache__ next to *.py files\nVENV - .venv next to pyproject.toml, setup.py, setup.cfg, requirements.txt or Pipfile\nGRADLE - .gradle next to build.gradle or settings.gradle\nCMAKE - build next to CMakeLists.txt\nWhole found directories are removed when deleting."
+    )]
+    pub kinds: Vec<ArtifactKind>,
+}
+
 #[derive(Debug, clap::Args)]
 pub struct SimilarImagesArgs {
     #[clap(flatten)]
     pub common_cli_items: CommonCliItems,
     #[clap(flatten)]
     pub reference_directories: ReferenceDirectories,
+    #[clap(flatten)]
+    pub html_report: HtmlReportFolder,
     #[clap(
         short,
         long,
@@ -436,6 +571,84 @@ pub struct SameMusicArgs {
         long_help = "Maximum allowed difference between audio segments (greater than 0.0, up to 10.0 inclusive). Value close to 0.0 will find only nearly identical segments, while 10.0 will find segments that are barely similar. Lower values mean stricter matching."
     )]
     pub maximum_difference: f64,
+    #[clap(
+        short = 'L',
+        long,
+        help = "Detect fake lossless files",
+        long_help = "Analyses spectrum of lossless files to find ones transcoded from lossy sources (e.g. FLAC created from MP3). Such files are ranked as lossy when using AEBQ delete method. Slows down first scan, because whole files need to be decoded."
+    )]
+    pub detect_fake_lossless: bool,
+    #[clap(
+        short = 'n',
+        long,
+        default_value = "",
+        value_parser = parse_tag_normalization,
+        help = "Normalization of artist and title tags (featuring,version,transliterate,split-artists)",
+        long_help = "Steps applied to artist and title tags before comparing them (may be mixed, but must be divided by commas).\nfeaturing - removes guest artists, e.g. \"Artist feat. X\" or \"Artist ft. X\", and compares multiple artists divided by \";\" regardless of their order\nversion - removes version qualifiers, e.g. \"(Remastered 2011)\" or \"[Live]\"\ntransliterate - converts non-latin characters to their ASCII equivalents\nsplit-artists - treats artists added after \"&\", \",\" or \" and \" as guests, so \"Artist & X\" matches \"Artist\" (may merge band names like \"Simon & Garfunkel\" with \"Simon\")"
+    )]
+    pub tag_normalization: TagNormalization,
+    #[clap(
+        short = 'u',
+        long,
+        value_parser = parse_fuzzy_similarity_threshold,
+        help = "Fuzzy similarity threshold of artist and title tags (0.0 - 1.0)",
+        long_help = "Instead of exact equality, artist and title tags are treated as equal when their similarity is at least this value. 1.0 means identical tags, lower values allow for typos and small differences."
+    )]
+    pub fuzzy_similarity_threshold: Option<f64>,
+    #[clap(
+        short = 'F',
+        long,
+        help = "Copy missing tags between similar files",
+        long_help = "Copies title, artist, album, year, genre and cover art to other files in group. Tags are taken from reference file or, without reference directories, from file with most filled tags. Use with -Q to only show what would be changed."
+    )]
+    pub copy_missing_tags: bool,
+    #[clap(
+        short = 'O',
+        long,
+        requires = "copy_missing_tags",
+        help = "Overwrite existing tags when copying",
+        long_help = "By default only missing or empty tags are filled, with this option different existing tags are replaced too"
+    )]
+    pub overwrite_tags: bool,
+    #[clap(
+        short = 'S',
+        long,
+        help = "Split audio files described by CUE sheets into tracks",
+        long_help = "Albums ripped to single audio file with CUE sheet are split into virtual tracks, which are compared with other music files. CUE sheets are searched in folders of found music files. Virtual tracks are never deleted and their tags are never modified."
+    )]
+    pub use_cue_sheets: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct MusicSnippetArgs {
+    #[clap(flatten)]
+    pub common_cli_items: CommonCliItems,
+    #[clap(
+        short,
+        long,
+        value_name = "file-path",
+        help = "Audio clip to search for",
+        long_help = "Short audio clip or single file whose content is searched for in music files from included directories"
+    )]
+    pub snippet: PathBuf,
+    #[clap(
+        short = 'l',
+        long,
+        value_parser = parse_minimum_segment_duration,
+        default_value = "5.0",
+        help = "Minimum matched duration in seconds",
+        long_help = "Minimum duration of audio segment which must match snippet, in seconds. Should be shorter than the snippet. Values should be between 0.0 and 3600.0"
+    )]
+    pub minimum_segment_duration: f32,
+    #[clap(
+        short = 'Y',
+        long,
+        value_parser = parse_maximum_difference,
+        default_value = "2.0",
+        help = "Maximum difference between audio segments",
+        long_help = "Maximum allowed difference between snippet and matched audio segment (greater than 0.0, up to 10.0 inclusive). Lower values mean stricter matching."
+    )]
+    pub maximum_difference: f64,
 }
 
 #[derive(Debug, clap::Args)]
@@ -461,6 +674,13 @@ pub struct BrokenFilesArgs {
         long_help = "Methods to search files - by default all types except video are checked (VIDEO_FFPROBE and VIDEO_FFMPEG require ffmpeg to be installed).\nPDF - finds broken PDF files,\nAUDIO - finds broken audio files,\nIMAGE - finds broken image files,\nARCHIVE - finds broken archive files (zip, 7z, gz, tar, zst, bz2, xz),\nFONT - finds broken font files (ttf, otf, ttc),\nMARKUP - finds broken JSON/XML/TOML/YAML/SVG files,\nVIDEO_FFPROBE - quick video check using ffprobe (header validation),\nVIDEO_FFMPEG - deep video check using ffmpeg (full decode)"
     )]
     pub checked_types: Vec<CheckedTypes>,
+    #[clap(
+        short = 'F',
+        long,
+        help = "Recover data from broken files",
+        long_help = "Tries to recover data from broken files, original files are never modified.\nZIP and tar - intact members are extracted to a sibling folder (e.g. 'archive.zip' -> 'archive.czkawka_recovered'),\ngz, zst and xz - stream is truncated to the last valid block (e.g. 'data.gz' -> 'data.czkawka_recovered.gz'),\nvideo - streams are remuxed with ffmpeg stream copy (e.g. 'movie.mp4' -> 'movie.czkawka_recovered.mp4')"
+    )]
+    pub repair: bool,
 }
 
 #[derive(Debug, clap::Args)]
@@ -470,6 +690,8 @@ pub struct SimilarVideosArgs {
     #[clap(flatten)]
     pub reference_directories: ReferenceDirectories,
     #[clap(flatten)]
+    pub html_report: HtmlReportFolder,
+    #[clap(flatten)]
     pub delete_method: DMethod,
     #[clap(flatten)]
     pub allow_hard_links: AllowHardLinks,
@@ -639,6 +861,47 @@ pub struct BadExtensionsArgs {
         long_help = "Automatically rename files to use proper extensions based on their detected file type"
     )]
     pub fix_extensions: bool,
+    #[clap(
+        short = 's',
+        long,
+        value_name = "FILE",
+        help = "TOML file with custom file signatures",
+        long_help = "TOML file with additional magic signatures ([[signature]] with extensions, offset and hex bytes) and accepted extension aliases ([aliases] table, e.g. zip = [\"pkg\"]), used together with built-in detection"
+    )]
+    pub signatures: Option<PathBuf>,
+    #[clap(
+        long,
+        default_value = "REPLACE",
+        value_parser = parse_extension_fix_mode,
+        requires = "fix_extensions",
+        help = "How extension is fixed (REPLACE, APPEND)",
+        long_help = "How bad extension is fixed.\nREPLACE - current extension is replaced with proper one (photo.txt -> photo.jpg),\nAPPEND - proper extension is added after current one (photo.txt -> photo.txt.jpg)"
+    )]
+    pub fix_mode: ExtensionFixMode,
+    #[clap(
+        long,
+        default_value = "SKIP",
+        value_parser = parse_extension_collision_mode,
+        requires = "fix_extensions",
+        help = "What to do when new name is already used (SKIP, NUMBER)",
+        long_help = "What to do when file with new name already exists or other fixed file would get the same name.\nSKIP - file is not renamed,\nNUMBER - number is added to name (photo_1.jpg)"
+    )]
+    pub on_collision: ExtensionCollisionMode,
+    #[clap(
+        long,
+        requires = "fix_extensions",
+        help = "Only show planned renames",
+        long_help = "Prints files which would be renamed by --fix-extensions, without renaming them"
+    )]
+    pub dry_run: bool,
+    #[clap(
+        long,
+        value_name = "FILE",
+        requires = "fix_extensions",
+        help = "Save rename log to file",
+        long_help = "Appends every rename done by --fix-extensions to given file (one JSON object per line), so renames can be reverted later"
+    )]
+    pub rollback_log: Option<PathBuf>,
 }
 
 #[derive(Debug, clap::Args)]
@@ -684,6 +947,50 @@ pub struct BadNamesArgs {
         long_help = "Detects files with duplicated non-alphanumeric characters (e.g., 'file__name' or 'file..txt')"
     )]
     pub remove_duplicated_non_alphanumeric: bool,
+    #[clap(
+        long,
+        help = "Check for names reserved on Windows",
+        long_help = "Detects files named like Windows devices (CON, PRN, AUX, NUL, COM1-9, LPT1-9), also with extension (e.g., 'nul.txt')"
+    )]
+    pub windows_reserved_name: bool,
+    #[clap(
+        long,
+        help = "Check for dots or spaces at end",
+        long_help = "Detects files whose names end with a dot or space, which are silently stripped or rejected on Windows"
+    )]
+    pub trailing_dot_or_space: bool,
+    #[clap(
+        long,
+        help = "Check for characters forbidden on Windows",
+        long_help = "Detects files with characters forbidden on Windows, exFAT and SMB shares: < > : \" | ? * \\ and control characters"
+    )]
+    pub windows_forbidden_characters: bool,
+    #[clap(long, help = "Check for too long names", long_help = "Detects files with names longer than 255 bytes")]
+    pub too_long_name: bool,
+    #[clap(
+        long,
+        help = "Check for too long paths",
+        long_help = "Detects files whose full path is longer than 260 characters (Windows MAX_PATH). Only file name is shortened when fixing"
+    )]
+    pub too_long_path: bool,
+    #[clap(
+        long,
+        help = "Check for names differing only in letter case",
+        long_help = "Detects sibling files which would be merged on case-insensitive filesystems (e.g., 'Photo.jpg' and 'photo.jpg'). Fixing adds '_N' suffix to all but one of them"
+    )]
+    pub case_collision: bool,
+    #[clap(
+        long,
+        help = "Check for names differing only in Unicode normalization",
+        long_help = "Detects sibling files whose names are equal after Unicode NFC normalization (e.g., NFC and NFD form of 'café.txt'). Fixing adds '_N' suffix to all but one of them"
+    )]
+    pub unicode_normalization_collision: bool,
+    #[clap(
+        long,
+        help = "Check for mojibake in names",
+        long_help = "Detects names of files which were UTF-8 decoded as Latin-1, CP1250 or CP437 (e.g., 'ZaÅ¼Ã³Å‚Ä‡.txt') and names which are not valid UTF-8, and proposes properly decoded names"
+    )]
+    pub mojibake: bool,
     #[clap(
         short = 'F',
         long,
@@ -691,6 +998,70 @@ pub struct BadNamesArgs {
         long_help = "Automatically rename files to fix detected naming issues"
     )]
     pub fix_names: bool,
+    #[clap(
+        long,
+        value_name = "FILE",
+        requires = "fix_names",
+        help = "Save rename log to file",
+        long_help = "Appends every rename done by --fix-names to given file (one JSON object per line), so renames can be reverted later"
+    )]
+    pub rollback_log: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct RenameArgs {
+    #[clap(
+        required = true,
+        value_name = "FILES",
+        help = "Files to rename",
+        long_help = "Files to rename, counter in template follows order of given files"
+    )]
+    pub files: Vec<PathBuf>,
+    #[clap(
+        short = 't',
+        long,
+        help = "Template of new name",
+        long_help = "Template of new name, supported tokens:\n{name} - original file name,\n{stem} - file name without extension,\n{ext} - extension, {.ext} - extension with dot or nothing,\n{parent} - name of parent directory,\n{counter}, {counter:N} - counter padded with zeros to N digits,\n{mtime}, {mtime:date} - modification date,\n{exif_date}, {exif_date:date} - EXIF original date.\nBraces can be escaped as {{ and }}"
+    )]
+    pub template: String,
+    #[clap(long, default_value_t = 1, help = "First value of counter")]
+    pub counter_start: u64,
+    #[clap(long, default_value_t = 1, help = "Value added to counter for every next file")]
+    pub counter_step: u64,
+    #[clap(
+        long,
+        value_name = "REGEX",
+        help = "Regex to find in generated name",
+        long_help = "Regular expression applied to name generated from template, matches are replaced with --replace"
+    )]
+    pub find: Option<String>,
+    #[clap(
+        long,
+        requires = "find",
+        help = "Replacement for --find",
+        long_help = "Replacement for matches of --find, may use capture groups like $1. Matches are removed when not set"
+    )]
+    pub replace: Option<String>,
+    #[clap(long, help = "Only show planned renames", long_help = "Prints files which would be renamed, without renaming them")]
+    pub dry_run: bool,
+    #[clap(
+        long,
+        value_name = "FILE",
+        conflicts_with = "dry_run",
+        help = "Save rename log to file",
+        long_help = "Appends every rename to given file (one JSON object per line), so renames can be reverted later with rollback-renames"
+    )]
+    pub rollback_log: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct RollbackRenamesArgs {
+    #[clap(
+        value_name = "FILE",
+        help = "Rename log to revert",
+        long_help = "Rename log saved with --rollback-log, renames from it are reverted starting from the last one"
+    )]
+    pub rollback_log: PathBuf,
 }
 
 #[derive(Debug, clap::Args)]
@@ -758,6 +1129,21 @@ pub struct TranscodeArgs {
         long_help = "Video encoding quality (0-51). Lower values mean better quality. 23 is default for h264/h265, 30 for av1/vp9."
     )]
     pub quality: u32,
+    #[clap(
+        long,
+        value_parser = clap::value_parser!(u64).range(50..=1_000_000),
+        conflicts_with = "target_size",
+        help = "Target video bitrate in kbit/s (50-1000000)",
+        long_help = "Encode video with given average bitrate in kbit/s instead of constant quality, using two-pass encoding (AV1 uses single pass). Audio is copied without changes. Hardware encoders are not used in this mode."
+    )]
+    pub target_bitrate: Option<u64>,
+    #[clap(
+        long,
+        value_parser = clap::value_parser!(u64).range(1..=16_777_216),
+        help = "Target file size in MiB (1-16777216)",
+        long_help = "Encode video to fit given file size in MiB instead of using constant quality. Video bitrate is calculated from duration and size of audio streams, which are copied without changes, and two-pass encoding is used (AV1 uses single pass). Hardware encoders are not used in this mode."
+    )]
+    pub target_size: Option<u64>,
     #[clap(long, help = "Fail if result not smaller", long_help = "Fail the optimization if resulting file is not smaller than original")]
     pub fail_if_not_smaller: bool,
     #[clap(long, help = "Overwrite original files", long_help = "Overwrite original video files with optimized versions")]
@@ -802,6 +1188,8 @@ pub struct TranscodeArgs {
         long_help = "Custom ffmpeg command-line arguments to pass to ffmpeg during transcoding. When set, most other encoding options are ignored."
     )]
     pub custom_ffmpeg_command: Option<String>,
+    #[clap(flatten)]
+    pub preserve_metadata: PreserveMetadataArgs,
 }
 
 #[derive(Debug, clap::Args)]
@@ -891,6 +1279,30 @@ pub struct CropArgs {
         long_help = "Video encoding quality when transcoding (0-51). Only used when target_codec is specified."
     )]
     pub quality: Option<u32>,
+    #[clap(flatten)]
+    pub preserve_metadata: PreserveMetadataArgs,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct PreserveMetadataArgs {
+    #[clap(
+        long,
+        help = "Copy metadata of original video",
+        long_help = "Copies global and per-stream metadata (e.g. creation time and GPS location) from original video and verifies that it is present in new file. Only used with -F flag."
+    )]
+    pub preserve_metadata: bool,
+    #[clap(
+        long,
+        help = "Restore modification and access time",
+        long_help = "Sets modification and access time of new file to the ones of original video. Only used with -F flag."
+    )]
+    pub preserve_timestamps: bool,
+    #[clap(
+        long,
+        help = "Copy extended attributes",
+        long_help = "Copies extended attributes (only user namespace on Linux) of original video to new file. Supported only on Linux and macOS. Only used with -F flag."
+    )]
+    pub preserve_xattrs: bool,
 }
 
 #[derive(Debug, clap::Args)]
@@ -915,6 +1327,71 @@ pub struct ExifRemoverArgs {
     pub override_file: bool,
 }
 
+#[derive(Debug, clap::Args)]
+pub struct BitRotArgs {
+    #[clap(flatten)]
+    pub common_cli_items: CommonCliItems,
+    #[clap(flatten)]
+    pub delete_method: SDMethod,
+    #[clap(
+        short = 't',
+        long,
+        default_value = "BLAKE3",
+        value_parser = parse_hash_type,
+        help = "Hash type (BLAKE3, CRC32, XXH3)",
+        long_help = "Hash algorithm of the duplicate finder hash cache that will be verified. Only files hashed earlier by `czkawka dup -s HASH` with the same hash type are checked - files smaller than the minimum cached file size of that scan are never stored in cache."
+    )]
+    pub hash_type: HashType,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ImageOptimizerArgs {
+    #[clap(flatten)]
+    pub common_cli_items: CommonCliItems,
+    #[clap(
+        short = 't',
+        long,
+        default_value = "lossless",
+        value_parser = parse_image_optimizer_format,
+        help = "Target format (lossless, webp, avif, jxl)",
+        long_help = "Target format of optimized images.\nlossless - recompress PNG files with oxipng and JPEG files with jpegtran, without changing pixels\nwebp - convert with cwebp\navif - convert with avifenc\njxl - convert with cjxl, JPEG files are recompressed losslessly when quality is 100\nUsed programs must be installed and available in PATH."
+    )]
+    pub target_format: ImageOptimizerFormat,
+    #[clap(
+        short = 'q',
+        long,
+        default_value = "80",
+        value_parser = clap::value_parser!(u8).range(1..=100),
+        help = "Encoding quality (1-100)",
+        long_help = "Quality of converted images (1-100), 100 means lossless encoding. Ignored in lossless mode."
+    )]
+    pub quality: u8,
+    #[clap(
+        short = 'm',
+        long,
+        default_value = "5",
+        value_parser = clap::value_parser!(u8).range(0..=99),
+        help = "Minimal savings in percent (0-99)",
+        long_help = "Images whose estimated size reduction is smaller than this percentage of original size are not reported"
+    )]
+    pub minimal_savings: u8,
+    #[clap(
+        short = 'F',
+        long,
+        help = "Optimize images",
+        long_help = "Actually optimize found images, optimized files are saved with 'czkawka_optimized' inserted before the extension"
+    )]
+    pub fix_images: bool,
+    #[clap(long, help = "Fail if result not smaller", long_help = "Fail the optimization if resulting file is not smaller than original")]
+    pub fail_if_not_smaller: bool,
+    #[clap(
+        long,
+        help = "Overwrite original files",
+        long_help = "Overwrite original images with optimized versions. When converting to other format, original file is removed and the optimized one gets the new extension (e.g. 'photo.jpg' becomes 'photo.webp')"
+    )]
+    pub overwrite_original: bool,
+}
+
 #[derive(Debug, clap::Args)]
 pub struct CommonCliItems {
     #[clap(
@@ -1027,8 +1504,8 @@ pub struct DMethod {
         long,
         default_value = "NONE",
         value_parser = parse_delete_method,
-        help = "Delete method (AEN, AEO, ON, OO, AEB, AES, OB, OS, HARD)",
-        long_help = "Method for selecting which files to delete from duplicate groups:\nAEN - All files Except Newest (keeps only newest)\nAEO - All files Except Oldest (keeps only oldest)\nON - Only the Newest deleted (keeps all but newest)\nOO - Only the Oldest deleted (keeps all but oldest)\nAEB - All files Except Biggest (keeps only biggest)\nAES - All files Except Smallest (keeps only smallest)\nOB - Only the Biggest deleted (keeps all but biggest)\nOS - Only the Smallest deleted (keeps all but smallest)\nHARD - create hard links to save space\nNONE - do not delete files (default)"
+        help = "Delete method (AEN, AEO, ON, OO, AEB, AES, OB, OS, AEBQ, AEMT, HARD)",
+        long_help = "Method for selecting which files to delete from duplicate groups:\nAEN - All files Except Newest (keeps only newest)\nAEO - All files Except Oldest (keeps only oldest)\nON - Only the Newest deleted (keeps all but newest)\nOO - Only the Oldest deleted (keeps all but oldest)\nAEB - All files Except Biggest (keeps only biggest)\nAES - All files Except Smallest (keeps only smallest)\nOB - Only the Biggest deleted (keeps all but biggest)\nOS - Only the Smallest deleted (keeps all but smallest)\nAEBQ - All files Except Best Quality (in music it keeps lossless files with highest resolution, in videos the version with best resolution, bitrate and codec without black bars, elsewhere the biggest file)\nAEMT - All files Except Most Tracks (in videos keeps the version with most audio and subtitle languages, elsewhere the biggest file)\nHARD - create hard links to save space\nNONE - do not delete files (default)"
     )]
     pub delete_method: DeleteMethod,
     #[clap(
@@ -1080,6 +1557,17 @@ pub struct FileToSave {
     pub file_to_save: Option<PathBuf>,
 }
 
+#[derive(Debug, clap::Args)]
+pub struct HtmlReportFolder {
+    #[clap(
+        long,
+        value_name = "folder",
+        help = "Save results to HTML report folder",
+        long_help = "Creates folder with index.html and previews of found files, which can be opened in any browser without access to scanned files. Video previews are available only when thumbnails are generated."
+    )]
+    pub html_report: Option<PathBuf>,
+}
+
 #[derive(Debug, clap::Args)]
 pub struct ReferenceDirectories {
     #[clap(
@@ -1210,15 +1698,42 @@ COMMANDS:
 EXAMPLES:
     {bin} dup -d /home/rafal -e /home/rafal/Obrazy  -m 25 -x 7z rar IMAGE -s hash -f results.txt -D aeo
     {bin} empty-folders -d /home/rafal/rr /home/gateway -f results.txt
+    {bin} empty-folders -d /home/rafal/rr -I DEFAULT,*.tmp -D
     {bin} big -d /home/rafal/ /home/piszczal -e /home/rafal/Roman -n 25 -x VIDEO -f results.txt
+    {bin} big -d /home/rafal/ --directories-per-depth 10 --treemap-json treemap.json
     {bin} empty-files -d /home/rafal /home/szczekacz -e /home/rafal/Pulpit -R -f results.txt
     {bin} temp -d /home/rafal/ -E */.git */tmp* *Pulpit -f results.txt -D
     {bin} image -d /home/rafal -e /home/rafal/Pulpit -f results.txt
+    {bin} image -d /home/rafal/Photos --html-report /mnt/nas/report
     {bin} music -d /home/rafal -e /home/rafal/Pulpit -z \"track_artist,year,track_title\"  -f results.txt
+    {bin} music -d /home/rafal/Music -s CONTENT -L -D AEBQ
+    {bin} music -d /home/rafal/Music -z \"track_artist,track_title\" -n featuring,version,transliterate -u 0.9
+    {bin} music -d /home/rafal/Music -s CONTENT -F -Q
+    {bin} music -d /home/rafal/Music -s CONTENT -S
+    {bin} video -d /home/rafal/Movies -D AEBQ
+    {bin} video -d /home/rafal/Movies -D AEMT
+    {bin} video -d /home/rafal/Movies --generate-thumbnails --html-report /mnt/nas/report
+    {bin} music-snippet -d /home/rafal/Music -s /home/rafal/clip.mp3 -f results.txt
     {bin} symlinks -d /home/kicikici/ /home/szczek -e /home/kicikici/jestempsem -x jpg -f results.txt
     {bin} broken -d /home/mikrut/ -e /home/mikrut/trakt -f results.txt
+    {bin} broken -d /home/mikrut/ -c ARCHIVE -F
     {bin} ext -d /home/mikrut/ -e /home/mikrut/trakt -f results.txt
+    {bin} ext -d /home/mikrut/ -s /home/mikrut/signatures.toml -f results.txt
+    {bin} ext -d /home/mikrut/ -F --fix-mode APPEND --on-collision NUMBER --dry-run
     {bin} bad-names -d /home/rafal -u -j -w -n -f results.txt
+    {bin} bad-names -d /mnt/old_samba_share --mojibake -F --rollback-log renames.jsonl
+    {bin} bad-names -d /mnt/to_migrate --windows-reserved-name --trailing-dot-or-space --windows-forbidden-characters --too-long-name --too-long-path --case-collision --unicode-normalization-collision -f results.txt
     {bin} video-optimizer -d /home/rafal transcode -c h264 -f results.txt
+    {bin} video-optimizer -d /home/rafal transcode -F --target-codec h264 --target-size 700
+    {bin} video-optimizer -d /home/rafal crop -m blackbars -F --overwrite-original --preserve-metadata --preserve-timestamps
     {bin} video-optimizer -d /home/rafal crop -m blackbars -f results.txt
-    {bin} exif-remover -d /home/rafal -x IMAGE -f results.txt"#;
+    {bin} exif-remover -d /home/rafal -x IMAGE -f results.txt
+    {bin} bit-rot -d /mnt/nas -t BLAKE3 -f results.txt
+    {bin} image-optimizer -d /home/rafal/Photos -f results.txt
+    {bin} image-optimizer -d /home/rafal/Photos -t webp -q 85 -F --overwrite-original
+    {bin} stale -d /home/rafal --modified-days 730 -f results.txt
+    {bin} stale -d /mnt/nas --accessed-days 180 --changed-days 180 -D
+    {bin} artifacts -d /home/rafal/Projects -f results.txt
+    {bin} artifacts -d /home/rafal/Projects -k RUST,NODE -D
+    {bin} rename -t \"{parent}_{counter:3}{.ext}\" --rollback-log renames.jsonl /home/rafal/Photos/*.jpg
+    {bin} rollback-renames renames.jsonl"#;
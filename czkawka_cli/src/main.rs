@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::thread;
@@ -10,32 +10,40 @@ use czkawka_core::common::config_cache_path::{print_infos_and_warnings, set_conf
 use czkawka_core::common::consts::DEFAULT_THREAD_SIZE;
 use czkawka_core::common::image::register_image_decoding_hooks;
 use czkawka_core::common::logger::{filtering_messages, print_version_mode, setup_logger};
+use czkawka_core::common::model::CheckingMethod;
 use czkawka_core::common::progress_data::ProgressData;
+use czkawka_core::common::rename;
+use czkawka_core::common::rename::{RenamePlan, RenameResult, RenameTemplate};
 use czkawka_core::common::set_number_of_threads;
 use czkawka_core::common::tool_data::{CommonData, DeleteMethod};
-use czkawka_core::common::traits::{AllTraits, FixingItems, PrintResults, Search};
+use czkawka_core::common::traits::{AllTraits, FixingItems, HtmlReport, PrintResults, Search};
 use czkawka_core::tools::bad_extensions::{BadExtensions, BadExtensionsFixParams, BadExtensionsParameters};
 use czkawka_core::tools::bad_names::{BadNames, BadNamesParameters, NameFixerParams, NameIssues};
 use czkawka_core::tools::big_file::{BigFile, BigFileParameters, SearchMode};
-use czkawka_core::tools::broken_files::{BrokenFiles, BrokenFilesParameters, CheckedTypes};
+use czkawka_core::tools::bit_rot::{BitRot, BitRotParameters};
+use czkawka_core::tools::broken_files::{BrokenFiles, BrokenFilesFixParams, BrokenFilesParameters, CheckedTypes};
+use czkawka_core::tools::build_artifacts::{BuildArtifacts, BuildArtifactsParameters};
 use czkawka_core::tools::duplicate::{DuplicateFinder, DuplicateFinderParameters};
 use czkawka_core::tools::empty_files::{EmptyFiles, EmptyFilesParameters};
-use czkawka_core::tools::empty_folder::EmptyFolder;
+use czkawka_core::tools::empty_folder::{DEFAULT_IGNORED_FILES, EmptyFolder, EmptyFolderParameters};
 use czkawka_core::tools::exif_remover::{ExifRemover, ExifRemoverParameters, ExifTagsFixerParams};
+use czkawka_core::tools::image_optimizer::{ImageOptimizer, ImageOptimizerFixParams, ImageOptimizerParameters};
 use czkawka_core::tools::invalid_symlinks::InvalidSymlinks;
-use czkawka_core::tools::same_music::{SameMusic, SameMusicParameters};
+use czkawka_core::tools::same_music::{MusicSimilarity, MusicTagsFixParams, SameMusic, SameMusicParameters, TagConflictPolicy, TagNormalization};
 use czkawka_core::tools::similar_images::{SimilarImages, SimilarImagesParameters};
 use czkawka_core::tools::similar_videos::{SimilarVideos, SimilarVideosParameters};
+use czkawka_core::tools::stale_files::{StaleFiles, StaleFilesParameters};
 use czkawka_core::tools::temporary::{Temporary, TemporaryParameters};
 use czkawka_core::tools::video_optimizer::{
-    HardwareEncoder, VideoCropFixParams, VideoCropParams, VideoCroppingMechanism, VideoOptimizer, VideoOptimizerFixParams, VideoOptimizerParameters, VideoTranscodeFixParams,
-    VideoTranscodeParams,
+    HardwareEncoder, MetadataPreservation, VideoCropFixParams, VideoCropParams, VideoCroppingMechanism, VideoOptimizer, VideoOptimizerFixParams, VideoOptimizerParameters,
+    VideoRateControl, VideoTranscodeFixParams, VideoTranscodeParams,
 };
 use log::{debug, error, info};
 
 use crate::commands::{
-    Args, BadExtensionsArgs, BadNamesArgs, BiggestFilesArgs, BrokenFilesArgs, CommonCliItems, DMethod, DuplicatesArgs, EmptyFilesArgs, EmptyFoldersArgs, ExifRemoverArgs,
-    InvalidSymlinksArgs, SDMethod, SameMusicArgs, SimilarImagesArgs, SimilarVideosArgs, TemporaryArgs, VideoOptimizerArgs, validate_file_sizes,
+    Args, BadExtensionsArgs, BadNamesArgs, BiggestFilesArgs, BitRotArgs, BrokenFilesArgs, BuildArtifactsArgs, CommonCliItems, DMethod, DuplicatesArgs, EmptyFilesArgs,
+    EmptyFoldersArgs, ExifRemoverArgs, ImageOptimizerArgs, InvalidSymlinksArgs, MusicSnippetArgs, PreserveMetadataArgs, RenameArgs, RollbackRenamesArgs, SDMethod, SameMusicArgs,
+    SimilarImagesArgs, SimilarVideosArgs, StaleFilesArgs, TemporaryArgs, VideoOptimizerArgs, validate_file_sizes,
 };
 use crate::progress::connect_progress;
 
@@ -81,6 +89,7 @@ fn main() {
             Commands::Temporary(temporary_args) => temporary(temporary_args, &stop_flag, &progress_sender),
             Commands::SimilarImages(similar_images_args) => similar_images(similar_images_args, &stop_flag, &progress_sender),
             Commands::SameMusic(same_music_args) => same_music(same_music_args, &stop_flag, &progress_sender),
+            Commands::MusicSnippet(music_snippet_args) => music_snippet(music_snippet_args, &stop_flag, &progress_sender),
             Commands::InvalidSymlinks(invalid_symlinks_args) => invalid_symlinks(invalid_symlinks_args, &stop_flag, &progress_sender),
             Commands::BrokenFiles(broken_files_args) => broken_files(broken_files_args, &stop_flag, &progress_sender),
             Commands::SimilarVideos(similar_videos_args) => similar_videos(similar_videos_args, &stop_flag, &progress_sender),
@@ -88,6 +97,12 @@ fn main() {
             Commands::BadNames(bad_names_args) => bad_names(bad_names_args, &stop_flag, &progress_sender),
             Commands::VideoOptimizer(video_optimizer_args) => video_optimizer(video_optimizer_args, &stop_flag, &progress_sender),
             Commands::ExifRemover(exif_remover_args) => exif_remover(exif_remover_args, &stop_flag, &progress_sender),
+            Commands::BitRot(bit_rot_args) => bit_rot(bit_rot_args, &stop_flag, &progress_sender),
+            Commands::ImageOptimizer(image_optimizer_args) => image_optimizer(image_optimizer_args, &stop_flag, &progress_sender),
+            Commands::StaleFiles(stale_files_args) => stale_files(stale_files_args, &stop_flag, &progress_sender),
+            Commands::BuildArtifacts(build_artifacts_args) => build_artifacts(build_artifacts_args, &stop_flag, &progress_sender),
+            Commands::Rename(rename_args) => rename(rename_args, &stop_flag),
+            Commands::RollbackRenames(rollback_renames_args) => rollback_renames(rollback_renames_args, &stop_flag),
         })
         .expect("Failed to spawn calculation thread");
 
@@ -156,9 +171,24 @@ fn duplicates(duplicates: DuplicatesArgs, stop_flag: &Arc<AtomicBool>, progress_
 }
 
 fn empty_folders(empty_folders: EmptyFoldersArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
-    let EmptyFoldersArgs { common_cli_items, delete_method } = empty_folders;
+    let EmptyFoldersArgs {
+        common_cli_items,
+        delete_method,
+        ignored_files,
+    } = empty_folders;
+
+    let ignored_files = ignored_files
+        .into_iter()
+        .flat_map(|item| {
+            if item == "DEFAULT" {
+                DEFAULT_IGNORED_FILES.iter().map(ToString::to_string).collect()
+            } else {
+                vec![item]
+            }
+        })
+        .collect();
 
-    let mut tool = EmptyFolder::new();
+    let mut tool = EmptyFolder::new(EmptyFolderParameters::new(ignored_files));
 
     set_common_settings(&mut tool, &common_cli_items, None);
     set_simple_delete(&mut tool, delete_method);
@@ -174,10 +204,15 @@ fn biggest_files(biggest_files: BiggestFilesArgs, stop_flag: &Arc<AtomicBool>, p
         number_of_files,
         delete_method,
         smallest_mode,
+        directories_per_depth,
+        treemap_json,
     } = biggest_files;
 
     let big_files_mode = if smallest_mode { SearchMode::SmallestFiles } else { SearchMode::BiggestFiles };
-    let params = BigFileParameters::new(number_of_files, big_files_mode);
+    let mut params = BigFileParameters::new(number_of_files, big_files_mode);
+    if let Some(directories_per_depth) = directories_per_depth {
+        params = params.with_directory_sizes(directories_per_depth);
+    }
     let mut tool = BigFile::new(params);
 
     set_common_settings(&mut tool, &common_cli_items, None);
@@ -185,6 +220,12 @@ fn biggest_files(biggest_files: BiggestFilesArgs, stop_flag: &Arc<AtomicBool>, p
 
     tool.search(stop_flag, Some(progress_sender));
 
+    if let Some(treemap_json) = treemap_json
+        && let Err(e) = tool.save_directory_treemap_as_json(&treemap_json.to_string_lossy(), false)
+    {
+        error!("Failed to save treemap json to file {e}");
+    }
+
     save_and_write_results_to_writer(&tool, &common_cli_items)
 }
 
@@ -236,6 +277,7 @@ fn similar_images(similar_images: SimilarImagesArgs, stop_flag: &Arc<AtomicBool>
     let SimilarImagesArgs {
         common_cli_items,
         reference_directories,
+        html_report,
         minimal_file_size,
         maximal_file_size,
         max_difference,
@@ -270,6 +312,7 @@ fn similar_images(similar_images: SimilarImagesArgs, stop_flag: &Arc<AtomicBool>
 
     tool.search(stop_flag, Some(progress_sender));
 
+    save_html_report(&tool, html_report.html_report.as_deref());
     save_and_write_results_to_writer(&tool, &common_cli_items)
 }
 
@@ -286,6 +329,12 @@ fn same_music(same_music: SameMusicArgs, stop_flag: &Arc<AtomicBool>, progress_s
         search_method,
         approximate_comparison,
         compare_fingerprints_only_with_similar_titles,
+        detect_fake_lossless,
+        tag_normalization,
+        fuzzy_similarity_threshold,
+        copy_missing_tags,
+        overwrite_tags,
+        use_cue_sheets,
     } = same_music;
 
     validate_file_sizes(minimal_file_size, maximal_file_size);
@@ -297,6 +346,10 @@ fn same_music(same_music: SameMusicArgs, stop_flag: &Arc<AtomicBool>, progress_s
         minimum_segment_duration,
         maximum_difference,
         compare_fingerprints_only_with_similar_titles,
+        detect_fake_lossless,
+        tag_normalization,
+        fuzzy_similarity_threshold,
+        use_cue_sheets,
     );
     let mut tool = SameMusic::new(params);
 
@@ -307,6 +360,40 @@ fn same_music(same_music: SameMusicArgs, stop_flag: &Arc<AtomicBool>, progress_s
 
     tool.search(stop_flag, Some(progress_sender));
 
+    if copy_missing_tags {
+        let conflict_policy = if overwrite_tags { TagConflictPolicy::Overwrite } else { TagConflictPolicy::FillEmpty };
+        tool.fix_items(stop_flag, Some(progress_sender), MusicTagsFixParams { conflict_policy });
+    }
+
+    save_and_write_results_to_writer(&tool, &common_cli_items)
+}
+
+fn music_snippet(music_snippet: MusicSnippetArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let MusicSnippetArgs {
+        common_cli_items,
+        snippet,
+        minimum_segment_duration,
+        maximum_difference,
+    } = music_snippet;
+
+    let params = SameMusicParameters::new(
+        MusicSimilarity::TRACK_TITLE,
+        false,
+        CheckingMethod::AudioContent,
+        minimum_segment_duration,
+        maximum_difference,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
+    let mut tool = SameMusic::new(params);
+
+    set_common_settings(&mut tool, &common_cli_items, None);
+
+    tool.search_snippet(&snippet, stop_flag, Some(progress_sender));
+
     save_and_write_results_to_writer(&tool, &common_cli_items)
 }
 
@@ -328,6 +415,7 @@ fn broken_files(broken_files: BrokenFilesArgs, stop_flag: &Arc<AtomicBool>, prog
         common_cli_items,
         delete_method,
         checked_types,
+        repair,
     } = broken_files;
 
     let mut checked_type = CheckedTypes::NONE;
@@ -342,6 +430,11 @@ fn broken_files(broken_files: BrokenFilesArgs, stop_flag: &Arc<AtomicBool>, prog
 
     tool.search(stop_flag, Some(progress_sender));
 
+    if repair {
+        let fix_params = BrokenFilesFixParams { repaired_types: checked_type };
+        tool.fix_items(stop_flag, Some(progress_sender), fix_params);
+    }
+
     save_and_write_results_to_writer(&tool, &common_cli_items)
 }
 
@@ -349,6 +442,7 @@ fn similar_videos(similar_videos: SimilarVideosArgs, stop_flag: &Arc<AtomicBool>
     let SimilarVideosArgs {
         reference_directories,
         common_cli_items,
+        html_report,
         tolerance,
         minimal_file_size,
         maximal_file_size,
@@ -407,13 +501,23 @@ fn similar_videos(similar_videos: SimilarVideosArgs, stop_flag: &Arc<AtomicBool>
 
     tool.search(stop_flag, Some(progress_sender));
 
+    save_html_report(&tool, html_report.html_report.as_deref());
     save_and_write_results_to_writer(&tool, &common_cli_items)
 }
 
 fn bad_extensions(bad_extensions: BadExtensionsArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
-    let BadExtensionsArgs { common_cli_items, fix_extensions } = bad_extensions;
-
-    let params = BadExtensionsParameters::new();
+    let BadExtensionsArgs {
+        common_cli_items,
+        fix_extensions,
+        signatures,
+        fix_mode,
+        on_collision,
+        dry_run,
+        rollback_log,
+    } = bad_extensions;
+
+    let mut params = BadExtensionsParameters::new();
+    params.custom_definitions_path = signatures;
     let mut tool = BadExtensions::new(params);
 
     set_common_settings(&mut tool, &common_cli_items, None);
@@ -421,7 +525,12 @@ fn bad_extensions(bad_extensions: BadExtensionsArgs, stop_flag: &Arc<AtomicBool>
     tool.search(stop_flag, Some(progress_sender));
 
     if fix_extensions {
-        let fix_params = BadExtensionsFixParams {};
+        let fix_params = BadExtensionsFixParams {
+            fix_mode,
+            collision_mode: on_collision,
+            dry_run,
+            rollback_log,
+        };
         tool.fix_items(stop_flag, Some(progress_sender), fix_params);
     }
 
@@ -438,7 +547,16 @@ fn bad_names(bad_names: BadNamesArgs, stop_flag: &Arc<AtomicBool>, progress_send
         non_ascii_graphical,
         restricted_charset,
         remove_duplicated_non_alphanumeric,
+        windows_reserved_name,
+        trailing_dot_or_space,
+        windows_forbidden_characters,
+        too_long_name,
+        too_long_path,
+        case_collision,
+        unicode_normalization_collision,
+        mojibake,
         fix_names,
+        rollback_log,
     } = bad_names;
 
     let restricted_charset_allowed = restricted_charset.and_then(|s| {
@@ -455,6 +573,14 @@ fn bad_names(bad_names: BadNamesArgs, stop_flag: &Arc<AtomicBool>, progress_send
         non_ascii_graphical,
         restricted_charset_allowed,
         remove_duplicated_non_alphanumeric,
+        windows_reserved_name,
+        trailing_dot_or_space,
+        windows_forbidden_characters,
+        too_long_name,
+        too_long_path,
+        case_collision,
+        unicode_normalization_collision,
+        mojibake,
     };
 
     let params = BadNamesParameters::new(name_issues);
@@ -466,13 +592,83 @@ fn bad_names(bad_names: BadNamesArgs, stop_flag: &Arc<AtomicBool>, progress_send
     tool.search(stop_flag, Some(progress_sender));
 
     if fix_names {
-        let fix_params = NameFixerParams::default();
+        let fix_params = NameFixerParams { rollback_log };
         tool.fix_items(stop_flag, Some(progress_sender), fix_params);
     }
 
     save_and_write_results_to_writer(&tool, &common_cli_items)
 }
 
+fn rename(rename: RenameArgs, stop_flag: &Arc<AtomicBool>) -> CliOutput {
+    let RenameArgs {
+        files,
+        template,
+        counter_start,
+        counter_step,
+        find,
+        replace,
+        dry_run,
+        rollback_log,
+    } = rename;
+
+    let template = RenameTemplate::new(&template).map(|template| template.with_counter(counter_start, counter_step));
+    let template = match (template, find) {
+        (Ok(template), Some(find)) => template.with_find_replace(&find, &replace.unwrap_or_default()),
+        (template, _) => template,
+    };
+    let template = match template {
+        Ok(template) => template,
+        Err(e) => return get_rename_output(&[], &[e], dry_run),
+    };
+
+    let plan = RenamePlan::from_template(files, &template);
+    let mut messages = plan.get_issue_messages();
+
+    if dry_run {
+        let planned: Vec<_> = plan
+            .get_entries()
+            .iter()
+            .filter(|entry| entry.issue.is_none())
+            .map(|entry| (entry.source.clone(), entry.target.clone()))
+            .collect();
+        return get_rename_output(&planned, &messages, true);
+    }
+
+    let RenameResult { renamed, errors, .. } = plan.execute(stop_flag, rollback_log.as_deref());
+    messages.extend(errors);
+    get_rename_output(&renamed, &messages, false)
+}
+
+fn rollback_renames(rollback_renames: RollbackRenamesArgs, stop_flag: &Arc<AtomicBool>) -> CliOutput {
+    let RollbackRenamesArgs { rollback_log } = rollback_renames;
+
+    let RenameResult { renamed, errors, .. } = rename::rollback_renames(&rollback_log, stop_flag);
+    get_rename_output(&renamed, &errors, false)
+}
+
+fn get_rename_output(renamed: &[(PathBuf, PathBuf)], messages: &[String], dry_run: bool) -> CliOutput {
+    let mut lines = if dry_run {
+        vec![format!("Files which would be renamed - {}", renamed.len())]
+    } else {
+        vec![format!("Renamed files - {}", renamed.len())]
+    };
+    lines.extend(
+        renamed
+            .iter()
+            .map(|(source, target)| format!("\"{}\" -> \"{}\"", source.to_string_lossy(), target.to_string_lossy())),
+    );
+    if !messages.is_empty() {
+        lines.push(format!("\nWarnings - {}", messages.len()));
+        lines.extend(messages.iter().cloned());
+    }
+
+    CliOutput {
+        found_any_files: false,
+        ignored_error_code_on_found: false,
+        output: lines.join("\n"),
+    }
+}
+
 fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
     use crate::commands::{CropArgs, TranscodeArgs, VideoOptimizerMode as CliVideoOptimizerMode};
 
@@ -488,6 +684,8 @@ fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBo
                 fix_videos,
                 target_codec,
                 quality,
+                target_bitrate,
+                target_size,
                 fail_if_not_smaller,
                 overwrite_original,
                 limit_video_size,
@@ -497,6 +695,7 @@ fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBo
                 noise_reduction,
                 noise_reduction_strength,
                 custom_ffmpeg_command,
+                preserve_metadata,
             } = transcode_args;
 
             let excluded_codecs_vec = excluded_codecs.map_or_else(
@@ -517,9 +716,15 @@ fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBo
             tool.search(stop_flag, Some(progress_sender));
 
             if fix_videos {
+                let rate_control = match (target_bitrate, target_size) {
+                    (Some(target_bitrate), _) => VideoRateControl::TargetBitrate(target_bitrate * 1000),
+                    (None, Some(target_size)) => VideoRateControl::TargetFileSize(target_size * 1024 * 1024),
+                    (None, None) => VideoRateControl::ConstantQuality,
+                };
                 let fix_params = VideoOptimizerFixParams::VideoTranscode(VideoTranscodeFixParams {
                     codec: target_codec,
                     quality,
+                    rate_control,
                     fail_if_not_smaller,
                     overwrite_original,
                     limit_video_size,
@@ -528,6 +733,7 @@ fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBo
                     noise_reduction,
                     noise_reduction_strength,
                     custom_ffmpeg_command,
+                    metadata_preservation: get_metadata_preservation(&preserve_metadata),
                     hardware_encoder: HardwareEncoder::None, // TODO  - missing hardware encoder
                 });
                 tool.fix_items(stop_flag, Some(progress_sender), fix_params);
@@ -550,6 +756,7 @@ fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBo
                 overwrite_original,
                 target_codec,
                 quality,
+                preserve_metadata,
             } = crop_args;
 
             #[expect(clippy::match_same_arms)]
@@ -581,6 +788,7 @@ fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBo
                     target_codec,
                     quality,
                     crop_mechanism: crop_mech,
+                    metadata_preservation: get_metadata_preservation(&preserve_metadata),
                 });
                 tool.fix_items(stop_flag, Some(progress_sender), fix_params);
             }
@@ -590,6 +798,14 @@ fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBo
     }
 }
 
+fn get_metadata_preservation(preserve_metadata: &PreserveMetadataArgs) -> MetadataPreservation {
+    MetadataPreservation {
+        copy_metadata: preserve_metadata.preserve_metadata,
+        restore_timestamps: preserve_metadata.preserve_timestamps,
+        copy_xattrs: preserve_metadata.preserve_xattrs,
+    }
+}
+
 fn exif_remover(exif_remover: ExifRemoverArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
     let ExifRemoverArgs {
         common_cli_items,
@@ -615,6 +831,113 @@ fn exif_remover(exif_remover: ExifRemoverArgs, stop_flag: &Arc<AtomicBool>, prog
     save_and_write_results_to_writer(&tool, &common_cli_items)
 }
 
+fn bit_rot(bit_rot: BitRotArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let BitRotArgs {
+        common_cli_items,
+        delete_method,
+        hash_type,
+    } = bit_rot;
+
+    let params = BitRotParameters::new(hash_type);
+    let mut tool = BitRot::new(params);
+
+    set_common_settings(&mut tool, &common_cli_items, None);
+    set_simple_delete(&mut tool, delete_method);
+
+    tool.search(stop_flag, Some(progress_sender));
+
+    save_and_write_results_to_writer(&tool, &common_cli_items)
+}
+
+fn save_html_report<T: HtmlReport>(component: &T, html_report: Option<&Path>) {
+    if let Some(folder) = html_report
+        && let Err(e) = component.save_results_to_html_report(folder)
+    {
+        error!("Failed to save HTML report to folder \"{}\": {e}", folder.display());
+    }
+}
+
+fn image_optimizer(image_optimizer: ImageOptimizerArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let ImageOptimizerArgs {
+        common_cli_items,
+        target_format,
+        quality,
+        minimal_savings,
+        fix_images,
+        fail_if_not_smaller,
+        overwrite_original,
+    } = image_optimizer;
+
+    let params = ImageOptimizerParameters::new(target_format, quality, minimal_savings);
+    let mut tool = ImageOptimizer::new(params);
+
+    set_common_settings(&mut tool, &common_cli_items, None);
+
+    tool.search(stop_flag, Some(progress_sender));
+
+    if fix_images {
+        let fix_params = ImageOptimizerFixParams {
+            fail_if_not_smaller,
+            overwrite_original,
+        };
+        tool.fix_items(stop_flag, Some(progress_sender), fix_params);
+    }
+
+    save_and_write_results_to_writer(&tool, &common_cli_items)
+}
+
+fn stale_files(stale_files: StaleFilesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let StaleFilesArgs {
+        common_cli_items,
+        delete_method,
+        minimal_file_size,
+        maximal_file_size,
+        modified_days,
+        accessed_days,
+        changed_days,
+    } = stale_files;
+
+    validate_file_sizes(minimal_file_size, maximal_file_size);
+
+    let params = if modified_days.is_none() && accessed_days.is_none() && changed_days.is_none() {
+        StaleFilesParameters::default()
+    } else {
+        StaleFilesParameters::new(modified_days, accessed_days, changed_days)
+    };
+    let mut tool = StaleFiles::new(params);
+
+    set_common_settings(&mut tool, &common_cli_items, None);
+    set_simple_delete(&mut tool, delete_method);
+    tool.set_minimal_file_size(minimal_file_size);
+    tool.set_maximal_file_size(maximal_file_size);
+
+    tool.search(stop_flag, Some(progress_sender));
+
+    save_and_write_results_to_writer(&tool, &common_cli_items)
+}
+
+fn build_artifacts(build_artifacts: BuildArtifactsArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let BuildArtifactsArgs {
+        common_cli_items,
+        delete_method,
+        kinds,
+    } = build_artifacts;
+
+    let params = if kinds.is_empty() {
+        BuildArtifactsParameters::default()
+    } else {
+        BuildArtifactsParameters::new(kinds)
+    };
+    let mut tool = BuildArtifacts::new(params);
+
+    set_common_settings(&mut tool, &common_cli_items, None);
+    set_simple_delete(&mut tool, delete_method);
+
+    tool.search(stop_flag, Some(progress_sender));
+
+    save_and_write_results_to_writer(&tool, &common_cli_items)
+}
+
 fn save_and_write_results_to_writer<T: CommonData + PrintResults>(component: &T, common_cli_items: &CommonCliItems) -> CliOutput {
     if let Some(file_name) = common_cli_items.file_to_save.file_name()
         && let Err(e) = component.print_results_to_file(file_name)
@@ -1,8 +1,11 @@
 use czkawka_core::common::model::{CheckingMethod, HashType};
 use czkawka_core::common::tool_data::DeleteMethod;
 use czkawka_core::re_exported::{FilterType, HashAlg};
+use czkawka_core::tools::bad_extensions::{ExtensionCollisionMode, ExtensionFixMode};
 use czkawka_core::tools::broken_files::CheckedTypes;
-use czkawka_core::tools::same_music::MusicSimilarity;
+use czkawka_core::tools::build_artifacts::ArtifactKind;
+use czkawka_core::tools::image_optimizer::ImageOptimizerFormat;
+use czkawka_core::tools::same_music::{MusicSimilarity, TagNormalization};
 use czkawka_core::tools::similar_images::GeometricInvariance;
 use czkawka_core::tools::similar_videos::{
     ALLOWED_AUDIO_LENGTH_RATIO, ALLOWED_AUDIO_SIMILARITY_PERCENT, ALLOWED_DURATION_TOLERANCE_PCT, ALLOWED_MATCH_FRACTION, ALLOWED_SKIP_FORWARD_AMOUNT, ALLOWED_VID_HASH_DURATION,
@@ -156,6 +159,34 @@ pub(crate) fn parse_hash_type(src: &str) -> Result<HashType, &'static str> {
     }
 }
 
+pub(crate) fn parse_extension_fix_mode(src: &str) -> Result<ExtensionFixMode, &'static str> {
+    match src.to_ascii_lowercase().as_str() {
+        "replace" => Ok(ExtensionFixMode::Replace),
+        "append" => Ok(ExtensionFixMode::Append),
+        _ => Err("Couldn't parse the extension fix mode (allowed: REPLACE, APPEND)"),
+    }
+}
+
+pub(crate) fn parse_artifact_kind(src: &str) -> Result<ArtifactKind, &'static str> {
+    match src.to_ascii_lowercase().as_str() {
+        "rust" => Ok(ArtifactKind::RustTarget),
+        "node" => Ok(ArtifactKind::NodeModules),
+        "pycache" => Ok(ArtifactKind::PythonCache),
+        "venv" => Ok(ArtifactKind::PythonVirtualEnv),
+        "gradle" => Ok(ArtifactKind::Gradle),
+        "cmake" => Ok(ArtifactKind::CMakeBuild),
+        _ => Err("Couldn't parse the artifact kind (allowed: RUST, NODE, PYCACHE, VENV, GRADLE, CMAKE)"),
+    }
+}
+
+pub(crate) fn parse_extension_collision_mode(src: &str) -> Result<ExtensionCollisionMode, &'static str> {
+    match src.to_ascii_lowercase().as_str() {
+        "skip" => Ok(ExtensionCollisionMode::Skip),
+        "number" => Ok(ExtensionCollisionMode::AddNumber),
+        _ => Err("Couldn't parse the collision mode (allowed: SKIP, NUMBER)"),
+    }
+}
+
 pub(crate) fn parse_tolerance(src: &str) -> Result<i32, &'static str> {
     match src.parse::<i32>() {
         Ok(t) => {
@@ -211,6 +242,10 @@ pub(crate) fn parse_video_codec(src: &str) -> Result<VideoCodec, &'static str> {
     }
 }
 
+pub(crate) fn parse_image_optimizer_format(src: &str) -> Result<ImageOptimizerFormat, String> {
+    src.parse()
+}
+
 pub(crate) fn parse_max_samples(src: &str) -> Result<usize, String> {
     match src.parse::<usize>() {
         Ok(val) if (5..=1000).contains(&val) => Ok(val),
@@ -239,7 +274,9 @@ pub(crate) fn parse_delete_method(src: &str) -> Result<DeleteMethod, &'static st
         "aes" => Ok(DeleteMethod::AllExceptSmallest),
         "ob" => Ok(DeleteMethod::OneBiggest),
         "os" => Ok(DeleteMethod::OneSmallest),
-        _ => Err("Couldn't parse the delete method (allowed: AEN, AEO, ON, OO, HARD, AEB, AES, OB, OS)"),
+        "aebq" => Ok(DeleteMethod::AllExceptBestQuality),
+        "aemt" => Ok(DeleteMethod::AllExceptMostTracks),
+        _ => Err("Couldn't parse the delete method (allowed: AEN, AEO, ON, OO, HARD, AEB, AES, OB, OS, AEBQ, AEMT)"),
     }
 }
 
@@ -350,6 +387,32 @@ pub(crate) fn parse_music_duplicate_type(src: &str) -> Result<MusicSimilarity, S
     Ok(similarity)
 }
 
+pub(crate) fn parse_tag_normalization(src: &str) -> Result<TagNormalization, String> {
+    let mut normalization = TagNormalization::NONE;
+    for part in src.split(',').map(|e| e.trim().to_lowercase()).filter(|e| !e.is_empty()) {
+        normalization |= match part.as_str() {
+            "featuring" => TagNormalization::STRIP_FEATURING,
+            "version" => TagNormalization::STRIP_VERSION_QUALIFIERS,
+            "transliterate" => TagNormalization::TRANSLITERATE,
+            "split-artists" => TagNormalization::SPLIT_ARTISTS,
+            _ => {
+                return Err(format!(
+                    "Couldn't parse the tag normalization step \"{part}\" (allowed: featuring,version,transliterate,split-artists)"
+                ));
+            }
+        };
+    }
+    Ok(normalization)
+}
+
+pub(crate) fn parse_fuzzy_similarity_threshold(src: &str) -> Result<f64, String> {
+    match src.parse::<f64>() {
+        Ok(threshold) if (0.0..=1.0).contains(&threshold) => Ok(threshold),
+        Ok(_) => Err("Fuzzy similarity threshold must be between 0.0 and 1.0".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 pub(crate) fn parse_crop_mechanism(src: &str) -> Result<String, String> {
     match src.to_lowercase().as_str() {
         "blackbars" | "staticcontent" => Ok(src.to_lowercase()),
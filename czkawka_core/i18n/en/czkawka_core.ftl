@@ -100,6 +100,7 @@ core_properly_loaded_cache_entries = Properly loaded { $count } cache entries.
 core_video_processing_stopped_by_user = Video processing was stopped by user
 core_thumbnail_generation_stopped_by_user = Thumbnail generation was stopped by user
 core_failed_to_optimize_video = Failed to optimize video "{ $file }": { $reason }
+core_video_optimizer_skipped_processed_videos = Skipped { $done } videos already processed with the same settings, according to job journal
 core_failed_to_crop_video = Failed to crop video "{ $file }": { $reason }
 core_failed_to_get_metadata_of_optimized_file = Failed to get metadata of optimized file "{ $file }": { $reason }
 core_cannot_create_config_folder = Cannot create config folder "{ $folder }", reason { $reason }
@@ -108,10 +109,45 @@ core_cannot_create_or_open_cache_file = Cannot create or open cache file "{ $fil
 core_cannot_set_config_cache_path = Cannot set config/cache path - config and cache will not be used.
 core_invalid_extension_contains_space = { $extension } is not a valid extension because it contains empty space inside
 core_invalid_extension_contains_dot = { $extension } is not a valid extension because it contains dot inside
+core_failed_to_read_original_metadata = Failed to read metadata of "{ $file }", reason { $reason }
+core_failed_to_restore_metadata = Failed to restore metadata of "{ $file }", reason { $reason }
+core_metadata_not_preserved = Metadata of "{ $file }" was not preserved: { $items }
+core_target_video_bitrate_too_low = Target video bitrate { $bitrate } bit/s is lower than minimum { $minimum } bit/s, choose bigger target size or bitrate
 core_ffmpeg_unknown_encoder = Cannot encode { $file } using the { $encoder } encoder. The current FFmpeg build does not support this encoder. Use a different FFmpeg version with the required codec support or select another encoder.
 core_ffmpeg_error = FFmpeg error while processing { $file }, status code { $code }, reason { $reason }
 core_custom_command_missing_path_placeholder = Custom FFmpeg command must contain {"{PATH}"} as input file placeholder
 core_custom_command_empty = Custom FFmpeg command cannot be empty
+core_failed_to_fingerprint_snippet = Failed to calculate fingerprint of audio snippet "{ $file }": { $reason }
+core_failed_to_repair_file = Failed to repair file "{ $file }": { $reason }
+core_repair_output_already_exists = Output path "{ $path }" already exists, so it will not be overwritten
+core_repair_nothing_recovered = Not found any intact data which could be recovered
+core_failed_to_copy_tags = Failed to copy tags to file "{ $file }": { $reason }
+core_music_tags_copy_requires_audio_content = Tags can be copied only between files grouped by audio content, so no file was modified
+core_bit_rot_missing_hash_cache = Cannot load { $hash_type } hash cache - to verify files, first run a duplicate search by hash with cache enabled and the same hash type
+core_unknown_image_format = Unknown image format { $format }, allowed formats are lossless, webp, avif and jxl
+core_image_optimizer_programs_not_found = Cannot find { $programs } - these external programs are needed by image optimizer and must be installed manually and available in PATH
+core_failed_to_optimize_image = Failed to optimize image "{ $file }": { $reason }
+core_image_encoder_error = { $encoder } failed to optimize image "{ $file }", status code { $code }, reason { $reason }
+core_image_processing_stopped_by_user = Image processing was stopped by user
+core_optimized_image_already_exists = Cannot save optimized image, because file "{ $file }" already exists
+core_exif_not_preserved = Failed to copy EXIF data of "{ $file }" to optimized image: { $reason }
+core_rename_template_unclosed_brace = Rename template "{ $template }" contains unclosed brace
+core_rename_template_unknown_token = Unknown token "{ $token }" in rename template
+core_rename_invalid_regex = Invalid regular expression "{ $regex }": { $reason }
+core_rename_missing_exif_date = File "{ $file }" does not contain EXIF date
+core_rename_cannot_read_modification_date = Cannot read modification date of "{ $file }": { $reason }
+core_rename_invalid_name = Cannot rename "{ $file }", because "{ $name }" is not valid file name
+core_rename_duplicate_target = Cannot rename "{ $file }", because other file would be renamed to "{ $target }" too
+core_rename_target_exists = Cannot rename "{ $file }", because "{ $target }" already exists
+core_rename_failed = Failed to rename "{ $file }" to "{ $target }": { $reason }
+core_rename_cannot_write_log = Cannot write rename log "{ $file }": { $reason }
+core_rename_cannot_read_log = Cannot read rename log "{ $file }": { $reason }
+core_custom_signatures_cannot_read = Cannot load file signatures from "{ $file }": { $reason }
+core_custom_signature_without_extension = Signature { $index } does not have any extension
+core_custom_signature_invalid_bytes = Signature { $index } has invalid bytes "{ $bytes }", expected hex values like "4D 5A"
+core_custom_signature_too_far = Signature { $index } ends too far from start of file, maximum is { $max } bytes
+core_stale_files_noatime_mount = Access times of files in "{ $path }" are not reliable, because "{ $mount }" is mounted with noatime option, so access time is never updated
+core_stale_files_relatime_mount = Files in "{ $path }" are placed on "{ $mount }" mounted with relatime option, so access time is updated at most once per day
 # Scan stage labels
 # Collecting / scanning files (the { $entries_checked } counter grows while the disk is walked)
 stage_collecting_files = Scanning { $entries_checked } file
@@ -131,6 +167,8 @@ stage_loading_fingerprints_cache = Loading fingerprints cache
 stage_saving_fingerprints_cache = Saving fingerprints cache
 stage_loading_exif_cache = Loading EXIF cache
 stage_saving_exif_cache = Saving EXIF cache
+stage_loading_image_optimizer_cache = Loading image optimizer cache
+stage_saving_image_optimizer_cache = Saving image optimizer cache
 # Per-tool work stages - items_stats holds "checked/total", size_stats holds the byte sizes
 stage_hiding_links = Hiding hard links { $items_stats }
 stage_analyzed_partial_hash = Analyzed partial hash of { $items_stats } files ({ $size_stats })
@@ -144,8 +182,14 @@ stage_compared_image_hashes = Compared image hashes of { $items_stats }
 stage_hashed_videos = Hashed { $items_stats } videos
 stage_created_thumbnails = Created thumbnails for { $items_stats } videos
 stage_checked_videos = Checked { $items_stats } videos ({ $size_stats })
+stage_encoding_first_pass = First pass of encoding { $items_stats } videos ({ $size_stats })
+stage_encoding_videos = Encoded { $items_stats } videos ({ $size_stats })
 stage_extracted_exif_tags = Extracted EXIF tags from { $items_stats } files ({ $size_stats })
+stage_verified_hash = Verified hash of { $items_stats } files ({ $size_stats })
+stage_checked_images = Checked { $items_stats } images ({ $size_stats })
+stage_optimized_images = Optimized { $items_stats } images ({ $size_stats })
 stage_checked_files = Checked { $items_stats } file ({ $size_stats })
+stage_repaired_files = Repaired { $items_stats } file ({ $size_stats })
 stage_checked_files_bad_extensions = Checked { $items_stats } file
 stage_checked_files_bad_names = Checked { $items_stats } file
 stage_checking_empty_files_content = Checking content of { $items_stats } files ({ $size_stats })
@@ -28,9 +28,10 @@ pub(crate) const CACHE_VERSION: u8 = 100;
 pub(crate) const CACHE_DUPLICATE_VERSION: u8 = 120;
 pub(crate) const CACHE_DUPLICATE_PREHASH_VERSION: u8 = 120;
 pub(crate) const CACHE_IMAGE_VERSION: u8 = 120;
-pub(crate) const CACHE_VIDEO_VERSION: u8 = 120;
+pub(crate) const CACHE_VIDEO_VERSION: u8 = 121;
 pub(crate) const CACHE_BROKEN_FILES_VERSION: u8 = 120;
-pub(crate) const CACHE_VIDEO_OPTIMIZE_VERSION: u8 = 110;
+pub(crate) const CACHE_VIDEO_OPTIMIZE_VERSION: u8 = 111;
+pub(crate) const CACHE_SAME_MUSIC_VERSION: u8 = 111;
 
 const MEMORY_LIMIT: u64 = 8 * 1024 * 1024 * 1024;
 const CLEANING_TIMESTAMPS_FILE: &str = "cleaning_timestamps.json";
@@ -69,3 +69,6 @@ pub const TEXT_FILES_EXTENSIONS: &[&str] = &["txt", "md", "csv", "log", "ini", "
 
 // "dng" - is theoretically a tiff file, but little_exif have problem with saving metadata to it
 pub const EXIF_FILES_EXTENSIONS: &[&str] = &["jpg", "jpeg", "jfif", "png", "tiff", "tif", "avif", "jxl", "webp", "heic", "heif"];
+
+// Formats which can be recompressed without losses or converted by image optimizer
+pub const IMAGE_OPTIMIZER_EXTENSIONS: &[&str] = &["jpg", "jpeg", "jfif", "png"];
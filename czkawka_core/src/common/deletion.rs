@@ -12,7 +12,9 @@ use crate::common::progress_data::{ProgressData, ToolStage};
 use crate::common::progress_stop_handler::check_if_stop_received;
 use crate::common::tool_data::{CommonToolData, DeleteMethod};
 use crate::common::traits::ResultEntry;
-use crate::common::{make_hard_link, remove_folder_if_contains_only_empty_folders, remove_single_file};
+use crate::common::{
+    make_hard_link, remove_folder_if_contains_only_empty_folders, remove_folder_if_contains_only_empty_folders_and_ignored_files, remove_single_file, remove_single_folder,
+};
 use crate::helpers::delayed_sender::DelayedSender;
 use crate::helpers::messages::Messages;
 
@@ -37,13 +39,19 @@ impl DeleteResult {
 pub enum DeleteItemType<T: ResultEntry + Sized + Send + Sync> {
     DeletingFiles(Vec<T>),
     DeletingFolders(Vec<T>),
+    // Folders which may contain, besides empty folders, files matching given ignored patterns, removed together with folder
+    DeletingFoldersWithIgnoredFiles(Vec<T>, Vec<String>),
+    // Whole folders are removed together with all their content, used e.g. for build artifacts
+    DeletingFoldersRecursively(Vec<T>),
     HardlinkingFiles(Vec<(T, Vec<T>)>),
 }
 
 impl<T: ResultEntry + Sized + Send + Sync> DeleteItemType<T> {
     fn calculate_size_to_delete(&self) -> u64 {
         match &self {
-            Self::DeletingFiles(items) | Self::DeletingFolders(items) => items.iter().map(|item| item.get_size()).sum(),
+            Self::DeletingFiles(items) | Self::DeletingFolders(items) | Self::DeletingFoldersWithIgnoredFiles(items, _) | Self::DeletingFoldersRecursively(items) => {
+                items.iter().map(|item| item.get_size()).sum()
+            }
             // Sum the linked files, not the originals, to match what progress accounts.
             Self::HardlinkingFiles(items) => items.iter().flat_map(|(_original, files)| files.iter().map(ResultEntry::get_size)).sum(),
         }
@@ -51,7 +59,7 @@ impl<T: ResultEntry + Sized + Send + Sync> DeleteItemType<T> {
 
     fn calculate_entries_to_delete(&self) -> usize {
         match &self {
-            Self::DeletingFiles(items) | Self::DeletingFolders(items) => items.len(),
+            Self::DeletingFiles(items) | Self::DeletingFolders(items) | Self::DeletingFoldersWithIgnoredFiles(items, _) | Self::DeletingFoldersRecursively(items) => items.len(),
             Self::HardlinkingFiles(items) => items.iter().map(|(_original, files)| files.len()).sum(),
         }
     }
@@ -82,7 +90,12 @@ pub(crate) fn delete_advanced_elements_and_add_to_messages<T: ResultEntry + Size
     let delete_method = cd.delete_method;
     let sorting_by_size = matches!(
         delete_method,
-        DeleteMethod::AllExceptBiggest | DeleteMethod::AllExceptSmallest | DeleteMethod::OneBiggest | DeleteMethod::OneSmallest
+        DeleteMethod::AllExceptBiggest
+            | DeleteMethod::AllExceptSmallest
+            | DeleteMethod::OneBiggest
+            | DeleteMethod::OneSmallest
+            | DeleteMethod::AllExceptBestQuality
+            | DeleteMethod::AllExceptMostTracks
     );
     let sort_items = |mut input: Vec<T>| -> Vec<T> {
         input.sort_unstable_by_key(if sorting_by_size { ResultEntry::get_size } else { ResultEntry::get_modified_date });
@@ -132,7 +145,7 @@ pub(crate) fn delete_advanced_elements_and_add_to_messages<T: ResultEntry + Size
                 let len = all_values.len();
                 match delete_method {
                     DeleteMethod::Delete => all_values,
-                    DeleteMethod::AllExceptNewest | DeleteMethod::AllExceptBiggest => {
+                    DeleteMethod::AllExceptNewest | DeleteMethod::AllExceptBiggest | DeleteMethod::AllExceptBestQuality | DeleteMethod::AllExceptMostTracks => {
                         all_values.truncate(len - 1);
                         all_values
                     }
@@ -193,7 +206,10 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
     let files_processed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
 
     let res = match delete_item_type {
-        DeleteItemType::DeletingFiles(ref items) | DeleteItemType::DeletingFolders(ref items) => items
+        DeleteItemType::DeletingFiles(ref items)
+        | DeleteItemType::DeletingFolders(ref items)
+        | DeleteItemType::DeletingFoldersWithIgnoredFiles(ref items, _)
+        | DeleteItemType::DeletingFoldersRecursively(ref items) => items
             .into_par_iter()
             .map(|e| {
                 if check_if_stop_received(stop_flag) {
@@ -214,10 +230,13 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
                     return Some(vec![(e, None, None)]);
                 }
 
-                let delete_res = if matches!(delete_item_type, DeleteItemType::DeletingFiles(_)) {
-                    remove_single_file(e.get_path(), move_to_trash)
-                } else {
-                    remove_folder_if_contains_only_empty_folders(e.get_path(), move_to_trash)
+                let delete_res = match delete_item_type {
+                    DeleteItemType::DeletingFiles(_) => remove_single_file(e.get_path(), move_to_trash),
+                    DeleteItemType::DeletingFoldersWithIgnoredFiles(_, ref ignored_files) => {
+                        remove_folder_if_contains_only_empty_folders_and_ignored_files(e.get_path(), ignored_files, move_to_trash)
+                    }
+                    DeleteItemType::DeletingFoldersRecursively(_) => remove_single_folder(e.get_path(), move_to_trash),
+                    _ => remove_folder_if_contains_only_empty_folders(e.get_path(), move_to_trash),
                 };
 
                 match delete_res {
@@ -408,6 +427,14 @@ mod tests {
         assert_eq!(delete_folders.calculate_size_to_delete(), 600);
         assert_eq!(delete_folders.calculate_entries_to_delete(), 3);
 
+        let delete_folders_with_ignored_files = DeleteItemType::DeletingFoldersWithIgnoredFiles(files.clone(), vec!["thumbs.db".to_string()]);
+        assert_eq!(delete_folders_with_ignored_files.calculate_size_to_delete(), 600);
+        assert_eq!(delete_folders_with_ignored_files.calculate_entries_to_delete(), 3);
+
+        let delete_folders_recursively = DeleteItemType::DeletingFoldersRecursively(files.clone());
+        assert_eq!(delete_folders_recursively.calculate_size_to_delete(), 600);
+        assert_eq!(delete_folders_recursively.calculate_entries_to_delete(), 3);
+
         let hardlink_files = DeleteItemType::HardlinkingFiles(vec![
             (files[0].clone(), vec![files[1].clone()]),
             (files[2].clone(), vec![files[0].clone(), files[1].clone()]),
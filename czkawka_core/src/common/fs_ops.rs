@@ -2,6 +2,7 @@ use std::io::Error;
 use std::path::Path;
 use std::{fs, io};
 
+use crate::common::items::{is_ignored_file_name, new_ignored_file_items};
 use crate::flc;
 
 const MAX_SYMLINK_HARDLINK_ATTEMPTS: u8 = 5;
@@ -36,7 +37,14 @@ where
 }
 
 pub fn check_if_folder_contains_only_empty_folders<P: AsRef<Path>>(path: P) -> Result<(), String> {
+    check_if_folder_contains_only_empty_folders_and_ignored_files(path, &[])
+}
+
+/// Like `check_if_folder_contains_only_empty_folders`, but files with names matching any of ignored patterns
+/// (case insensitive, with `*` wildcards) are allowed inside too.
+pub fn check_if_folder_contains_only_empty_folders_and_ignored_files<P: AsRef<Path>>(path: P, ignored_files: &[String]) -> Result<(), String> {
     let path = path.as_ref();
+    let ignored_items = new_ignored_file_items(ignored_files);
     if !path.is_dir() {
         return Err(flc!("core_not_directory_remove", path = path.to_string_lossy()));
     }
@@ -65,6 +73,9 @@ pub fn check_if_folder_contains_only_empty_folders<P: AsRef<Path>>(path: P) -> R
         };
 
         if !file_type.is_dir() {
+            if is_ignored_file_name(&entry.file_name(), &ignored_items) {
+                continue;
+            }
             return Err(flc!(
                 "core_folder_contains_file_inside",
                 entry = entry.path().to_string_lossy().to_string(),
@@ -121,7 +132,13 @@ fn trash_delete<P: AsRef<Path>>(path: P) -> Result<(), String> {
 /// Note: if used on Android or iOS platforms, ensure `remove_to_trash` is false, as trash is not supported
 /// and will always return an [`Error`].
 pub fn remove_folder_if_contains_only_empty_folders<P: AsRef<Path>>(path: P, remove_to_trash: bool) -> Result<(), String> {
-    check_if_folder_contains_only_empty_folders(&path)?;
+    remove_folder_if_contains_only_empty_folders_and_ignored_files(path, &[], remove_to_trash)
+}
+
+/// Remove the folder if it only contains empty folders and ignored files, which are removed together with folder.
+/// See `check_if_folder_contains_only_empty_folders_and_ignored_files` for format of ignored files.
+pub fn remove_folder_if_contains_only_empty_folders_and_ignored_files<P: AsRef<Path>>(path: P, ignored_files: &[String], remove_to_trash: bool) -> Result<(), String> {
+    check_if_folder_contains_only_empty_folders_and_ignored_files(&path, ignored_files)?;
 
     let path = path.as_ref();
 
@@ -155,14 +172,14 @@ pub fn remove_single_file<P: AsRef<Path>>(full_path: P, remove_to_trash: bool) -
 ///
 /// Note: if used on Android or iOS platforms, ensure `remove_to_trash` is false, as trash is not supported
 /// and will always return an [`Error`].
-pub fn remove_single_folder(full_path: &str, remove_to_trash: bool) -> Result<(), String> {
+pub fn remove_single_folder<P: AsRef<Path>>(full_path: P, remove_to_trash: bool) -> Result<(), String> {
     if remove_to_trash {
-        if let Err(e) = trash_delete(full_path) {
-            return Err(flc!("core_error_moving_to_trash", file = full_path, error = e));
+        if let Err(e) = trash_delete(&full_path) {
+            return Err(flc!("core_error_moving_to_trash", file = full_path.as_ref().to_string_lossy().to_string(), error = e));
         }
     } else {
-        if let Err(e) = fs::remove_dir_all(full_path) {
-            return Err(flc!("core_error_removing", file = full_path, error = e.to_string()));
+        if let Err(e) = fs::remove_dir_all(&full_path) {
+            return Err(flc!("core_error_removing", file = full_path.as_ref().to_string_lossy().to_string(), error = e.to_string()));
         }
     }
     Ok(())
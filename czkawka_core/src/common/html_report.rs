@@ -0,0 +1,242 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use fast_image_resize::FilterType as FirFilterType;
+use image::DynamicImage;
+use log::debug;
+use rayon::prelude::*;
+
+use crate::common::image::{ImgResizeOptions, get_dynamic_image_from_path};
+
+pub const HTML_REPORT_INDEX_FILE: &str = "index.html";
+const PREVIEWS_SUBFOLDER: &str = "previews";
+const PREVIEW_MAX_SIZE: u32 = 320;
+
+#[derive(Debug, Clone)]
+pub enum HtmlReportPreview {
+    // Original image, which is scaled down when creating report
+    Image(PathBuf),
+    // Already small image, e.g. video thumbnail, which is copied without changes
+    Thumbnail(PathBuf),
+}
+
+#[derive(Debug, Clone)]
+pub struct HtmlReportItem {
+    pub path: PathBuf,
+    pub preview: Option<HtmlReportPreview>,
+    // Must have the same length as column names of report
+    pub columns: Vec<String>,
+    pub is_reference: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HtmlReportGroup {
+    pub items: Vec<HtmlReportItem>,
+    // Additional information about whole group, e.g. matched time ranges
+    pub notes: Vec<String>,
+}
+
+// Report is a self-contained folder with index.html and previews, so it can be moved or opened from network share without access to scanned files
+pub(crate) fn write_html_report(folder: &Path, title: &str, column_names: &[&str], groups: &[HtmlReportGroup]) -> std::io::Result<()> {
+    let previews_folder = folder.join(PREVIEWS_SUBFOLDER);
+    fs::create_dir_all(&previews_folder)?;
+
+    let items: Vec<&HtmlReportItem> = groups.iter().flat_map(|group| &group.items).collect();
+    let preview_names: Vec<Option<String>> = items
+        .par_iter()
+        .enumerate()
+        .map(|(idx, item)| item.preview.as_ref().and_then(|preview| create_preview(preview, &previews_folder, idx)))
+        .collect();
+
+    let mut html = String::new();
+    write_header(&mut html, title);
+    let _ = writeln!(html, "<h1>{}</h1>", escape_html(title));
+    let _ = writeln!(html, "<p>{} groups, {} files</p>", groups.len(), items.len());
+
+    let mut preview_names = preview_names.into_iter();
+    for (group_idx, group) in groups.iter().enumerate() {
+        let differing_columns: Vec<bool> = (0..column_names.len())
+            .map(|column_idx| {
+                let mut values = group.items.iter().map(|item| item.columns.get(column_idx));
+                let first = values.next().flatten();
+                values.any(|value| value != first)
+            })
+            .collect();
+
+        let _ = writeln!(html, "<section class=\"group\">");
+        let _ = writeln!(html, "<h2>Group {} ({} files)</h2>", group_idx + 1, group.items.len());
+        for note in &group.notes {
+            let _ = writeln!(html, "<p class=\"note\">{}</p>", escape_html(note));
+        }
+        let _ = writeln!(html, "<div class=\"items\">");
+        for item in &group.items {
+            let preview_name = preview_names.next().flatten();
+            write_item(&mut html, item, preview_name.as_deref(), column_names, &differing_columns);
+        }
+        let _ = writeln!(html, "</div>\n</section>");
+    }
+    let _ = writeln!(html, "</body>\n</html>");
+
+    let mut writer = BufWriter::new(fs::File::create(folder.join(HTML_REPORT_INDEX_FILE))?);
+    writer.write_all(html.as_bytes())?;
+    writer.flush()
+}
+
+fn write_header(html: &mut String, title: &str) {
+    let _ = write!(
+        html,
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{}</title>
+<style>
+body {{ font-family: sans-serif; margin: 1em; background: #f4f4f4; color: #222; }}
+.group {{ background: #fff; border: 1px solid #ccc; border-radius: 6px; margin-bottom: 1.5em; padding: 0.5em 1em; }}
+.items {{ display: flex; flex-wrap: wrap; gap: 1em; }}
+.item {{ width: {PREVIEW_MAX_SIZE}px; border: 1px solid #ddd; padding: 0.5em; }}
+.item.reference {{ border: 2px solid #2a7ae2; }}
+.preview {{ width: {PREVIEW_MAX_SIZE}px; height: {PREVIEW_MAX_SIZE}px; object-fit: contain; background: #000; }}
+.no-preview {{ width: {PREVIEW_MAX_SIZE}px; height: {PREVIEW_MAX_SIZE}px; background: #ddd; display: flex; align-items: center; justify-content: center; }}
+.path {{ word-break: break-all; font-size: 0.85em; }}
+.note {{ font-style: italic; }}
+table {{ border-collapse: collapse; width: 100%; font-size: 0.85em; }}
+th {{ text-align: left; padding-right: 0.5em; }}
+td.diff {{ background: #ffe08a; font-weight: bold; }}
+</style>
+</head>
+<body>
+"#,
+        escape_html(title)
+    );
+}
+
+fn write_item(html: &mut String, item: &HtmlReportItem, preview_name: Option<&str>, column_names: &[&str], differing_columns: &[bool]) {
+    let class = if item.is_reference { "item reference" } else { "item" };
+    let _ = writeln!(html, "<div class=\"{class}\">");
+    match preview_name {
+        Some(preview_name) => {
+            let _ = writeln!(html, "<img class=\"preview\" src=\"{PREVIEWS_SUBFOLDER}/{}\" alt=\"\">", escape_html(preview_name));
+        }
+        None => {
+            let _ = writeln!(html, "<div class=\"no-preview\">No preview</div>");
+        }
+    }
+    if item.is_reference {
+        let _ = writeln!(html, "<p><b>Reference file</b></p>");
+    }
+    let _ = writeln!(html, "<p class=\"path\">{}</p>\n<table>", escape_html(&item.path.to_string_lossy()));
+    for ((column_name, value), differs) in column_names.iter().zip(&item.columns).zip(differing_columns) {
+        let class = if *differs { " class=\"diff\"" } else { "" };
+        let _ = writeln!(html, "<tr><th>{}</th><td{class}>{}</td></tr>", escape_html(column_name), escape_html(value));
+    }
+    let _ = writeln!(html, "</table>\n</div>");
+}
+
+// Returns name of created file inside previews folder
+fn create_preview(preview: &HtmlReportPreview, previews_folder: &Path, idx: usize) -> Option<String> {
+    match preview {
+        HtmlReportPreview::Image(path) => {
+            let resize_options = ImgResizeOptions {
+                max_width: PREVIEW_MAX_SIZE,
+                max_height: PREVIEW_MAX_SIZE,
+                filter: FirFilterType::Bilinear,
+            };
+            let loaded_image = match get_dynamic_image_from_path(&path.to_string_lossy(), Some(resize_options)) {
+                Ok(loaded_image) => loaded_image,
+                Err(e) => {
+                    debug!("Failed to create preview of \"{}\" - {e}", path.to_string_lossy());
+                    return None;
+                }
+            };
+            let preview_name = format!("{idx}.jpg");
+            // JPEG does not support transparency
+            let rgb_image = DynamicImage::ImageRgb8(loaded_image.image.to_rgb8());
+            if let Err(e) = rgb_image.save(previews_folder.join(&preview_name)) {
+                debug!("Failed to save preview of \"{}\" - {e}", path.to_string_lossy());
+                return None;
+            }
+            Some(preview_name)
+        }
+        HtmlReportPreview::Thumbnail(path) => {
+            // Empty thumbnail marks video, for which thumbnail could not be generated
+            if fs::metadata(path).map(|metadata| metadata.len()).unwrap_or_default() == 0 {
+                return None;
+            }
+            let extension = path.extension().map_or_else(|| "jpg".to_string(), |extension| extension.to_string_lossy().to_string());
+            let preview_name = format!("{idx}.{extension}");
+            if let Err(e) = fs::copy(path, previews_folder.join(&preview_name)) {
+                debug!("Failed to copy thumbnail \"{}\" - {e}", path.to_string_lossy());
+                return None;
+            }
+            Some(preview_name)
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use image::RgbaImage;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_write_html_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let image_path = temp_dir.path().join("big <image>.png");
+        RgbaImage::new(1000, 500).save(&image_path).unwrap();
+        let thumbnail_path = temp_dir.path().join("thumbnail.jpg");
+        fs::write(&thumbnail_path, b"not really jpeg").unwrap();
+        let empty_thumbnail_path = temp_dir.path().join("empty.jpg");
+        fs::write(&empty_thumbnail_path, b"").unwrap();
+
+        let mk = |path: &Path, preview: HtmlReportPreview, size: &str| HtmlReportItem {
+            path: path.to_path_buf(),
+            preview: Some(preview),
+            columns: vec![size.to_string(), "100x100".to_string()],
+            is_reference: false,
+        };
+        let groups = vec![HtmlReportGroup {
+            items: vec![
+                mk(&image_path, HtmlReportPreview::Image(image_path.clone()), "1 KiB"),
+                mk(&thumbnail_path, HtmlReportPreview::Thumbnail(thumbnail_path.clone()), "2 KiB"),
+                mk(&empty_thumbnail_path, HtmlReportPreview::Thumbnail(empty_thumbnail_path.clone()), "2 KiB"),
+            ],
+            notes: vec!["Note about group".to_string()],
+        }];
+
+        let report_folder = temp_dir.path().join("report");
+        write_html_report(&report_folder, "Similar images", &["Size", "Dimensions"], &groups).unwrap();
+
+        let preview = image::open(report_folder.join(PREVIEWS_SUBFOLDER).join("0.jpg")).unwrap();
+        assert_eq!((preview.width(), preview.height()), (320, 160));
+        assert!(report_folder.join(PREVIEWS_SUBFOLDER).join("1.jpg").exists());
+        assert!(!report_folder.join(PREVIEWS_SUBFOLDER).join("2.jpg").exists());
+
+        let html = fs::read_to_string(report_folder.join(HTML_REPORT_INDEX_FILE)).unwrap();
+        assert!(html.contains("big &lt;image&gt;.png"));
+        assert!(html.contains("src=\"previews/0.jpg\""));
+        assert!(html.contains("No preview"));
+        assert!(html.contains("Note about group"));
+        assert!(html.contains("<td class=\"diff\">1 KiB</td>"), "Different sizes should be highlighted");
+        assert!(html.contains("<td>100x100</td>"), "Same dimensions should not be highlighted");
+    }
+}
@@ -1,5 +1,8 @@
+use std::ffi::OsStr;
 use std::path::Path;
 
+use log::warn;
+
 use crate::common::regex_check;
 use crate::flc;
 use crate::helpers::messages::Messages;
@@ -151,6 +154,34 @@ pub fn new_excluded_item(expression: &str) -> SingleExcludedItem {
     }
 }
 
+/// Prepares case insensitive file name patterns, used e.g. to find junk files like `Thumbs.db` or `._*`
+///
+/// Patterns without any literal text, like `*`, would match every file and make folders with real data
+/// look empty, so they are skipped
+pub fn new_ignored_file_items(patterns: &[String]) -> Vec<SingleExcludedItem> {
+    patterns
+        .iter()
+        .map(|pattern| pattern.trim().to_lowercase())
+        .filter(|pattern| !pattern.is_empty())
+        .map(|pattern| new_excluded_item(&pattern))
+        .filter(|item| {
+            if item.expression_splits.is_empty() {
+                warn!("Ignored file pattern \"{}\" does not contain any text besides wildcards, so it is skipped", item.expression);
+                return false;
+            }
+            true
+        })
+        .collect()
+}
+
+pub fn is_ignored_file_name(file_name: &OsStr, ignored_items: &[SingleExcludedItem]) -> bool {
+    if ignored_items.is_empty() {
+        return false;
+    }
+    let file_name = file_name.to_string_lossy().to_lowercase();
+    ignored_items.iter().any(|item| regex_check(item, &file_name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
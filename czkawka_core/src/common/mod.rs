@@ -8,6 +8,7 @@ pub mod dir_traversal;
 pub mod directories;
 pub mod extensions;
 pub mod ffmpeg_utils;
+pub mod html_report;
 pub mod image;
 pub mod items;
 pub mod logger;
@@ -15,6 +16,7 @@ pub mod model;
 pub mod process_utils;
 pub mod progress_data;
 pub mod progress_stop_handler;
+pub mod rename;
 pub mod tool_data;
 pub mod traits;
 pub mod video_utils;
@@ -344,6 +346,20 @@ mod test {
         writeln!(file, "Hello, world!").expect("Cannot write to file");
         assert!(remove_folder_if_contains_only_empty_folders(&sub_dir, false).is_err());
         assert!(Path::new(&sub_dir).exists());
+
+        // File is removed together with folder only when it matches ignored files
+        let ignored_files = vec!["Thumbs.db".to_string(), "._*".to_string()];
+        assert!(remove_folder_if_contains_only_empty_folders_and_ignored_files(&sub_dir, &ignored_files, false).is_err());
+        // Pattern without any text would match every file, so it is skipped
+        assert!(remove_folder_if_contains_only_empty_folders_and_ignored_files(&sub_dir, &["*".to_string()], false).is_err());
+        assert!(Path::new(&sub_dir).join("file.txt").exists());
+        fs::remove_file(sub_dir.join("file.txt")).expect("Cannot remove file");
+        File::create(sub_dir.join("THUMBS.DB")).expect("Cannot create file");
+        fs::create_dir(sub_dir.join("nested")).expect("Cannot create directory");
+        File::create(sub_dir.join("nested").join("._file.txt")).expect("Cannot create file");
+        assert!(remove_folder_if_contains_only_empty_folders(&sub_dir, false).is_err());
+        remove_folder_if_contains_only_empty_folders_and_ignored_files(&sub_dir, &ignored_files, false).unwrap();
+        assert!(!Path::new(&sub_dir).exists());
     }
 
     #[test]
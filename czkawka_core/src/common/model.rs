@@ -22,6 +22,10 @@ pub enum ToolType {
     TemporaryFiles,
     ExifRemover,
     VideoOptimizer,
+    BitRot,
+    ImageOptimizer,
+    StaleFiles,
+    BuildArtifacts,
     #[default]
     None,
 }
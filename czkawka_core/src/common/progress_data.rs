@@ -74,6 +74,25 @@ pub enum ExifRemoverStage {
 pub enum VideoOptimizerStage {
     ProcessingVideos,
     CreatingThumbnails,
+    // Analysis pass of two-pass encoding
+    EncodingFirstPass,
+    // Single pass or second pass of two-pass encoding
+    EncodingVideos,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BitRotStage {
+    LoadingCache(CacheLoadPhase),
+    VerifyingHashes,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ImageOptimizerStage {
+    LoadingCache(CacheLoadPhase),
+    // Trial encoding, to estimate size after optimization
+    CheckingImages,
+    SavingCache,
+    OptimizingImages,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -95,8 +114,11 @@ pub enum ToolStage {
     SimilarVideos(SimilarVideosMode, SimilarVideosStage),
     ExifRemover(ExifRemoverStage),
     VideoOptimizer(VideoOptimizerStage),
+    BitRot(BitRotStage),
+    ImageOptimizer(ImageOptimizerStage),
 
     BrokenFilesChecking,
+    BrokenFilesRepairing,
     BadExtensionsChecking,
     BadNamesChecking,
     EmptyFilesCheckingContent,
@@ -121,6 +143,8 @@ impl ToolStage {
                 | Self::SameMusic(_, SameMusicStage::LoadingTagsCache(_) | SameMusicStage::LoadingFingerprintCache(_))
                 | Self::ExifRemover(ExifRemoverStage::LoadingCache(_))
                 | Self::SimilarVideos(_, SimilarVideosStage::LoadingAudioCache(_))
+                | Self::BitRot(BitRotStage::LoadingCache(_))
+                | Self::ImageOptimizer(ImageOptimizerStage::LoadingCache(_))
         )
     }
 
@@ -131,6 +155,7 @@ impl ToolStage {
                 | Self::SameMusic(_, SameMusicStage::SavingTagsCache | SameMusicStage::SavingFingerprintCache)
                 | Self::ExifRemover(ExifRemoverStage::SavingCache)
                 | Self::SimilarVideos(_, SimilarVideosStage::SavingAudioCache)
+                | Self::ImageOptimizer(ImageOptimizerStage::SavingCache)
         )
     }
 
@@ -154,6 +179,8 @@ impl ToolStage {
                 )
                 | Self::ExifRemover(ExifRemoverStage::LoadingCache(CacheLoadPhase::Loading) | ExifRemoverStage::SavingCache)
                 | Self::SimilarVideos(_, SimilarVideosStage::LoadingAudioCache(CacheLoadPhase::Loading) | SimilarVideosStage::SavingAudioCache)
+                | Self::BitRot(BitRotStage::LoadingCache(CacheLoadPhase::Loading))
+                | Self::ImageOptimizer(ImageOptimizerStage::LoadingCache(CacheLoadPhase::Loading) | ImageOptimizerStage::SavingCache)
         )
     }
 
@@ -166,8 +193,11 @@ impl ToolStage {
                 | Self::SimilarVideos(_, SimilarVideosStage::CalculatingAudioFingerprints)
                 | Self::ExifRemover(ExifRemoverStage::ExtractingTags)
                 | Self::BrokenFilesChecking
+                | Self::BrokenFilesRepairing
                 | Self::EmptyFilesCheckingContent
-                | Self::VideoOptimizer(VideoOptimizerStage::ProcessingVideos)
+                | Self::VideoOptimizer(VideoOptimizerStage::ProcessingVideos | VideoOptimizerStage::EncodingFirstPass | VideoOptimizerStage::EncodingVideos)
+                | Self::BitRot(BitRotStage::VerifyingHashes)
+                | Self::ImageOptimizer(ImageOptimizerStage::CheckingImages | ImageOptimizerStage::OptimizingImages)
         )
     }
 
@@ -232,11 +262,22 @@ impl ToolStage {
             },
 
             Self::VideoOptimizer(s) => match s {
-                VideoOptimizerStage::ProcessingVideos => 1,
-                VideoOptimizerStage::CreatingThumbnails => 2,
+                VideoOptimizerStage::ProcessingVideos | VideoOptimizerStage::EncodingFirstPass => 1,
+                VideoOptimizerStage::CreatingThumbnails | VideoOptimizerStage::EncodingVideos => 2,
+            },
+
+            Self::BitRot(s) => match s {
+                BitRotStage::LoadingCache(_) => 1,
+                BitRotStage::VerifyingHashes => 2,
             },
 
-            Self::BrokenFilesChecking | Self::BadExtensionsChecking | Self::BadNamesChecking | Self::EmptyFilesCheckingContent => 1,
+            Self::ImageOptimizer(s) => match s {
+                ImageOptimizerStage::LoadingCache(_) => 1,
+                ImageOptimizerStage::CheckingImages | ImageOptimizerStage::OptimizingImages => 2,
+                ImageOptimizerStage::SavingCache => 3,
+            },
+
+            Self::BrokenFilesChecking | Self::BrokenFilesRepairing | Self::BadExtensionsChecking | Self::BadNamesChecking | Self::EmptyFilesCheckingContent => 1,
         }
     }
 
@@ -253,10 +294,10 @@ impl ToolStage {
             | Self::CleaningExif => 0,
             Self::Duplicate(_) | Self::SameMusic(SameMusicMode::AudioContent, _) => 7,
             Self::SameMusic(SameMusicMode::AudioTags, _) => 4,
-            Self::SimilarImages(_) | Self::ExifRemover(_) | Self::SimilarVideos(SimilarVideosMode::VisualHash, _) => 3,
+            Self::SimilarImages(_) | Self::ExifRemover(_) | Self::SimilarVideos(SimilarVideosMode::VisualHash, _) | Self::ImageOptimizer(_) => 3,
             Self::SimilarVideos(SimilarVideosMode::AudioContent, _) => 6,
-            Self::VideoOptimizer(_) => 2,
-            Self::BrokenFilesChecking | Self::BadExtensionsChecking | Self::BadNamesChecking | Self::EmptyFilesCheckingContent => 1,
+            Self::VideoOptimizer(_) | Self::BitRot(_) => 2,
+            Self::BrokenFilesChecking | Self::BrokenFilesRepairing | Self::BadExtensionsChecking | Self::BadNamesChecking | Self::EmptyFilesCheckingContent => 1,
         }
     }
 }
@@ -365,7 +406,9 @@ impl ProgressData {
             // Cache load/save (granular per cache kind, indeterminate)
             ToolStage::Duplicate(DuplicateStage::LoadingPreHashCache(CacheLoadPhase::Loading)) => flc!("stage_loading_prehash_cache"),
             ToolStage::Duplicate(DuplicateStage::SavingPreHashCache) => flc!("stage_saving_prehash_cache"),
-            ToolStage::Duplicate(DuplicateStage::LoadingHashCache(CacheLoadPhase::Loading)) => flc!("stage_loading_hash_cache"),
+            ToolStage::Duplicate(DuplicateStage::LoadingHashCache(CacheLoadPhase::Loading)) | ToolStage::BitRot(BitRotStage::LoadingCache(CacheLoadPhase::Loading)) => {
+                flc!("stage_loading_hash_cache")
+            }
             ToolStage::Duplicate(DuplicateStage::SavingHashCache) => flc!("stage_saving_hash_cache"),
             ToolStage::SameMusic(_, SameMusicStage::LoadingTagsCache(CacheLoadPhase::Loading)) => flc!("stage_loading_tags_cache"),
             ToolStage::SameMusic(_, SameMusicStage::SavingTagsCache) => flc!("stage_saving_tags_cache"),
@@ -376,6 +419,8 @@ impl ProgressData {
             }
             ToolStage::ExifRemover(ExifRemoverStage::LoadingCache(CacheLoadPhase::Loading)) => flc!("stage_loading_exif_cache"),
             ToolStage::ExifRemover(ExifRemoverStage::SavingCache) => flc!("stage_saving_exif_cache"),
+            ToolStage::ImageOptimizer(ImageOptimizerStage::LoadingCache(CacheLoadPhase::Loading)) => flc!("stage_loading_image_optimizer_cache"),
+            ToolStage::ImageOptimizer(ImageOptimizerStage::SavingCache) => flc!("stage_saving_image_optimizer_cache"),
 
             // Filtering outdated cache entries (determinate sub-phase of any cache load)
             ToolStage::Duplicate(DuplicateStage::LoadingPreHashCache(CacheLoadPhase::FilteringOutdated) | DuplicateStage::LoadingHashCache(CacheLoadPhase::FilteringOutdated))
@@ -384,7 +429,9 @@ impl ProgressData {
                 SameMusicStage::LoadingTagsCache(CacheLoadPhase::FilteringOutdated) | SameMusicStage::LoadingFingerprintCache(CacheLoadPhase::FilteringOutdated),
             )
             | ToolStage::SimilarVideos(_, SimilarVideosStage::LoadingAudioCache(CacheLoadPhase::FilteringOutdated))
-            | ToolStage::ExifRemover(ExifRemoverStage::LoadingCache(CacheLoadPhase::FilteringOutdated)) => flc!("stage_filtering_outdated_cache"),
+            | ToolStage::ExifRemover(ExifRemoverStage::LoadingCache(CacheLoadPhase::FilteringOutdated))
+            | ToolStage::BitRot(BitRotStage::LoadingCache(CacheLoadPhase::FilteringOutdated))
+            | ToolStage::ImageOptimizer(ImageOptimizerStage::LoadingCache(CacheLoadPhase::FilteringOutdated)) => flc!("stage_filtering_outdated_cache"),
 
             // Per-tool work stages
             ToolStage::Duplicate(DuplicateStage::HidingHardLinks)
@@ -406,8 +453,14 @@ impl ProgressData {
             ToolStage::SimilarVideos(_, SimilarVideosStage::CreatingThumbnails | SimilarVideosStage::CreatingAudioThumbnails)
             | ToolStage::VideoOptimizer(VideoOptimizerStage::CreatingThumbnails) => flc!("stage_created_thumbnails", items_stats = items_stats),
             ToolStage::VideoOptimizer(VideoOptimizerStage::ProcessingVideos) => flc!("stage_checked_videos", items_stats = items_stats, size_stats = size_stats),
+            ToolStage::VideoOptimizer(VideoOptimizerStage::EncodingFirstPass) => flc!("stage_encoding_first_pass", items_stats = items_stats, size_stats = size_stats),
+            ToolStage::VideoOptimizer(VideoOptimizerStage::EncodingVideos) => flc!("stage_encoding_videos", items_stats = items_stats, size_stats = size_stats),
             ToolStage::ExifRemover(ExifRemoverStage::ExtractingTags) => flc!("stage_extracted_exif_tags", items_stats = items_stats, size_stats = size_stats),
+            ToolStage::BitRot(BitRotStage::VerifyingHashes) => flc!("stage_verified_hash", items_stats = items_stats, size_stats = size_stats),
+            ToolStage::ImageOptimizer(ImageOptimizerStage::CheckingImages) => flc!("stage_checked_images", items_stats = items_stats, size_stats = size_stats),
+            ToolStage::ImageOptimizer(ImageOptimizerStage::OptimizingImages) => flc!("stage_optimized_images", items_stats = items_stats, size_stats = size_stats),
             ToolStage::BrokenFilesChecking => flc!("stage_checked_files", items_stats = items_stats, size_stats = size_stats),
+            ToolStage::BrokenFilesRepairing => flc!("stage_repaired_files", items_stats = items_stats, size_stats = size_stats),
             ToolStage::BadExtensionsChecking => flc!("stage_checked_files_bad_extensions", items_stats = items_stats),
             ToolStage::BadNamesChecking => flc!("stage_checked_files_bad_names", items_stats = items_stats),
             ToolStage::EmptyFilesCheckingContent => flc!("stage_checking_empty_files_content", items_stats = items_stats, size_stats = size_stats),
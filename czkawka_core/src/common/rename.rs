@@ -0,0 +1,549 @@
+mod template;
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+pub use template::RenameTemplate;
+
+use crate::common::progress_stop_handler::check_if_stop_received;
+use crate::flc;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RenameIssue {
+    InvalidName,
+    // New name is the same as the old one, nothing to do
+    Unchanged,
+    // Other file from the same batch would get the same name
+    DuplicateTarget,
+    // File with new name already exists and is not renamed in this batch
+    TargetExists,
+    // New name could not be generated, e.g. template requires EXIF date which file does not have
+    Error(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct PlannedRename {
+    pub source: PathBuf,
+    pub new_name: String,
+    pub target: PathBuf,
+    pub issue: Option<RenameIssue>,
+}
+
+impl PlannedRename {
+    pub fn get_issue_message(&self) -> Option<String> {
+        let file = self.source.to_string_lossy();
+        let target = self.target.to_string_lossy();
+        Some(match self.issue.as_ref()? {
+            RenameIssue::InvalidName => flc!("core_rename_invalid_name", file = file, name = self.new_name.as_str()),
+            RenameIssue::Unchanged => return None,
+            RenameIssue::DuplicateTarget => flc!("core_rename_duplicate_target", file = file, target = target),
+            RenameIssue::TargetExists => flc!("core_rename_target_exists", file = file, target = target),
+            RenameIssue::Error(error) => error.clone(),
+        })
+    }
+}
+
+// Single filesystem operation saved in rollback log, reverting all of them in reverse order restores original names
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenameLogEntry {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RenameResult {
+    pub renamed: Vec<(PathBuf, PathBuf)>,
+    // Files which could not be renamed, with the same message as in `errors`
+    pub failed: Vec<(PathBuf, String)>,
+    pub errors: Vec<String>,
+}
+
+impl RenameResult {
+    fn add_failed(&mut self, source: &Path, error: String) {
+        self.failed.push((source.to_path_buf(), error.clone()));
+        self.errors.push(error);
+    }
+}
+
+/// Batch of renames, checked for collisions before anything is touched
+///
+/// Entries can be previewed with `get_entries`, only entries without issue are renamed by `execute`.
+/// Renames inside batch may depend on each other (`a -> b`, `b -> c` or even `a -> b`, `b -> a`),
+/// such files are first moved to temporary names, so no file is overwritten.
+#[derive(Clone, Debug, Default)]
+pub struct RenamePlan {
+    entries: Vec<PlannedRename>,
+}
+
+impl RenamePlan {
+    /// Plan with already generated names, e.g. proposed by bad names tool
+    pub fn from_names(items: impl IntoIterator<Item = (PathBuf, String)>) -> Self {
+        let entries = items.into_iter().map(|(source, new_name)| create_planned_rename(source, new_name, None)).collect();
+        Self::with_checked_conflicts(entries)
+    }
+
+    /// Plan with names generated from template, counter follows order of given paths
+    pub fn from_template(paths: impl IntoIterator<Item = PathBuf>, template: &RenameTemplate) -> Self {
+        let entries = paths
+            .into_iter()
+            .enumerate()
+            .map(|(idx, source)| match template.render(&source, idx) {
+                Ok(new_name) => create_planned_rename(source, new_name, None),
+                Err(e) => create_planned_rename(source, String::new(), Some(RenameIssue::Error(e))),
+            })
+            .collect();
+        Self::with_checked_conflicts(entries)
+    }
+
+    pub fn get_entries(&self) -> &[PlannedRename] {
+        &self.entries
+    }
+
+    pub fn get_ready_entries_count(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.issue.is_none()).count()
+    }
+
+    /// Messages for entries which will not be renamed, unchanged entries are skipped
+    pub fn get_issue_messages(&self) -> Vec<String> {
+        self.entries.iter().filter_map(PlannedRename::get_issue_message).collect()
+    }
+
+    fn with_checked_conflicts(mut entries: Vec<PlannedRename>) -> Self {
+        let mut target_counts: HashMap<PathBuf, usize> = HashMap::new();
+        for entry in entries.iter().filter(|entry| entry.issue.is_none()) {
+            *target_counts.entry(entry.target.clone()).or_default() += 1;
+        }
+        for entry in entries.iter_mut().filter(|entry| entry.issue.is_none()) {
+            if target_counts.get(&entry.target).copied().unwrap_or_default() > 1 {
+                entry.issue = Some(RenameIssue::DuplicateTarget);
+            }
+        }
+
+        // Existing target is not a problem only when it is renamed in the same batch, which may not happen
+        // when its own rename has issue, so this is repeated until nothing changes
+        let existing_targets: HashSet<PathBuf> = entries
+            .iter()
+            .filter(|entry| entry.issue.is_none() && entry.target.symlink_metadata().is_ok() && !is_same_file(&entry.source, &entry.target))
+            .map(|entry| entry.target.clone())
+            .collect();
+        loop {
+            let moved_sources: HashSet<PathBuf> = entries.iter().filter(|entry| entry.issue.is_none()).map(|entry| entry.source.clone()).collect();
+            let mut changed = false;
+            for entry in entries.iter_mut().filter(|entry| entry.issue.is_none()) {
+                if existing_targets.contains(&entry.target) && !moved_sources.contains(&entry.target) {
+                    entry.issue = Some(RenameIssue::TargetExists);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        Self { entries }
+    }
+
+    /// Renames all entries without issue, every filesystem operation is saved to rollback log before next one starts
+    pub fn execute(self, stop_flag: &Arc<AtomicBool>, rollback_log: Option<&Path>) -> RenameResult {
+        let mut result = RenameResult::default();
+
+        let mut log_writer = match rollback_log.map(RenameLogWriter::open).transpose() {
+            Ok(log_writer) => log_writer,
+            Err(e) => {
+                result.errors.push(e);
+                return result;
+            }
+        };
+
+        let ready_entries: Vec<PlannedRename> = self.entries.into_iter().filter(|entry| entry.issue.is_none()).collect();
+        let moved_sources: HashSet<PathBuf> = ready_entries.iter().map(|entry| entry.source.clone()).collect();
+        let (staged_entries, direct_entries): (Vec<_>, Vec<_>) = ready_entries.into_iter().partition(|entry| moved_sources.contains(&entry.target));
+
+        // Files whose target is still occupied by other file from batch are moved out of the way first
+        let mut staged = Vec::new();
+        for (idx, entry) in staged_entries.into_iter().enumerate() {
+            let temporary_path = get_temporary_path(&entry.source, idx);
+            match rename_and_log(&entry.source, &temporary_path, log_writer.as_mut()) {
+                Ok(()) => staged.push((entry, temporary_path)),
+                Err(e) => result.add_failed(&entry.source, e),
+            }
+        }
+
+        for entry in direct_entries {
+            if check_if_stop_received(stop_flag) {
+                break;
+            }
+            match rename_and_log(&entry.source, &entry.target, log_writer.as_mut()) {
+                Ok(()) => result.renamed.push((entry.source, entry.target)),
+                Err(e) => result.add_failed(&entry.source, e),
+            }
+        }
+
+        // Staged files are always finished, to not leave them with temporary names
+        for (entry, temporary_path) in staged {
+            match rename_and_log(&temporary_path, &entry.target, log_writer.as_mut()) {
+                Ok(()) => result.renamed.push((entry.source, entry.target)),
+                Err(e) => {
+                    result.add_failed(&entry.source, e);
+                    if let Err(e) = rename_and_log(&temporary_path, &entry.source, log_writer.as_mut()) {
+                        result.errors.push(e);
+                    }
+                }
+            }
+        }
+
+        if let Some(error) = log_writer.and_then(|log_writer| log_writer.error) {
+            result.errors.push(error);
+        }
+
+        info!("Renamed {} files, {} errors", result.renamed.len(), result.errors.len());
+        result
+    }
+}
+
+struct RenameLogWriter {
+    file: File,
+    path: PathBuf,
+    // Only first error is kept, renaming is not stopped, because files are already partially renamed
+    error: Option<String>,
+}
+
+impl RenameLogWriter {
+    fn open(path: &Path) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)
+            .map_err(|e| flc!("core_rename_cannot_write_log", file = path.to_string_lossy(), reason = e.to_string()))?;
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+            error: None,
+        })
+    }
+
+    fn write(&mut self, entry: &RenameLogEntry) {
+        let result = serde_json::to_string(entry)
+            .map_err(std::io::Error::other)
+            .and_then(|line| writeln!(self.file, "{line}"))
+            .and_then(|()| self.file.flush());
+        if let Err(e) = result
+            && self.error.is_none()
+        {
+            self.error = Some(flc!("core_rename_cannot_write_log", file = self.path.to_string_lossy(), reason = e.to_string()));
+        }
+    }
+}
+
+fn create_planned_rename(source: PathBuf, new_name: String, issue: Option<RenameIssue>) -> PlannedRename {
+    let (target, issue) = if issue.is_some() {
+        (source.clone(), issue)
+    } else if !is_valid_file_name(&new_name) {
+        (source.clone(), Some(RenameIssue::InvalidName))
+    } else {
+        let target = source.with_file_name(&new_name);
+        let issue = (target == source).then_some(RenameIssue::Unchanged);
+        (target, issue)
+    };
+
+    PlannedRename { source, new_name, target, issue }
+}
+
+pub fn is_valid_file_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\', '\0'])
+}
+
+/// Adds number before extension, `photo.jpg` becomes `photo_1.jpg`
+pub(crate) fn add_name_suffix(name: &str, number: usize) -> String {
+    match name.rfind('.') {
+        Some(idx) if idx > 0 => {
+            let (base, extension) = name.split_at(idx);
+            format!("{base}_{number}{extension}")
+        }
+        _ => format!("{name}_{number}"),
+    }
+}
+
+// On case-insensitive filesystems, changing only case of name points target to the same file
+#[cfg(unix)]
+fn is_same_file(first: &Path, second: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (first.symlink_metadata(), second.symlink_metadata()) {
+        (Ok(first), Ok(second)) => first.dev() == second.dev() && first.ino() == second.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_first: &Path, _second: &Path) -> bool {
+    false
+}
+
+fn get_temporary_path(source: &Path, idx: usize) -> PathBuf {
+    let file_name = source.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let mut number = idx;
+    loop {
+        let temporary_path = source.with_file_name(format!(".czkawka_rename_{number}_{file_name}"));
+        if temporary_path.symlink_metadata().is_err() {
+            return temporary_path;
+        }
+        number += 1;
+    }
+}
+
+/// Renames single file, refusing to overwrite existing one
+pub fn rename_file(source: &Path, target: &Path) -> Result<(), String> {
+    if target.symlink_metadata().is_ok() && !is_same_file(source, target) {
+        return Err(flc!("core_rename_target_exists", file = source.to_string_lossy(), target = target.to_string_lossy()));
+    }
+    fs::rename(source, target).map_err(|e| {
+        flc!(
+            "core_rename_failed",
+            file = source.to_string_lossy(),
+            target = target.to_string_lossy(),
+            reason = e.to_string()
+        )
+    })?;
+    debug!("Renamed {} to {}", source.to_string_lossy(), target.to_string_lossy());
+    Ok(())
+}
+
+fn rename_and_log(source: &Path, target: &Path, log_writer: Option<&mut RenameLogWriter>) -> Result<(), String> {
+    rename_file(source, target)?;
+    if let Some(log_writer) = log_writer {
+        log_writer.write(&RenameLogEntry {
+            from: source.to_path_buf(),
+            to: target.to_path_buf(),
+        });
+    }
+    Ok(())
+}
+
+pub fn load_rename_log(rollback_log: &Path) -> Result<Vec<RenameLogEntry>, String> {
+    let error = |reason: String| flc!("core_rename_cannot_read_log", file = rollback_log.to_string_lossy(), reason = reason);
+
+    let file = File::open(rollback_log).map_err(|e| error(e.to_string()))?;
+    BufReader::new(file)
+        .lines()
+        .filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|line| {
+            let line = line.map_err(|e| error(e.to_string()))?;
+            serde_json::from_str(&line).map_err(|e| error(e.to_string()))
+        })
+        .collect()
+}
+
+/// Reverts all renames saved in rollback log, starting from the last one
+pub fn rollback_renames(rollback_log: &Path, stop_flag: &Arc<AtomicBool>) -> RenameResult {
+    let mut result = RenameResult::default();
+    let entries = match load_rename_log(rollback_log) {
+        Ok(entries) => entries,
+        Err(e) => {
+            result.errors.push(e);
+            return result;
+        }
+    };
+
+    for entry in entries.into_iter().rev() {
+        if check_if_stop_received(stop_flag) {
+            break;
+        }
+        match rename_file(&entry.to, &entry.from) {
+            Ok(()) => result.renamed.push((entry.to, entry.from)),
+            Err(e) => result.add_failed(&entry.to, e),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+
+    use tempfile::TempDir;
+
+    use super::template::{format_timestamp, parse_exif_date};
+    use super::*;
+
+    fn create_files(dir: &Path, names: &[&str]) {
+        for name in names {
+            fs::write(dir.join(name), name).unwrap();
+        }
+    }
+
+    fn read_dir_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().file_name().to_string_lossy().to_string()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    #[expect(clippy::literal_string_with_formatting_args)] // Rename templates use the same braces as format strings
+    fn test_template_rendering() {
+        let temp_dir = TempDir::new().unwrap();
+        let parent = temp_dir.path().join("Holidays");
+        fs::create_dir(&parent).unwrap();
+        let path = parent.join("IMG_001.JPG");
+        fs::write(&path, "").unwrap();
+
+        let template = RenameTemplate::new("{parent}_{counter:3}_{stem}{.ext}").unwrap().with_counter(10, 5);
+        assert_eq!(template.render(&path, 0).unwrap(), "Holidays_010_IMG_001.JPG");
+        assert_eq!(template.render(&path, 2).unwrap(), "Holidays_020_IMG_001.JPG");
+
+        let template = RenameTemplate::new("{{{name}}}-{ext}{.ext}").unwrap();
+        assert_eq!(template.render(Path::new("/a/file"), 0).unwrap(), "{file}-");
+
+        let template = RenameTemplate::new("{stem}.{ext}").unwrap().with_find_replace(r"IMG_(\d+)", "photo-$1").unwrap();
+        assert_eq!(template.render(&path, 0).unwrap(), "photo-001.JPG");
+
+        let mtime = RenameTemplate::new("{mtime:date}").unwrap().render(&path, 0).unwrap();
+        assert_eq!(mtime.len(), 10);
+
+        let exif = RenameTemplate::new("{exif_date}{.ext}").unwrap();
+        exif.render(&path, 0).unwrap_err();
+    }
+
+    #[test]
+    fn test_template_errors() {
+        RenameTemplate::new("{stem").unwrap_err();
+        RenameTemplate::new("{unknown}").unwrap_err();
+        RenameTemplate::new("{counter:abc}").unwrap_err();
+        RenameTemplate::new("{stem}").unwrap().with_find_replace("(", "").unwrap_err();
+    }
+
+    #[test]
+    fn test_date_formatting() {
+        assert_eq!(format_timestamp(0, false), "1970-01-01_00-00-00");
+        assert_eq!(format_timestamp(951_782_400, true), "2000-02-29");
+        assert_eq!(format_timestamp(1_700_000_000, false), "2023-11-14_22-13-20");
+        assert_eq!(parse_exif_date("2021:07:04 18:30:05\0"), Some("2021-07-04_18-30-05".to_string()));
+        assert_eq!(parse_exif_date("0000:00:00 00:00:00"), None);
+        assert_eq!(parse_exif_date("2021-07-04"), None);
+    }
+
+    #[test]
+    fn test_plan_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        create_files(dir, &["a.txt", "b.txt", "c.txt", "d.txt", "existing.txt"]);
+
+        let plan = RenamePlan::from_names(vec![
+            (dir.join("a.txt"), "same.txt".to_string()),
+            (dir.join("b.txt"), "same.txt".to_string()),
+            (dir.join("c.txt"), "existing.txt".to_string()),
+            (dir.join("d.txt"), "d.txt".to_string()),
+            (dir.join("existing.txt"), "bad/name".to_string()),
+        ]);
+        let issues: Vec<_> = plan.get_entries().iter().map(|entry| entry.issue.clone()).collect();
+        assert_eq!(
+            issues,
+            vec![
+                Some(RenameIssue::DuplicateTarget),
+                Some(RenameIssue::DuplicateTarget),
+                // `existing.txt` would be free, but its own rename is invalid
+                Some(RenameIssue::TargetExists),
+                Some(RenameIssue::Unchanged),
+                Some(RenameIssue::InvalidName),
+            ]
+        );
+        assert_eq!(plan.get_ready_entries_count(), 0);
+        assert_eq!(plan.get_issue_messages().len(), 4);
+
+        let result = plan.execute(&Arc::new(AtomicBool::new(false)), None);
+        assert!(result.renamed.is_empty());
+        assert_eq!(read_dir_names(dir), vec!["a.txt", "b.txt", "c.txt", "d.txt", "existing.txt"]);
+    }
+
+    #[test]
+    fn test_execute_chain_swap_and_rollback() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("files");
+        fs::create_dir(&dir).unwrap();
+        create_files(&dir, &["1.txt", "2.txt", "3.txt", "x.txt", "y.txt"]);
+        let log = temp_dir.path().join("rename_log.jsonl");
+
+        let plan = RenamePlan::from_names(vec![
+            // Chain
+            (dir.join("1.txt"), "2.txt".to_string()),
+            (dir.join("2.txt"), "3.txt".to_string()),
+            (dir.join("3.txt"), "4.txt".to_string()),
+            // Swap
+            (dir.join("x.txt"), "y.txt".to_string()),
+            (dir.join("y.txt"), "x.txt".to_string()),
+        ]);
+        assert_eq!(plan.get_ready_entries_count(), 5);
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let result = plan.execute(&stop_flag, Some(&log));
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.renamed.len(), 5);
+        assert_eq!(read_dir_names(&dir), vec!["2.txt", "3.txt", "4.txt", "x.txt", "y.txt"]);
+        assert_eq!(fs::read_to_string(dir.join("4.txt")).unwrap(), "3.txt");
+        assert_eq!(fs::read_to_string(dir.join("2.txt")).unwrap(), "1.txt");
+        assert_eq!(fs::read_to_string(dir.join("x.txt")).unwrap(), "y.txt");
+
+        let result = rollback_renames(&log, &stop_flag);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(read_dir_names(&dir), vec!["1.txt", "2.txt", "3.txt", "x.txt", "y.txt"]);
+        for name in ["1.txt", "2.txt", "3.txt", "x.txt", "y.txt"] {
+            assert_eq!(fs::read_to_string(dir.join(name)).unwrap(), name);
+        }
+    }
+
+    #[test]
+    fn test_execute_from_template() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        create_files(dir, &["b.jpg", "a.png", "photo_2.jpg"]);
+
+        let template = RenameTemplate::new("photo_{counter}{.ext}").unwrap();
+        let plan = RenamePlan::from_template(vec![dir.join("a.png"), dir.join("b.jpg"), dir.join("photo_2.jpg")], &template);
+        let new_names: Vec<_> = plan.get_entries().iter().map(|entry| entry.new_name.as_str()).collect();
+        assert_eq!(new_names, vec!["photo_1.png", "photo_2.jpg", "photo_3.jpg"]);
+
+        let result = plan.execute(&Arc::new(AtomicBool::new(false)), None);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(read_dir_names(dir), vec!["photo_1.png", "photo_2.jpg", "photo_3.jpg"]);
+    }
+
+    #[test]
+    fn test_execute_reports_failed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        create_files(dir, &["a.txt"]);
+
+        let plan = RenamePlan::from_names(vec![(dir.join("a.txt"), "b.txt".to_string()), (dir.join("missing.txt"), "c.txt".to_string())]);
+        assert_eq!(plan.get_ready_entries_count(), 2);
+
+        let result = plan.execute(&Arc::new(AtomicBool::new(false)), None);
+        assert_eq!(result.renamed, vec![(dir.join("a.txt"), dir.join("b.txt"))]);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0, dir.join("missing.txt"));
+        assert_eq!(result.errors, vec![result.failed[0].1.clone()]);
+    }
+
+    #[test]
+    fn test_rename_file_refuses_overwrite() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        create_files(dir, &["a.txt", "b.txt"]);
+
+        rename_file(&dir.join("a.txt"), &dir.join("b.txt")).unwrap_err();
+        rename_file(&dir.join("a.txt"), &dir.join("c.txt")).unwrap();
+        assert_eq!(read_dir_names(dir), vec!["b.txt", "c.txt"]);
+
+        assert!(is_valid_file_name("file.txt"));
+        assert!(!is_valid_file_name(".."));
+        assert!(!is_valid_file_name("a/b"));
+    }
+}
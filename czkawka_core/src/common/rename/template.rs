@@ -0,0 +1,252 @@
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use little_exif::exif_tag::ExifTag;
+use little_exif::ifd::ExifTagGroup;
+use little_exif::metadata::Metadata;
+use regex::Regex;
+
+use crate::flc;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TemplatePart {
+    Text(String),
+    Name,
+    Stem,
+    Extension,
+    // Extension with leading dot, empty for files without extension
+    DotExtension,
+    Parent,
+    Counter { width: usize },
+    ModifiedDate { only_date: bool },
+    ExifDate { only_date: bool },
+}
+
+/// Template used to generate new file names
+///
+/// Supported tokens:
+/// - `{name}` - original file name
+/// - `{stem}` - original file name without extension
+/// - `{ext}` - original extension, `{.ext}` - extension with dot or nothing if file has no extension
+/// - `{parent}` - name of parent directory
+/// - `{counter}`, `{counter:N}` - counter padded with zeros to N digits
+/// - `{mtime}`, `{mtime:date}` - modification date in UTC, as `YYYY-MM-DD_HH-MM-SS` or `YYYY-MM-DD`
+/// - `{exif_date}`, `{exif_date:date}` - EXIF original date, in the same format as `{mtime}`
+///
+/// Braces can be escaped as `{{` and `}}`
+#[derive(Clone, Debug)]
+pub struct RenameTemplate {
+    parts: Vec<TemplatePart>,
+    find_replace: Option<(Regex, String)>,
+    counter_start: u64,
+    counter_step: u64,
+}
+
+impl RenameTemplate {
+    pub fn new(template: &str) -> Result<Self, String> {
+        Ok(Self {
+            parts: parse_template(template)?,
+            find_replace: None,
+            counter_start: 1,
+            counter_step: 1,
+        })
+    }
+
+    #[must_use]
+    pub fn with_counter(mut self, start: u64, step: u64) -> Self {
+        self.counter_start = start;
+        self.counter_step = step;
+        self
+    }
+
+    /// Regex is applied to name generated from template, replacement may use capture groups like `$1`
+    pub fn with_find_replace(mut self, find: &str, replace: &str) -> Result<Self, String> {
+        let regex = Regex::new(find).map_err(|e| flc!("core_rename_invalid_regex", regex = find, reason = e.to_string()))?;
+        self.find_replace = Some((regex, replace.to_string()));
+        Ok(self)
+    }
+
+    /// Generates new name for file, `index` is position of file in renamed batch and is used by counter
+    pub fn render(&self, path: &Path, index: usize) -> Result<String, String> {
+        let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        let extension = path.extension().map(|extension| extension.to_string_lossy().to_string());
+
+        let mut new_name = String::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Text(text) => new_name.push_str(text),
+                TemplatePart::Name => new_name.push_str(&path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()),
+                TemplatePart::Stem => new_name.push_str(&stem),
+                TemplatePart::Extension => new_name.push_str(extension.as_deref().unwrap_or_default()),
+                TemplatePart::DotExtension => {
+                    if let Some(extension) = &extension {
+                        new_name.push('.');
+                        new_name.push_str(extension);
+                    }
+                }
+                TemplatePart::Parent => new_name.push_str(
+                    &path
+                        .parent()
+                        .and_then(Path::file_name)
+                        .map(|parent| parent.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                ),
+                TemplatePart::Counter { width } => {
+                    let counter = self.counter_start.saturating_add(self.counter_step.saturating_mul(index as u64));
+                    new_name.push_str(&format!("{counter:0width$}"));
+                }
+                TemplatePart::ModifiedDate { only_date } => {
+                    let modified = fs::metadata(path)
+                        .and_then(|metadata| metadata.modified())
+                        .map_err(|e| flc!("core_rename_cannot_read_modification_date", file = path.to_string_lossy(), reason = e.to_string()))?;
+                    let seconds = modified.duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or_default();
+                    new_name.push_str(&format_timestamp(seconds, *only_date));
+                }
+                TemplatePart::ExifDate { only_date } => {
+                    let date = read_exif_date(path).ok_or_else(|| flc!("core_rename_missing_exif_date", file = path.to_string_lossy()))?;
+                    new_name.push_str(if *only_date { date.get(..10).unwrap_or(&date) } else { &date });
+                }
+            }
+        }
+
+        if let Some((regex, replace)) = &self.find_replace {
+            new_name = regex.replace_all(&new_name, replace.as_str()).to_string();
+        }
+
+        Ok(new_name)
+    }
+}
+
+fn parse_template(template: &str) -> Result<Vec<TemplatePart>, String> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut token = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => token.push(c),
+                        None => return Err(flc!("core_rename_template_unclosed_brace", template = template)),
+                    }
+                }
+                if !text.is_empty() {
+                    parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                }
+                parts.push(parse_token(&token)?);
+            }
+            _ => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(TemplatePart::Text(text));
+    }
+
+    Ok(parts)
+}
+
+fn parse_token(token: &str) -> Result<TemplatePart, String> {
+    let (name, argument) = match token.split_once(':') {
+        Some((name, argument)) => (name, Some(argument)),
+        None => (token, None),
+    };
+
+    let part = match (name, argument) {
+        ("name", None) => TemplatePart::Name,
+        ("stem", None) => TemplatePart::Stem,
+        ("ext", None) => TemplatePart::Extension,
+        (".ext", None) => TemplatePart::DotExtension,
+        ("parent", None) => TemplatePart::Parent,
+        ("counter", None) => TemplatePart::Counter { width: 1 },
+        ("counter", Some(width)) => match width.parse::<usize>() {
+            Ok(width) if width <= 20 => TemplatePart::Counter { width },
+            _ => return Err(flc!("core_rename_template_unknown_token", token = token)),
+        },
+        ("mtime", None) => TemplatePart::ModifiedDate { only_date: false },
+        ("mtime", Some("date")) => TemplatePart::ModifiedDate { only_date: true },
+        ("exif_date", None) => TemplatePart::ExifDate { only_date: false },
+        ("exif_date", Some("date")) => TemplatePart::ExifDate { only_date: true },
+        _ => return Err(flc!("core_rename_template_unknown_token", token = token)),
+    };
+    Ok(part)
+}
+
+// Formats UTC date without pulling whole date library, algorithm from http://howardhinnant.github.io/date_algorithms.html
+pub(crate) fn format_timestamp(seconds: u64, only_date: bool) -> String {
+    let days = seconds / 86_400;
+    let seconds_of_day = seconds % 86_400;
+
+    let shifted_days = days + 719_468;
+    let era = shifted_days / 146_097;
+    let day_of_era = shifted_days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    if only_date {
+        format!("{year:04}-{month:02}-{day:02}")
+    } else {
+        format!(
+            "{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}",
+            seconds_of_day / 3600,
+            seconds_of_day % 3600 / 60,
+            seconds_of_day % 60
+        )
+    }
+}
+
+// EXIF stores date as `YYYY:MM:DD HH:MM:SS`, result uses the same format as modification date
+fn read_exif_date(path: &Path) -> Option<String> {
+    let date = std::panic::catch_unwind(|| {
+        let metadata = Metadata::new_from_path(path).ok()?;
+        match metadata.get_tag_by_hex(0x9003, Some(ExifTagGroup::EXIF)).next() {
+            Some(ExifTag::DateTimeOriginal(date)) => Some(date.clone()),
+            _ => None,
+        }
+    })
+    .ok()??;
+
+    parse_exif_date(&date)
+}
+
+pub(crate) fn parse_exif_date(date: &str) -> Option<String> {
+    let date = date.trim_end_matches('\0').trim();
+    let bytes = date.as_bytes();
+    let is_valid = bytes.len() >= 19
+        && bytes.iter().take(19).enumerate().all(|(idx, byte)| match idx {
+            4 | 7 | 13 | 16 => *byte == b':',
+            10 => *byte == b' ',
+            _ => byte.is_ascii_digit(),
+        });
+    // Cameras without set clock write zeros
+    if !is_valid || date.starts_with("0000") {
+        return None;
+    }
+
+    let date: String = date
+        .chars()
+        .take(19)
+        .enumerate()
+        .map(|(idx, c)| match idx {
+            10 => '_',
+            _ if c == ':' => '-',
+            _ => c,
+        })
+        .collect();
+    Some(date)
+}
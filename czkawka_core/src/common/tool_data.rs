@@ -49,6 +49,8 @@ pub enum DeleteMethod {
     AllExceptSmallest,
     OneBiggest,
     OneSmallest,
+    AllExceptBestQuality, // Tools without quality information keep biggest file
+    AllExceptMostTracks,  // Keeps file with most audio/subtitle languages, tools without such information keep biggest file
 }
 
 impl CommonToolData {
@@ -115,6 +115,11 @@ pub trait PrintResults: CommonData {
     }
 }
 
+// Implemented only by tools, which results can be reviewed visually
+pub trait HtmlReport {
+    fn save_results_to_html_report(&self, folder: &Path) -> std::io::Result<()>;
+}
+
 pub trait DeletingItems {
     #[must_use]
     fn delete_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus;
@@ -25,6 +25,30 @@ pub struct VideoMetadata {
     pub width: Option<u32>,
     pub height: Option<u32>,
     pub duration: Option<f64>,
+    pub audio_streams: Vec<MediaStream>,
+    pub subtitle_streams: Vec<MediaStream>,
+}
+
+// Audio or subtitle stream embedded in video file
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct MediaStream {
+    pub codec: Option<String>,
+    // ISO 639-2 code, e.g. "eng", None when stream has no language or it is undetermined
+    pub language: Option<String>,
+}
+
+impl MediaStream {
+    // E.g. "eng (aac), pol (ac3), ? (opus)"
+    pub fn format_streams(streams: &[Self]) -> String {
+        streams
+            .iter()
+            .map(|stream| match &stream.codec {
+                Some(codec) => format!("{} ({codec})", stream.language.as_deref().unwrap_or("?")),
+                None => stream.language.clone().unwrap_or_else(|| "?".to_string()),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
 }
 
 impl VideoMetadata {
@@ -39,6 +63,18 @@ impl VideoMetadata {
             metadata.duration = Some(d);
         }
 
+        for stream in &info.streams {
+            let media_stream = || MediaStream {
+                codec: stream.codec_name.clone(),
+                language: stream.get_language().map(str::to_string),
+            };
+            match stream.codec_type.as_deref() {
+                Some("audio") => metadata.audio_streams.push(media_stream()),
+                Some("subtitle") => metadata.subtitle_streams.push(media_stream()),
+                _ => {}
+            }
+        }
+
         if let Some(stream) = info.streams.into_iter().find(|s| s.codec_type.as_deref() == Some("video")) {
             metadata.codec = stream.codec_name;
 
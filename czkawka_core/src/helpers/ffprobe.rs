@@ -253,6 +253,7 @@ pub struct StreamTags {
     pub encoder: Option<String>,
     pub timecode: Option<String>,
     pub reel_name: Option<String>,
+    pub title: Option<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -271,6 +272,15 @@ pub struct Format {
     pub tags: Option<FormatTags>,
 }
 
+impl Stream {
+    /// Get the language of the stream, e.g. "eng".
+    ///
+    /// Will return [`None`] if no language is set, or if it is set to "und" (undetermined).
+    pub fn get_language(&self) -> Option<&str> {
+        self.tags.as_ref()?.language.as_deref().filter(|language| !language.is_empty() && *language != "und")
+    }
+}
+
 impl Format {
     /// Get the duration parsed into a [`std::time::Duration`].
     pub fn try_get_duration(&self) -> Option<Result<std::time::Duration, std::num::ParseFloatError>> {
@@ -1,5 +1,6 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashSet};
 use std::mem;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
@@ -14,9 +15,13 @@ use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
 use crate::common::model::{FileEntry, ToolType, WorkContinueStatus};
 use crate::common::progress_data::{ProgressData, ToolStage};
 use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
+use crate::common::rename::{RenamePlan, add_name_suffix};
 use crate::common::tool_data::CommonToolData;
 use crate::tools::bad_extensions::workarounds::{DISABLED_EXTENSIONS, WORKAROUNDS};
-use crate::tools::bad_extensions::{BadExtensions, BadExtensionsParameters, BadFileEntry, Info};
+use crate::tools::bad_extensions::{
+    BadExtensions, BadExtensionsFixParams, BadExtensionsParameters, BadFileEntry, CustomDefinitions, CustomSignature, ExtensionCollisionMode, ExtensionFixMode, ExtensionFixReport,
+    Info,
+};
 
 // Text longer than 10 characters is not considered as extension
 const MAX_EXTENSION_LENGTH: usize = 10;
@@ -29,6 +34,31 @@ impl BadExtensions {
             files_to_check: Default::default(),
             bad_extensions_files: Default::default(),
             params,
+            custom_definitions: Default::default(),
+            fix_report: None,
+        }
+    }
+
+    pub(crate) fn load_custom_definitions(&mut self) -> WorkContinueStatus {
+        let Some(path) = &self.params.custom_definitions_path else {
+            return WorkContinueStatus::Continue;
+        };
+
+        match CustomDefinitions::load_from_file(path) {
+            Ok(custom_definitions) => {
+                debug!(
+                    "Loaded {} custom signatures and {} aliases from {}",
+                    custom_definitions.signatures.len(),
+                    custom_definitions.aliases.len(),
+                    path.to_string_lossy()
+                );
+                self.custom_definitions = custom_definitions;
+                WorkContinueStatus::Continue
+            }
+            Err(e) => {
+                self.common_data.text_messages.critical = Some(e);
+                WorkContinueStatus::Stop
+            }
         }
     }
 
@@ -68,6 +98,11 @@ impl BadExtensions {
         for (proper, found) in WORKAROUNDS {
             workarounds.entry(found).or_default().push(proper);
         }
+        for (proper, accepted) in &self.custom_definitions.aliases {
+            for found in accepted {
+                workarounds.entry(found.as_str()).or_default().push(proper.as_str());
+            }
+        }
 
         self.bad_extensions_files = self.verify_extensions(files_to_check, progress_handler.items_counter(), stop_flag, &workarounds);
 
@@ -86,17 +121,26 @@ impl BadExtensions {
     }
 
     fn verify_extension_of_file(&self, file_entry: FileEntry, workarounds: &IndexMap<&str, Vec<&str>>) -> Option<BadFileEntry> {
+        // User signatures are checked first, because in-house formats are often containers recognized by infer as e.g. zip
+        let custom_signature = self.custom_definitions.find_matching_signature(&file_entry.path);
+
         // Check what exactly content file contains
-        let kind = match infer::get_from_path(&file_entry.path) {
-            Ok(k) => k?,
-            Err(_) => return None,
+        let proper_extension = if let Some(custom_signature) = custom_signature {
+            custom_signature.get_proper_extension()
+        } else {
+            match infer::get_from_path(&file_entry.path) {
+                Ok(k) => k?.extension(),
+                Err(_) => return None,
+            }
         };
-        let proper_extension = kind.extension();
 
         let current_extension = Self::get_and_validate_extension(&file_entry, proper_extension)?;
 
         // Check for all extensions that file can use(not sure if it is worth to do it)
-        let (mut all_available_extensions, valid_extensions) = Self::check_for_all_extensions_that_file_can_use(workarounds, &current_extension, proper_extension);
+        let (mut all_available_extensions, valid_extensions) = match custom_signature {
+            Some(custom_signature) => Self::get_extensions_of_custom_signature(workarounds, &current_extension, custom_signature),
+            None => Self::check_for_all_extensions_that_file_can_use(workarounds, &current_extension, proper_extension),
+        };
 
         if all_available_extensions.is_empty() {
             // Not found any extension
@@ -177,6 +221,20 @@ impl BadExtensions {
             }
         }
 
+        Self::apply_workarounds(workarounds, current_extension, proper_extension, all_available_extensions)
+    }
+
+    fn get_extensions_of_custom_signature(workarounds: &IndexMap<&str, Vec<&str>>, current_extension: &str, custom_signature: &CustomSignature) -> (BTreeSet<String>, String) {
+        let all_available_extensions = custom_signature.extensions.iter().cloned().collect();
+        Self::apply_workarounds(workarounds, current_extension, custom_signature.get_proper_extension(), all_available_extensions)
+    }
+
+    fn apply_workarounds(
+        workarounds: &IndexMap<&str, Vec<&str>>,
+        current_extension: &str,
+        proper_extension: &str,
+        mut all_available_extensions: BTreeSet<String>,
+    ) -> (BTreeSet<String>, String) {
         // Workarounds:
         if !current_extension.is_empty()
             && let Some(vec_pre) = workarounds.get(current_extension)
@@ -199,29 +257,65 @@ impl BadExtensions {
     }
 
     #[fun_time(message = "fix_bad_extensions", level = "debug")]
-    pub fn fix_bad_extensions(&mut self, _fix_params: super::BadExtensionsFixParams, stop_flag: &Arc<AtomicBool>) {
-        let warnings: Vec<_> = mem::take(&mut self.bad_extensions_files)
-            .into_par_iter()
-            .map(|entry| {
-                if check_if_stop_received(stop_flag) {
-                    return None;
-                }
-
-                let new_path = entry.path.with_extension(&entry.proper_extension);
+    pub fn fix_bad_extensions(&mut self, fix_params: BadExtensionsFixParams, stop_flag: &Arc<AtomicBool>) {
+        let mut used_targets = HashSet::new();
+        let plan = RenamePlan::from_names(self.bad_extensions_files.iter().map(|entry| {
+            let new_name = get_fixed_name(&entry.path, &entry.proper_extension, fix_params.fix_mode);
+            let new_name = match fix_params.collision_mode {
+                ExtensionCollisionMode::Skip => new_name,
+                ExtensionCollisionMode::AddNumber => get_free_name(&entry.path, &new_name, &mut used_targets),
+            };
+            (entry.path.clone(), new_name)
+        }));
+        self.common_data.text_messages.warnings.extend(plan.get_issue_messages());
+
+        let renamed: Vec<(PathBuf, PathBuf)> = if fix_params.dry_run {
+            plan.get_entries()
+                .iter()
+                .filter(|entry| entry.issue.is_none())
+                .map(|entry| (entry.source.clone(), entry.target.clone()))
+                .collect()
+        } else {
+            let result = plan.execute(stop_flag, fix_params.rollback_log.as_deref());
+            self.common_data.text_messages.warnings.extend(result.errors);
+            result.renamed
+        };
 
-                if new_path.exists() {
-                    return Some(Some(format!("Cannot rename {:?} to {:?}: target file already exists", entry.path, new_path)));
-                }
+        let not_renamed = self.bad_extensions_files.len() - renamed.len();
+        if !fix_params.dry_run {
+            let renamed_paths: HashSet<&PathBuf> = renamed.iter().map(|(source, _)| source).collect();
+            self.bad_extensions_files.retain(|entry| !renamed_paths.contains(&entry.path));
+        }
+        debug!(
+            "Fixed extensions of {} files (dry run: {}), {not_renamed} files not renamed",
+            renamed.len(),
+            fix_params.dry_run
+        );
+
+        self.fix_report = Some(ExtensionFixReport {
+            dry_run: fix_params.dry_run,
+            renamed,
+            not_renamed,
+        });
+    }
+}
 
-                match std::fs::rename(&entry.path, &new_path) {
-                    Ok(()) => Some(None),
-                    Err(e) => Some(Some(format!("Failed to rename {:?} to {:?}: {}", entry.path, new_path, e))),
-                }
-            })
-            .while_some()
-            .flatten()
-            .collect();
+fn get_fixed_name(path: &Path, proper_extension: &str, fix_mode: ExtensionFixMode) -> String {
+    let name_path = match fix_mode {
+        ExtensionFixMode::Replace => path.with_extension(proper_extension),
+        ExtensionFixMode::Append => path.with_file_name(format!("{}.{proper_extension}", path.file_name().unwrap_or_default().to_string_lossy())),
+    };
+    name_path.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
 
-        self.common_data.text_messages.warnings.extend(warnings);
+// Name is free when no file uses it and no other file from batch was already assigned to it
+fn get_free_name(path: &Path, new_name: &str, used_targets: &mut HashSet<PathBuf>) -> String {
+    let mut candidate = new_name.to_string();
+    let mut number = 1;
+    while used_targets.contains(&path.with_file_name(&candidate)) || path.with_file_name(&candidate).symlink_metadata().is_ok() {
+        candidate = add_name_suffix(new_name, number);
+        number += 1;
     }
+    used_targets.insert(path.with_file_name(&candidate));
+    candidate
 }
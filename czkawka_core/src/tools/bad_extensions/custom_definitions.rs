@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::flc;
+
+// Header of every checked file is read up to the furthest signature end, so it must stay small
+const MAX_SIGNATURE_END: usize = 4 * 1024 * 1024;
+
+/// User defined signatures and extension aliases, loaded from TOML file:
+///
+/// ```toml
+/// # First extension is the proper one, others are also accepted
+/// [[signature]]
+/// extensions = ["myf", "myformat"]
+/// offset = 0
+/// bytes = "4D 59 46 00"
+///
+/// # Files recognized as zip may also use `pkg` and `bundle` extensions
+/// [aliases]
+/// zip = ["pkg", "bundle"]
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CustomDefinitions {
+    pub signatures: Vec<CustomSignature>,
+    // Extension of real content mapped to extensions which are also accepted for such content
+    pub aliases: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CustomSignature {
+    pub extensions: Vec<String>,
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
+
+impl CustomSignature {
+    pub fn get_proper_extension(&self) -> &str {
+        self.extensions.first().map(String::as_str).unwrap_or_default()
+    }
+
+    fn get_end(&self) -> Option<usize> {
+        self.offset.checked_add(self.bytes.len())
+    }
+
+    fn matches(&self, header: &[u8]) -> bool {
+        self.get_end().and_then(|end| header.get(self.offset..end)) == Some(self.bytes.as_slice())
+    }
+}
+
+#[derive(Deserialize)]
+struct RawCustomDefinitions {
+    #[serde(default, rename = "signature")]
+    signatures: Vec<RawCustomSignature>,
+    #[serde(default)]
+    aliases: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct RawCustomSignature {
+    extensions: Vec<String>,
+    #[serde(default)]
+    offset: usize,
+    bytes: String,
+}
+
+impl CustomDefinitions {
+    pub fn load_from_file(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| flc!("core_custom_signatures_cannot_read", file = path.to_string_lossy(), reason = e.to_string()))?;
+        Self::from_toml(&content).map_err(|reason| flc!("core_custom_signatures_cannot_read", file = path.to_string_lossy(), reason = reason))
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        let raw: RawCustomDefinitions = toml::from_str(content).map_err(|e| e.to_string())?;
+
+        let signatures = raw
+            .signatures
+            .into_iter()
+            .enumerate()
+            .map(|(idx, signature)| {
+                let index = idx + 1;
+                let extensions: Vec<String> = signature
+                    .extensions
+                    .iter()
+                    .map(|extension| normalize_extension(extension))
+                    .filter(|e| !e.is_empty())
+                    .collect();
+                if extensions.is_empty() {
+                    return Err(flc!("core_custom_signature_without_extension", index = index));
+                }
+                let bytes = parse_hex_bytes(&signature.bytes).ok_or_else(|| flc!("core_custom_signature_invalid_bytes", index = index, bytes = signature.bytes.as_str()))?;
+                let custom_signature = CustomSignature {
+                    extensions,
+                    offset: signature.offset,
+                    bytes,
+                };
+                if custom_signature.get_end().is_none_or(|end| end > MAX_SIGNATURE_END) {
+                    let max_end = MAX_SIGNATURE_END;
+                    return Err(flc!("core_custom_signature_too_far", index = index, max = max_end));
+                }
+                Ok(custom_signature)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let aliases = raw
+            .aliases
+            .into_iter()
+            .map(|(proper, accepted)| (normalize_extension(&proper), accepted.iter().map(|extension| normalize_extension(extension)).collect()))
+            .collect();
+
+        Ok(Self { signatures, aliases })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty() && self.aliases.is_empty()
+    }
+
+    /// Finds first signature matching start of file, IO errors are treated as no match, so infer can still try
+    pub(crate) fn find_matching_signature(&self, path: &Path) -> Option<&CustomSignature> {
+        let header_length = self.signatures.iter().filter_map(CustomSignature::get_end).max()?.min(MAX_SIGNATURE_END);
+
+        // Buffer grows only up to real file size, most files are much smaller than furthest signature end
+        let mut header = Vec::new();
+        File::open(path).ok()?.take(header_length as u64).read_to_end(&mut header).ok()?;
+
+        self.signatures.iter().find(|signature| signature.matches(&header))
+    }
+}
+
+fn normalize_extension(extension: &str) -> String {
+    extension.trim().trim_start_matches('.').to_lowercase()
+}
+
+// Accepts "4D 5A 90", "4d5a90" and "0x4D 0x5A" forms
+fn parse_hex_bytes(bytes: &str) -> Option<Vec<u8>> {
+    let hex: String = bytes
+        .split_whitespace()
+        .map(|part| part.strip_prefix("0x").or_else(|| part.strip_prefix("0X")).unwrap_or(part))
+        .collect();
+    if hex.is_empty() || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|idx| hex.get(idx..idx + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+        .collect()
+}
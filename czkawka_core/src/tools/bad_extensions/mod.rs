@@ -1,4 +1,5 @@
 pub mod core;
+mod custom_definitions;
 #[cfg(test)]
 mod tests;
 pub mod traits;
@@ -7,8 +8,9 @@ mod workarounds;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+pub use self::custom_definitions::{CustomDefinitions, CustomSignature};
 use crate::common::model::FileEntry;
 use crate::common::tool_data::CommonToolData;
 use crate::common::traits::ResultEntry;
@@ -41,18 +43,54 @@ pub struct Info {
     pub scanning_time: Duration,
 }
 
-#[derive(Clone, Debug, Default, Copy)]
-pub struct BadExtensionsFixParams {}
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExtensionFixMode {
+    // `photo.txt` -> `photo.jpg`
+    #[default]
+    Replace,
+    // `photo.txt` -> `photo.txt.jpg`, original name stays visible
+    Append,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExtensionCollisionMode {
+    // File is not renamed and warning is reported
+    #[default]
+    Skip,
+    // First free name with number is used, e.g. `photo_1.jpg`
+    AddNumber,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct BadExtensionsFixParams {
+    pub fix_mode: ExtensionFixMode,
+    pub collision_mode: ExtensionCollisionMode,
+    // Only plan renames and report them, without touching files
+    pub dry_run: bool,
+    // When set, every rename is appended to this file, so it can be reverted with `rollback_renames`
+    pub rollback_log: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ExtensionFixReport {
+    pub dry_run: bool,
+    // Pairs of old and new path, in dry run these are only planned renames
+    pub renamed: Vec<(PathBuf, PathBuf)>,
+    pub not_renamed: usize,
+}
 
 #[derive(Clone)]
 pub struct BadExtensionsParameters {
     pub include_files_without_extension: bool,
+    // TOML file with additional signatures and accepted extensions, see `CustomDefinitions`
+    pub custom_definitions_path: Option<PathBuf>,
 }
 
 impl BadExtensionsParameters {
     pub fn new() -> Self {
         Self {
             include_files_without_extension: false,
+            custom_definitions_path: None,
         }
     }
 }
@@ -68,10 +106,16 @@ pub struct BadExtensions {
     files_to_check: Vec<FileEntry>,
     bad_extensions_files: Vec<BadFileEntry>,
     params: BadExtensionsParameters,
+    custom_definitions: CustomDefinitions,
+    fix_report: Option<ExtensionFixReport>,
 }
 
 impl BadExtensions {
     pub const fn get_bad_extensions_files(&self) -> &Vec<BadFileEntry> {
         &self.bad_extensions_files
     }
+
+    pub const fn get_fix_report(&self) -> Option<&ExtensionFixReport> {
+        self.fix_report.as_ref()
+    }
 }
@@ -6,8 +6,8 @@ use std::sync::atomic::AtomicBool;
 use tempfile::TempDir;
 
 use crate::common::tool_data::CommonData;
-use crate::common::traits::Search;
-use crate::tools::bad_extensions::{BadExtensions, BadExtensionsParameters};
+use crate::common::traits::{FixingItems, Search};
+use crate::tools::bad_extensions::{BadExtensions, BadExtensionsFixParams, BadExtensionsParameters, CustomDefinitions, CustomSignature, ExtensionCollisionMode, ExtensionFixMode};
 
 #[test]
 fn test_find_bad_extension_png_as_jpg() {
@@ -107,3 +107,181 @@ fn test_file_without_extension_included() {
     assert_eq!(bad_files[0].current_extension, "", "Current extension should be empty");
     assert_eq!(bad_files[0].proper_extension, "png");
 }
+
+fn search_with_custom_definitions(path: &std::path::Path, definitions: &str) -> BadExtensions {
+    let definitions_path = path.join("signatures.toml.cfg");
+    fs::write(&definitions_path, definitions).unwrap();
+
+    let mut params = BadExtensionsParameters::new();
+    params.custom_definitions_path = Some(definitions_path);
+    let mut finder = BadExtensions::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_excluded_items(vec!["*.cfg".to_string()]);
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+    finder
+}
+
+#[test]
+fn test_custom_signatures() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    // In-house format is a zip container with own marker after local file header
+    let mut content = vec![0x50, 0x4B, 0x03, 0x04];
+    content.extend_from_slice(&[0; 26]);
+    content.extend_from_slice(b"MYFORMAT");
+    fs::write(path.join("proper.myf"), &content).unwrap();
+    fs::write(path.join("alternative.myformat"), &content).unwrap();
+    fs::write(path.join("wrong.zip"), &content).unwrap();
+
+    let definitions = r#"
+        [[signature]]
+        extensions = [".MYF", "myformat"]
+        offset = 30
+        bytes = "4D 59 46 4F 52 4D 41 54"
+    "#;
+    let finder = search_with_custom_definitions(path, definitions);
+
+    let bad_files = finder.get_bad_extensions_files();
+    assert_eq!(bad_files.len(), 1, "{bad_files:?}");
+    assert_eq!(bad_files[0].current_extension, "zip");
+    assert_eq!(bad_files[0].proper_extension, "myf");
+    assert_eq!(bad_files[0].proper_extensions_group, "(myf) - myf,myformat");
+}
+
+#[test]
+fn test_custom_aliases() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    let png_data = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D];
+    fs::write(path.join("texture.tex"), png_data).unwrap();
+    fs::write(path.join("image.jpg"), png_data).unwrap();
+
+    let finder = search_with_custom_definitions(path, "[aliases]\npng = [\"tex\"]\n");
+
+    let bad_files = finder.get_bad_extensions_files();
+    assert_eq!(bad_files.len(), 1);
+    assert_eq!(bad_files[0].current_extension, "jpg");
+}
+
+#[test]
+fn test_invalid_custom_definitions() {
+    let temp_dir = TempDir::new().unwrap();
+    let finder = search_with_custom_definitions(temp_dir.path(), "[[signature]]\nextensions = [\"abc\"]\nbytes = \"4G\"\n");
+    assert!(finder.get_text_messages().critical.is_some());
+
+    CustomDefinitions::from_toml("[[signature]]\nextensions = []\nbytes = \"00\"").unwrap_err();
+    CustomDefinitions::from_toml("[[signature]]\nextensions = [\"abc\"]\nbytes = \"123\"").unwrap_err();
+    CustomDefinitions::from_toml("[[signature]]\nextensions = [\"abc\"]\noffset = 4194304\nbytes = \"00\"").unwrap_err();
+    CustomDefinitions::from_toml("[[signature]]\nextensions = [\"abc\"]\noffset = 9223372036854775807\nbytes = \"00\"").unwrap_err();
+    CustomDefinitions::from_toml("[[signature]]\nextensions = [\"abc\"]\noffset = 4194303\nbytes = \"00\"").unwrap();
+    let definitions = CustomDefinitions::from_toml("[[signature]]\nextensions = [\"abc\"]\nbytes = \"0x4D 5a90\"").unwrap();
+    assert_eq!(definitions.signatures[0].bytes, vec![0x4D, 0x5A, 0x90]);
+    assert_eq!(definitions.signatures[0].offset, 0);
+
+    // Signatures created directly are not validated, so their end must not overflow
+    let file_path = temp_dir.path().join("file.abc");
+    fs::write(&file_path, [0x4D, 0x5A, 0x90]).unwrap();
+    let definitions = CustomDefinitions {
+        signatures: vec![
+            CustomSignature {
+                extensions: vec!["big".to_string()],
+                offset: usize::MAX,
+                bytes: vec![0x00],
+            },
+            CustomSignature {
+                extensions: vec!["abc".to_string()],
+                offset: 0,
+                bytes: vec![0x4D, 0x5A],
+            },
+        ],
+        aliases: Default::default(),
+    };
+    assert_eq!(definitions.find_matching_signature(&file_path).map(CustomSignature::get_proper_extension), Some("abc"));
+}
+
+const PNG_DATA: [u8; 12] = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D];
+
+fn search_and_fix(path: &std::path::Path, fix_params: BadExtensionsFixParams) -> BadExtensions {
+    let mut finder = BadExtensions::new(BadExtensionsParameters::new());
+    finder.set_included_paths(vec![path.to_path_buf()]);
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+    finder.fix_items(&stop_flag, None, fix_params);
+    finder
+}
+
+fn read_dir_names(path: &std::path::Path) -> Vec<String> {
+    let mut names: Vec<_> = fs::read_dir(path).unwrap().map(|e| e.unwrap().file_name().to_string_lossy().to_string()).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_fix_modes() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    fs::write(path.join("photo.txt"), PNG_DATA).unwrap();
+
+    let finder = search_and_fix(path, BadExtensionsFixParams::default());
+    assert_eq!(read_dir_names(path), vec!["photo.png"]);
+    assert!(finder.get_bad_extensions_files().is_empty());
+    let report = finder.get_fix_report().unwrap();
+    assert_eq!(report.renamed, vec![(path.join("photo.txt"), path.join("photo.png"))]);
+    assert_eq!(report.not_renamed, 0);
+
+    fs::rename(path.join("photo.png"), path.join("photo.txt")).unwrap();
+    let fix_params = BadExtensionsFixParams {
+        fix_mode: ExtensionFixMode::Append,
+        ..Default::default()
+    };
+    search_and_fix(path, fix_params);
+    assert_eq!(read_dir_names(path), vec!["photo.txt.png"]);
+}
+
+#[test]
+fn test_fix_dry_run() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    fs::write(path.join("photo.txt"), PNG_DATA).unwrap();
+
+    let fix_params = BadExtensionsFixParams {
+        dry_run: true,
+        ..Default::default()
+    };
+    let finder = search_and_fix(path, fix_params);
+
+    assert_eq!(read_dir_names(path), vec!["photo.txt"]);
+    assert_eq!(finder.get_bad_extensions_files().len(), 1);
+    let report = finder.get_fix_report().unwrap();
+    assert!(report.dry_run);
+    assert_eq!(report.renamed, vec![(path.join("photo.txt"), path.join("photo.png"))]);
+}
+
+#[test]
+fn test_fix_collisions() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    fs::write(path.join("photo.png"), PNG_DATA).unwrap();
+    fs::write(path.join("photo.txt"), PNG_DATA).unwrap();
+    fs::write(path.join("photo.bin"), PNG_DATA).unwrap();
+
+    let finder = search_and_fix(path, BadExtensionsFixParams::default());
+    assert_eq!(read_dir_names(path), vec!["photo.bin", "photo.png", "photo.txt"]);
+    assert_eq!(finder.get_bad_extensions_files().len(), 2);
+    assert_eq!(finder.get_fix_report().unwrap().not_renamed, 2);
+    assert_eq!(finder.get_text_messages().warnings.len(), 2);
+
+    let fix_params = BadExtensionsFixParams {
+        collision_mode: ExtensionCollisionMode::AddNumber,
+        ..Default::default()
+    };
+    let finder = search_and_fix(path, fix_params);
+    assert_eq!(read_dir_names(path), vec!["photo.png", "photo_1.png", "photo_2.png"]);
+    assert_eq!(finder.get_fix_report().unwrap().renamed.len(), 2);
+    assert!(finder.get_text_messages().warnings.is_empty());
+}
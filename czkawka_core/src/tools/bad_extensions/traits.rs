@@ -23,6 +23,9 @@ impl Search for BadExtensions {
             if self.prepare_items(None).is_err() {
                 return;
             }
+            if self.load_custom_definitions() == WorkContinueStatus::Stop {
+                return;
+            }
             if self.check_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
                 self.common_data.stopped_search = true;
                 return;
@@ -80,6 +83,22 @@ impl PrintResults for BadExtensions {
             writeln!(writer, "\"{}\" ----- {}", file_entry.path.to_string_lossy(), file_entry.proper_extensions_group)?;
         }
 
+        if let Some(fix_report) = &self.fix_report {
+            if fix_report.dry_run {
+                writeln!(
+                    writer,
+                    "\nDry run - {} files would be renamed, {} files would be skipped.",
+                    fix_report.renamed.len(),
+                    fix_report.not_renamed
+                )?;
+            } else {
+                writeln!(writer, "\nRenamed {} files, {} files were not renamed.", fix_report.renamed.len(), fix_report.not_renamed)?;
+            }
+            for (source, target) in &fix_report.renamed {
+                writeln!(writer, "\"{}\" -> \"{}\"", source.to_string_lossy(), target.to_string_lossy())?;
+            }
+        }
+
         Ok(())
     }
 
@@ -1,20 +1,32 @@
-use std::path::Path;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::mem;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
-use std::{fs, mem};
+use std::time::UNIX_EPOCH;
 
 use crossbeam_channel::Sender;
 use fun_time::fun_time;
 use log::debug;
 use rayon::prelude::*;
+use unicode_normalization::UnicodeNormalization;
 
 use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
-use crate::common::model::{ToolType, WorkContinueStatus};
+use crate::common::model::{FileEntry, ToolType, WorkContinueStatus};
 use crate::common::progress_data::{ProgressData, ToolStage};
 use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
+use crate::common::rename::{RenamePlan, add_name_suffix};
 use crate::common::tool_data::CommonToolData;
+use crate::tools::bad_names::mojibake::repair_mojibake;
 use crate::tools::bad_names::{BadNameEntry, BadNames, BadNamesParameters, Info, NameFixerParams, NameIssues};
 
+// Most filesystems limit single name to 255 bytes (ext4) or 255 UTF-16 units (NTFS, exFAT)
+const MAX_NAME_BYTES: usize = 255;
+// Windows MAX_PATH, still enforced by many programs and SMB clients
+const MAX_PATH_LENGTH: usize = 260;
+const WINDOWS_RESERVED_NAMES: &[&str] = &["CON", "PRN", "AUX", "NUL"];
+
 impl BadNames {
     pub fn new(params: BadNamesParameters) -> Self {
         Self {
@@ -65,26 +77,26 @@ impl BadNames {
         let checked_issues = self.params.checked_issues.clone();
 
         debug!("look_for_bad_names_files - started checking for bad names");
-        let bad_names_files: Vec<BadNameEntry> = files_to_check
+        let checked_files: Vec<(FileEntry, Option<String>)> = files_to_check
             .into_par_iter()
-            .filter_map(|file_entry| {
+            .map(|file_entry| {
                 if check_if_stop_received(stop_flag) {
                     return None;
                 }
 
-                let size = file_entry.size;
-                let result = check_and_generate_new_name(&file_entry.path, &checked_issues).map(|new_name| BadNameEntry {
-                    path: file_entry.path,
-                    modified_date: file_entry.modified_date,
-                    size: file_entry.size,
-                    new_name,
-                });
+                let new_name = check_and_generate_new_name(&file_entry.path, &checked_issues);
 
                 progress_handler.increase_items(1);
-                progress_handler.increase_size(size);
+                progress_handler.increase_size(file_entry.size);
 
-                result
+                // Files without issues are still needed to find collisions between siblings
+                if new_name.is_none() && !checked_issues.checks_collisions() {
+                    return Some(None);
+                }
+                Some(Some((file_entry, new_name)))
             })
+            .while_some()
+            .flatten()
             .collect();
 
         debug!("look_for_bad_names_files - ended checking for bad names");
@@ -94,7 +106,24 @@ impl BadNames {
             return WorkContinueStatus::Stop;
         }
 
-        self.bad_names_files = bad_names_files;
+        let checked_files = if checked_issues.checks_collisions() {
+            let directories = get_scanned_directories(&checked_files, &self.common_data.directories.included_directories);
+            resolve_name_collisions(checked_files, directories, &checked_issues)
+        } else {
+            checked_files
+        };
+
+        self.bad_names_files = checked_files
+            .into_iter()
+            .filter_map(|(file_entry, new_name)| {
+                Some(BadNameEntry {
+                    path: file_entry.path,
+                    modified_date: file_entry.modified_date,
+                    size: file_entry.size,
+                    new_name: new_name?,
+                })
+            })
+            .collect();
         self.information.number_of_files_with_bad_names = self.bad_names_files.len();
         debug!("Found {} files with bad names.", self.information.number_of_files_with_bad_names);
 
@@ -102,38 +131,25 @@ impl BadNames {
     }
 
     #[fun_time(message = "fix_bad_names", level = "debug")]
-    pub fn fix_bad_names(&mut self, _fix_params: NameFixerParams, stop_flag: &Arc<AtomicBool>) {
-        let warnings: Vec<_> = mem::take(&mut self.bad_names_files)
-            .into_par_iter()
-            .map(|entry| {
-                if check_if_stop_received(stop_flag) {
-                    return None;
-                }
-
-                let new_path = entry.path.with_file_name(&entry.new_name);
-
-                if new_path.exists() {
-                    return Some(Some(format!("Cannot rename {:?} to {:?}: target file already exists", entry.path, new_path)));
-                }
-
-                match fs::rename(&entry.path, &new_path) {
-                    Ok(()) => Some(None),
-                    Err(e) => Some(Some(format!("Failed to rename {:?} to {:?}: {}", entry.path, new_path, e))),
-                }
-            })
-            .while_some()
-            .flatten()
-            .collect();
+    pub fn fix_bad_names(&mut self, fix_params: NameFixerParams, stop_flag: &Arc<AtomicBool>) {
+        let plan = RenamePlan::from_names(mem::take(&mut self.bad_names_files).into_iter().map(|entry| (entry.path, entry.new_name)));
+        self.common_data.text_messages.warnings.extend(plan.get_issue_messages());
 
-        self.common_data.text_messages.warnings.extend(warnings);
+        let result = plan.execute(stop_flag, fix_params.rollback_log.as_deref());
+        self.common_data.text_messages.warnings.extend(result.errors);
     }
 }
 
 // Check file name against NameIssues and generate a new fixed name if issues are found
 pub fn check_and_generate_new_name(path: &Path, checked_issues: &NameIssues) -> Option<String> {
-    let file_name = path.file_name()?.to_string_lossy();
-    let mut stem = path.file_stem()?.to_string_lossy().to_string();
-    let mut extension = path.extension().map(|e| e.to_string_lossy().to_string());
+    let original_name = path.file_name()?;
+    let file_name = original_name.to_string_lossy();
+
+    // Repaired name is used as a base for all other fixes
+    let repaired_name = if checked_issues.mojibake { repair_mojibake(original_name) } else { None };
+    let name_path = repaired_name.as_ref().map_or(path, Path::new);
+    let mut stem = name_path.file_stem()?.to_string_lossy().to_string();
+    let mut extension = name_path.extension().map(|e| e.to_string_lossy().to_string());
 
     if checked_issues.uppercase_extension
         && let Some(ref mut ext) = extension
@@ -150,6 +166,14 @@ pub fn check_and_generate_new_name(path: &Path, checked_issues: &NameIssues) ->
         }
     }
 
+    if checked_issues.windows_forbidden_characters {
+        stem = replace_windows_forbidden_characters(&stem);
+
+        if let Some(ref mut ext) = extension {
+            *ext = replace_windows_forbidden_characters(ext);
+        }
+    }
+
     if checked_issues.non_ascii_graphical {
         stem = deunicode::deunicode(&stem)
             .chars()
@@ -193,15 +217,187 @@ pub fn check_and_generate_new_name(path: &Path, checked_issues: &NameIssues) ->
         stem = "empty".to_string();
     }
 
-    let new_name = if let Some(ext) = extension {
+    let mut new_name = if let Some(ext) = extension {
         if ext.is_empty() { stem } else { format!("{stem}.{ext}") }
     } else {
         stem
     };
 
+    if checked_issues.trailing_dot_or_space {
+        new_name = trim_trailing_dots_and_spaces(&new_name);
+    }
+
+    if checked_issues.windows_reserved_name && is_windows_reserved_name(&new_name) {
+        // Windows checks only part before first dot, so `CON.tar.gz` is reserved too
+        let base_end = new_name.find('.').unwrap_or(new_name.len());
+        new_name.insert(base_end, '_');
+    }
+
+    if checked_issues.too_long_name
+        && let Some(shortened) = shorten_name(&new_name, MAX_NAME_BYTES, str::len)
+    {
+        new_name = shortened;
+    }
+
+    if checked_issues.too_long_path {
+        // Only name can be shortened here, so paths with too long parent directories are left untouched
+        let parent_length = path.parent().map_or(0, |parent| {
+            let parent = parent.to_string_lossy();
+            if parent.is_empty() { 0 } else { parent.encode_utf16().count() + 1 }
+        });
+        if let Some(shortened) = shorten_name(&new_name, MAX_PATH_LENGTH.saturating_sub(parent_length), |name| name.encode_utf16().count()) {
+            new_name = shortened;
+        }
+    }
+
+    // Shortening may leave dot or space at the end
+    if checked_issues.trailing_dot_or_space {
+        new_name = trim_trailing_dots_and_spaces(&new_name);
+    }
+
     if new_name != file_name.as_ref() as &str { Some(new_name) } else { None }
 }
 
+// Key under which names are compared - the same key means that files would be merged into one on target filesystem
+fn get_collision_key(name: &str, checked_issues: &NameIssues) -> String {
+    let name = if checked_issues.unicode_normalization_collision {
+        name.nfc().collect::<String>()
+    } else {
+        name.to_string()
+    };
+    if checked_issues.case_collision { name.to_lowercase() } else { name }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum CollisionItemKind {
+    // Directory found on disk which is not scanned, it blocks its name, but cannot be renamed
+    Blocker,
+    File,
+    Directory,
+}
+
+// Directories are not returned by traversal, but their names may collide too (e.g. `Photos/` and `photos/`),
+// so scanned directories are taken from paths of found files, up to included directories
+pub(crate) fn get_scanned_directories(files: &[(FileEntry, Option<String>)], included_directories: &[PathBuf]) -> Vec<FileEntry> {
+    let mut directories = BTreeMap::new();
+    for (file_entry, _) in files {
+        for ancestor in file_entry.path.ancestors().skip(1) {
+            if included_directories.iter().any(|included| included == ancestor) || !included_directories.iter().any(|included| ancestor.starts_with(included)) {
+                break;
+            }
+            if directories.contains_key(ancestor) {
+                break;
+            }
+            let modified_date = fs::metadata(ancestor)
+                .ok()
+                .and_then(|metadata| metadata.modified().ok())
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |duration| duration.as_secs());
+            directories.insert(
+                ancestor.to_path_buf(),
+                FileEntry {
+                    path: ancestor.to_path_buf(),
+                    size: 0,
+                    modified_date,
+                },
+            );
+        }
+    }
+    directories.into_values().collect()
+}
+
+// Groups sibling files and directories by the name they will have after fixing other issues and renames all but one item in each
+// colliding group by adding `_N` suffix before extension. Names already used on disk by items not included in results are also avoided,
+// sibling directories which are not scanned are never renamed, but block their names.
+// Items which keep their original name are preferred to stay unchanged, ties are resolved by name, so results are deterministic.
+// Renamed directories are returned after files, deepest first, so renaming them does not change paths of other renamed items.
+#[expect(clippy::indexing_slicing)] // Indexes come from enumerating the same vector
+pub(crate) fn resolve_name_collisions(files: Vec<(FileEntry, Option<String>)>, directories: Vec<FileEntry>, checked_issues: &NameIssues) -> Vec<(FileEntry, Option<String>)> {
+    let mut items: Vec<(FileEntry, String, bool, CollisionItemKind)> = files
+        .into_iter()
+        .map(|(file_entry, new_name)| (file_entry, new_name, CollisionItemKind::File))
+        .chain(directories.into_iter().map(|directory| (directory, None, CollisionItemKind::Directory)))
+        .filter_map(|(file_entry, new_name, kind)| {
+            let changed = new_name.is_some();
+            let target_name = match new_name {
+                Some(new_name) => new_name,
+                None => file_entry.path.file_name()?.to_string_lossy().to_string(),
+            };
+            Some((file_entry, target_name, changed, kind))
+        })
+        .collect();
+
+    let known_paths: HashSet<PathBuf> = items.iter().map(|(file_entry, ..)| file_entry.path.clone()).collect();
+    let parents: HashSet<PathBuf> = items.iter().filter_map(|(file_entry, ..)| file_entry.path.parent().map(Path::to_path_buf)).collect();
+    for parent in parents {
+        let Ok(read_dir) = fs::read_dir(&parent) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            if known_paths.contains(&path) || !entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                continue;
+            }
+            let target_name = entry.file_name().to_string_lossy().to_string();
+            let blocker = FileEntry { path, ..Default::default() };
+            items.push((blocker, target_name, false, CollisionItemKind::Blocker));
+        }
+    }
+
+    let mut groups: BTreeMap<(PathBuf, String), Vec<usize>> = BTreeMap::new();
+    for (idx, (file_entry, target_name, _, _)) in items.iter().enumerate() {
+        let parent = file_entry.path.parent().map(Path::to_path_buf).unwrap_or_default();
+        groups.entry((parent, get_collision_key(target_name, checked_issues))).or_default().push(idx);
+    }
+    let mut used_keys: HashSet<(PathBuf, String)> = groups.keys().cloned().collect();
+
+    // Files skipped by scan are not in results, so name must be also checked on disk
+    let is_free_on_disk = |parent: &Path, name: &str, current_path: &Path| {
+        let path = parent.join(name);
+        let Ok(metadata) = path.symlink_metadata() else {
+            return true;
+        };
+        // On case insensitive filesystems, file renamed by changing only case of its name is found on disk under new name
+        !metadata.file_type().is_symlink() && matches!((fs::canonicalize(&path), fs::canonicalize(current_path)), (Ok(found_path), Ok(current_path)) if found_path == current_path)
+    };
+
+    for ((parent, _), mut indexes) in groups {
+        indexes.sort_by_cached_key(|idx| {
+            let (file_entry, target_name, changed, kind) = &items[*idx];
+            (*kind != CollisionItemKind::Blocker, *changed, target_name.clone(), file_entry.path.clone())
+        });
+
+        for (position, idx) in indexes.into_iter().enumerate() {
+            let (file_entry, target_name, changed, kind) = &items[idx];
+            if *kind == CollisionItemKind::Blocker || (position == 0 && (!changed || is_free_on_disk(&parent, target_name, &file_entry.path))) {
+                continue;
+            }
+            let mut number = 1;
+            let new_name = loop {
+                let candidate = add_name_suffix(target_name, number);
+                if is_free_on_disk(&parent, &candidate, &file_entry.path) && used_keys.insert((parent.clone(), get_collision_key(&candidate, checked_issues))) {
+                    break candidate;
+                }
+                number += 1;
+            };
+            items[idx].1 = new_name;
+            items[idx].2 = true;
+        }
+    }
+
+    let (files, mut directories): (Vec<_>, Vec<_>) = items
+        .into_iter()
+        .filter(|(_, _, changed, kind)| *kind == CollisionItemKind::File || (*kind == CollisionItemKind::Directory && *changed))
+        .partition(|(_, _, _, kind)| *kind == CollisionItemKind::File);
+    directories.sort_by_key(|(file_entry, ..)| std::cmp::Reverse(file_entry.path.components().count()));
+
+    files
+        .into_iter()
+        .chain(directories)
+        .map(|(file_entry, target_name, changed, _)| (file_entry, changed.then_some(target_name)))
+        .collect()
+}
+
 fn is_alphanumeric(c: char) -> bool {
     c.is_ascii_alphanumeric()
 }
@@ -213,6 +409,58 @@ fn is_shell_glob_char(c: char) -> bool {
     matches!(c, '*' | '?' | '[' | ']')
 }
 
+// Besides printable characters, Windows also forbids control characters in names
+fn replace_windows_forbidden_characters(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*' | '\\') || c.is_ascii_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+fn trim_trailing_dots_and_spaces(name: &str) -> String {
+    let trimmed = name.trim_end_matches(['.', ' ']);
+    if trimmed.is_empty() { "empty".to_string() } else { trimmed.to_string() }
+}
+
+fn is_windows_reserved_name(name: &str) -> bool {
+    let base = name.split('.').next().unwrap_or_default().trim_end_matches(' ').to_uppercase();
+    if WINDOWS_RESERVED_NAMES.contains(&base.as_str()) {
+        return true;
+    }
+
+    let mut chars = base.chars();
+    let prefix: String = chars.by_ref().take(3).collect();
+    (prefix == "COM" || prefix == "LPT") && matches!((chars.next(), chars.next()), (Some('0'..='9' | '¹' | '²' | '³'), None))
+}
+
+// Shortens part of name before extension, so the whole name fits in the limit
+// Returns None when name is already short enough or cannot be shortened without touching extension
+fn shorten_name(name: &str, limit: usize, length: fn(&str) -> usize) -> Option<String> {
+    if length(name) <= limit {
+        return None;
+    }
+
+    let (base, extension) = match name.rfind('.') {
+        Some(idx) if idx > 0 => name.split_at(idx),
+        _ => (name, ""),
+    };
+
+    let mut base = base.to_string();
+    while length(&base) + length(extension) > limit {
+        base.pop();
+        if base.is_empty() {
+            return None;
+        }
+    }
+
+    Some(format!("{base}{extension}"))
+}
+
 fn remove_duplicated_non_alphanumeric(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
     let mut chars = s.chars().peekable();
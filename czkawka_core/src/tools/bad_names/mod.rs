@@ -1,4 +1,5 @@
 pub mod core;
+mod mojibake;
 #[cfg(test)]
 mod tests;
 pub mod traits;
@@ -40,6 +41,17 @@ pub struct NameIssues {
     pub non_ascii_graphical: bool,
     pub restricted_charset_allowed: Option<Vec<char>>,
     pub remove_duplicated_non_alphanumeric: bool,
+    // Checks below find names which cannot be copied to Windows, exFAT or SMB shares
+    pub windows_reserved_name: bool,
+    pub trailing_dot_or_space: bool,
+    pub windows_forbidden_characters: bool,
+    pub too_long_name: bool,
+    pub too_long_path: bool,
+    // Sibling files which would be merged on case-insensitive or normalization-insensitive filesystems
+    pub case_collision: bool,
+    pub unicode_normalization_collision: bool,
+    // UTF-8 names decoded with legacy code page (Latin-1, CP1250, CP437) or names which are not valid UTF-8
+    pub mojibake: bool,
 }
 
 impl NameIssues {
@@ -51,6 +63,14 @@ impl NameIssues {
             non_ascii_graphical: true,
             restricted_charset_allowed: Some(vec!['_', '-', ' ', '.']),
             remove_duplicated_non_alphanumeric: true,
+            windows_reserved_name: true,
+            trailing_dot_or_space: true,
+            windows_forbidden_characters: true,
+            too_long_name: true,
+            too_long_path: true,
+            case_collision: true,
+            unicode_normalization_collision: true,
+            mojibake: true,
         }
     }
 
@@ -58,6 +78,10 @@ impl NameIssues {
         Self::default()
     }
 
+    pub fn checks_collisions(&self) -> bool {
+        self.case_collision || self.unicode_normalization_collision
+    }
+
     pub fn is_empty(&self) -> bool {
         !self.uppercase_extension
             && !self.emoji_used
@@ -65,12 +89,21 @@ impl NameIssues {
             && !self.non_ascii_graphical
             && self.restricted_charset_allowed.is_none()
             && !self.remove_duplicated_non_alphanumeric
+            && !self.windows_reserved_name
+            && !self.trailing_dot_or_space
+            && !self.windows_forbidden_characters
+            && !self.too_long_name
+            && !self.too_long_path
+            && !self.case_collision
+            && !self.unicode_normalization_collision
+            && !self.mojibake
     }
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct NameFixerParams {
-    // Empty - fixing has no parameters
+    // When set, every rename is appended to this file, so it can be reverted with `rollback_renames`
+    pub rollback_log: Option<PathBuf>,
 }
 
 #[derive(Default, Clone, Copy)]
@@ -0,0 +1,163 @@
+use std::ffi::OsStr;
+
+// Order matters only when more than one code page gives valid result, the most common ones are checked first
+const CODE_PAGES: [CodePage; 3] = [CodePage::Latin1, CodePage::Windows1250, CodePage::Cp437];
+// Names may be mangled more than once, e.g. when copied through two old tools
+const MAX_REPAIR_ROUNDS: usize = 3;
+
+#[derive(Clone, Copy, Debug)]
+enum CodePage {
+    // ISO-8859-1 with Windows-1252 extensions, which are used by most tools claiming to use Latin-1
+    Latin1,
+    Windows1250,
+    Cp437,
+}
+
+impl CodePage {
+    fn decode_byte(self, byte: u8) -> char {
+        if byte < 0x80 {
+            return byte as char;
+        }
+        let idx = (byte - 0x80) as usize;
+        match self {
+            Self::Latin1 => WINDOWS_1252_HIGH.get(idx).copied().unwrap_or(byte as char),
+            Self::Windows1250 => WINDOWS_1250.get(idx).copied().unwrap_or(byte as char),
+            Self::Cp437 => CP437.get(idx).copied().unwrap_or(byte as char),
+        }
+    }
+
+    fn encode_char(self, c: char) -> Option<u8> {
+        if c.is_ascii() {
+            return Some(c as u8);
+        }
+        let table: &[char] = match self {
+            Self::Latin1 => &WINDOWS_1252_HIGH,
+            Self::Windows1250 => &WINDOWS_1250,
+            Self::Cp437 => &CP437,
+        };
+        if let Some(idx) = table.iter().position(|table_char| *table_char == c) {
+            return u8::try_from(idx + 0x80).ok();
+        }
+        // Strict Latin-1 decoders map 0x80-0x9F to C1 control characters instead of Windows-1252 symbols
+        match (self, u32::from(c)) {
+            (Self::Latin1, 0x80..=0xFF) => u8::try_from(u32::from(c)).ok(),
+            _ => None,
+        }
+    }
+}
+
+// Returns properly decoded file name, when name looks like UTF-8 decoded with legacy code page or is not valid UTF-8 at all
+pub(crate) fn repair_mojibake(file_name: &OsStr) -> Option<String> {
+    let bytes = get_name_bytes(file_name);
+    let original = std::str::from_utf8(&bytes).ok();
+    let mut name = original.map_or_else(|| decode_with_best_code_page(&bytes), str::to_string);
+
+    for _ in 0..MAX_REPAIR_ROUNDS {
+        match CODE_PAGES.iter().find_map(|code_page| reencode_as_utf8(&name, *code_page)) {
+            Some(repaired) => name = repaired,
+            None => break,
+        }
+    }
+
+    if original == Some(name.as_str()) { None } else { Some(name) }
+}
+
+#[cfg(unix)]
+fn get_name_bytes(file_name: &OsStr) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    file_name.as_bytes().to_vec()
+}
+
+// On other systems names are stored as UTF-16, so only double encoded names can be found there
+#[cfg(not(unix))]
+fn get_name_bytes(file_name: &OsStr) -> Vec<u8> {
+    file_name.to_string_lossy().as_bytes().to_vec()
+}
+
+// Encodes name back with code page and checks if result is valid UTF-8
+// Uppercase letter followed by symbol like `’` may form valid sequence by accident, e.g. `É’` gives `ɒ`,
+// so result must also look more plausible than current name
+fn reencode_as_utf8(name: &str, code_page: CodePage) -> Option<String> {
+    if name.is_ascii() {
+        return None;
+    }
+    let bytes = name.chars().map(|c| code_page.encode_char(c)).collect::<Option<Vec<u8>>>()?;
+    let repaired = String::from_utf8(bytes).ok()?;
+    is_plausible_repair(name, &repaired).then_some(repaired)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Ipa,
+    Greek,
+    Cyrillic,
+    Other,
+}
+
+fn get_script(c: char) -> Option<Script> {
+    if !c.is_alphabetic() {
+        return None;
+    }
+    Some(match u32::from(c) {
+        0..=0x024F | 0x1E00..=0x1EFF => Script::Latin,
+        0x0250..=0x02AF => Script::Ipa,
+        0x0370..=0x03FF => Script::Greek,
+        0x0400..=0x052F => Script::Cyrillic,
+        _ => Script::Other,
+    })
+}
+
+// Every multibyte character is decoded by code page as at least two characters, so real repair always
+// decreases number of non-ASCII characters, and it never produces IPA letters or mixes scripts inside a word
+fn is_plausible_repair(name: &str, repaired: &str) -> bool {
+    let count_non_ascii = |text: &str| text.chars().filter(|c| !c.is_ascii()).count();
+    if count_non_ascii(repaired) >= count_non_ascii(name) {
+        return false;
+    }
+
+    let scripts: Vec<Option<Script>> = repaired.chars().map(get_script).collect();
+    if scripts.contains(&Some(Script::Ipa)) {
+        return false;
+    }
+    scripts.windows(2).all(|pair| match pair {
+        [Some(first), Some(second)] => first == second,
+        _ => true,
+    })
+}
+
+// Every byte is valid in each code page, so the one which gives the most letters instead of symbols is chosen
+fn decode_with_best_code_page(bytes: &[u8]) -> String {
+    CODE_PAGES
+        .iter()
+        .map(|code_page| bytes.iter().map(|byte| code_page.decode_byte(*byte)).collect::<String>())
+        .enumerate()
+        .max_by_key(|(idx, name)| {
+            let score: i64 = name.chars().filter(|c| !c.is_ascii()).map(|c| if c.is_alphabetic() { 1 } else { -1 }).sum();
+            (score, std::cmp::Reverse(*idx))
+        })
+        .map(|(_, name)| name)
+        .unwrap_or_default()
+}
+
+// Bytes 0x80-0x9F of Windows-1252, unassigned bytes are decoded as C1 control characters like in Latin-1
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}',
+    'ž', 'Ÿ',
+];
+
+// Bytes 0x80-0xFF of Windows-1250, unassigned bytes are decoded as C1 control characters
+const WINDOWS_1250: [char; 128] = [
+    '€', '\u{81}', '‚', '\u{83}', '„', '…', '†', '‡', '\u{88}', '‰', 'Š', '‹', 'Ś', 'Ť', 'Ž', 'Ź', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '\u{98}', '™', 'š', '›', 'ś', 'ť',
+    'ž', 'ź', '\u{a0}', 'ˇ', '˘', 'Ł', '¤', 'Ą', '¦', '§', '¨', '©', 'Ş', '«', '¬', '\u{ad}', '®', 'Ż', '°', '±', '˛', 'ł', '´', 'µ', '¶', '·', '¸', 'ą', 'ş', '»', 'Ľ', '˝', 'ľ',
+    'ż', 'Ŕ', 'Á', 'Â', 'Ă', 'Ä', 'Ĺ', 'Ć', 'Ç', 'Č', 'É', 'Ę', 'Ë', 'Ě', 'Í', 'Î', 'Ď', 'Đ', 'Ń', 'Ň', 'Ó', 'Ô', 'Ő', 'Ö', '×', 'Ř', 'Ů', 'Ú', 'Ű', 'Ü', 'Ý', 'Ţ', 'ß', 'ŕ', 'á',
+    'â', 'ă', 'ä', 'ĺ', 'ć', 'ç', 'č', 'é', 'ę', 'ë', 'ě', 'í', 'î', 'ď', 'đ', 'ń', 'ň', 'ó', 'ô', 'ő', 'ö', '÷', 'ř', 'ů', 'ú', 'ű', 'ü', 'ý', 'ţ', '˙',
+];
+
+// Bytes 0x80-0xFF of CP437, default code page of ZIP archives
+const CP437: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', 'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', 'á', 'í', 'ó',
+    'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐', '└', '┴', '┬', '├', '─', '┼',
+    '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧', '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀', 'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ',
+    'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
@@ -21,6 +21,7 @@ mod tests2 {
             non_ascii_graphical: false,
             restricted_charset_allowed: None,
             remove_duplicated_non_alphanumeric: false,
+            ..NameIssues::default()
         });
         let mut bad_names = BadNames::new(params);
         bad_names.get_cd_mut().directories.set_included_paths(vec![temp_dir.path().to_path_buf()]);
@@ -45,6 +46,7 @@ mod tests2 {
             non_ascii_graphical: false,
             restricted_charset_allowed: None,
             remove_duplicated_non_alphanumeric: false,
+            ..NameIssues::default()
         });
         let mut bad_names = BadNames::new(params);
         bad_names.get_cd_mut().directories.set_included_paths(vec![temp_dir.path().to_path_buf()]);
@@ -69,6 +71,7 @@ mod tests2 {
             non_ascii_graphical: false,
             restricted_charset_allowed: None,
             remove_duplicated_non_alphanumeric: false,
+            ..NameIssues::default()
         });
         let mut bad_names = BadNames::new(params);
         bad_names.get_cd_mut().directories.set_included_paths(vec![temp_dir.path().to_path_buf()]);
@@ -93,6 +96,7 @@ mod tests2 {
             non_ascii_graphical: false,
             restricted_charset_allowed: None,
             remove_duplicated_non_alphanumeric: false,
+            ..NameIssues::default()
         });
         let mut bad_names = BadNames::new(params);
         bad_names.get_cd_mut().directories.set_included_paths(vec![temp_dir.path().to_path_buf()]);
@@ -117,6 +121,7 @@ mod tests2 {
             non_ascii_graphical: true,
             restricted_charset_allowed: None,
             remove_duplicated_non_alphanumeric: false,
+            ..NameIssues::default()
         });
         let mut bad_names = BadNames::new(params);
         bad_names.get_cd_mut().directories.set_included_paths(vec![temp_dir.path().to_path_buf()]);
@@ -141,6 +146,7 @@ mod tests2 {
             non_ascii_graphical: false,
             restricted_charset_allowed: Some(vec!['_', '-', ' ']),
             remove_duplicated_non_alphanumeric: false,
+            ..NameIssues::default()
         });
         let mut bad_names = BadNames::new(params);
         bad_names.get_cd_mut().directories.set_included_paths(vec![temp_dir.path().to_path_buf()]);
@@ -165,6 +171,7 @@ mod tests2 {
             non_ascii_graphical: false,
             restricted_charset_allowed: None,
             remove_duplicated_non_alphanumeric: true,
+            ..NameIssues::default()
         });
         let mut bad_names = BadNames::new(params);
         bad_names.get_cd_mut().directories.set_included_paths(vec![temp_dir.path().to_path_buf()]);
@@ -516,6 +523,7 @@ mod tests2 {
             non_ascii_graphical: true,
             restricted_charset_allowed: Some(vec!['_', '-', ' ']),
             remove_duplicated_non_alphanumeric: true,
+            ..NameIssues::default()
         };
 
         let mut errors = Vec::new();
@@ -596,7 +604,8 @@ mod tests2 {
             ("😀😀😀.txt", "empty.txt"),
             ("___", "_"),
             ("---", "-"),
-            ("...", "."),
+            // Trailing dots are not allowed on Windows
+            ("...", "empty"),
             (" 😀 .TXT ", "empty.txt"),
             ("test.", "test"),
             (".test", ".test"),
@@ -623,4 +632,327 @@ mod tests2 {
 
         assert!(errors.is_empty(), "Edge cases tests failed:\n{}", errors.join("\n"));
     }
+
+    #[test]
+    fn test_portability_issues_unit() {
+        let check_params = NameIssues {
+            windows_reserved_name: true,
+            trailing_dot_or_space: true,
+            windows_forbidden_characters: true,
+            too_long_name: true,
+            too_long_path: true,
+            ..NameIssues::default()
+        };
+
+        let long_stem = "a".repeat(300);
+        let shortened_stem = "a".repeat(251);
+        let mut errors = Vec::new();
+        let test_cases = [
+            ("CON", "CON_".to_string()),
+            ("nul.txt", "nul_.txt".to_string()),
+            ("Com1.tar.gz", "Com1_.tar.gz".to_string()),
+            ("LPT9 .log", "LPT9 _.log".to_string()),
+            ("file.txt.", "file.txt".to_string()),
+            ("file .. ", "file".to_string()),
+            ("what?.txt", "what_.txt".to_string()),
+            ("a<b>c:d|e*f\\g\".txt", "a_b_c_d_e_f_g_.txt".to_string()),
+            ("tab\tname.txt", "tab_name.txt".to_string()),
+            (&format!("{long_stem}.txt"), format!("{shortened_stem}.txt")),
+        ];
+
+        for (input, expected_output) in &test_cases {
+            let path = Path::new(input);
+            if let Some(new_name) = check_and_generate_new_name(path, &check_params) {
+                if &new_name != expected_output {
+                    errors.push(format!("Input: '{input}', Expected: '{expected_output}', Got: '{new_name}'"));
+                }
+
+                let fixed_path = Path::new(&new_name);
+                if check_and_generate_new_name(fixed_path, &check_params).is_some() {
+                    errors.push(format!("Double fix should return None for: '{new_name}'"));
+                }
+            } else {
+                errors.push(format!("Input: '{input}' was not fixed"));
+            }
+        }
+
+        for input in ["CONSOLE.txt", "COM10.txt", "LPT.txt", "my.con", "normal_file.txt"] {
+            if let Some(new_name) = check_and_generate_new_name(Path::new(input), &check_params) {
+                errors.push(format!("Input: '{input}' should not be changed but got: '{new_name}'"));
+            }
+        }
+
+        assert!(errors.is_empty(), "Portability tests failed:\n{}", errors.join("\n"));
+    }
+
+    #[test]
+    fn test_too_long_path_unit() {
+        let check_params = NameIssues {
+            too_long_path: true,
+            ..NameIssues::default()
+        };
+
+        let parent = format!("/{}", "d".repeat(200));
+        let path = Path::new(&parent).join(format!("{}.mkv", "v".repeat(100)));
+        let new_name = check_and_generate_new_name(&path, &check_params).unwrap();
+        assert_eq!(new_name, format!("{}.mkv", "v".repeat(54)));
+        assert_eq!(path.with_file_name(&new_name).to_string_lossy().encode_utf16().count(), 260);
+
+        // Parent directory alone exceeds the limit, so renaming file cannot help
+        let parent = format!("/{}", "d".repeat(300));
+        assert_eq!(check_and_generate_new_name(&Path::new(&parent).join("file.txt"), &check_params), None);
+    }
+}
+
+#[cfg(test)]
+mod collision_tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+
+    use crate::common::model::FileEntry;
+    use crate::common::tool_data::CommonData;
+    use crate::common::traits::Search;
+    use crate::tools::bad_names::core::resolve_name_collisions;
+    use crate::tools::bad_names::{BadNames, BadNamesParameters, NameIssues};
+
+    fn entry(path: &str) -> FileEntry {
+        FileEntry {
+            path: PathBuf::from(path),
+            ..Default::default()
+        }
+    }
+
+    fn resolve(files: &[(&str, Option<&str>)], checked_issues: &NameIssues) -> Vec<(String, Option<String>)> {
+        let files = files.iter().map(|(path, new_name)| (entry(path), new_name.map(str::to_string))).collect();
+        let mut result: Vec<_> = resolve_name_collisions(files, Vec::new(), checked_issues)
+            .into_iter()
+            .map(|(file_entry, new_name)| (file_entry.path.to_string_lossy().to_string(), new_name))
+            .collect();
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn test_case_collisions() {
+        let checked_issues = NameIssues {
+            case_collision: true,
+            ..NameIssues::default()
+        };
+
+        let result = resolve(
+            &[
+                ("/a/photo.jpg", None),
+                ("/a/Photo.jpg", None),
+                ("/a/PHOTO.jpg", None),
+                ("/a/photo_1.jpg", None),
+                ("/b/Photo.jpg", None),
+            ],
+            &checked_issues,
+        );
+        assert_eq!(
+            result,
+            vec![
+                ("/a/PHOTO.jpg".to_string(), None),
+                ("/a/Photo.jpg".to_string(), Some("Photo_2.jpg".to_string())),
+                ("/a/photo.jpg".to_string(), Some("photo_3.jpg".to_string())),
+                ("/a/photo_1.jpg".to_string(), None),
+                ("/b/Photo.jpg".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collisions_after_other_fixes() {
+        let checked_issues = NameIssues {
+            case_collision: true,
+            ..NameIssues::default()
+        };
+
+        // File keeping its name is preferred over file which would be renamed to the same name
+        let result = resolve(&[("/a/Notes.TXT", Some("Notes.txt")), ("/a/notes.txt", None)], &checked_issues);
+        assert_eq!(
+            result,
+            vec![("/a/Notes.TXT".to_string(), Some("Notes_1.txt".to_string())), ("/a/notes.txt".to_string(), None),]
+        );
+    }
+
+    #[test]
+    fn test_collisions_with_items_on_disk() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = |name: &str| temp_dir.path().join(name).to_string_lossy().to_string();
+        // Not included in results e.g. because of excluded extension or being directory
+        std::fs::create_dir(temp_dir.path().join("photo_1.jpg")).unwrap();
+        std::fs::write(temp_dir.path().join("notes.txt"), "").unwrap();
+
+        let checked_issues = NameIssues {
+            case_collision: true,
+            ..NameIssues::default()
+        };
+        let (photo, upper_photo, notes) = (path("photo.jpg"), path("Photo.jpg"), path("Notes.TXT"));
+        let result = resolve(&[(&photo, None), (&upper_photo, None), (&notes, Some("notes.txt"))], &checked_issues);
+        assert_eq!(
+            result,
+            vec![(notes, Some("notes_1.txt".to_string())), (upper_photo, None), (photo, Some("photo_2.jpg".to_string())),]
+        );
+    }
+
+    #[test]
+    fn test_unicode_normalization_collisions() {
+        let nfc = "/a/caf\u{e9}.txt";
+        let nfd = "/a/cafe\u{301}.txt";
+
+        let case_only = NameIssues {
+            case_collision: true,
+            ..NameIssues::default()
+        };
+        assert!(resolve(&[(nfc, None), (nfd, None)], &case_only).iter().all(|(_, new_name)| new_name.is_none()));
+
+        let checked_issues = NameIssues {
+            unicode_normalization_collision: true,
+            ..NameIssues::default()
+        };
+        let result = resolve(&[(nfc, None), (nfd, None), ("/a/CAF\u{c9}.txt", None)], &checked_issues);
+        assert_eq!(
+            result,
+            vec![
+                ("/a/CAF\u{c9}.txt".to_string(), None),
+                (nfd.to_string(), None),
+                (nfc.to_string(), Some("caf\u{e9}_1.txt".to_string())),
+            ]
+        );
+    }
+
+    fn find_collisions(dir: &std::path::Path) -> Vec<(PathBuf, String)> {
+        let params = BadNamesParameters::new(NameIssues {
+            case_collision: true,
+            ..NameIssues::default()
+        });
+        let mut bad_names = BadNames::new(params);
+        bad_names.get_cd_mut().directories.set_included_paths(vec![dir.to_path_buf()]);
+        bad_names.search(&Arc::new(AtomicBool::new(false)), None);
+
+        let mut result: Vec<_> = bad_names
+            .get_bad_names_files()
+            .iter()
+            .map(|entry| (entry.path.strip_prefix(dir).unwrap().to_path_buf(), entry.new_name.clone()))
+            .collect();
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn test_directory_collisions() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        for name in ["Photos", "photos"] {
+            fs::create_dir(temp_dir.path().join(name)).unwrap();
+            fs::write(temp_dir.path().join(name).join("image.jpg"), "").unwrap();
+        }
+
+        // Only one directory is renamed, files inside them do not collide, because they have different parents
+        assert_eq!(find_collisions(temp_dir.path()), vec![(PathBuf::from("photos"), "photos_1".to_string())]);
+    }
+
+    #[test]
+    fn test_file_collisions_with_directories() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        // Scanned directory
+        fs::create_dir(temp_dir.path().join("Notes")).unwrap();
+        fs::write(temp_dir.path().join("Notes").join("todo.txt"), "").unwrap();
+        fs::write(temp_dir.path().join("notes"), "").unwrap();
+        // Directory without any files, so it is only visible on disk
+        fs::create_dir(temp_dir.path().join("Music")).unwrap();
+        fs::write(temp_dir.path().join("music"), "").unwrap();
+
+        assert_eq!(
+            find_collisions(temp_dir.path()),
+            vec![(PathBuf::from("music"), "music_1".to_string()), (PathBuf::from("notes"), "notes_1".to_string())]
+        );
+    }
+}
+
+#[cfg(test)]
+mod mojibake_tests {
+    use std::path::Path;
+
+    use crate::tools::bad_names::NameIssues;
+    use crate::tools::bad_names::core::check_and_generate_new_name;
+
+    fn mojibake_issues() -> NameIssues {
+        NameIssues {
+            mojibake: true,
+            ..NameIssues::default()
+        }
+    }
+
+    #[test]
+    fn test_double_encoded_names() {
+        let check_params = mojibake_issues();
+
+        let test_cases = [
+            // Windows-1252
+            ("Za\u{c5}\u{bc}\u{c3}\u{b3}\u{c5}\u{201a}\u{c4}\u{2021}.txt", "Zażółć.txt"),
+            // Strict Latin-1
+            ("Za\u{c5}\u{bc}\u{c3}\u{b3}\u{c5}\u{82}\u{c4}\u{87}.txt", "Zażółć.txt"),
+            ("Ã©tÃ©.jpg", "été.jpg"),
+            ("Ð”Ð¾Ð¼.txt", "Дом.txt"),
+            // Windows-1250
+            ("ĹĽĂłĹ‚w.pdf", "żółw.pdf"),
+            // CP437
+            ("Za┼╝├│┼é─ç.txt", "Zażółć.txt"),
+            ("M├╝ller.doc", "Müller.doc"),
+            // Encoded twice
+            ("ÃƒÂ©tÃƒÂ©.jpg", "été.jpg"),
+        ];
+
+        let mut errors = Vec::new();
+        for (input, expected_output) in test_cases {
+            match check_and_generate_new_name(Path::new(input), &check_params) {
+                Some(new_name) if new_name == expected_output => {}
+                result => errors.push(format!("Input: '{input}', Expected: '{expected_output}', Got: '{result:?}'")),
+            }
+        }
+
+        for input in [
+            "Zażółć.txt",
+            "été.jpg",
+            "naïve café.txt",
+            "Ã.txt",
+            "├─ tree.txt",
+            "plain.txt",
+            // Valid UTF-8 after re-encoding, but gives IPA or mixed script letters
+            "CAFÉ’S.txt",
+            "NAÏVE®",
+            "ÉTÉ’S ÜBER”.txt",
+            "Ð”OG.txt",
+        ] {
+            if let Some(new_name) = check_and_generate_new_name(Path::new(input), &check_params) {
+                errors.push(format!("Input: '{input}' should not be changed but got: '{new_name}'"));
+            }
+        }
+
+        assert!(errors.is_empty(), "Mojibake tests failed:\n{}", errors.join("\n"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_invalid_utf8_names() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let check_params = mojibake_issues();
+
+        // "Zażółć.txt" in Windows-1250
+        let name = OsStr::from_bytes(b"Za\xbf\xf3\xb3\xe6.txt");
+        assert_eq!(check_and_generate_new_name(Path::new(name), &check_params), Some("Zażółć.txt".to_string()));
+
+        // "Müller.doc" in CP437, as written by old ZIP tools
+        let name = OsStr::from_bytes(b"M\x81ller.doc");
+        assert_eq!(check_and_generate_new_name(Path::new(name), &check_params), Some("Müller.doc".to_string()));
+
+        // "café.txt" in Latin-1
+        let name = OsStr::from_bytes(b"caf\xe9.txt");
+        assert_eq!(check_and_generate_new_name(Path::new(name), &check_params), Some("café.txt".to_string()));
+    }
 }
@@ -1,4 +1,6 @@
 use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 
@@ -8,10 +10,11 @@ use log::debug;
 use rayon::prelude::*;
 
 use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
-use crate::common::model::{ToolType, WorkContinueStatus};
+use crate::common::model::{FileEntry, ToolType, WorkContinueStatus};
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData};
-use crate::tools::big_file::{BigFile, BigFileParameters, Info, SearchMode};
+use crate::common::traits::PrintResults;
+use crate::tools::big_file::{BigFile, BigFileParameters, DirectoryEntry, DirectoryStats, Info, SearchMode, TreemapNode};
 
 impl BigFile {
     pub fn new(params: BigFileParameters) -> Self {
@@ -19,6 +22,8 @@ impl BigFile {
             common_data: CommonToolData::new(ToolType::BigFile),
             information: Info::default(),
             big_files: Default::default(),
+            directory_stats: Default::default(),
+            biggest_directories: Default::default(),
             params,
         }
     }
@@ -30,7 +35,8 @@ impl BigFile {
             .stop_flag(stop_flag)
             .progress_sender(progress_sender)
             .common_data(&self.common_data)
-            .minimal_file_size(1)
+            // Empty files are not listed, but are counted in directories
+            .minimal_file_size(u64::from(self.params.directories_per_depth.is_none()))
             .maximal_file_size(u64::MAX)
             .build()
             .run();
@@ -39,6 +45,11 @@ impl BigFile {
             DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } => {
                 let mut all_files = grouped_file_entries.into_values().flatten().collect::<Vec<_>>();
 
+                if let Some(directories_per_depth) = self.params.directories_per_depth {
+                    self.calculate_directory_sizes(&all_files, directories_per_depth);
+                    all_files.retain(|fe| fe.size > 0);
+                }
+
                 if self.get_params().search_mode == SearchMode::BiggestFiles {
                     all_files.par_sort_unstable_by_key(|fe| Reverse(fe.size));
                 } else {
@@ -58,4 +69,115 @@ impl BigFile {
             DirTraversalResult::Stopped => WorkContinueStatus::Stop,
         }
     }
+
+    // Sizes are first summed for directories directly containing files, so walking up the tree is done once per directory, not once per file
+    #[fun_time(message = "calculate_directory_sizes", level = "debug")]
+    fn calculate_directory_sizes(&mut self, files: &[FileEntry], directories_per_depth: usize) {
+        let mut own_sizes: HashMap<&Path, (u64, usize)> = HashMap::new();
+        for file_entry in files {
+            if let Some(parent) = file_entry.path.parent() {
+                let (size, count) = own_sizes.entry(parent).or_default();
+                *size += file_entry.size;
+                *count += 1;
+            }
+        }
+
+        let included_directories = &self.common_data.directories.included_directories;
+        let mut directory_stats: BTreeMap<PathBuf, DirectoryStats> = included_directories.iter().map(|root| (root.clone(), DirectoryStats::default())).collect();
+        for (directory, (own_size, own_files)) in own_sizes {
+            let Some(root) = included_directories
+                .iter()
+                .filter(|root| directory.starts_with(root))
+                .max_by_key(|root| root.components().count())
+            else {
+                continue;
+            };
+            let root_depth = root.components().count();
+
+            let stats = directory_stats.entry(directory.to_path_buf()).or_default();
+            stats.own_size += own_size;
+            stats.own_files += own_files;
+            for ancestor in directory.ancestors() {
+                let stats = directory_stats.entry(ancestor.to_path_buf()).or_default();
+                stats.depth = ancestor.components().count() - root_depth;
+                stats.size += own_size;
+                stats.files += own_files;
+                if ancestor == root {
+                    break;
+                }
+            }
+        }
+
+        let mut directories: Vec<DirectoryEntry> = directory_stats
+            .iter()
+            .map(|(path, stats)| DirectoryEntry {
+                path: path.clone(),
+                depth: stats.depth,
+                size: stats.size,
+                files: stats.files,
+            })
+            .collect();
+        directories.sort_unstable_by(|a, b| a.depth.cmp(&b.depth).then(b.size.cmp(&a.size)).then_with(|| a.path.cmp(&b.path)));
+        self.biggest_directories = directories
+            .chunk_by(|a, b| a.depth == b.depth)
+            .flat_map(|same_depth| same_depth.iter().take(directories_per_depth).cloned())
+            .collect();
+        self.directory_stats = directory_stats;
+        debug!("calculate_directory_sizes - Found {} directories", self.directory_stats.len());
+    }
+
+    /// Whole directory tree, available only when directory sizes were calculated, with more searched directories they are put into one unnamed node
+    pub fn get_directory_treemap(&self) -> Option<TreemapNode> {
+        let mut children: HashMap<&Path, Vec<&Path>> = HashMap::new();
+        for (path, stats) in &self.directory_stats {
+            if stats.depth > 0
+                && let Some(parent) = path.parent()
+            {
+                children.entry(parent).or_default().push(path);
+            }
+        }
+
+        let mut roots: Vec<TreemapNode> = self
+            .directory_stats
+            .iter()
+            .filter(|(_, stats)| stats.depth == 0)
+            .map(|(path, _)| self.create_treemap_node(path, &children))
+            .collect();
+        if roots.len() <= 1 {
+            return roots.pop();
+        }
+        Some(TreemapNode {
+            name: String::new(),
+            path: PathBuf::new(),
+            value: 0,
+            size: roots.iter().map(|node| node.size).sum(),
+            files: roots.iter().map(|node| node.files).sum(),
+            children: roots,
+        })
+    }
+
+    fn create_treemap_node(&self, path: &Path, children: &HashMap<&Path, Vec<&Path>>) -> TreemapNode {
+        let stats = self.directory_stats.get(path).copied().unwrap_or_default();
+        let mut child_nodes: Vec<TreemapNode> = children
+            .get(path)
+            .map(|child_paths| child_paths.iter().map(|child_path| self.create_treemap_node(child_path, children)).collect())
+            .unwrap_or_default();
+        child_nodes.sort_unstable_by_key(|node| Reverse(node.size));
+
+        TreemapNode {
+            name: path
+                .file_name()
+                .map_or_else(|| path.to_string_lossy().to_string(), |name| name.to_string_lossy().to_string()),
+            path: path.to_path_buf(),
+            value: stats.own_size,
+            size: stats.size,
+            files: stats.files,
+            children: child_nodes,
+        }
+    }
+
+    pub fn save_directory_treemap_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+        let treemap = self.get_directory_treemap().ok_or_else(|| std::io::Error::other("Directory sizes were not calculated"))?;
+        self.save_results_to_file_as_json_internal(file_name, &treemap, pretty_print)
+    }
 }
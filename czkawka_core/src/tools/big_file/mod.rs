@@ -3,8 +3,12 @@ pub mod core;
 mod tests;
 pub mod traits;
 
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
+use serde::Serialize;
+
 use crate::common::model::FileEntry;
 use crate::common::tool_data::CommonToolData;
 
@@ -24,6 +28,8 @@ pub struct Info {
 pub struct BigFileParameters {
     pub number_of_files_to_check: usize,
     pub search_mode: SearchMode,
+    // When set, file sizes are summed up through directory tree and this many heaviest directories are kept at each depth
+    pub directories_per_depth: Option<usize>,
 }
 
 impl BigFileParameters {
@@ -31,14 +37,55 @@ impl BigFileParameters {
         Self {
             number_of_files_to_check: number_of_files.max(1),
             search_mode,
+            directories_per_depth: None,
         }
     }
+
+    #[must_use]
+    pub fn with_directory_sizes(mut self, directories_per_depth: usize) -> Self {
+        self.directories_per_depth = Some(directories_per_depth.max(1));
+        self
+    }
+}
+
+/// Directory with summed sizes of all files inside it, also in subdirectories
+#[derive(Clone, Debug, Serialize)]
+pub struct DirectoryEntry {
+    pub path: PathBuf,
+    // 0 for searched directory, 1 for its direct subdirectories etc.
+    pub depth: usize,
+    pub size: u64,
+    pub files: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct DirectoryStats {
+    depth: usize,
+    // Files placed directly in directory
+    own_size: u64,
+    own_files: usize,
+    size: u64,
+    files: usize,
+}
+
+/// Node of directory tree in format used by treemap viewers (e.g. d3 `hierarchy(...).sum(d => d.value)`),
+/// `value` contains only size of files placed directly in directory, `size` is size of the whole subtree
+#[derive(Clone, Debug, Serialize)]
+pub struct TreemapNode {
+    pub name: String,
+    pub path: PathBuf,
+    pub value: u64,
+    pub size: u64,
+    pub files: usize,
+    pub children: Vec<Self>,
 }
 
 pub struct BigFile {
     common_data: CommonToolData,
     information: Info,
     big_files: Vec<FileEntry>,
+    directory_stats: BTreeMap<PathBuf, DirectoryStats>,
+    biggest_directories: Vec<DirectoryEntry>,
     params: BigFileParameters,
 }
 
@@ -46,4 +93,9 @@ impl BigFile {
     pub const fn get_big_files(&self) -> &Vec<FileEntry> {
         &self.big_files
     }
+
+    /// Heaviest directories at each depth, sorted by depth and then by size
+    pub const fn get_biggest_directories(&self) -> &Vec<DirectoryEntry> {
+        &self.biggest_directories
+    }
 }
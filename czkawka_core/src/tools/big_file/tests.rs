@@ -91,3 +91,66 @@ fn test_empty_directory() {
     let big_files = finder.get_big_files();
     assert!(big_files.is_empty(), "Should find no files in empty directory");
 }
+
+#[test]
+fn test_directory_sizes() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    fs::create_dir_all(path.join("a").join("deep")).unwrap();
+    fs::create_dir_all(path.join("b")).unwrap();
+    fs::create_dir_all(path.join("c")).unwrap();
+    fs::write(path.join("root.txt"), vec![b'A'; 10]).unwrap();
+    fs::write(path.join("a").join("file.txt"), vec![b'A'; 20]).unwrap();
+    fs::write(path.join("a").join("deep").join("file.txt"), vec![b'A'; 300]).unwrap();
+    fs::write(path.join("a").join("deep").join("empty.txt"), b"").unwrap();
+    fs::write(path.join("b").join("file.txt"), vec![b'A'; 100]).unwrap();
+    fs::write(path.join("c").join("file.txt"), vec![b'A'; 1]).unwrap();
+
+    let params = BigFileParameters::new(10, SearchMode::BiggestFiles).with_directory_sizes(2);
+    let mut finder = BigFile::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+
+    // Empty file is only counted in directories
+    assert_eq!(finder.get_big_files().len(), 5);
+
+    let directories: Vec<_> = finder.get_biggest_directories().iter().map(|e| (e.path.clone(), e.depth, e.size, e.files)).collect();
+    assert_eq!(
+        directories,
+        vec![
+            (path.to_path_buf(), 0, 431, 6),
+            (path.join("a"), 1, 320, 3),
+            (path.join("b"), 1, 100, 1),
+            (path.join("a").join("deep"), 2, 300, 2),
+        ]
+    );
+
+    let treemap = finder.get_directory_treemap().unwrap();
+    assert_eq!((treemap.value, treemap.size, treemap.files), (10, 431, 6));
+    let children: Vec<_> = treemap.children.iter().map(|node| (node.name.as_str(), node.value, node.size)).collect();
+    assert_eq!(children, vec![("a", 20, 320), ("b", 100, 100), ("c", 1, 1)]);
+    assert_eq!(treemap.children[0].children[0].name, "deep");
+
+    let json_path = path.join("treemap.json");
+    finder.save_directory_treemap_as_json(&json_path.to_string_lossy(), false).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(json["children"][0]["children"][0]["value"], 300);
+}
+
+#[test]
+fn test_directory_sizes_disabled() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    fs::write(path.join("file.txt"), b"12").unwrap();
+
+    let mut finder = BigFile::new(BigFileParameters::new(10, SearchMode::BiggestFiles));
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+
+    assert!(finder.get_biggest_directories().is_empty());
+    assert!(finder.get_directory_treemap().is_none());
+}
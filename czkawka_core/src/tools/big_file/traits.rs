@@ -64,6 +64,25 @@ impl PrintResults for BigFile {
             writeln!(writer, "Not found any files.")?;
         }
 
+        if !self.biggest_directories.is_empty() {
+            writeln!(writer, "\nThe biggest directories at each depth.")?;
+            let mut current_depth = None;
+            for directory in &self.biggest_directories {
+                if current_depth != Some(directory.depth) {
+                    current_depth = Some(directory.depth);
+                    writeln!(writer, "\nDepth {}:", directory.depth)?;
+                }
+                writeln!(
+                    writer,
+                    "{} ({}) - {} files - \"{}\"",
+                    format_size(directory.size, BINARY),
+                    directory.size,
+                    directory.files,
+                    directory.path.to_string_lossy()
+                )?;
+            }
+        }
+
         Ok(())
     }
 
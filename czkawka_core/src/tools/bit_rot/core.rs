@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::mem;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use log::debug;
+use rayon::prelude::*;
+
+use crate::common::cache::load_cache_from_file_generalized_by_path;
+use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
+use crate::common::model::{ToolType, WorkContinueStatus};
+use crate::common::progress_data::{BitRotStage, CacheLoadPhase, ProgressData, ToolStage};
+use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
+use crate::common::tool_data::{CommonData, CommonToolData};
+use crate::flc;
+use crate::tools::bit_rot::{BitRot, BitRotEntry, BitRotParameters, Info};
+use crate::tools::duplicate::core::get_duplicate_cache_file;
+use crate::tools::duplicate::{DuplicateEntry, THREAD_BUFFER, hash_calculation};
+
+impl BitRot {
+    pub fn new(params: BitRotParameters) -> Self {
+        Self {
+            common_data: CommonToolData::new(ToolType::BitRot),
+            information: Info::default(),
+            files_to_check: Default::default(),
+            corrupted_files: Vec::new(),
+            params,
+        }
+    }
+
+    #[fun_time(message = "find_files_to_verify", level = "debug")]
+    pub(crate) fn find_files_to_verify(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        let result = DirTraversalBuilder::new()
+            .common_data(&self.common_data)
+            .group_by(|_fe| ())
+            .stop_flag(stop_flag)
+            .progress_sender(progress_sender)
+            .build()
+            .run();
+
+        match result {
+            DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } => {
+                self.files_to_check = grouped_file_entries.into_values().flatten().map(|fe| (fe.path.to_string_lossy().to_string(), fe)).collect();
+
+                self.common_data.text_messages.warnings.extend(warnings);
+                debug!("find_files_to_verify - Found {} files to check.", self.files_to_check.len());
+
+                WorkContinueStatus::Continue
+            }
+
+            DirTraversalResult::Stopped => WorkContinueStatus::Stop,
+        }
+    }
+
+    // Returns files whose size and modification date are the same as in hash cache
+    #[fun_time(message = "load_unchanged_cached_files", level = "debug")]
+    fn load_unchanged_cached_files(&mut self, progress_sender: Option<&Sender<ProgressData>>) -> Vec<DuplicateEntry> {
+        let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::BitRot(BitRotStage::LoadingCache(CacheLoadPhase::Loading)), 0, 0);
+
+        let used_files: BTreeMap<String, DuplicateEntry> = mem::take(&mut self.files_to_check)
+            .into_iter()
+            .map(|(name, fe)| {
+                let entry = DuplicateEntry {
+                    path: fe.path,
+                    modified_date: fe.modified_date,
+                    size: fe.size,
+                    hash: String::new(),
+                };
+                (name, entry)
+            })
+            .collect();
+
+        let cache_file_name = get_duplicate_cache_file(self.params.hash_type, false);
+        // Outdated entries must not be removed here - this tool only reads cache created by duplicate finder
+        let (messages, loaded_items) = load_cache_from_file_generalized_by_path::<DuplicateEntry>(&cache_file_name, false, &used_files);
+        self.get_text_messages_mut().extend_with_another_messages(messages);
+
+        progress_handler.join_thread();
+
+        let Some(loaded_items) = loaded_items else {
+            self.common_data
+                .text_messages
+                .warnings
+                .push(flc!("core_bit_rot_missing_hash_cache", hash_type = format!("{:?}", self.params.hash_type)));
+            self.information.number_of_files_not_in_cache = used_files.len();
+            return Vec::new();
+        };
+
+        // Entries with changed size or modification date are already filtered out while loading cache
+        let mut unchanged_files = Vec::new();
+        for (name, file_entry) in used_files {
+            match loaded_items.get(&name) {
+                Some(cached_entry) if !cached_entry.hash.is_empty() => unchanged_files.push(cached_entry.clone()),
+                _ => {
+                    debug!("File \"{}\" is not available in hash cache or was modified", file_entry.path.to_string_lossy());
+                    self.information.number_of_files_not_in_cache += 1;
+                }
+            }
+        }
+
+        unchanged_files
+    }
+
+    #[fun_time(message = "verify_hashes", level = "debug")]
+    pub(crate) fn verify_hashes(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        if self.files_to_check.is_empty() {
+            return WorkContinueStatus::Continue;
+        }
+
+        let unchanged_files = self.load_unchanged_cached_files(progress_sender);
+        if check_if_stop_received(stop_flag) {
+            return WorkContinueStatus::Stop;
+        }
+
+        let progress_handler = prepare_thread_handler_common(
+            progress_sender,
+            ToolStage::BitRot(BitRotStage::VerifyingHashes),
+            unchanged_files.len(),
+            unchanged_files.iter().map(|e| e.size).sum::<u64>(),
+        );
+
+        let hash_type = self.params.hash_type;
+        debug!("verify_hashes - started rehashing {} files", unchanged_files.len());
+        let results: Vec<(Option<BitRotEntry>, Option<String>)> = unchanged_files
+            .into_par_iter()
+            .map(|cached_entry| {
+                if check_if_stop_received(stop_flag) {
+                    return None;
+                }
+
+                let result = THREAD_BUFFER.with_borrow_mut(|buffer| hash_calculation(buffer, &cached_entry, hash_type, progress_handler.size_counter(), stop_flag));
+                progress_handler.increase_items(1);
+
+                match result {
+                    Ok(current_hash) => {
+                        let current_hash = current_hash?;
+                        if current_hash == cached_entry.hash {
+                            return Some((None, None));
+                        }
+                        let bit_rot_entry = BitRotEntry {
+                            path: cached_entry.path,
+                            size: cached_entry.size,
+                            modified_date: cached_entry.modified_date,
+                            cached_hash: cached_entry.hash,
+                            current_hash,
+                        };
+                        Some((Some(bit_rot_entry), None))
+                    }
+                    Err(e) => Some((None, Some(e))),
+                }
+            })
+            .while_some()
+            .collect();
+        debug!("verify_hashes - finished rehashing files");
+
+        progress_handler.join_thread();
+
+        if check_if_stop_received(stop_flag) {
+            return WorkContinueStatus::Stop;
+        }
+
+        self.information.number_of_checked_files = results.len();
+        for (bit_rot_entry, error) in results {
+            if let Some(bit_rot_entry) = bit_rot_entry {
+                self.corrupted_files.push(bit_rot_entry);
+            }
+            if let Some(error) = error {
+                self.common_data.text_messages.warnings.push(error);
+            }
+        }
+        self.corrupted_files.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        self.information.number_of_corrupted_files = self.corrupted_files.len();
+
+        WorkContinueStatus::Continue
+    }
+}
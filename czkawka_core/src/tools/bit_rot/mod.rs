@@ -0,0 +1,76 @@
+pub mod core;
+#[cfg(test)]
+mod tests;
+pub mod traits;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::model::{FileEntry, HashType};
+use crate::common::tool_data::CommonToolData;
+use crate::common::traits::ResultEntry;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Info {
+    pub number_of_checked_files: usize,
+    pub number_of_files_not_in_cache: usize,
+    pub number_of_corrupted_files: usize,
+    pub scanning_time: Duration,
+}
+
+#[derive(Clone)]
+pub struct BitRotParameters {
+    // Must match hash type used by duplicate finder, because its full hash cache is reused here
+    pub hash_type: HashType,
+}
+
+impl BitRotParameters {
+    pub fn new(hash_type: HashType) -> Self {
+        Self { hash_type }
+    }
+}
+
+impl Default for BitRotParameters {
+    fn default() -> Self {
+        Self::new(HashType::Blake3)
+    }
+}
+
+// File whose content hash changed, while its size and modification date stayed the same
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BitRotEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified_date: u64,
+    pub cached_hash: String,
+    pub current_hash: String,
+}
+
+impl ResultEntry for BitRotEntry {
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+    fn get_modified_date(&self) -> u64 {
+        self.modified_date
+    }
+    fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+pub struct BitRot {
+    common_data: CommonToolData,
+    information: Info,
+    files_to_check: BTreeMap<String, FileEntry>,
+    corrupted_files: Vec<BitRotEntry>,
+    params: BitRotParameters,
+}
+
+impl BitRot {
+    pub const fn get_corrupted_files(&self) -> &Vec<BitRotEntry> {
+        &self.corrupted_files
+    }
+}
@@ -0,0 +1,116 @@
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Once};
+
+use filetime::FileTime;
+use tempfile::TempDir;
+
+use crate::common::config_cache_path::set_config_cache_path_test;
+use crate::common::model::{CheckingMethod, HashType};
+use crate::common::tool_data::CommonData;
+use crate::common::traits::Search;
+use crate::tools::bit_rot::{BitRot, BitRotParameters};
+use crate::tools::duplicate::{DuplicateFinder, DuplicateFinderParameters};
+
+static INIT: Once = Once::new();
+
+fn setup_cache_path() {
+    INIT.call_once(|| {
+        let temp_cache = TempDir::new().expect("Failed to create temp cache dir");
+        let temp_config = TempDir::new().expect("Failed to create temp config dir");
+        let cache_path = temp_cache.path().to_path_buf();
+        let config_path = temp_config.path().to_path_buf();
+        set_config_cache_path_test(cache_path, config_path);
+        std::mem::forget(temp_cache);
+        std::mem::forget(temp_config);
+    });
+}
+
+// Duplicate finder saves full hashes to cache only for files that have the same size
+fn fill_hash_cache(path: &Path, hash_type: HashType) {
+    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, hash_type, false, 0, 0, true);
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(true);
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+}
+
+fn overwrite_keeping_metadata(path: &Path, content: &[u8]) {
+    let metadata = fs::metadata(path).unwrap();
+    assert_eq!(metadata.len(), content.len() as u64, "Content must have the same size as the original file");
+    let modified = FileTime::from_last_modification_time(&metadata);
+    fs::write(path, content).unwrap();
+    filetime::set_file_mtime(path, modified).unwrap();
+}
+
+#[test]
+fn test_bit_rot_detects_changed_content_with_same_metadata() {
+    setup_cache_path();
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    fs::write(path.join("healthy.bin"), b"AAAAAAAAAAAAAAAA").unwrap();
+    fs::write(path.join("rotten.bin"), b"AAAAAAAAAAAAAAAA").unwrap();
+    fill_hash_cache(path, HashType::Xxh3);
+
+    overwrite_keeping_metadata(&path.join("rotten.bin"), b"AAAAAAAABAAAAAAA");
+
+    let mut finder = BitRot::new(BitRotParameters::new(HashType::Xxh3));
+    finder.set_included_paths(vec![path.to_path_buf()]);
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+
+    let info = finder.get_information();
+    assert_eq!(info.number_of_checked_files, 2, "Both files should be verified");
+    assert_eq!(info.number_of_corrupted_files, 1, "Only one file should be reported");
+
+    let corrupted = finder.get_corrupted_files();
+    assert_eq!(corrupted[0].path, path.join("rotten.bin"));
+    assert_ne!(corrupted[0].cached_hash, corrupted[0].current_hash);
+}
+
+#[test]
+fn test_bit_rot_ignores_files_with_changed_metadata() {
+    setup_cache_path();
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    fs::write(path.join("file1.bin"), b"BBBBBBBBBBBBBBBB").unwrap();
+    fs::write(path.join("file2.bin"), b"BBBBBBBBBBBBBBBB").unwrap();
+    fill_hash_cache(path, HashType::Crc32);
+
+    // Regular modification changes size, so file is no longer comparable with cache
+    fs::write(path.join("file2.bin"), b"CCCCCCCCCCCCCCCCCCCC").unwrap();
+    fs::write(path.join("not_cached.bin"), b"unique content").unwrap();
+
+    let mut finder = BitRot::new(BitRotParameters::new(HashType::Crc32));
+    finder.set_included_paths(vec![path.to_path_buf()]);
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+
+    let info = finder.get_information();
+    assert_eq!(info.number_of_checked_files, 1, "Only unchanged cached file should be verified");
+    assert_eq!(info.number_of_files_not_in_cache, 2, "Modified and new file should not be verified");
+    assert_eq!(info.number_of_corrupted_files, 0, "Regular modification is not bit rot");
+}
+
+#[test]
+fn test_bit_rot_empty_directory() {
+    setup_cache_path();
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut finder = BitRot::new(BitRotParameters::default());
+    finder.set_included_paths(vec![temp_dir.path().to_path_buf()]);
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+
+    assert!(finder.get_corrupted_files().is_empty());
+    assert!(!finder.found_any_items());
+}
@@ -0,0 +1,131 @@
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Instant;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use humansize::{BINARY, format_size};
+
+use crate::common::model::WorkContinueStatus;
+use crate::common::progress_data::ProgressData;
+use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
+use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
+use crate::tools::bit_rot::{BitRot, BitRotParameters, Info};
+
+impl AllTraits for BitRot {}
+
+impl DeletingItems for BitRot {
+    #[fun_time(message = "delete_files", level = "debug")]
+    fn delete_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        match self.common_data.delete_method {
+            DeleteMethod::Delete => self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFiles(self.corrupted_files.clone())),
+            DeleteMethod::None => WorkContinueStatus::Continue,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl DebugPrint for BitRot {
+    #[expect(clippy::print_stdout)]
+    fn debug_print(&self) {
+        if !cfg!(debug_assertions) || cfg!(test) {
+            return;
+        }
+
+        println!("### INDIVIDUAL DEBUG PRINT ###");
+        println!("Info: {:?}", self.information);
+        println!("Hash type - {:?}", self.params.hash_type);
+        self.debug_print_common();
+        println!("-----------------------------------------");
+    }
+}
+
+impl PrintResults for BitRot {
+    fn write_results<T: Write>(&self, writer: &mut T) -> std::io::Result<()> {
+        self.write_base_search_paths(writer)?;
+
+        writeln!(
+            writer,
+            "Verified {} files with unchanged size and modification date, {} files were not available in {:?} hash cache.\n",
+            self.information.number_of_checked_files, self.information.number_of_files_not_in_cache, self.params.hash_type
+        )?;
+
+        if self.information.number_of_corrupted_files != 0 {
+            writeln!(
+                writer,
+                "Found {} files with changed content but unchanged metadata.\n",
+                self.information.number_of_corrupted_files
+            )?;
+            for file_entry in &self.corrupted_files {
+                writeln!(
+                    writer,
+                    "\"{}\" - {} - cached hash {} - current hash {}",
+                    file_entry.path.to_string_lossy(),
+                    format_size(file_entry.size, BINARY),
+                    file_entry.cached_hash,
+                    file_entry.current_hash
+                )?;
+            }
+        } else {
+            writeln!(writer, "Not found any files with changed content.")?;
+        }
+
+        Ok(())
+    }
+
+    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+        self.save_results_to_file_as_json_internal(file_name, &self.corrupted_files, pretty_print)
+    }
+}
+
+impl Search for BitRot {
+    #[fun_time(message = "find_bit_rot", level = "info")]
+    fn search(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) {
+        let start_time = Instant::now();
+
+        let () = (|| {
+            if self.prepare_items(None).is_err() {
+                return;
+            }
+            if self.find_files_to_verify(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+                return;
+            }
+            if self.verify_hashes(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+                return;
+            }
+            if self.delete_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+            }
+        })();
+
+        self.information.scanning_time = start_time.elapsed();
+
+        if !self.common_data.stopped_search {
+            self.debug_print();
+        }
+    }
+}
+
+impl CommonData for BitRot {
+    type Info = Info;
+    type Parameters = BitRotParameters;
+
+    fn get_information(&self) -> Self::Info {
+        self.information
+    }
+    fn get_params(&self) -> Self::Parameters {
+        self.params.clone()
+    }
+    fn get_cd(&self) -> &CommonToolData {
+        &self.common_data
+    }
+    fn get_cd_mut(&mut self) -> &mut CommonToolData {
+        &mut self.common_data
+    }
+    fn found_any_items(&self) -> bool {
+        self.information.number_of_corrupted_files > 0
+    }
+}
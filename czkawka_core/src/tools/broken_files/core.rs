@@ -25,8 +25,10 @@ use crate::common::progress_data::{ProgressData, ToolStage};
 use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
 use crate::common::tool_data::CommonToolData;
 use crate::common::{create_crash_message_generic, normalize_error_string};
+use crate::flc;
 use crate::helpers::audio_checker;
-use crate::tools::broken_files::{BrokenEntry, BrokenFiles, BrokenFilesParameters, CheckedTypes, CheckedTypesSingle, Info, TypeOfFile};
+use crate::tools::broken_files::repair::repair_broken_file;
+use crate::tools::broken_files::{BrokenEntry, BrokenFiles, BrokenFilesFixParams, BrokenFilesParameters, CheckedTypes, CheckedTypesSingle, Info, RepairReport, TypeOfFile};
 
 impl BrokenFiles {
     pub fn new(params: BrokenFilesParameters) -> Self {
@@ -35,6 +37,7 @@ impl BrokenFiles {
             information: Info::default(),
             files_to_check: Default::default(),
             broken_files: Default::default(),
+            repair_reports: Vec::new(),
             params,
         }
     }
@@ -567,10 +570,48 @@ impl BrokenFiles {
 
         WorkContinueStatus::Continue
     }
+
+    #[fun_time(message = "fix_files", level = "debug")]
+    pub(crate) fn fix_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>, fix_params: BrokenFilesFixParams) {
+        let progress_handler = prepare_thread_handler_common(
+            progress_sender,
+            ToolStage::BrokenFilesRepairing,
+            self.broken_files.len(),
+            self.broken_files.iter().map(|entry| entry.size).sum(),
+        );
+
+        let repair_reports: Vec<RepairReport> = self
+            .broken_files
+            .par_iter()
+            .with_max_len(1)
+            .map(|entry| {
+                if check_if_stop_received(stop_flag) {
+                    return None;
+                }
+                let report = repair_broken_file(entry, fix_params.repaired_types, stop_flag);
+                progress_handler.increase_items(1);
+                progress_handler.increase_size(entry.size);
+                Some(report)
+            })
+            .while_some()
+            .flatten()
+            .collect();
+        progress_handler.join_thread();
+
+        for report in &repair_reports {
+            if let Some(error) = &report.error {
+                self.common_data
+                    .text_messages
+                    .warnings
+                    .push(flc!("core_failed_to_repair_file", file = report.path.to_string_lossy(), reason = error));
+            }
+        }
+        self.repair_reports = repair_reports;
+    }
 }
 
 #[expect(clippy::string_slice)] // Valid, because we address up to the dot, which is known ascii character
-fn check_extension_availability(full_name: &Path) -> Option<TypeOfFile> {
+pub(crate) fn check_extension_availability(full_name: &Path) -> Option<TypeOfFile> {
     let Some(file_name) = full_name.file_name() else {
         error!("Missing file name in file - \"{}\"", full_name.to_string_lossy());
         debug_assert!(false, "Missing file name in file - \"{}\"", full_name.to_string_lossy());
@@ -1,6 +1,7 @@
 use bitflags::bitflags;
 
 pub mod core;
+pub mod repair;
 #[cfg(test)]
 mod tests;
 pub mod traits;
@@ -105,6 +106,30 @@ bitflags! {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum RepairAction {
+    ExtractIntactMembers,
+    TruncateStream,
+    RemuxVideo,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct RepairReport {
+    pub path: PathBuf,
+    pub action: RepairAction,
+    // Recovered file or folder, set only when repair succeeded
+    pub output_path: Option<PathBuf>,
+    pub recovered_items: usize,
+    pub recovered_bytes: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct BrokenFilesFixParams {
+    // ARCHIVE enables archive extraction and stream truncation, any video flag enables remuxing with ffmpeg
+    pub repaired_types: CheckedTypes,
+}
+
 #[derive(Default, Clone, Copy)]
 pub struct Info {
     pub number_of_broken_files: usize,
@@ -127,6 +152,7 @@ pub struct BrokenFiles {
     information: Info,
     files_to_check: BTreeMap<String, BrokenEntry>,
     broken_files: Vec<BrokenEntry>,
+    repair_reports: Vec<RepairReport>,
     params: BrokenFilesParameters,
 }
 
@@ -135,6 +161,10 @@ impl BrokenFiles {
         &self.broken_files
     }
 
+    pub const fn get_repair_reports(&self) -> &Vec<RepairReport> {
+        &self.repair_reports
+    }
+
     pub(crate) fn get_params(&self) -> &BrokenFilesParameters {
         &self.params
     }
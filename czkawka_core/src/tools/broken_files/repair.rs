@@ -0,0 +1,919 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use lzma_rust2::Lzma2Reader;
+use ruzstd::decoding::errors::{FrameDecoderError, ReadFrameHeaderError};
+use ruzstd::decoding::{BlockDecodingStrategy, FrameDecoder};
+use sha2::{Digest, Sha256};
+
+use crate::common::normalize_error_string;
+use crate::common::process_utils::run_command_interruptible;
+use crate::flc;
+use crate::tools::broken_files::core::check_extension_availability;
+use crate::tools::broken_files::{BrokenEntry, CheckedTypes, RepairAction, RepairReport, TypeOfFile};
+
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+// Returns None when file cannot be repaired or when stop flag was set while repairing
+pub(crate) fn repair_broken_file(entry: &BrokenEntry, repaired_types: CheckedTypes, stop_flag: &Arc<AtomicBool>) -> Option<RepairReport> {
+    let file_type = check_extension_availability(&entry.path)?;
+    let action = match file_type {
+        TypeOfFile::ArchiveZip | TypeOfFile::ArchiveTar if repaired_types.contains(CheckedTypes::ARCHIVE) => RepairAction::ExtractIntactMembers,
+        TypeOfFile::ArchiveGz | TypeOfFile::ArchiveZst | TypeOfFile::ArchiveXz if repaired_types.contains(CheckedTypes::ARCHIVE) => RepairAction::TruncateStream,
+        TypeOfFile::Video if repaired_types.intersects(CheckedTypes::VIDEO_FFPROBE | CheckedTypes::VIDEO_FFMPEG) => RepairAction::RemuxVideo,
+        _ => return None,
+    };
+
+    let output_path = get_recovered_path(&entry.path, action);
+    let result = if output_path.exists() {
+        Err(flc!("core_repair_output_already_exists", path = output_path.to_string_lossy()))
+    } else {
+        match file_type {
+            TypeOfFile::ArchiveZip => extract_intact_zip_members(&entry.path, &output_path),
+            TypeOfFile::ArchiveTar => extract_intact_tar_members(&entry.path, &output_path),
+            TypeOfFile::ArchiveGz | TypeOfFile::ArchiveZst | TypeOfFile::ArchiveXz => truncate_stream(&entry.path, &output_path, file_type),
+            TypeOfFile::Video => remux_video(&entry.path, &output_path, stop_flag)?,
+            _ => unreachable!("Only files with repair action can be repaired"),
+        }
+    };
+
+    let mut report = RepairReport {
+        path: entry.path.clone(),
+        action,
+        output_path: None,
+        recovered_items: 0,
+        recovered_bytes: 0,
+        error: None,
+    };
+    match result {
+        Ok((recovered_items, recovered_bytes)) => {
+            report.output_path = Some(output_path);
+            report.recovered_items = recovered_items;
+            report.recovered_bytes = recovered_bytes;
+        }
+        Err(e) => report.error = Some(e),
+    }
+    Some(report)
+}
+
+// Original file is never touched - recovered data always lands next to it, e.g. "archive.zip" -> "archive.czkawka_recovered/"
+pub(crate) fn get_recovered_path(path: &Path, action: RepairAction) -> PathBuf {
+    match (action, path.extension()) {
+        (RepairAction::ExtractIntactMembers, _) | (_, None) => path.with_extension("czkawka_recovered"),
+        (_, Some(extension)) => path.with_extension(format!("czkawka_recovered.{}", extension.to_string_lossy())),
+    }
+}
+
+// Returns (extracted members, extracted bytes)
+fn extract_intact_zip_members(path: &Path, output_folder: &Path) -> Result<(usize, u64), String> {
+    let file = File::open(path).map_err(|e| normalize_error_string(&e.to_string()))?;
+    let mut saver = RecoveredMembersSaver::new(output_folder);
+
+    match zip::ZipArchive::new(BufReader::new(file)) {
+        Ok(mut archive) => {
+            for idx in 0..archive.len() {
+                // Damaged or encrypted members are skipped, rest of the archive is still usable thanks to central directory
+                let Ok(mut member) = archive.by_index(idx) else {
+                    continue;
+                };
+                if member.is_dir() {
+                    continue;
+                }
+                let Some(member_path) = member.enclosed_name() else {
+                    continue;
+                };
+                // CRC is checked after reading whole member, so damaged member is removed only after writing it
+                saver.save(&member_path, &mut member, None)?;
+            }
+        }
+        Err(_) => {
+            // Central directory is damaged, so local headers are read one after another until first damaged member
+            let file = File::open(path).map_err(|e| normalize_error_string(&e.to_string()))?;
+            let mut reader = BufReader::new(file);
+            while let Ok(Some(mut member)) = zip::read::read_zipfile_from_stream(&mut reader) {
+                if member.is_dir() {
+                    continue;
+                }
+                let Some(member_path) = member.enclosed_name() else {
+                    continue;
+                };
+                if saver.save(&member_path, &mut member, None)? == MemberSaveResult::Damaged {
+                    break;
+                }
+            }
+        }
+    }
+
+    saver.finish()
+}
+
+// Returns (extracted members, extracted bytes)
+fn extract_intact_tar_members(path: &Path, output_folder: &Path) -> Result<(usize, u64), String> {
+    let file = File::open(path).map_err(|e| normalize_error_string(&e.to_string()))?;
+    let mut archive = tar::Archive::new(BufReader::new(file));
+    let entries = archive.entries().map_err(|e| normalize_error_string(&e.to_string()))?;
+    let mut saver = RecoveredMembersSaver::new(output_folder);
+
+    // Tar has no index, so after first damaged header, position of next member is unknown
+    for entry in entries {
+        let Ok(mut entry) = entry else {
+            break;
+        };
+        if entry.header().entry_type() != tar::EntryType::Regular {
+            continue;
+        }
+        let Some(member_path) = entry.path().ok().and_then(|member_path| sanitize_member_path(&member_path)) else {
+            continue;
+        };
+        // Truncated member is read without error, but with less data than declared in header
+        let expected_size = entry.size();
+        if saver.save(&member_path, &mut entry, Some(expected_size))? == MemberSaveResult::Damaged {
+            break;
+        }
+    }
+
+    saver.finish()
+}
+
+// Only plain relative paths are allowed, so damaged archive cannot write outside output folder
+fn sanitize_member_path(member_path: &Path) -> Option<PathBuf> {
+    let mut sanitized = PathBuf::new();
+    for component in member_path.components() {
+        match component {
+            Component::Normal(part) => sanitized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    if sanitized.as_os_str().is_empty() { None } else { Some(sanitized) }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MemberSaveResult {
+    Saved,
+    Skipped,
+    Damaged,
+}
+
+// Members are streamed one by one straight into output folder, which is created just before saving first member
+struct RecoveredMembersSaver<'a> {
+    output_folder: &'a Path,
+    folder_created: bool,
+    recovered_items: usize,
+    recovered_bytes: u64,
+}
+
+impl<'a> RecoveredMembersSaver<'a> {
+    fn new(output_folder: &'a Path) -> Self {
+        Self {
+            output_folder,
+            folder_created: false,
+            recovered_items: 0,
+            recovered_bytes: 0,
+        }
+    }
+
+    fn save<R: Read>(&mut self, member_path: &Path, member: &mut R, expected_size: Option<u64>) -> Result<MemberSaveResult, String> {
+        if !self.folder_created {
+            // Fails if folder already exists, so no existing data can be overwritten
+            fs::create_dir(self.output_folder).map_err(|e| normalize_error_string(&e.to_string()))?;
+            self.folder_created = true;
+        }
+
+        let member_output_path = self.output_folder.join(member_path);
+        if let Some(parent) = member_output_path.parent()
+            && let Err(e) = fs::create_dir_all(parent)
+        {
+            return Err(self.remove_after_error(&e));
+        }
+        // Archive may contain multiple members with same name - only first one is saved
+        let Ok(file) = OpenOptions::new().write(true).create_new(true).open(&member_output_path) else {
+            return Ok(MemberSaveResult::Skipped);
+        };
+
+        let mut writer = BufWriter::new(file);
+        let copy_result = copy_until_read_error(member, &mut writer).and_then(|copy_result| writer.flush().map(|()| copy_result));
+        drop(writer);
+        match copy_result {
+            Ok((copied_bytes, true)) if expected_size.is_none_or(|expected_size| expected_size == copied_bytes) => {
+                self.recovered_items += 1;
+                self.recovered_bytes += copied_bytes;
+                Ok(MemberSaveResult::Saved)
+            }
+            Ok(_) => {
+                let _ = fs::remove_file(&member_output_path);
+                Ok(MemberSaveResult::Damaged)
+            }
+            Err(e) => Err(self.remove_after_error(&e)),
+        }
+    }
+
+    // Failed write means that output is not usable(e.g. disk is full), so everything saved so far is removed
+    fn remove_after_error(&mut self, error: &io::Error) -> String {
+        let _ = fs::remove_dir_all(self.output_folder);
+        self.folder_created = false;
+        normalize_error_string(&error.to_string())
+    }
+
+    fn finish(self) -> Result<(usize, u64), String> {
+        if self.recovered_items == 0 {
+            if self.folder_created {
+                let _ = fs::remove_dir_all(self.output_folder);
+            }
+            return Err(flc!("core_repair_nothing_recovered"));
+        }
+        Ok((self.recovered_items, self.recovered_bytes))
+    }
+}
+
+// Intact part of the stream is copied byte by byte and only its end is rewritten, so the result is valid stream again.
+// Returns (1, decompressed bytes of the result)
+fn truncate_stream(path: &Path, output_path: &Path, file_type: TypeOfFile) -> Result<(usize, u64), String> {
+    match file_type {
+        TypeOfFile::ArchiveGz => truncate_gz(path, output_path),
+        TypeOfFile::ArchiveZst => truncate_zst(path, output_path),
+        TypeOfFile::ArchiveXz => truncate_xz(path, output_path),
+        _ => unreachable!("Only compressed streams can be truncated"),
+    }
+}
+
+// Deflate blocks are not aligned to bytes, so only boundaries between gzip members are safe places to cut the stream.
+// Intact members are copied as is, readable part of the first damaged member is compressed again as the last member
+fn truncate_gz(path: &Path, output_path: &Path) -> Result<(usize, u64), String> {
+    let file = File::open(path).map_err(|e| normalize_error_string(&e.to_string()))?;
+    let mut reader = PositionTrackingReader::new(BufReader::new(file));
+    let mut intact_end = 0;
+    let mut recovered_bytes = 0;
+    while reader.has_data_left() {
+        // Checksum and size of member are verified after decompressing it whole
+        let member_result = {
+            let mut decoder = flate2::bufread::GzDecoder::new(&mut reader);
+            io::copy(&mut decoder, &mut io::sink())
+        };
+        let Ok(member_size) = member_result else {
+            break;
+        };
+        intact_end = reader.position;
+        recovered_bytes += member_size;
+    }
+
+    let mut source = File::open(path).map_err(|e| normalize_error_string(&e.to_string()))?;
+    source.seek(SeekFrom::Start(intact_end)).map_err(|e| normalize_error_string(&e.to_string()))?;
+    let mut damaged_member_decoder = flate2::read::GzDecoder::new(BufReader::new(source));
+    let mut first_chunk = vec![0u8; READ_BUFFER_SIZE];
+    let first_chunk_size = damaged_member_decoder.read(&mut first_chunk).unwrap_or(0);
+    if intact_end == 0 && first_chunk_size == 0 {
+        return Err(flc!("core_repair_nothing_recovered"));
+    }
+
+    let mut source = File::open(path).map_err(|e| normalize_error_string(&e.to_string()))?;
+    let mut damaged_member_bytes = 0;
+    write_output_file(output_path, |writer| {
+        copy_file_range(&mut source, writer, 0, intact_end)?;
+        if first_chunk_size > 0 {
+            let mut encoder = GzEncoder::new(writer, Compression::default());
+            encoder.write_all(first_chunk.get(..first_chunk_size).unwrap_or_default())?;
+            let (copied_bytes, _fully_read) = copy_until_read_error(&mut damaged_member_decoder, &mut encoder)?;
+            encoder.finish()?;
+            damaged_member_bytes = first_chunk_size as u64 + copied_bytes;
+        }
+        Ok(())
+    })?;
+
+    Ok((1, recovered_bytes + damaged_member_bytes))
+}
+
+const ZSTD_LAST_BLOCK_FLAG: u8 = 0x01;
+const ZSTD_BLOCK_HEADER_SIZE: u64 = 3;
+
+// Positions are counted from the start of the file
+struct DamagedZstdFrame {
+    frame_start: u64,
+    header_end: u64,
+    last_block_start: u64,
+    last_block_end: u64,
+}
+
+enum ZstdFrameScan {
+    Skippable,
+    // Decompressed size of the frame
+    Complete(u64),
+    // None when not a single block of the frame is intact
+    Damaged(Option<DamagedZstdFrame>),
+}
+
+// Zstd blocks are aligned to bytes, so frame can be cut right after its last intact block.
+// Intact frames are copied as is and damaged one gets new header(without checksum and content size) and its last intact block is marked as the final one
+fn truncate_zst(path: &Path, output_path: &Path) -> Result<(usize, u64), String> {
+    let file = File::open(path).map_err(|e| normalize_error_string(&e.to_string()))?;
+    let mut reader = PositionTrackingReader::new(BufReader::new(file));
+    let mut decoder = FrameDecoder::new();
+    let mut intact_end = 0;
+    let mut recovered_bytes = 0;
+    let mut damaged_frame = None;
+    while reader.has_data_left() {
+        match scan_zstd_frame(&mut decoder, &mut reader) {
+            ZstdFrameScan::Skippable => intact_end = reader.position,
+            ZstdFrameScan::Complete(content_size) => {
+                intact_end = reader.position;
+                recovered_bytes += content_size;
+            }
+            ZstdFrameScan::Damaged(frame) => {
+                damaged_frame = frame;
+                break;
+            }
+        }
+    }
+    if intact_end == 0 && damaged_frame.is_none() {
+        return Err(flc!("core_repair_nothing_recovered"));
+    }
+
+    let mut source = File::open(path).map_err(|e| normalize_error_string(&e.to_string()))?;
+    write_output_file(output_path, |writer| {
+        copy_file_range(&mut source, writer, 0, intact_end)?;
+        if let Some(frame) = &damaged_frame {
+            write_truncated_zstd_frame(&mut source, writer, frame)?;
+        }
+        Ok(())
+    })?;
+
+    // Decompressed size of truncated frame is known only after decoding it again, which also verifies rewritten frame
+    if damaged_frame.is_some() {
+        let truncated_frame_scan = File::open(output_path).and_then(|mut output_file| {
+            output_file.seek(SeekFrom::Start(intact_end))?;
+            Ok(scan_zstd_frame(&mut decoder, &mut PositionTrackingReader::new(BufReader::new(output_file))))
+        });
+        match truncated_frame_scan {
+            Ok(ZstdFrameScan::Complete(content_size)) => recovered_bytes += content_size,
+            _ => {
+                let _ = fs::remove_file(output_path);
+                return Err(flc!("core_repair_nothing_recovered"));
+            }
+        }
+    }
+
+    Ok((1, recovered_bytes))
+}
+
+fn scan_zstd_frame<R: BufRead>(decoder: &mut FrameDecoder, reader: &mut PositionTrackingReader<R>) -> ZstdFrameScan {
+    let frame_start = reader.position;
+    match decoder.init(&mut *reader) {
+        Ok(()) => {}
+        // Content of skippable frames is not checked by anything, so it is enough that it is complete
+        Err(FrameDecoderError::ReadFrameHeaderError(ReadFrameHeaderError::SkipFrame { length, .. })) => {
+            let length = u64::from(length);
+            return match io::copy(&mut reader.by_ref().take(length), &mut io::sink()) {
+                Ok(skipped) if skipped == length => ZstdFrameScan::Skippable,
+                _ => ZstdFrameScan::Damaged(None),
+            };
+        }
+        Err(_) => return ZstdFrameScan::Damaged(None),
+    }
+
+    let header_end = frame_start + decoder.bytes_read_from_source();
+    let mut last_block = None;
+    let mut content_size = 0;
+    loop {
+        let block_start = frame_start + decoder.bytes_read_from_source();
+        match decoder.decode_blocks(&mut *reader, BlockDecodingStrategy::UptoBlocks(1)) {
+            Ok(finished) => {
+                // Until frame is finished, only data outside of window is collected, so memory usage stays limited
+                let Ok(collected_bytes) = decoder.collect_to_writer(io::sink()) else {
+                    break;
+                };
+                content_size += collected_bytes as u64;
+                if finished {
+                    // Some block was decoded into wrong data, but it is not possible to find which one
+                    if decoder.get_checksum_from_data().is_some_and(|checksum| Some(checksum) != decoder.get_calculated_checksum()) {
+                        return ZstdFrameScan::Damaged(None);
+                    }
+                    return ZstdFrameScan::Complete(content_size);
+                }
+                last_block = Some((block_start, frame_start + decoder.bytes_read_from_source()));
+            }
+            // All blocks are intact, only checksum at the end of frame is missing
+            Err(FrameDecoderError::FailedToReadChecksum(_)) => {
+                last_block = Some((block_start, frame_start + decoder.bytes_read_from_source()));
+                break;
+            }
+            Err(_) => break,
+        }
+    }
+
+    ZstdFrameScan::Damaged(last_block.map(|(last_block_start, last_block_end)| DamagedZstdFrame {
+        frame_start,
+        header_end,
+        last_block_start,
+        last_block_end,
+    }))
+}
+
+fn write_truncated_zstd_frame<W: Write>(source: &mut File, writer: &mut W, frame: &DamagedZstdFrame) -> io::Result<()> {
+    let mut header = vec![0u8; usize::try_from(frame.header_end - frame.frame_start).unwrap_or_default()];
+    source.seek(SeekFrom::Start(frame.frame_start))?;
+    source.read_exact(&mut header)?;
+    let new_header = rewrite_zstd_frame_header(&header).ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?;
+    writer.write_all(&new_header)?;
+
+    copy_file_range(source, writer, frame.header_end, frame.last_block_start)?;
+    let mut block_header = [0u8; ZSTD_BLOCK_HEADER_SIZE as usize];
+    source.read_exact(&mut block_header)?;
+    block_header[0] |= ZSTD_LAST_BLOCK_FLAG;
+    writer.write_all(&block_header)?;
+    copy_file_range(source, writer, frame.last_block_start + ZSTD_BLOCK_HEADER_SIZE, frame.last_block_end)
+}
+
+// Content size and checksum from original header do not match truncated frame, so both are removed.
+// Single segment frames take window size from content size, so window size needs to be then written explicitly
+pub(crate) fn rewrite_zstd_frame_header(header: &[u8]) -> Option<Vec<u8>> {
+    let magic = header.get(..4)?;
+    let descriptor = *header.get(4)?;
+    let single_segment = descriptor & 0x20 != 0;
+    let dictionary_id_size = match descriptor & 0x03 {
+        0 => 0,
+        1 => 1,
+        2 => 2,
+        _ => 4,
+    };
+    let content_size_size = match descriptor >> 6 {
+        0 => usize::from(single_segment),
+        1 => 2,
+        2 => 4,
+        _ => 8,
+    };
+    let dictionary_id_start = 5 + usize::from(!single_segment);
+    let dictionary_id = header.get(dictionary_id_start..dictionary_id_start + dictionary_id_size)?;
+
+    let window_descriptor = if single_segment {
+        let content_size_start = dictionary_id_start + dictionary_id_size;
+        let content_size_bytes = header.get(content_size_start..content_size_start + content_size_size)?;
+        let mut content_size = content_size_bytes.iter().rev().fold(0u64, |size, byte| (size << 8) | u64::from(*byte));
+        if content_size_size == 2 {
+            content_size += 256;
+        }
+        // Window size is equal to 2^(10 + exponent), mantissa is left empty
+        let exponent = (0..=31u8).find(|exponent| 1u64 << (10 + exponent) >= content_size)?;
+        exponent << 3
+    } else {
+        *header.get(5)?
+    };
+
+    let mut new_header = Vec::with_capacity(header.len());
+    new_header.extend_from_slice(magic);
+    // Only dictionary id flag is kept
+    new_header.push(descriptor & 0x03);
+    new_header.push(window_descriptor);
+    new_header.extend_from_slice(dictionary_id);
+    Some(new_header)
+}
+
+const XZ_HEADER_MAGIC: &[u8] = &[0xFD, b'7', b'z', b'X', b'Z', 0x00];
+const XZ_FOOTER_MAGIC: &[u8] = b"YZ";
+const XZ_STREAM_HEADER_SIZE: usize = 12;
+const XZ_FILTER_LZMA2: u64 = 0x21;
+// The biggest dictionary which can be used by xz utils
+const XZ_MAX_DICT_SIZE: u32 = 1536 * 1024 * 1024;
+
+static CRC_64_XZ: crc::Crc<u64> = crc::Crc::<u64>::new(&crc::CRC_64_XZ);
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct XzBlockRecord {
+    pub(crate) unpadded_size: u64,
+    pub(crate) uncompressed_size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct XzBlockHeader {
+    pub(crate) compressed_size: Option<u64>,
+    pub(crate) uncompressed_size: Option<u64>,
+    pub(crate) dict_size: u32,
+}
+
+enum XzBlockScan {
+    Intact(XzBlockRecord),
+    IndexReached,
+    Damaged,
+}
+
+enum XzCheck {
+    NoCheck,
+    Crc32(crc32fast::Hasher),
+    Crc64(crc::Digest<'static, u64>),
+    Sha256(Sha256),
+}
+
+impl XzCheck {
+    // Only check types used by xz utils are supported
+    fn new(check_type: u8) -> Option<Self> {
+        match check_type {
+            0x00 => Some(Self::NoCheck),
+            0x01 => Some(Self::Crc32(crc32fast::Hasher::new())),
+            0x04 => Some(Self::Crc64(CRC_64_XZ.digest())),
+            0x0A => Some(Self::Sha256(Sha256::new())),
+            _ => None,
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::NoCheck => {}
+            Self::Crc32(hasher) => hasher.update(data),
+            Self::Crc64(digest) => digest.update(data),
+            Self::Sha256(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        match self {
+            Self::NoCheck => Vec::new(),
+            Self::Crc32(hasher) => hasher.finalize().to_le_bytes().to_vec(),
+            Self::Crc64(digest) => digest.finalize().to_le_bytes().to_vec(),
+            Self::Sha256(hasher) => hasher.finalize().to_vec(),
+        }
+    }
+}
+
+// Each xz block has its own check, so stream can be cut after the last block which decodes properly and matches it.
+// Blocks are copied as is, only index and footer are created again to describe remaining blocks
+fn truncate_xz(path: &Path, output_path: &Path) -> Result<(usize, u64), String> {
+    let file = File::open(path).map_err(|e| normalize_error_string(&e.to_string()))?;
+    let mut reader = PositionTrackingReader::new(BufReader::new(file));
+    let mut intact_end = 0;
+    let mut recovered_bytes = 0;
+    let mut stream_end: Option<Vec<u8>> = None;
+
+    // File may contain multiple streams, separated by padding made of zeros
+    while reader.has_data_left() {
+        let Some(stream_flags) = read_xz_stream_header(&mut reader) else {
+            break;
+        };
+        let check_type = stream_flags[1];
+
+        let mut records = Vec::new();
+        let mut blocks_end = reader.position;
+        let mut all_blocks_intact = false;
+        loop {
+            match read_xz_block(&mut reader, check_type) {
+                XzBlockScan::Intact(record) => {
+                    blocks_end = reader.position;
+                    records.push(record);
+                }
+                XzBlockScan::IndexReached => {
+                    all_blocks_intact = true;
+                    break;
+                }
+                XzBlockScan::Damaged => break,
+            }
+        }
+
+        let index = encode_xz_index(&records);
+        let footer = encode_xz_footer(index.len(), stream_flags);
+        let stream_bytes: u64 = records.iter().map(|record| record.uncompressed_size).sum();
+        // Index indicator was already read, so only rest of index and footer is compared with expected ones
+        if all_blocks_intact && read_equals(&mut reader, index.get(1..).unwrap_or_default()) && read_equals(&mut reader, &footer) {
+            intact_end = reader.position;
+            recovered_bytes += stream_bytes;
+            skip_xz_stream_padding(&mut reader);
+            continue;
+        }
+
+        if !records.is_empty() {
+            intact_end = blocks_end;
+            recovered_bytes += stream_bytes;
+            stream_end = Some([index, footer].concat());
+        }
+        break;
+    }
+    if intact_end == 0 {
+        return Err(flc!("core_repair_nothing_recovered"));
+    }
+
+    let mut source = File::open(path).map_err(|e| normalize_error_string(&e.to_string()))?;
+    write_output_file(output_path, |writer| {
+        copy_file_range(&mut source, writer, 0, intact_end)?;
+        if let Some(stream_end) = &stream_end {
+            writer.write_all(stream_end)?;
+        }
+        Ok(())
+    })?;
+
+    Ok((1, recovered_bytes))
+}
+
+// Returns stream flags
+fn read_xz_stream_header<R: BufRead>(reader: &mut PositionTrackingReader<R>) -> Option<[u8; 2]> {
+    let mut header = [0u8; XZ_STREAM_HEADER_SIZE];
+    reader.read_exact(&mut header).ok()?;
+    let stream_flags = [header[6], header[7]];
+    let header_valid = header.starts_with(XZ_HEADER_MAGIC) && header.get(8..) == Some(crc32fast::hash(&stream_flags).to_le_bytes().as_slice());
+    (header_valid && stream_flags[0] == 0 && XzCheck::new(stream_flags[1]).is_some()).then_some(stream_flags)
+}
+
+fn read_xz_block<R: BufRead>(reader: &mut PositionTrackingReader<R>, check_type: u8) -> XzBlockScan {
+    let mut header_size_byte = [0u8; 1];
+    if reader.read_exact(&mut header_size_byte).is_err() {
+        return XzBlockScan::Damaged;
+    }
+    // Zero in place of block header size starts index, which is placed after all blocks
+    if header_size_byte[0] == 0 {
+        return XzBlockScan::IndexReached;
+    }
+
+    let header_size = (usize::from(header_size_byte[0]) + 1) * 4;
+    let mut header = vec![0u8; header_size];
+    let Some((first_byte, rest_of_header)) = header.split_first_mut() else {
+        return XzBlockScan::Damaged;
+    };
+    *first_byte = header_size_byte[0];
+    if reader.read_exact(rest_of_header).is_err() {
+        return XzBlockScan::Damaged;
+    }
+    let Some(block_header) = parse_xz_block_header(&header) else {
+        return XzBlockScan::Damaged;
+    };
+    let Some(mut check) = XzCheck::new(check_type) else {
+        return XzBlockScan::Damaged;
+    };
+
+    let data_start = reader.position;
+    let mut uncompressed_size = 0;
+    {
+        // Lzma2 decoder reads exactly the compressed data, so its size is known after decoding
+        let mut decoder = Lzma2Reader::new(&mut *reader, block_header.dict_size, None);
+        let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+        loop {
+            match decoder.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read_bytes) => {
+                    check.update(buffer.get(..read_bytes).unwrap_or_default());
+                    uncompressed_size += read_bytes as u64;
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => return XzBlockScan::Damaged,
+            }
+        }
+    }
+    let compressed_size = reader.position - data_start;
+    if block_header.compressed_size.is_some_and(|size| size != compressed_size) || block_header.uncompressed_size.is_some_and(|size| size != uncompressed_size) {
+        return XzBlockScan::Damaged;
+    }
+
+    // Block is padded with zeros to multiple of 4 bytes, and check is placed after padding
+    let unpadded_size_without_check = header_size as u64 + compressed_size;
+    let padding_size = usize::try_from((4 - unpadded_size_without_check % 4) % 4).unwrap_or_default();
+    let expected_check = check.finalize();
+    let mut padding_and_check = vec![0u8; padding_size + expected_check.len()];
+    if reader.read_exact(&mut padding_and_check).is_err() {
+        return XzBlockScan::Damaged;
+    }
+    let Some((padding, stored_check)) = padding_and_check.split_at_checked(padding_size) else {
+        return XzBlockScan::Damaged;
+    };
+    if padding.iter().any(|byte| *byte != 0) || stored_check != expected_check.as_slice() {
+        return XzBlockScan::Damaged;
+    }
+
+    XzBlockScan::Intact(XzBlockRecord {
+        unpadded_size: unpadded_size_without_check + expected_check.len() as u64,
+        uncompressed_size,
+    })
+}
+
+// Only blocks compressed with single LZMA2 filter are supported, which is default in xz utils
+pub(crate) fn parse_xz_block_header(header: &[u8]) -> Option<XzBlockHeader> {
+    let (content, stored_crc) = header.split_at_checked(header.len().checked_sub(4)?)?;
+    if crc32fast::hash(content).to_le_bytes().as_slice() != stored_crc {
+        return None;
+    }
+
+    let flags = *content.get(1)?;
+    // Lowest bits contain number of filters minus one, next ones are reserved
+    if flags & 0x3F != 0 {
+        return None;
+    }
+    let mut position = 2;
+    let compressed_size = if flags & 0x40 != 0 { Some(read_xz_varint(content, &mut position)?) } else { None };
+    let uncompressed_size = if flags & 0x80 != 0 { Some(read_xz_varint(content, &mut position)?) } else { None };
+    let filter_id = read_xz_varint(content, &mut position)?;
+    let properties_size = read_xz_varint(content, &mut position)?;
+    if filter_id != XZ_FILTER_LZMA2 || properties_size != 1 {
+        return None;
+    }
+    let dict_property = *content.get(position)?;
+    if content.get(position + 1..)?.iter().any(|byte| *byte != 0) {
+        return None;
+    }
+
+    let dict_size = match dict_property {
+        0..40 => (2 | u32::from(dict_property & 1)) << (dict_property / 2 + 11),
+        40 => u32::MAX,
+        _ => return None,
+    };
+    if dict_size > XZ_MAX_DICT_SIZE {
+        return None;
+    }
+
+    Some(XzBlockHeader {
+        compressed_size,
+        uncompressed_size,
+        dict_size,
+    })
+}
+
+fn read_xz_varint(data: &[u8], position: &mut usize) -> Option<u64> {
+    let mut value = 0;
+    // Up to 9 bytes, each with 7 bits of value
+    for shift in (0..63).step_by(7) {
+        let byte = *data.get(*position)?;
+        *position += 1;
+        value |= u64::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn write_xz_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+pub(crate) fn encode_xz_index(records: &[XzBlockRecord]) -> Vec<u8> {
+    let mut index = vec![0x00];
+    write_xz_varint(&mut index, records.len() as u64);
+    for record in records {
+        write_xz_varint(&mut index, record.unpadded_size);
+        write_xz_varint(&mut index, record.uncompressed_size);
+    }
+    while !index.len().is_multiple_of(4) {
+        index.push(0);
+    }
+    let crc = crc32fast::hash(&index);
+    index.extend_from_slice(&crc.to_le_bytes());
+    index
+}
+
+fn encode_xz_footer(index_size: usize, stream_flags: [u8; 2]) -> Vec<u8> {
+    // Index size is stored as number of 4 byte units minus one
+    let backward_size = u32::try_from(index_size / 4 - 1).unwrap_or(u32::MAX);
+    let mut footer_content = backward_size.to_le_bytes().to_vec();
+    footer_content.extend_from_slice(&stream_flags);
+
+    let mut footer = crc32fast::hash(&footer_content).to_le_bytes().to_vec();
+    footer.extend_from_slice(&footer_content);
+    footer.extend_from_slice(XZ_FOOTER_MAGIC);
+    footer
+}
+
+fn read_equals<R: Read>(reader: &mut R, expected: &[u8]) -> bool {
+    let mut data = vec![0u8; expected.len()];
+    reader.read_exact(&mut data).is_ok() && data == expected
+}
+
+fn skip_xz_stream_padding<R: BufRead>(reader: &mut R) {
+    loop {
+        let zeros = match reader.fill_buf() {
+            Ok(buffer) => buffer.iter().take_while(|byte| **byte == 0).count(),
+            Err(_) => return,
+        };
+        if zeros == 0 {
+            return;
+        }
+        reader.consume(zeros);
+    }
+}
+
+// Remembers how many bytes were consumed, to find exact places where independent parts of the stream start
+struct PositionTrackingReader<R> {
+    inner: R,
+    position: u64,
+}
+
+impl<R: BufRead> PositionTrackingReader<R> {
+    fn new(inner: R) -> Self {
+        Self { inner, position: 0 }
+    }
+
+    fn has_data_left(&mut self) -> bool {
+        self.fill_buf().is_ok_and(|buffer| !buffer.is_empty())
+    }
+}
+
+impl<R: Read> Read for PositionTrackingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read_bytes = self.inner.read(buf)?;
+        self.position += read_bytes as u64;
+        Ok(read_bytes)
+    }
+}
+
+impl<R: BufRead> BufRead for PositionTrackingReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position += amount as u64;
+        self.inner.consume(amount);
+    }
+}
+
+// Partially written output is removed, so after failure nothing is left next to original file
+fn write_output_file<F: FnOnce(&mut BufWriter<File>) -> io::Result<()>>(output_path: &Path, write_content: F) -> Result<(), String> {
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(output_path)
+        .map_err(|e| normalize_error_string(&e.to_string()))?;
+    let mut writer = BufWriter::new(file);
+    let result = write_content(&mut writer).and_then(|()| writer.flush());
+    drop(writer);
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(output_path);
+        return Err(normalize_error_string(&e.to_string()));
+    }
+    Ok(())
+}
+
+fn copy_file_range<W: Write>(source: &mut File, writer: &mut W, start: u64, end: u64) -> io::Result<()> {
+    source.seek(SeekFrom::Start(start))?;
+    let length = end.saturating_sub(start);
+    if io::copy(&mut (&mut *source).take(length), writer)? != length {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+    }
+    Ok(())
+}
+
+// Returns (copied bytes, whether whole input was read). Read error only marks start of damaged data, so it is not reported
+fn copy_until_read_error<R: Read, W: Write>(reader: &mut R, writer: &mut W) -> io::Result<(u64, bool)> {
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+    let mut copied_bytes = 0;
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok((copied_bytes, true)),
+            Ok(read_bytes) => {
+                writer.write_all(buffer.get(..read_bytes).unwrap_or_default())?;
+                copied_bytes += read_bytes as u64;
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(_) => return Ok((copied_bytes, false)),
+        }
+    }
+}
+
+// Returns None if stop flag was set
+fn remux_video(path: &Path, output_path: &Path, stop_flag: &Arc<AtomicBool>) -> Option<Result<(usize, u64), String>> {
+    let mut command = Command::new("ffmpeg");
+    command
+        .arg("-v")
+        .arg("error")
+        .arg("-nostdin")
+        .arg("-n") // Never overwrite existing files
+        .arg("-err_detect")
+        .arg("ignore_err")
+        .arg("-fflags")
+        .arg("+genpts+discardcorrupt")
+        .arg("-i")
+        .arg(path)
+        .arg("-map")
+        .arg("0")
+        .arg("-c")
+        .arg("copy")
+        .arg("-ignore_unknown")
+        .arg(output_path);
+
+    let result = match run_command_interruptible(command, stop_flag) {
+        None => {
+            let _ = fs::remove_file(output_path);
+            return None;
+        }
+        Some(Err(e)) => Err(normalize_error_string(&format!("Failed to run ffmpeg: {e}"))),
+        Some(Ok(output)) if !output.status.success() => Err(flc!(
+            "core_ffmpeg_error",
+            file = path.to_string_lossy(),
+            code = output.status.to_string(),
+            reason = normalize_error_string(output.stderr.trim())
+        )),
+        Some(Ok(_output)) => match fs::metadata(output_path) {
+            Ok(metadata) => Ok((1, metadata.len())),
+            Err(e) => Err(normalize_error_string(&e.to_string())),
+        },
+    };
+
+    // Output was checked before to not exist, so partially written file is created by ffmpeg
+    if result.is_err() {
+        let _ = fs::remove_file(output_path);
+    }
+    Some(result)
+}
@@ -1,14 +1,20 @@
 use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 
+use flate2::Compression;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use ruzstd::decoding::StreamingDecoder;
 use tempfile::TempDir;
 
 use crate::common::tool_data::CommonData;
-use crate::common::traits::Search;
-use crate::tools::broken_files::{BrokenEntry, BrokenFiles, BrokenFilesParameters, CheckedTypes, CheckedTypesSingle};
+use crate::common::traits::{FixingItems, PrintResults, Search};
+use crate::tools::broken_files::repair::{XzBlockHeader, XzBlockRecord, encode_xz_index, get_recovered_path, parse_xz_block_header, repair_broken_file, rewrite_zstd_frame_header};
+use crate::tools::broken_files::{BrokenEntry, BrokenFiles, BrokenFilesFixParams, BrokenFilesParameters, CheckedTypes, CheckedTypesSingle, RepairAction, RepairReport};
 
 fn run_check(dir: &TempDir, checked_types: CheckedTypes) -> Vec<BrokenEntry> {
     let params = BrokenFilesParameters::new(checked_types);
@@ -416,3 +422,321 @@ fn test_multiple_avif_valid_and_broken() {
     assert_eq!(results.len(), 1, "Only the corrupted AVIF should be detected");
     assert!(results[0].has_errors());
 }
+
+fn run_check_and_repair(dir: &TempDir, checked_types: CheckedTypes) -> Vec<RepairReport> {
+    let params = BrokenFilesParameters::new(checked_types);
+    let mut finder = BrokenFiles::new(params);
+    finder.set_included_paths(vec![dir.path().to_path_buf()]);
+    finder.set_recursive_search(true);
+    finder.set_use_cache(false);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+    finder.fix_items(&stop_flag, None, BrokenFilesFixParams { repaired_types: checked_types });
+    finder.get_repair_reports().clone()
+}
+
+fn truncate_file(path: &Path, new_len: usize) {
+    let content = fs::read(path).unwrap();
+    fs::write(path, &content[..new_len]).unwrap();
+}
+
+#[test]
+fn test_repair_truncated_gz() {
+    let temp_dir = TempDir::new().unwrap();
+    let gz_path = temp_dir.path().join("data.gz");
+    let original_data: Vec<u8> = (0..200_000u32).flat_map(|i| i.to_le_bytes()).collect();
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&original_data).unwrap();
+    let compressed = encoder.finish().unwrap();
+    fs::write(&gz_path, &compressed).unwrap();
+    truncate_file(&gz_path, compressed.len() / 2);
+    let broken_content = fs::read(&gz_path).unwrap();
+
+    let reports = run_check_and_repair(&temp_dir, CheckedTypes::ARCHIVE);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].action, RepairAction::TruncateStream);
+    assert_eq!(reports[0].error, None);
+
+    let output_path = reports[0].output_path.clone().unwrap();
+    assert_eq!(output_path, temp_dir.path().join("data.czkawka_recovered.gz"));
+
+    let mut recovered_data = Vec::new();
+    flate2::read::GzDecoder::new(fs::File::open(&output_path).unwrap())
+        .read_to_end(&mut recovered_data)
+        .unwrap();
+    assert!(!recovered_data.is_empty(), "Part of data should be recovered");
+    assert_eq!(recovered_data.len() as u64, reports[0].recovered_bytes);
+    assert_eq!(recovered_data, original_data[..recovered_data.len()], "Recovered data should be prefix of original data");
+    assert_eq!(fs::read(&gz_path).unwrap(), broken_content, "Original file must not be modified");
+}
+
+#[test]
+fn test_repair_truncated_tar() {
+    let temp_dir = TempDir::new().unwrap();
+    let tar_path = temp_dir.path().join("archive.tar");
+
+    let mut builder = tar::Builder::new(Vec::new());
+    for (name, content) in [("first.txt", vec![b'a'; 1000]), ("dir/second.txt", vec![b'b'; 1000]), ("third.txt", vec![b'c'; 5000])] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, content.as_slice()).unwrap();
+    }
+    let archive = builder.into_inner().unwrap();
+    fs::write(&tar_path, &archive).unwrap();
+    // Cuts in the middle of third member
+    truncate_file(&tar_path, 512 * 6 + 1000);
+
+    let reports = run_check_and_repair(&temp_dir, CheckedTypes::ARCHIVE);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].action, RepairAction::ExtractIntactMembers);
+    assert_eq!(reports[0].error, None);
+    assert_eq!(reports[0].recovered_items, 2);
+
+    let output_folder = temp_dir.path().join("archive.czkawka_recovered");
+    assert_eq!(reports[0].output_path, Some(output_folder.clone()));
+    assert_eq!(fs::read(output_folder.join("first.txt")).unwrap(), vec![b'a'; 1000]);
+    assert_eq!(fs::read(output_folder.join("dir").join("second.txt")).unwrap(), vec![b'b'; 1000]);
+    assert!(!output_folder.join("third.txt").exists(), "Truncated member should not be extracted");
+}
+
+#[test]
+fn test_repair_zip_with_missing_central_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let zip_path = temp_dir.path().join("archive.zip");
+
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    writer.start_file("first.txt", options).unwrap();
+    writer.write_all(b"first file content").unwrap();
+    writer.start_file("second.txt", options).unwrap();
+    writer.write_all(b"second file content").unwrap();
+    let archive = writer.finish().unwrap().into_inner();
+    fs::write(&zip_path, &archive).unwrap();
+    // Removes end of central directory record, so archive cannot be opened normally
+    truncate_file(&zip_path, archive.len() - 10);
+
+    let reports = run_check_and_repair(&temp_dir, CheckedTypes::ARCHIVE);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].error, None);
+    assert_eq!(reports[0].recovered_items, 2);
+
+    let output_folder = temp_dir.path().join("archive.czkawka_recovered");
+    assert_eq!(fs::read(output_folder.join("first.txt")).unwrap(), b"first file content");
+    assert_eq!(fs::read(output_folder.join("second.txt")).unwrap(), b"second file content");
+}
+
+#[test]
+fn test_repair_does_not_overwrite_existing_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let gz_path = temp_dir.path().join("data.gz");
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&vec![b'x'; 100_000]).unwrap();
+    let compressed = encoder.finish().unwrap();
+    fs::write(&gz_path, &compressed[..compressed.len() - 4]).unwrap();
+
+    let existing_output = temp_dir.path().join("data.czkawka_recovered.gz");
+    fs::write(&existing_output, b"user data").unwrap();
+
+    let reports = run_check_and_repair(&temp_dir, CheckedTypes::ARCHIVE);
+    // Existing output is also checked as gz file and found as broken, so only original file is interesting here
+    let report = reports.iter().find(|report| report.path == gz_path).unwrap();
+    assert!(report.error.is_some(), "Repair should fail when output already exists");
+    assert_eq!(report.output_path, None);
+    assert_eq!(fs::read(&existing_output).unwrap(), b"user data", "Existing file must not be overwritten");
+}
+
+#[test]
+fn test_recovered_path() {
+    assert_eq!(
+        get_recovered_path(Path::new("/tmp/movie.mp4"), RepairAction::RemuxVideo),
+        PathBuf::from("/tmp/movie.czkawka_recovered.mp4")
+    );
+    assert_eq!(
+        get_recovered_path(Path::new("/tmp/archive.zip"), RepairAction::ExtractIntactMembers),
+        PathBuf::from("/tmp/archive.czkawka_recovered")
+    );
+}
+
+#[test]
+fn test_repair_gz_keeps_intact_members() {
+    let temp_dir = TempDir::new().unwrap();
+    let gz_path = temp_dir.path().join("data.gz");
+    let first_data = vec![b'a'; 50_000];
+    let second_data: Vec<u8> = (0..100_000u32).flat_map(|i| i.to_le_bytes()).collect();
+
+    let mut members = Vec::new();
+    for data in [&first_data, &second_data] {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        members.push(encoder.finish().unwrap());
+    }
+    let first_member_size = members[0].len();
+    fs::write(&gz_path, members.concat()).unwrap();
+    truncate_file(&gz_path, first_member_size + members[1].len() / 2);
+
+    // Check finds damage only in first member, so repair is called directly
+    let entry = make_broken_entry(&gz_path.to_string_lossy(), &[]);
+    let report = repair_broken_file(&entry, CheckedTypes::ARCHIVE, &Arc::new(AtomicBool::new(false))).unwrap();
+    assert_eq!(report.error, None);
+
+    let output = fs::read(report.output_path.as_ref().unwrap()).unwrap();
+    assert_eq!(output[..first_member_size], members[0], "Intact member should be copied without recompression");
+
+    let mut recovered_data = Vec::new();
+    MultiGzDecoder::new(output.as_slice()).read_to_end(&mut recovered_data).unwrap();
+    assert!(recovered_data.len() > first_data.len(), "Part of damaged member should be recovered");
+    assert_eq!(recovered_data.len() as u64, report.recovered_bytes);
+    assert_eq!(recovered_data, [first_data, second_data].concat()[..recovered_data.len()]);
+}
+
+#[test]
+fn test_repair_truncated_zst() {
+    let temp_dir = TempDir::new().unwrap();
+    let zst_path = temp_dir.path().join("data.zst");
+    // Blocks contain up to 128 KiB of data, so stream has multiple blocks
+    let original_data: Vec<u8> = (0..400_000u32).flat_map(|i| (i / 3).to_le_bytes()).collect();
+    let compressed = ruzstd::encoding::compress_to_vec(original_data.as_slice(), ruzstd::encoding::CompressionLevel::Fastest);
+    fs::write(&zst_path, &compressed).unwrap();
+    truncate_file(&zst_path, compressed.len() * 2 / 3);
+
+    let reports = run_check_and_repair(&temp_dir, CheckedTypes::ARCHIVE);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].error, None);
+
+    let output = fs::read(reports[0].output_path.as_ref().unwrap()).unwrap();
+    assert!(output.len() < compressed.len() * 2 / 3);
+    let mut recovered_data = Vec::new();
+    StreamingDecoder::new(output.as_slice()).unwrap().read_to_end(&mut recovered_data).unwrap();
+    assert!(!recovered_data.is_empty(), "Intact blocks should be recovered");
+    assert_eq!(recovered_data.len() as u64, reports[0].recovered_bytes);
+    assert_eq!(recovered_data, original_data[..recovered_data.len()]);
+}
+
+#[test]
+fn test_rewrite_zstd_frame_header() {
+    // Single segment frame with 2 byte content size(300 - 256), checksum and 1 byte dictionary id
+    let header = [0x28, 0xB5, 0x2F, 0xFD, 0b0110_0101, 0x07, 44, 0];
+    assert_eq!(rewrite_zstd_frame_header(&header), Some(vec![0x28, 0xB5, 0x2F, 0xFD, 0b0000_0001, 0x00, 0x07]));
+
+    // Window descriptor is kept when present
+    let header = [0x28, 0xB5, 0x2F, 0xFD, 0b1000_0100, 0x58, 1, 2, 3, 4];
+    assert_eq!(rewrite_zstd_frame_header(&header), Some(vec![0x28, 0xB5, 0x2F, 0xFD, 0b0000_0000, 0x58]));
+
+    // Content size bigger than 1 KiB needs bigger window
+    let header = [0x28, 0xB5, 0x2F, 0xFD, 0b1010_0000, 0x00, 0x10, 0x00, 0x00];
+    assert_eq!(rewrite_zstd_frame_header(&header), Some(vec![0x28, 0xB5, 0x2F, 0xFD, 0b0000_0000, 2 << 3]));
+
+    assert_eq!(rewrite_zstd_frame_header(&header[..6]), None);
+}
+
+#[test]
+fn test_repair_xz_keeps_intact_streams() {
+    let temp_dir = TempDir::new().unwrap();
+    let xz_path = temp_dir.path().join("data.xz");
+    let first_data = vec![b'x'; 30_000];
+    let second_data: Vec<u8> = (0..50_000u32).flat_map(|i| i.to_le_bytes()).collect();
+
+    let mut streams = Vec::new();
+    for data in [&first_data, &second_data] {
+        let mut compressed = Vec::new();
+        lzma_rs::xz_compress(&mut data.as_slice(), &mut compressed).unwrap();
+        streams.push(compressed);
+    }
+    fs::write(&xz_path, [streams[0].as_slice(), &streams[1][..streams[1].len() / 2]].concat()).unwrap();
+
+    let reports = run_check_and_repair(&temp_dir, CheckedTypes::ARCHIVE);
+    let report = reports.iter().find(|report| report.path == xz_path).unwrap();
+    assert_eq!(report.error, None);
+    assert_eq!(report.recovered_bytes, first_data.len() as u64);
+    assert_eq!(fs::read(report.output_path.as_ref().unwrap()).unwrap(), streams[0], "Only intact stream should be left");
+}
+
+#[test]
+fn test_repair_xz_with_damaged_index() {
+    let temp_dir = TempDir::new().unwrap();
+    let xz_path = temp_dir.path().join("data.xz");
+    let original_data: Vec<u8> = (0..50_000u32).flat_map(|i| i.to_le_bytes()).collect();
+    let mut compressed = Vec::new();
+    lzma_rs::xz_compress(&mut original_data.as_slice(), &mut compressed).unwrap();
+    // Removes part of the footer, so all blocks are intact
+    fs::write(&xz_path, &compressed[..compressed.len() - 6]).unwrap();
+
+    let reports = run_check_and_repair(&temp_dir, CheckedTypes::ARCHIVE);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].error, None);
+    assert_eq!(reports[0].recovered_bytes, original_data.len() as u64);
+    assert_eq!(
+        fs::read(reports[0].output_path.as_ref().unwrap()).unwrap(),
+        compressed,
+        "Index and footer should be created again exactly like in original file"
+    );
+}
+
+#[test]
+fn test_parse_xz_block_header() {
+    let mut header = vec![0x02, 0x00, 0x21, 0x01, 22, 0x00, 0x00, 0x00];
+    header.extend_from_slice(&crc32fast::hash(&header).to_le_bytes());
+    assert_eq!(
+        parse_xz_block_header(&header),
+        Some(XzBlockHeader {
+            compressed_size: None,
+            uncompressed_size: None,
+            dict_size: 8 * 1024 * 1024,
+        })
+    );
+
+    // Damaged header checksum
+    header[4] = 23;
+    assert_eq!(parse_xz_block_header(&header), None);
+
+    // Two filters are not supported
+    let mut header = vec![0x02, 0x01, 0x21, 0x01, 22, 0x00, 0x00, 0x00];
+    header.extend_from_slice(&crc32fast::hash(&header).to_le_bytes());
+    assert_eq!(parse_xz_block_header(&header), None);
+}
+
+#[test]
+fn test_encode_xz_index() {
+    let records = [XzBlockRecord {
+        unpadded_size: 300,
+        uncompressed_size: 100,
+    }];
+    let index = encode_xz_index(&records);
+    // Indicator, number of records, two varints, padding and CRC32
+    assert_eq!(index[..8], [0x00, 0x01, 0xAC, 0x02, 0x64, 0x00, 0x00, 0x00]);
+    assert_eq!(index[8..], crc32fast::hash(&index[..8]).to_le_bytes());
+}
+
+#[test]
+fn test_json_export_contains_repair_reports() {
+    let temp_dir = TempDir::new().unwrap();
+    let gz_path = temp_dir.path().join("data.gz");
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&vec![b'x'; 100_000]).unwrap();
+    let compressed = encoder.finish().unwrap();
+    fs::write(&gz_path, &compressed[..compressed.len() - 4]).unwrap();
+
+    let mut finder = BrokenFiles::new(BrokenFilesParameters::new(CheckedTypes::ARCHIVE));
+    finder.set_included_paths(vec![temp_dir.path().to_path_buf()]);
+    finder.set_use_cache(false);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+    finder.fix_items(
+        &stop_flag,
+        None,
+        BrokenFilesFixParams {
+            repaired_types: CheckedTypes::ARCHIVE,
+        },
+    );
+
+    let json_path = temp_dir.path().join("results.json");
+    finder.save_results_to_file_as_json(&json_path.to_string_lossy(), false).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(json["broken_files"].as_array().unwrap().len(), 1);
+    assert_eq!(json["repair_reports"].as_array().unwrap().len(), 1);
+    assert_eq!(json["repair_reports"][0]["action"], "TruncateStream");
+}
@@ -5,6 +5,8 @@ use std::time::Instant;
 
 use crossbeam_channel::Sender;
 use fun_time::fun_time;
+use humansize::{BINARY, format_size};
+use serde::Serialize;
 
 use crate::common::consts::{
     AUDIO_FILES_CONTENT_EXTENSIONS, BZ2_FILES_EXTENSIONS, FONT_FILES_EXTENSIONS, GZ_FILES_EXTENSIONS, IMAGE_RS_BROKEN_FILES_EXTENSIONS, JSON_FILES_EXTENSIONS,
@@ -15,9 +17,9 @@ use crate::common::ffmpeg_utils::check_if_ffprobe_ffmpeg_exists;
 use crate::common::model::WorkContinueStatus;
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
-use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
+use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, FixingItems, PrintResults, Search};
 use crate::flc;
-use crate::tools::broken_files::{BrokenFiles, BrokenFilesParameters, CheckedTypes, Info};
+use crate::tools::broken_files::{BrokenEntry, BrokenFiles, BrokenFilesFixParams, BrokenFilesParameters, CheckedTypes, Info, RepairReport};
 
 impl AllTraits for BrokenFiles {}
 
@@ -124,13 +126,59 @@ impl PrintResults for BrokenFiles {
             write!(writer, "Not found any broken files.")?;
         }
 
+        if !self.repair_reports.is_empty() {
+            writeln!(writer, "\nRepaired {} files.", self.repair_reports.iter().filter(|report| report.error.is_none()).count())?;
+            for report in &self.repair_reports {
+                match (&report.output_path, &report.error) {
+                    (Some(output_path), None) => writeln!(
+                        writer,
+                        "\"{}\" - {:?} - recovered {} items ({}) to \"{}\"",
+                        report.path.to_string_lossy(),
+                        report.action,
+                        report.recovered_items,
+                        format_size(report.recovered_bytes, BINARY),
+                        output_path.to_string_lossy()
+                    )?,
+                    (_, error) => writeln!(
+                        writer,
+                        "\"{}\" - {:?} - failed: {}",
+                        report.path.to_string_lossy(),
+                        report.action,
+                        error.as_deref().unwrap_or_default()
+                    )?,
+                }
+            }
+        }
+
         Ok(())
     }
 
+    // Plain list of broken files is kept when nothing was repaired, to not change format of results without repair
     fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
-        self.save_results_to_file_as_json_internal(file_name, &self.broken_files, pretty_print)
+        if self.repair_reports.is_empty() {
+            return self.save_results_to_file_as_json_internal(file_name, &self.broken_files, pretty_print);
+        }
+
+        #[derive(Serialize, Debug)]
+        struct BrokenFilesWithRepairReports<'a> {
+            broken_files: &'a Vec<BrokenEntry>,
+            repair_reports: &'a Vec<RepairReport>,
+        }
+        let results = BrokenFilesWithRepairReports {
+            broken_files: &self.broken_files,
+            repair_reports: &self.repair_reports,
+        };
+        self.save_results_to_file_as_json_internal(file_name, &results, pretty_print)
+    }
+}
+impl FixingItems for BrokenFiles {
+    type FixParams = BrokenFilesFixParams;
+    #[fun_time(message = "fix_items", level = "debug")]
+    fn fix_items(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>, fix_params: Self::FixParams) {
+        self.fix_files(stop_flag, progress_sender, fix_params);
     }
 }
+
 impl DeletingItems for BrokenFiles {
     #[fun_time(message = "delete_files", level = "debug")]
     fn delete_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
@@ -0,0 +1,208 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use log::debug;
+use rayon::prelude::*;
+
+use crate::common::dir_traversal::{common_get_entry_data, common_get_metadata_dir, common_read_dir, get_modified_time};
+use crate::common::model::{ToolType, WorkContinueStatus};
+use crate::common::progress_data::{ProgressData, ToolStage};
+use crate::common::progress_stop_handler::{ProgressThreadHandler, check_if_stop_received, prepare_thread_handler_common};
+use crate::common::tool_data::CommonToolData;
+use crate::tools::build_artifacts::{ArtifactEntry, ArtifactKind, BuildArtifacts, BuildArtifactsParameters, Info};
+
+struct FoundArtifact {
+    path: PathBuf,
+    project_path: PathBuf,
+    kind: ArtifactKind,
+}
+
+impl BuildArtifacts {
+    pub fn new(params: BuildArtifactsParameters) -> Self {
+        Self {
+            common_data: CommonToolData::new(ToolType::BuildArtifacts),
+            information: Info::default(),
+            artifacts: Default::default(),
+            params,
+        }
+    }
+
+    #[fun_time(message = "look_for_artifacts", level = "debug")]
+    pub(crate) fn look_for_artifacts(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::CollectingFolders, 0, 0);
+
+        let Some(found_artifacts) = self.find_artifact_directories(stop_flag, &progress_handler) else {
+            progress_handler.join_thread();
+            return WorkContinueStatus::Stop;
+        };
+
+        // Artifact directories usually contain a lot of small files, so each of them is measured in separate task
+        let measured: Vec<_> = found_artifacts
+            .into_par_iter()
+            .map(|found| {
+                let mut warnings = Vec::new();
+                let entry = measure_artifact_directory(found, stop_flag, &progress_handler, &mut warnings);
+                (entry, warnings)
+            })
+            .collect();
+        progress_handler.join_thread();
+
+        if check_if_stop_received(stop_flag) {
+            return WorkContinueStatus::Stop;
+        }
+
+        let mut artifacts = Vec::with_capacity(measured.len());
+        for (entry, warnings) in measured {
+            self.common_data.text_messages.warnings.extend(warnings);
+            artifacts.extend(entry);
+        }
+        artifacts.sort_unstable_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+        self.information.number_of_artifact_directories = artifacts.len();
+        self.information.size_of_artifact_directories = artifacts.iter().map(|e| e.size).sum();
+        self.artifacts = artifacts;
+        debug!("look_for_artifacts - Found {} artifact directories.", self.artifacts.len());
+        WorkContinueStatus::Continue
+    }
+
+    // Artifact directories are not entered, so nested ones(e.g. node_modules inside node_modules) are reported only once, as part of the outer one
+    fn find_artifact_directories(&mut self, stop_flag: &Arc<AtomicBool>, progress_handler: &ProgressThreadHandler) -> Option<Vec<FoundArtifact>> {
+        let mut folders_to_check: Vec<PathBuf> = self.common_data.directories.included_directories.clone();
+        let excluded_items = &self.common_data.excluded_items;
+        let directories = &self.common_data.directories;
+        let kinds = &self.params.kinds;
+
+        let mut found_artifacts = Vec::new();
+        let mut all_warnings = Vec::new();
+
+        while !folders_to_check.is_empty() {
+            if check_if_stop_received(stop_flag) {
+                return None;
+            }
+
+            let segments: Vec<_> = folders_to_check
+                .into_par_iter()
+                .map(|current_folder| {
+                    let mut warnings = Vec::new();
+                    let mut subfolders = Vec::new();
+                    let mut file_names = Vec::new();
+                    let mut artifacts = Vec::new();
+
+                    let Some(read_dir) = common_read_dir(&current_folder, &mut warnings) else {
+                        return (subfolders, artifacts, warnings);
+                    };
+
+                    for entry in &read_dir {
+                        let Some(entry_data) = common_get_entry_data(entry, &mut warnings, &current_folder) else {
+                            continue;
+                        };
+                        let Ok(file_type) = entry_data.file_type() else { continue };
+
+                        if file_type.is_dir() {
+                            let next_folder = entry_data.path();
+                            if excluded_items.is_excluded(&next_folder) || directories.is_excluded_dir(&next_folder) {
+                                continue;
+                            }
+                            #[cfg(target_family = "unix")]
+                            if directories.exclude_other_filesystems() {
+                                match directories.is_on_other_filesystems(&next_folder) {
+                                    Ok(true) => continue,
+                                    Err(e) => warnings.push(e),
+                                    _ => (),
+                                }
+                            }
+                            subfolders.push(next_folder);
+                        } else {
+                            file_names.push(entry_data.file_name().to_string_lossy().to_string());
+                        }
+                    }
+                    progress_handler.increase_items(subfolders.len());
+
+                    // Markers are checked only after reading whole directory, because they may be listed after artifact directory
+                    subfolders.retain(|subfolder| {
+                        let Some(kind) = find_artifact_kind(subfolder, kinds, &file_names) else {
+                            return true;
+                        };
+                        artifacts.push(FoundArtifact {
+                            path: subfolder.clone(),
+                            project_path: current_folder.clone(),
+                            kind,
+                        });
+                        false
+                    });
+
+                    (subfolders, artifacts, warnings)
+                })
+                .collect();
+
+            folders_to_check = Vec::with_capacity(segments.iter().map(|(subfolders, _, _)| subfolders.len()).sum());
+            for (subfolders, artifacts, warnings) in segments {
+                folders_to_check.extend(subfolders);
+                found_artifacts.extend(artifacts);
+                all_warnings.extend(warnings);
+            }
+        }
+
+        self.common_data.text_messages.warnings.extend(all_warnings);
+        Some(found_artifacts)
+    }
+}
+
+pub(crate) fn find_artifact_kind(folder: &Path, kinds: &[ArtifactKind], file_names_in_parent: &[String]) -> Option<ArtifactKind> {
+    let folder_name = folder.file_name()?.to_str()?;
+    kinds
+        .iter()
+        .copied()
+        .find(|kind| kind.directory_name() == folder_name && file_names_in_parent.iter().any(|file_name| kind.is_project_marker(file_name)))
+}
+
+// Symlinks are not followed, so size contains only data which would be freed after removing directory
+fn measure_artifact_directory(found: FoundArtifact, stop_flag: &Arc<AtomicBool>, progress_handler: &ProgressThreadHandler, warnings: &mut Vec<String>) -> Option<ArtifactEntry> {
+    let mut size = 0;
+    let mut files = 0;
+    let mut modified_date = found
+        .path
+        .symlink_metadata()
+        .map_or(0, |metadata| get_modified_time(&metadata, warnings, &found.path, true));
+
+    let mut folders_to_check = vec![found.path.clone()];
+    while let Some(current_folder) = folders_to_check.pop() {
+        if check_if_stop_received(stop_flag) {
+            return None;
+        }
+
+        let Some(read_dir) = common_read_dir(&current_folder, warnings) else {
+            continue;
+        };
+        for entry in &read_dir {
+            let Some(entry_data) = common_get_entry_data(entry, warnings, &current_folder) else {
+                continue;
+            };
+            let Some(metadata) = common_get_metadata_dir(entry_data, warnings, &current_folder) else {
+                continue;
+            };
+
+            let is_dir = metadata.is_dir();
+            modified_date = modified_date.max(get_modified_time(&metadata, warnings, &entry_data.path(), is_dir));
+            if is_dir {
+                folders_to_check.push(entry_data.path());
+            } else {
+                size += metadata.len();
+                files += 1;
+            }
+        }
+        progress_handler.increase_items(1);
+    }
+
+    Some(ArtifactEntry {
+        path: found.path,
+        project_path: found.project_path,
+        kind: found.kind,
+        size,
+        files,
+        modified_date,
+    })
+}
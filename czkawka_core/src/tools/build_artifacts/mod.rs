@@ -0,0 +1,126 @@
+pub mod core;
+#[cfg(test)]
+mod tests;
+pub mod traits;
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::tool_data::CommonToolData;
+use crate::common::traits::ResultEntry;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ArtifactKind {
+    RustTarget,
+    NodeModules,
+    PythonCache,
+    PythonVirtualEnv,
+    Gradle,
+    CMakeBuild,
+}
+
+pub const ALL_ARTIFACT_KINDS: &[ArtifactKind] = &[
+    ArtifactKind::RustTarget,
+    ArtifactKind::NodeModules,
+    ArtifactKind::PythonCache,
+    ArtifactKind::PythonVirtualEnv,
+    ArtifactKind::Gradle,
+    ArtifactKind::CMakeBuild,
+];
+
+impl ArtifactKind {
+    /// Name of directory created by build system or package manager
+    pub fn directory_name(self) -> &'static str {
+        match self {
+            Self::RustTarget => "target",
+            Self::NodeModules => "node_modules",
+            Self::PythonCache => "__pycache__",
+            Self::PythonVirtualEnv => ".venv",
+            Self::Gradle => ".gradle",
+            Self::CMakeBuild => "build",
+        }
+    }
+
+    /// Files, one of which must be placed next to the artifact directory, to treat its parent as project root.
+    /// `*.ext` matches any file with given extension
+    pub fn project_markers(self) -> &'static [&'static str] {
+        match self {
+            Self::RustTarget => &["Cargo.toml"],
+            Self::NodeModules => &["package.json"],
+            Self::PythonCache => &["*.py"],
+            Self::PythonVirtualEnv => &["pyproject.toml", "setup.py", "setup.cfg", "requirements.txt", "Pipfile"],
+            Self::Gradle => &["build.gradle", "build.gradle.kts", "settings.gradle", "settings.gradle.kts"],
+            Self::CMakeBuild => &["CMakeLists.txt"],
+        }
+    }
+
+    pub fn is_project_marker(self, file_name: &str) -> bool {
+        self.project_markers().iter().any(|marker| match marker.strip_prefix('*') {
+            Some(extension) => file_name.len() > extension.len() && file_name.ends_with(extension),
+            None => file_name == *marker,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Info {
+    pub number_of_artifact_directories: usize,
+    pub size_of_artifact_directories: u64,
+    pub scanning_time: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct BuildArtifactsParameters {
+    pub kinds: Vec<ArtifactKind>,
+}
+
+impl BuildArtifactsParameters {
+    pub fn new(kinds: Vec<ArtifactKind>) -> Self {
+        Self { kinds }
+    }
+}
+
+impl Default for BuildArtifactsParameters {
+    fn default() -> Self {
+        Self::new(ALL_ARTIFACT_KINDS.to_vec())
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ArtifactEntry {
+    pub path: PathBuf,
+    pub project_path: PathBuf,
+    pub kind: ArtifactKind,
+    // Summed size of all files inside directory
+    pub size: u64,
+    pub files: usize,
+    // The newest modification date of directory and anything inside it
+    pub modified_date: u64,
+}
+
+impl ResultEntry for ArtifactEntry {
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+    fn get_modified_date(&self) -> u64 {
+        self.modified_date
+    }
+    fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+pub struct BuildArtifacts {
+    common_data: CommonToolData,
+    information: Info,
+    artifacts: Vec<ArtifactEntry>,
+    params: BuildArtifactsParameters,
+}
+
+impl BuildArtifacts {
+    pub const fn get_artifacts(&self) -> &Vec<ArtifactEntry> {
+        &self.artifacts
+    }
+}
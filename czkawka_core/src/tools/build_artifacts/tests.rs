@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use tempfile::TempDir;
+
+use crate::common::tool_data::{CommonData, DeleteMethod};
+use crate::common::traits::Search;
+use crate::tools::build_artifacts::core::find_artifact_kind;
+use crate::tools::build_artifacts::{ALL_ARTIFACT_KINDS, ArtifactKind, BuildArtifacts, BuildArtifactsParameters};
+
+fn create_file(path: &Path, size: usize) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, vec![b'A'; size]).unwrap();
+}
+
+fn search(path: &Path, params: BuildArtifactsParameters, delete: bool) -> BuildArtifacts {
+    let mut finder = BuildArtifacts::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    if delete {
+        finder.set_delete_method(DeleteMethod::Delete);
+    }
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+    finder
+}
+
+fn create_projects(path: &Path) {
+    // Rust project with nested files in target
+    create_file(&path.join("rust/Cargo.toml"), 10);
+    create_file(&path.join("rust/target/debug/app"), 300);
+    create_file(&path.join("rust/target/debug/deps/lib.rlib"), 200);
+
+    // Node project, nested node_modules must not be reported separately
+    create_file(&path.join("web/package.json"), 10);
+    create_file(&path.join("web/node_modules/a/index.js"), 100);
+    create_file(&path.join("web/node_modules/a/package.json"), 10);
+    create_file(&path.join("web/node_modules/a/node_modules/b/index.js"), 50);
+
+    create_file(&path.join("cpp/CMakeLists.txt"), 10);
+    create_file(&path.join("cpp/build/app.o"), 20);
+
+    create_file(&path.join("py/tool.py"), 10);
+    create_file(&path.join("py/__pycache__/tool.cpython-312.pyc"), 5);
+
+    // Directories with artifact names, but without project markers
+    create_file(&path.join("docs/build/index.html"), 1000);
+    create_file(&path.join("other/target/file.txt"), 1000);
+}
+
+#[test]
+fn test_find_artifacts() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    create_projects(path);
+
+    let finder = search(path, BuildArtifactsParameters::default(), false);
+    let artifacts = finder.get_artifacts();
+
+    let found: Vec<(PathBuf, ArtifactKind, u64, usize)> = artifacts
+        .iter()
+        .map(|e| (e.path.strip_prefix(path).unwrap().to_path_buf(), e.kind, e.size, e.files))
+        .collect();
+    // Sorted from the biggest
+    assert_eq!(
+        found,
+        vec![
+            (PathBuf::from("rust/target"), ArtifactKind::RustTarget, 500, 2),
+            (PathBuf::from("web/node_modules"), ArtifactKind::NodeModules, 160, 3),
+            (PathBuf::from("cpp/build"), ArtifactKind::CMakeBuild, 20, 1),
+            (PathBuf::from("py/__pycache__"), ArtifactKind::PythonCache, 5, 1),
+        ]
+    );
+    assert_eq!(artifacts[0].project_path, path.join("rust"));
+    assert!(artifacts.iter().all(|e| e.modified_date > 0));
+
+    let info = finder.get_information();
+    assert_eq!(info.number_of_artifact_directories, 4);
+    assert_eq!(info.size_of_artifact_directories, 685);
+}
+
+#[test]
+fn test_find_selected_kinds() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    create_projects(path);
+
+    let finder = search(path, BuildArtifactsParameters::new(vec![ArtifactKind::NodeModules, ArtifactKind::PythonCache]), false);
+    let kinds: Vec<ArtifactKind> = finder.get_artifacts().iter().map(|e| e.kind).collect();
+    assert_eq!(kinds, vec![ArtifactKind::NodeModules, ArtifactKind::PythonCache]);
+}
+
+#[test]
+fn test_delete_artifacts() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    create_projects(path);
+
+    let finder = search(path, BuildArtifactsParameters::new(vec![ArtifactKind::RustTarget, ArtifactKind::NodeModules]), true);
+    assert_eq!(finder.get_artifacts().len(), 2);
+
+    // Whole non-empty directories are removed, but projects stay untouched
+    assert!(!path.join("rust/target").exists());
+    assert!(!path.join("web/node_modules").exists());
+    assert!(path.join("rust/Cargo.toml").exists());
+    assert!(path.join("web/package.json").exists());
+    assert!(path.join("cpp/build/app.o").exists());
+    assert!(path.join("other/target/file.txt").exists());
+}
+
+#[test]
+fn test_find_artifact_kind() {
+    let names = |items: &[&str]| items.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    assert_eq!(
+        find_artifact_kind(Path::new("/p/.gradle"), ALL_ARTIFACT_KINDS, &names(&["settings.gradle.kts"])),
+        Some(ArtifactKind::Gradle)
+    );
+    assert_eq!(
+        find_artifact_kind(Path::new("/p/.venv"), ALL_ARTIFACT_KINDS, &names(&["README.md", "pyproject.toml"])),
+        Some(ArtifactKind::PythonVirtualEnv)
+    );
+    assert_eq!(find_artifact_kind(Path::new("/p/.venv"), ALL_ARTIFACT_KINDS, &names(&["README.md"])), None);
+    // Extension marker needs some file name before extension
+    assert_eq!(find_artifact_kind(Path::new("/p/__pycache__"), ALL_ARTIFACT_KINDS, &names(&[".py"])), None);
+    assert_eq!(
+        find_artifact_kind(Path::new("/p/__pycache__"), ALL_ARTIFACT_KINDS, &names(&["main.py"])),
+        Some(ArtifactKind::PythonCache)
+    );
+    assert_eq!(find_artifact_kind(Path::new("/p/target"), &[ArtifactKind::NodeModules], &names(&["Cargo.toml"])), None);
+}
@@ -0,0 +1,126 @@
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use humansize::{BINARY, format_size};
+
+use crate::common::model::WorkContinueStatus;
+use crate::common::progress_data::ProgressData;
+use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
+use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
+use crate::tools::build_artifacts::{BuildArtifacts, BuildArtifactsParameters, Info};
+
+impl AllTraits for BuildArtifacts {}
+
+impl DeletingItems for BuildArtifacts {
+    #[fun_time(message = "delete_files", level = "debug")]
+    fn delete_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        match self.common_data.delete_method {
+            DeleteMethod::Delete => self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFoldersRecursively(self.artifacts.clone())),
+            DeleteMethod::None => WorkContinueStatus::Continue,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl DebugPrint for BuildArtifacts {
+    #[expect(clippy::print_stdout)]
+    fn debug_print(&self) {
+        if !cfg!(debug_assertions) || cfg!(test) {
+            return;
+        }
+
+        println!("### INDIVIDUAL DEBUG PRINT ###");
+        println!("Info: {:?}", self.information);
+        println!("Params: {:?}", self.params);
+        self.debug_print_common();
+        println!("-----------------------------------------");
+    }
+}
+
+impl PrintResults for BuildArtifacts {
+    fn write_results<T: Write>(&self, writer: &mut T) -> std::io::Result<()> {
+        self.write_base_search_paths(writer)?;
+
+        if self.information.number_of_artifact_directories != 0 {
+            writeln!(
+                writer,
+                "Found {} build artifact directories, taking {}.\n",
+                self.information.number_of_artifact_directories,
+                format_size(self.information.size_of_artifact_directories, BINARY)
+            )?;
+            let current_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            for entry in &self.artifacts {
+                writeln!(
+                    writer,
+                    "{} ({}) - {:?} - \"{}\" - {} files, modified {} days ago - project \"{}\"",
+                    format_size(entry.size, BINARY),
+                    entry.size,
+                    entry.kind,
+                    entry.path.to_string_lossy(),
+                    entry.files,
+                    current_time.saturating_sub(entry.modified_date) / (24 * 60 * 60),
+                    entry.project_path.to_string_lossy()
+                )?;
+            }
+        } else {
+            writeln!(writer, "Not found any build artifact directories.")?;
+        }
+
+        Ok(())
+    }
+
+    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+        self.save_results_to_file_as_json_internal(file_name, &self.artifacts, pretty_print)
+    }
+}
+
+impl Search for BuildArtifacts {
+    #[fun_time(message = "find_build_artifacts", level = "info")]
+    fn search(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) {
+        let start_time = Instant::now();
+
+        let () = (|| {
+            if self.prepare_items(None).is_err() {
+                return;
+            }
+            if self.look_for_artifacts(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+                return;
+            }
+            if self.delete_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+            }
+        })();
+
+        self.information.scanning_time = start_time.elapsed();
+
+        if !self.common_data.stopped_search {
+            self.debug_print();
+        }
+    }
+}
+
+impl CommonData for BuildArtifacts {
+    type Info = Info;
+    type Parameters = BuildArtifactsParameters;
+
+    fn get_information(&self) -> Self::Info {
+        self.information.clone()
+    }
+    fn get_params(&self) -> Self::Parameters {
+        self.params.clone()
+    }
+    fn get_cd(&self) -> &CommonToolData {
+        &self.common_data
+    }
+    fn get_cd_mut(&mut self) -> &mut CommonToolData {
+        &mut self.common_data
+    }
+    fn found_any_items(&self) -> bool {
+        self.information.number_of_artifact_directories > 0
+    }
+}
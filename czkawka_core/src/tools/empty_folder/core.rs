@@ -11,19 +11,20 @@ use rayon::prelude::*;
 
 use crate::common::dir_traversal::{common_get_entry_data, common_get_metadata_dir, common_read_dir, get_modified_time};
 use crate::common::directories::Directories;
-use crate::common::items::ExcludedItems;
+use crate::common::items::{ExcludedItems, is_ignored_file_name, new_ignored_file_items};
 use crate::common::model::{ToolType, WorkContinueStatus};
 use crate::common::progress_data::{ProgressData, ToolStage};
 use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
 use crate::common::tool_data::CommonToolData;
-use crate::tools::empty_folder::{EmptyFolder, FolderEmptiness, FolderEntry, Info};
+use crate::tools::empty_folder::{EmptyFolder, EmptyFolderParameters, FolderEmptiness, FolderEntry, Info};
 
 impl EmptyFolder {
-    pub fn new() -> Self {
+    pub fn new(params: EmptyFolderParameters) -> Self {
         Self {
             common_data: CommonToolData::new(ToolType::EmptyFolders),
             information: Default::default(),
             empty_folder_list: Default::default(),
+            params,
         }
     }
 
@@ -62,6 +63,7 @@ impl EmptyFolder {
 
         let excluded_items = self.common_data.excluded_items.clone();
         let directories = self.common_data.directories.clone();
+        let ignored_items = new_ignored_file_items(&self.params.ignored_files);
 
         let mut non_empty_folders: Vec<String> = Vec::new();
 
@@ -116,7 +118,7 @@ impl EmptyFolder {
                                 &mut non_empty_folder,
                                 &mut folder_entries_list,
                             );
-                        } else if non_empty_folder.is_none() {
+                        } else if non_empty_folder.is_none() && !is_ignored_file_name(&entry_data.file_name(), &ignored_items) {
                             non_empty_folder = Some(current_folder_as_string.clone());
                         }
                     }
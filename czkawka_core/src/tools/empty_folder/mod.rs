@@ -34,10 +34,26 @@ impl ResultEntry for FolderEntry {
     }
 }
 
+/// Files created by file managers and OS, which are usually the only content of otherwise empty folders
+pub const DEFAULT_IGNORED_FILES: &[&str] = &["Thumbs.db", "ehthumbs.db", "desktop.ini", ".DS_Store", "._*"];
+
+#[derive(Clone, Debug, Default)]
+pub struct EmptyFolderParameters {
+    // File name patterns (case insensitive, with `*` wildcards), folders containing only such files are treated as empty
+    pub ignored_files: Vec<String>,
+}
+
+impl EmptyFolderParameters {
+    pub fn new(ignored_files: Vec<String>) -> Self {
+        Self { ignored_files }
+    }
+}
+
 pub struct EmptyFolder {
     common_data: CommonToolData,
     information: Info,
     empty_folder_list: IndexMap<String, FolderEntry>, // Path, FolderEntry
+    params: EmptyFolderParameters,
 }
 
 /// Enum with values which show if folder is empty.
@@ -57,6 +73,6 @@ pub struct Info {
 
 impl Default for EmptyFolder {
     fn default() -> Self {
-        Self::new()
+        Self::new(EmptyFolderParameters::default())
     }
 }
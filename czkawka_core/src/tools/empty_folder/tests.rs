@@ -4,9 +4,9 @@ use std::sync::atomic::AtomicBool;
 
 use tempfile::TempDir;
 
-use crate::common::tool_data::CommonData;
+use crate::common::tool_data::{CommonData, DeleteMethod};
 use crate::common::traits::Search;
-use crate::tools::empty_folder::EmptyFolder;
+use crate::tools::empty_folder::{DEFAULT_IGNORED_FILES, EmptyFolder, EmptyFolderParameters};
 
 #[test]
 fn test_find_empty_folders() {
@@ -22,7 +22,7 @@ fn test_find_empty_folders() {
     fs::create_dir(&non_empty).unwrap();
     fs::write(non_empty.join("file.txt"), b"content").unwrap();
 
-    let mut finder = EmptyFolder::new();
+    let mut finder = EmptyFolder::new(EmptyFolderParameters::default());
     finder.set_included_paths(vec![path.to_path_buf()]);
     finder.set_recursive_search(true);
 
@@ -44,7 +44,7 @@ fn test_nested_empty_folders() {
     fs::create_dir(&parent).unwrap();
     fs::create_dir(&child).unwrap();
 
-    let mut finder = EmptyFolder::new();
+    let mut finder = EmptyFolder::new(EmptyFolderParameters::default());
     finder.set_included_paths(vec![path.to_path_buf()]);
     finder.set_recursive_search(true);
 
@@ -66,7 +66,7 @@ fn test_no_empty_folders() {
     fs::create_dir(&dir).unwrap();
     fs::write(dir.join("file.txt"), b"content").unwrap();
 
-    let mut finder = EmptyFolder::new();
+    let mut finder = EmptyFolder::new(EmptyFolderParameters::default());
     finder.set_included_paths(vec![path.to_path_buf()]);
     finder.set_recursive_search(true);
 
@@ -88,7 +88,7 @@ fn test_folder_with_only_empty_subfolders() {
     fs::create_dir(parent.join("empty_child1")).unwrap();
     fs::create_dir(parent.join("empty_child2")).unwrap();
 
-    let mut finder = EmptyFolder::new();
+    let mut finder = EmptyFolder::new(EmptyFolderParameters::default());
     finder.set_included_paths(vec![path.to_path_buf()]);
     finder.set_recursive_search(true);
 
@@ -102,3 +102,64 @@ fn test_folder_with_only_empty_subfolders() {
         "Should find 1 empty folder (the parent) - which contains only empty subfolders"
     );
 }
+
+#[test]
+fn test_folders_with_ignored_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    let junk_only = path.join("junk_only");
+    fs::create_dir_all(junk_only.join("nested")).unwrap();
+    fs::write(junk_only.join("Thumbs.db"), b"junk").unwrap();
+    fs::write(junk_only.join("nested").join("._photo.jpg"), b"junk").unwrap();
+    fs::write(junk_only.join("nested").join(".ds_store"), b"junk").unwrap();
+
+    let with_file = path.join("with_file");
+    fs::create_dir(&with_file).unwrap();
+    fs::write(with_file.join("desktop.ini"), b"junk").unwrap();
+    fs::write(with_file.join("photo.jpg"), b"content").unwrap();
+
+    let mut finder = EmptyFolder::new(EmptyFolderParameters::default());
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_recursive_search(true);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+    assert_eq!(finder.get_information().number_of_empty_folders, 0);
+
+    let ignored_files = DEFAULT_IGNORED_FILES.iter().map(ToString::to_string).collect();
+    let mut finder = EmptyFolder::new(EmptyFolderParameters::new(ignored_files));
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_recursive_search(true);
+    finder.set_delete_method(DeleteMethod::Delete);
+    finder.search(&stop_flag, None);
+
+    assert_eq!(finder.get_information().number_of_empty_folders, 1);
+    assert!(finder.get_empty_folder_list().contains_key(&junk_only.to_string_lossy().to_string()));
+    assert!(!junk_only.exists());
+    assert!(with_file.join("desktop.ini").exists());
+}
+
+#[test]
+fn test_ignored_files_pattern_without_text_keeps_folder_with_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    let with_file = path.join("with_file");
+    fs::create_dir(&with_file).unwrap();
+    fs::write(with_file.join("photo.jpg"), b"content").unwrap();
+    let junk_only = path.join("junk_only");
+    fs::create_dir(&junk_only).unwrap();
+    fs::write(junk_only.join("Thumbs.db"), b"junk").unwrap();
+
+    let ignored_files = vec!["*".to_string(), "**".to_string(), "thumbs.db".to_string()];
+    let mut finder = EmptyFolder::new(EmptyFolderParameters::new(ignored_files));
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_recursive_search(true);
+    finder.set_delete_method(DeleteMethod::Delete);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+
+    assert_eq!(finder.get_information().number_of_empty_folders, 1);
+    assert!(!junk_only.exists());
+    assert!(with_file.join("photo.jpg").exists());
+}
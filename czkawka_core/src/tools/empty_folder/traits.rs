@@ -11,7 +11,7 @@ use crate::common::model::WorkContinueStatus;
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
 use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
-use crate::tools::empty_folder::{EmptyFolder, Info};
+use crate::tools::empty_folder::{EmptyFolder, EmptyFolderParameters, Info};
 
 impl AllTraits for EmptyFolder {}
 
@@ -82,12 +82,14 @@ impl PrintResults for EmptyFolder {
 
 impl CommonData for EmptyFolder {
     type Info = Info;
-    type Parameters = ();
+    type Parameters = EmptyFolderParameters;
 
     fn get_information(&self) -> Self::Info {
         self.information
     }
-    fn get_params(&self) -> Self::Parameters {}
+    fn get_params(&self) -> Self::Parameters {
+        self.params.clone()
+    }
     fn get_cd(&self) -> &CommonToolData {
         &self.common_data
     }
@@ -103,11 +105,15 @@ impl DeletingItems for EmptyFolder {
     #[fun_time(message = "delete_files", level = "debug")]
     fn delete_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
         match self.common_data.delete_method {
-            DeleteMethod::Delete => self.delete_simple_elements_and_add_to_messages(
-                stop_flag,
-                progress_sender,
-                DeleteItemType::DeletingFolders(self.empty_folder_list.values().cloned().collect::<Vec<_>>()),
-            ),
+            DeleteMethod::Delete => {
+                let folders = self.empty_folder_list.values().cloned().collect::<Vec<_>>();
+                let delete_item_type = if self.params.ignored_files.is_empty() {
+                    DeleteItemType::DeletingFolders(folders)
+                } else {
+                    DeleteItemType::DeletingFoldersWithIgnoredFiles(folders, self.params.ignored_files.clone())
+                };
+                self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, delete_item_type)
+            }
             DeleteMethod::None => WorkContinueStatus::Continue,
             _ => unreachable!(),
         }
@@ -0,0 +1,371 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::{fs, mem, panic};
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use little_exif::filetype::FileExtension;
+use little_exif::metadata::Metadata;
+use log::{debug, error};
+use rayon::prelude::*;
+
+use crate::common::cache::{CACHE_VERSION, load_and_split_cache_generalized_by_path, save_and_connect_cache_generalized_by_path};
+use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
+use crate::common::model::{ToolType, WorkContinueStatus};
+use crate::common::process_utils::{disable_windows_console_window, run_command_interruptible};
+use crate::common::progress_data::{CacheLoadPhase, ImageOptimizerStage, ProgressData, ToolStage};
+use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
+use crate::common::tool_data::CommonToolData;
+use crate::flc;
+use crate::tools::image_optimizer::{ImageOptimizer, ImageOptimizerEntry, ImageOptimizerFixParams, ImageOptimizerParameters, Info};
+
+impl ImageOptimizer {
+    pub fn new(params: ImageOptimizerParameters) -> Self {
+        Self {
+            common_data: CommonToolData::new(ToolType::ImageOptimizer),
+            information: Info::default(),
+            images_to_check: Default::default(),
+            optimizable_images: Vec::new(),
+            params,
+        }
+    }
+
+    #[fun_time(message = "find_images", level = "debug")]
+    pub(crate) fn find_images(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        let result = DirTraversalBuilder::new()
+            .common_data(&self.common_data)
+            .group_by(|_fe| ())
+            .stop_flag(stop_flag)
+            .progress_sender(progress_sender)
+            .build()
+            .run();
+
+        match result {
+            DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } => {
+                self.images_to_check = grouped_file_entries
+                    .into_values()
+                    .flatten()
+                    .map(|fe| {
+                        let image_entry = ImageOptimizerEntry {
+                            path: fe.path.clone(),
+                            size: fe.size,
+                            modified_date: fe.modified_date,
+                            error: None,
+                            optimized_size: 0,
+                        };
+                        (fe.path.to_string_lossy().to_string(), image_entry)
+                    })
+                    .collect();
+
+                self.common_data.text_messages.warnings.extend(warnings);
+                debug!("find_images - Found {} images to check.", self.images_to_check.len());
+
+                WorkContinueStatus::Continue
+            }
+
+            DirTraversalResult::Stopped => WorkContinueStatus::Stop,
+        }
+    }
+
+    #[fun_time(message = "load_cache", level = "debug")]
+    fn load_cache(
+        &mut self,
+        progress_sender: Option<&Sender<ProgressData>>,
+    ) -> (
+        BTreeMap<String, ImageOptimizerEntry>,
+        BTreeMap<String, ImageOptimizerEntry>,
+        BTreeMap<String, ImageOptimizerEntry>,
+    ) {
+        let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::ImageOptimizer(ImageOptimizerStage::LoadingCache(CacheLoadPhase::Loading)), 0, 0);
+        let res = load_and_split_cache_generalized_by_path(&get_image_optimizer_cache_file(&self.params), mem::take(&mut self.images_to_check), self);
+
+        progress_handler.join_thread();
+        res
+    }
+
+    #[fun_time(message = "save_to_cache", level = "debug")]
+    fn save_to_cache(&mut self, vec_file_entry: &[ImageOptimizerEntry], loaded_hash_map: BTreeMap<String, ImageOptimizerEntry>, progress_sender: Option<&Sender<ProgressData>>) {
+        let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::ImageOptimizer(ImageOptimizerStage::SavingCache), 0, 0);
+
+        save_and_connect_cache_generalized_by_path(&get_image_optimizer_cache_file(&self.params), vec_file_entry, loaded_hash_map, self);
+
+        progress_handler.join_thread();
+    }
+
+    #[fun_time(message = "check_images", level = "debug")]
+    pub(crate) fn check_images(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        if self.images_to_check.is_empty() {
+            return WorkContinueStatus::Continue;
+        }
+        self.information.number_of_checked_images = self.images_to_check.len();
+
+        let (loaded_hash_map, records_already_cached, non_cached_files_to_check) = self.load_cache(progress_sender);
+
+        let progress_handler = prepare_thread_handler_common(
+            progress_sender,
+            ToolStage::ImageOptimizer(ImageOptimizerStage::CheckingImages),
+            non_cached_files_to_check.len(),
+            non_cached_files_to_check.values().map(|item| item.size).sum::<u64>(),
+        );
+
+        let params = self.params.clone();
+        let mut vec_file_entry: Vec<ImageOptimizerEntry> = non_cached_files_to_check
+            .into_par_iter()
+            .map(|(_, mut file_entry)| {
+                if check_if_stop_received(stop_flag) {
+                    return None;
+                }
+
+                let res = estimate_optimized_size(&file_entry.path, &params, stop_flag);
+                if check_if_stop_received(stop_flag) {
+                    return None;
+                }
+
+                progress_handler.increase_items(1);
+                progress_handler.increase_size(file_entry.size);
+
+                match res {
+                    Ok(optimized_size) => file_entry.optimized_size = optimized_size,
+                    Err(e) => file_entry.error = Some(e),
+                }
+                Some(file_entry)
+            })
+            .while_some()
+            .collect();
+
+        progress_handler.join_thread();
+
+        vec_file_entry.extend(records_already_cached.into_values());
+
+        self.save_to_cache(&vec_file_entry, loaded_hash_map, progress_sender);
+
+        if check_if_stop_received(stop_flag) {
+            return WorkContinueStatus::Stop;
+        }
+
+        let minimal_savings_percent = f64::from(self.params.minimal_savings_percent);
+        for entry in vec_file_entry {
+            if let Some(error) = entry.error {
+                self.common_data.text_messages.warnings.push(error);
+            } else if entry.optimized_size > 0 && entry.get_estimated_savings() > 0 && entry.get_estimated_savings_percent() >= minimal_savings_percent {
+                self.optimizable_images.push(entry);
+            }
+        }
+        self.optimizable_images
+            .sort_unstable_by(|a, b| b.get_estimated_savings().cmp(&a.get_estimated_savings()).then_with(|| a.path.cmp(&b.path)));
+
+        self.information.number_of_optimizable_images = self.optimizable_images.len();
+        self.information.estimated_savings = self.optimizable_images.iter().map(ImageOptimizerEntry::get_estimated_savings).sum();
+        debug!(
+            "Found {} images which can be optimized, estimated savings {} bytes.",
+            self.information.number_of_optimizable_images, self.information.estimated_savings
+        );
+
+        WorkContinueStatus::Continue
+    }
+
+    #[fun_time(message = "fix_files", level = "debug")]
+    pub(crate) fn fix_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>, fix_params: ImageOptimizerFixParams) {
+        let entries = mem::take(&mut self.optimizable_images);
+        let progress_handler = prepare_thread_handler_common(
+            progress_sender,
+            ToolStage::ImageOptimizer(ImageOptimizerStage::OptimizingImages),
+            entries.len(),
+            entries.iter().map(|entry| entry.size).sum(),
+        );
+
+        let params = self.params.clone();
+        let warnings: Vec<_> = entries
+            .into_par_iter()
+            .map(|entry| {
+                if check_if_stop_received(stop_flag) {
+                    return None;
+                }
+
+                let result = optimize_image(&entry.path, entry.size, &params, fix_params, stop_flag);
+                progress_handler.increase_items(1);
+                progress_handler.increase_size(entry.size);
+                match result {
+                    Ok(_output_path) => Some(None),
+                    Err(e) => Some(Some(flc!("core_failed_to_optimize_image", file = entry.path.to_string_lossy(), reason = e))),
+                }
+            })
+            .while_some()
+            .flatten()
+            .collect();
+        progress_handler.join_thread();
+
+        self.common_data.text_messages.warnings.extend(warnings);
+    }
+}
+
+/// Optimizes single image and returns path of the result.
+///
+/// Optimized image is first written next to original, checked and only then replaces it, if `overwrite_original` is set.
+/// When image is converted to other format, original file is removed and optimized one gets the new extension.
+pub fn optimize_image(
+    path: &Path,
+    original_size: u64,
+    params: &ImageOptimizerParameters,
+    fix_params: ImageOptimizerFixParams,
+    stop_flag: &Arc<AtomicBool>,
+) -> Result<PathBuf, String> {
+    let extension = params.target_format.get_output_extension(path);
+    let temp_output = path.with_extension(format!("czkawka_optimized.{extension}"));
+
+    encode_image(path, &temp_output, params, stop_flag)?;
+
+    let new_size = fs::metadata(&temp_output)
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp_output);
+            flc!(
+                "core_failed_to_get_metadata_of_optimized_file",
+                file = temp_output.to_string_lossy(),
+                reason = e.to_string()
+            )
+        })?
+        .len();
+
+    if fix_params.fail_if_not_smaller && new_size >= original_size {
+        let _ = fs::remove_file(&temp_output);
+        return Err(flc!(
+            "core_optimized_file_larger",
+            optimized = temp_output.to_string_lossy(),
+            new_size = new_size,
+            original = path.to_string_lossy(),
+            original_size = original_size
+        ));
+    }
+
+    preserve_exif(path, &temp_output).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_output);
+    })?;
+
+    if !fix_params.overwrite_original {
+        return Ok(temp_output);
+    }
+
+    let final_path = path.with_extension(&extension);
+    if final_path != path && final_path.exists() {
+        let _ = fs::remove_file(&temp_output);
+        return Err(flc!("core_optimized_image_already_exists", file = final_path.to_string_lossy()));
+    }
+    fs::rename(&temp_output, &final_path).map_err(|e| {
+        let _ = fs::remove_file(&temp_output);
+        flc!("core_failed_to_replace_with_optimized", file = path.to_string_lossy(), reason = e.to_string())
+    })?;
+    if final_path != path {
+        fs::remove_file(path).map_err(|e| flc!("core_failed_to_replace_with_optimized", file = path.to_string_lossy(), reason = e.to_string()))?;
+    }
+
+    Ok(final_path)
+}
+
+// Image is encoded into temporary file, which is removed after checking its size
+fn estimate_optimized_size(path: &Path, params: &ImageOptimizerParameters, stop_flag: &Arc<AtomicBool>) -> Result<u64, String> {
+    let extension = params.target_format.get_output_extension(path);
+    let temp_output = tempfile::Builder::new()
+        .prefix("czkawka_image_optimizer_")
+        .suffix(&format!(".{extension}"))
+        .tempfile()
+        .map_err(|e| flc!("core_failed_to_optimize_image", file = path.to_string_lossy(), reason = e.to_string()))?
+        .into_temp_path();
+
+    encode_image(path, &temp_output, params, stop_flag)?;
+
+    fs::metadata(&temp_output).map(|metadata| metadata.len()).map_err(|e| {
+        flc!(
+            "core_failed_to_get_metadata_of_optimized_file",
+            file = temp_output.to_string_lossy(),
+            reason = e.to_string()
+        )
+    })
+}
+
+fn encode_image(source: &Path, output: &Path, params: &ImageOptimizerParameters, stop_flag: &Arc<AtomicBool>) -> Result<(), String> {
+    let command = params.target_format.create_command(source, output, params.quality);
+    let encoder = command.get_program().to_string_lossy().to_string();
+
+    match run_command_interruptible(command, stop_flag) {
+        None => {
+            let _ = fs::remove_file(output);
+            Err(flc!("core_image_processing_stopped_by_user"))
+        }
+        Some(Err(e)) => {
+            let _ = fs::remove_file(output);
+            Err(flc!("core_failed_to_optimize_image", file = source.to_string_lossy(), reason = e))
+        }
+        Some(Ok(command_output)) => {
+            if !command_output.status.success() {
+                error!(
+                    "{encoder} failed to optimize image \"{}\" with status {}. Stdout: {}, Stderr: {}",
+                    source.to_string_lossy(),
+                    command_output.status,
+                    command_output.stdout,
+                    command_output.stderr
+                );
+                let _ = fs::remove_file(output);
+                return Err(flc!(
+                    "core_image_encoder_error",
+                    file = source.to_string_lossy(),
+                    encoder = encoder,
+                    code = command_output.status.to_string(),
+                    reason = command_output.stderr
+                ));
+            }
+            Ok(())
+        }
+    }
+}
+
+// Encoders should copy EXIF by themselves, but some versions or input formats silently drop it, so it is copied again if missing
+pub(crate) fn preserve_exif(source: &Path, output: &Path) -> Result<(), String> {
+    panic::catch_unwind(|| {
+        let source_data = fs::read(source).map_err(|e| e.to_string())?;
+        let Some(source_type) = FileExtension::auto_detect(&mut std::io::Cursor::new(&source_data)) else {
+            return Ok(());
+        };
+        let Ok(source_metadata) = Metadata::new_from_vec(&source_data, source_type) else {
+            debug!("Cannot read EXIF of \"{}\", so it is not copied", source.to_string_lossy());
+            return Ok(());
+        };
+        let source_tags = (&source_metadata).into_iter().count();
+        if source_tags == 0 {
+            return Ok(());
+        }
+
+        let mut output_data = fs::read(output).map_err(|e| e.to_string())?;
+        let output_type = FileExtension::auto_detect(&mut std::io::Cursor::new(&output_data)).ok_or_else(|| "Failed to detect file type".to_string())?;
+        let output_tags = Metadata::new_from_vec(&output_data, output_type).map_or(0, |metadata| (&metadata).into_iter().count());
+        if output_tags >= source_tags {
+            return Ok(());
+        }
+
+        source_metadata.write_to_vec(&mut output_data, output_type).map_err(|e| e.to_string())?;
+        fs::write(output, output_data).map_err(|e| e.to_string())
+    })
+    .map_err(|e| format!("Panic occurred while copying EXIF: {e:?}"))?
+    .map_err(|e: String| flc!("core_exif_not_preserved", file = source.to_string_lossy(), reason = e))
+}
+
+pub fn get_missing_image_optimizer_programs(params: &ImageOptimizerParameters) -> Vec<&'static str> {
+    params
+        .target_format
+        .get_required_programs()
+        .iter()
+        .copied()
+        .filter(|program| {
+            let mut command = Command::new(program);
+            disable_windows_console_window(&mut command);
+            // Not every encoder supports --version, so only ability to start program is checked
+            command.arg("--version").stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null()).status().is_err()
+        })
+        .collect()
+}
+
+pub fn get_image_optimizer_cache_file(params: &ImageOptimizerParameters) -> String {
+    format!("cache_image_optimizer_{CACHE_VERSION}_{:?}_q{}.bin", params.target_format, params.quality)
+}
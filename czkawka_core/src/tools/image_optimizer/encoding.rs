@@ -0,0 +1,98 @@
+use std::path::Path;
+use std::process::Command;
+
+use crate::flc;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ImageOptimizerFormat {
+    /// Recompress PNG and JPEG files without changing their format and pixels
+    Lossless,
+    WebP,
+    Avif,
+    JpegXl,
+}
+
+impl ImageOptimizerFormat {
+    /// External programs used to encode images, all of them must be available in PATH
+    pub const fn get_required_programs(self) -> &'static [&'static str] {
+        match self {
+            Self::Lossless => &["oxipng", "jpegtran"],
+            Self::WebP => &["cwebp"],
+            Self::Avif => &["avifenc"],
+            Self::JpegXl => &["cjxl"],
+        }
+    }
+
+    /// Extension of optimized file, lossless mode keeps extension of original file
+    pub fn get_output_extension(self, source: &Path) -> String {
+        match self {
+            Self::Lossless => source.extension().map(|extension| extension.to_string_lossy().to_string()).unwrap_or_default(),
+            Self::WebP => "webp".to_string(),
+            Self::Avif => "avif".to_string(),
+            Self::JpegXl => "jxl".to_string(),
+        }
+    }
+
+    // All used encoders copy EXIF from source file by default or with given flags, result is verified later anyway
+    pub(crate) fn create_command(self, source: &Path, output: &Path, quality: u8) -> Command {
+        let is_png = source.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+        let lossless = quality == 100;
+
+        match self {
+            Self::Lossless if is_png => {
+                let mut command = Command::new("oxipng");
+                command.arg("-o").arg("2").arg("--out").arg(output).arg(source);
+                command
+            }
+            Self::Lossless => {
+                let mut command = Command::new("jpegtran");
+                command.arg("-copy").arg("all").arg("-optimize").arg("-progressive").arg("-outfile").arg(output).arg(source);
+                command
+            }
+            Self::WebP => {
+                let mut command = Command::new("cwebp");
+                command.arg("-quiet").arg("-metadata").arg("all");
+                if lossless {
+                    command.arg("-lossless");
+                } else {
+                    command.arg("-q").arg(quality.to_string());
+                }
+                command.arg(source).arg("-o").arg(output);
+                command
+            }
+            Self::Avif => {
+                let mut command = Command::new("avifenc");
+                if lossless {
+                    command.arg("--lossless");
+                } else {
+                    command.arg("-q").arg(quality.to_string());
+                }
+                command.arg(source).arg(output);
+                command
+            }
+            Self::JpegXl => {
+                let mut command = Command::new("cjxl");
+                command.arg(source).arg(output);
+                // Without quality argument, JPEG files are recompressed losslessly and can be restored bit by bit
+                if !lossless || is_png {
+                    command.arg("--lossless_jpeg=0").arg("-q").arg(quality.to_string());
+                }
+                command
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for ImageOptimizerFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_lowercase().as_str() {
+            "lossless" => Ok(Self::Lossless),
+            "webp" => Ok(Self::WebP),
+            "avif" => Ok(Self::Avif),
+            "jxl" | "jpegxl" | "jpeg-xl" => Ok(Self::JpegXl),
+            _ => Err(flc!("core_unknown_image_format", format = format)),
+        }
+    }
+}
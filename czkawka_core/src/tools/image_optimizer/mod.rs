@@ -0,0 +1,107 @@
+pub mod core;
+mod encoding;
+#[cfg(test)]
+mod tests;
+pub mod traits;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+pub use self::encoding::ImageOptimizerFormat;
+use crate::common::tool_data::CommonToolData;
+use crate::common::traits::ResultEntry;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Info {
+    pub number_of_checked_images: usize,
+    pub number_of_optimizable_images: usize,
+    pub estimated_savings: u64,
+    pub scanning_time: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageOptimizerParameters {
+    pub(crate) target_format: ImageOptimizerFormat,
+    // 1-100, ignored in lossless mode, 100 means lossless encoding in target format
+    pub(crate) quality: u8,
+    // Images with lower estimated savings are not reported, because optimizing them is not worth the time
+    pub(crate) minimal_savings_percent: u8,
+}
+
+impl ImageOptimizerParameters {
+    pub fn new(target_format: ImageOptimizerFormat, quality: u8, minimal_savings_percent: u8) -> Self {
+        assert!((1..=100).contains(&quality), "quality must be 1-100, got {quality}");
+        assert!(minimal_savings_percent <= 99, "minimal_savings_percent must be 0-99, got {minimal_savings_percent}");
+        Self {
+            target_format,
+            quality,
+            minimal_savings_percent,
+        }
+    }
+}
+
+impl Default for ImageOptimizerParameters {
+    fn default() -> Self {
+        Self::new(ImageOptimizerFormat::Lossless, 80, 5)
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct ImageOptimizerFixParams {
+    pub fail_if_not_smaller: bool,
+    // When converting to other format, original file is removed and the result gets the new extension
+    pub overwrite_original: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageOptimizerEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified_date: u64,
+    pub error: Option<String>,
+
+    // Size of image after trial optimization
+    pub optimized_size: u64,
+}
+
+impl ImageOptimizerEntry {
+    pub fn get_estimated_savings(&self) -> u64 {
+        self.size.saturating_sub(self.optimized_size)
+    }
+
+    pub fn get_estimated_savings_percent(&self) -> f64 {
+        if self.size == 0 {
+            return 0.0;
+        }
+        self.get_estimated_savings() as f64 * 100.0 / self.size as f64
+    }
+}
+
+impl ResultEntry for ImageOptimizerEntry {
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+    fn get_modified_date(&self) -> u64 {
+        self.modified_date
+    }
+    fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+pub struct ImageOptimizer {
+    common_data: CommonToolData,
+    information: Info,
+    images_to_check: BTreeMap<String, ImageOptimizerEntry>,
+    optimizable_images: Vec<ImageOptimizerEntry>,
+    params: ImageOptimizerParameters,
+}
+
+impl ImageOptimizer {
+    pub const fn get_optimizable_images(&self) -> &Vec<ImageOptimizerEntry> {
+        &self.optimizable_images
+    }
+}
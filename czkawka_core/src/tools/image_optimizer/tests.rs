@@ -0,0 +1,105 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use little_exif::metadata::Metadata;
+use tempfile::TempDir;
+
+use crate::tools::image_optimizer::core::preserve_exif;
+use crate::tools::image_optimizer::{ImageOptimizerEntry, ImageOptimizerFormat};
+
+fn get_test_resources_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_resources").join("images")
+}
+
+fn command_args(format: ImageOptimizerFormat, source: &str, quality: u8) -> Vec<String> {
+    format
+        .create_command(Path::new(source), Path::new("output"), quality)
+        .get_args()
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect()
+}
+
+fn count_exif_tags(path: &Path) -> usize {
+    Metadata::new_from_path(path).map_or(0, |metadata| (&metadata).into_iter().count())
+}
+
+#[test]
+fn test_preserve_exif_copies_missing_tags() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("source.jpg");
+    let output = temp_dir.path().join("source.czkawka_optimized.jpg");
+    fs::copy(get_test_resources_path().join("normal.jpg"), &source).unwrap();
+    // Image crate does not write EXIF, so it behaves like encoder which drops metadata
+    image::open(&source).unwrap().save(&output).unwrap();
+
+    let source_tags = count_exif_tags(&source);
+    assert!(source_tags > 0);
+    assert_eq!(count_exif_tags(&output), 0);
+
+    preserve_exif(&source, &output).unwrap();
+    assert_eq!(count_exif_tags(&output), source_tags);
+}
+
+#[test]
+fn test_preserve_exif_without_source_tags() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("source.png");
+    let output = temp_dir.path().join("output.png");
+    image::RgbImage::new(8, 8).save(&source).unwrap();
+    fs::copy(&source, &output).unwrap();
+
+    preserve_exif(&source, &output).unwrap();
+    assert_eq!(fs::read(&source).unwrap(), fs::read(&output).unwrap());
+}
+
+#[test]
+fn test_encoder_commands() {
+    assert_eq!(command_args(ImageOptimizerFormat::Lossless, "a.PNG", 80), vec!["-o", "2", "--out", "output", "a.PNG"]);
+    assert_eq!(
+        command_args(ImageOptimizerFormat::Lossless, "a.jpg", 80),
+        vec!["-copy", "all", "-optimize", "-progressive", "-outfile", "output", "a.jpg"]
+    );
+    assert_eq!(
+        command_args(ImageOptimizerFormat::WebP, "a.jpg", 75),
+        vec!["-quiet", "-metadata", "all", "-q", "75", "a.jpg", "-o", "output"]
+    );
+    assert_eq!(
+        command_args(ImageOptimizerFormat::WebP, "a.png", 100),
+        vec!["-quiet", "-metadata", "all", "-lossless", "a.png", "-o", "output"]
+    );
+    assert_eq!(command_args(ImageOptimizerFormat::Avif, "a.jpg", 100), vec!["--lossless", "a.jpg", "output"]);
+    // JPEG files are recompressed losslessly, PNG files need quality to be set
+    assert_eq!(command_args(ImageOptimizerFormat::JpegXl, "a.jpg", 100), vec!["a.jpg", "output"]);
+    assert_eq!(
+        command_args(ImageOptimizerFormat::JpegXl, "a.png", 100),
+        vec!["a.png", "output", "--lossless_jpeg=0", "-q", "100"]
+    );
+}
+
+#[test]
+fn test_output_extension_and_format_parsing() {
+    assert_eq!(ImageOptimizerFormat::Lossless.get_output_extension(Path::new("photo.JPG")), "JPG");
+    assert_eq!(ImageOptimizerFormat::JpegXl.get_output_extension(Path::new("photo.jpg")), "jxl");
+    assert_eq!(ImageOptimizerFormat::from_str("JXL"), Ok(ImageOptimizerFormat::JpegXl));
+    assert_eq!(ImageOptimizerFormat::from_str("webp"), Ok(ImageOptimizerFormat::WebP));
+    ImageOptimizerFormat::from_str("gif").unwrap_err();
+}
+
+#[test]
+fn test_estimated_savings() {
+    let entry = ImageOptimizerEntry {
+        size: 1000,
+        optimized_size: 750,
+        ..Default::default()
+    };
+    assert_eq!(entry.get_estimated_savings(), 250);
+    assert!((entry.get_estimated_savings_percent() - 25.0).abs() < f64::EPSILON);
+
+    let larger = ImageOptimizerEntry {
+        size: 1000,
+        optimized_size: 1200,
+        ..Default::default()
+    };
+    assert_eq!(larger.get_estimated_savings(), 0);
+}
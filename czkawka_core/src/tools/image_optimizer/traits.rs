@@ -0,0 +1,142 @@
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Instant;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use humansize::{BINARY, format_size};
+
+use crate::common::consts::IMAGE_OPTIMIZER_EXTENSIONS;
+use crate::common::model::WorkContinueStatus;
+use crate::common::progress_data::ProgressData;
+use crate::common::tool_data::{CommonData, CommonToolData};
+use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, FixingItems, PrintResults, Search};
+use crate::flc;
+use crate::tools::image_optimizer::core::get_missing_image_optimizer_programs;
+use crate::tools::image_optimizer::{ImageOptimizer, ImageOptimizerFixParams, ImageOptimizerParameters, Info};
+
+impl AllTraits for ImageOptimizer {}
+
+impl DeletingItems for ImageOptimizer {
+    #[fun_time(message = "delete_files", level = "debug")]
+    fn delete_files(&mut self, _stop_flag: &Arc<AtomicBool>, _progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        unreachable!("ImageOptimizer does not support deleting files");
+    }
+}
+
+impl FixingItems for ImageOptimizer {
+    type FixParams = ImageOptimizerFixParams;
+    #[fun_time(message = "fix_items", level = "debug")]
+    fn fix_items(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>, fix_params: Self::FixParams) {
+        self.fix_files(stop_flag, progress_sender, fix_params);
+    }
+}
+
+impl DebugPrint for ImageOptimizer {
+    #[expect(clippy::print_stdout)]
+    fn debug_print(&self) {
+        if !cfg!(debug_assertions) || cfg!(test) {
+            return;
+        }
+
+        println!("### INDIVIDUAL DEBUG PRINT ###");
+        println!("Info: {:?}", self.information);
+        println!("Params: {:?}", self.params);
+        println!("Optimizable images: {}", self.optimizable_images.len());
+        self.debug_print_common();
+        println!("-----------------------------------------");
+    }
+}
+
+impl PrintResults for ImageOptimizer {
+    fn write_results<T: Write>(&self, writer: &mut T) -> std::io::Result<()> {
+        self.write_base_search_paths(writer)?;
+
+        writeln!(
+            writer,
+            "Checked {} images, target format {:?}, quality {}.\n",
+            self.information.number_of_checked_images, self.params.target_format, self.params.quality
+        )?;
+
+        if self.information.number_of_optimizable_images != 0 {
+            writeln!(
+                writer,
+                "Found {} images which can be optimized, estimated savings {}.\n",
+                self.information.number_of_optimizable_images,
+                format_size(self.information.estimated_savings, BINARY)
+            )?;
+            for entry in &self.optimizable_images {
+                writeln!(
+                    writer,
+                    "\"{}\" - {} -> {} (-{:.1}%)",
+                    entry.path.to_string_lossy(),
+                    format_size(entry.size, BINARY),
+                    format_size(entry.optimized_size, BINARY),
+                    entry.get_estimated_savings_percent()
+                )?;
+            }
+        } else {
+            writeln!(writer, "Not found any images which can be optimized.")?;
+        }
+
+        Ok(())
+    }
+
+    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+        self.save_results_to_file_as_json_internal(file_name, &self.optimizable_images, pretty_print)
+    }
+}
+
+impl Search for ImageOptimizer {
+    #[fun_time(message = "find_optimizable_images", level = "info")]
+    fn search(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) {
+        let start_time = Instant::now();
+
+        let () = (|| {
+            let missing_programs = get_missing_image_optimizer_programs(&self.params);
+            if !missing_programs.is_empty() {
+                self.common_data.text_messages.critical = Some(flc!("core_image_optimizer_programs_not_found", programs = missing_programs.join(", ")));
+                return;
+            }
+
+            if self.prepare_items(Some(IMAGE_OPTIMIZER_EXTENSIONS)).is_err() {
+                return;
+            }
+            if self.find_images(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+                return;
+            }
+            if self.check_images(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+            }
+        })();
+
+        self.information.scanning_time = start_time.elapsed();
+
+        if !self.common_data.stopped_search {
+            self.debug_print();
+        }
+    }
+}
+
+impl CommonData for ImageOptimizer {
+    type Info = Info;
+    type Parameters = ImageOptimizerParameters;
+
+    fn get_information(&self) -> Self::Info {
+        self.information
+    }
+    fn get_params(&self) -> Self::Parameters {
+        self.params.clone()
+    }
+    fn get_cd(&self) -> &CommonToolData {
+        &self.common_data
+    }
+    fn get_cd_mut(&mut self) -> &mut CommonToolData {
+        &mut self.common_data
+    }
+    fn found_any_items(&self) -> bool {
+        self.information.number_of_optimizable_images > 0
+    }
+}
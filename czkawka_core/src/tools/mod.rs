@@ -1,14 +1,18 @@
 pub mod bad_extensions;
 pub mod bad_names;
 pub mod big_file;
+pub mod bit_rot;
 pub mod broken_files;
+pub mod build_artifacts;
 pub mod duplicate;
 pub mod empty_files;
 pub mod empty_folder;
 pub mod exif_remover;
+pub mod image_optimizer;
 pub mod invalid_symlinks;
 pub mod same_music;
 pub mod similar_images;
 pub mod similar_videos;
+pub mod stale_files;
 pub mod temporary;
 pub mod video_optimizer;
@@ -1,5 +1,6 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::fs::File;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::{mem, panic};
@@ -10,12 +11,12 @@ use indexmap::IndexSet;
 use lofty::file::{AudioFile, TaggedFileExt};
 use lofty::prelude::*;
 use lofty::read_from;
-use log::{debug, error};
+use log::{debug, error, warn};
 use rayon::prelude::*;
 use rusty_chromaprint::{Configuration, match_fingerprints};
 
 use crate::common::audio_fingerprint::calc_fingerprint_and_duration;
-use crate::common::cache::{CACHE_VERSION, load_and_split_cache_generalized_by_path, save_and_connect_cache_generalized_by_path};
+use crate::common::cache::{CACHE_SAME_MUSIC_VERSION, load_and_split_cache_generalized_by_path, save_and_connect_cache_generalized_by_path};
 use crate::common::create_crash_message;
 use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
 use crate::common::model::{CheckingMethod, ToolType, WorkContinueStatus};
@@ -24,7 +25,11 @@ use crate::common::progress_stop_handler::{check_if_stop_received, prepare_threa
 use crate::common::tool_data::CommonToolData;
 use crate::common::traits::ResultEntry;
 use crate::flc;
-use crate::tools::same_music::{GroupedFilesToCheck, Info, MusicEntry, MusicSimilarity, SameMusic, SameMusicParameters};
+use crate::tools::same_music::cue::join_cue_track_fingerprints;
+use crate::tools::same_music::normalization::{group_by_similar_values, normalize_tag};
+use crate::tools::same_music::quality::{check_fake_lossless, get_codec_info};
+use crate::tools::same_music::tag_copy::copy_tags_in_group;
+use crate::tools::same_music::{GroupedFilesToCheck, Info, MusicEntry, MusicSimilarity, MusicTagsFixParams, SameMusic, SameMusicParameters, TagCopyReport, TagNormalization};
 
 impl SameMusic {
     pub fn new(params: SameMusicParameters) -> Self {
@@ -36,6 +41,9 @@ impl SameMusic {
             music_to_check: Default::default(),
             duplicated_music_entries_referenced: Vec::new(),
             hash_preset_config: Configuration::preset_test1(), // TODO allow to change this and move to parameters
+            snippet_path: None,
+            snippet_matches: Vec::new(),
+            tag_copy_reports: Vec::new(),
             params,
         }
     }
@@ -91,16 +99,25 @@ impl SameMusic {
         // We only calculate fingerprints, for files with similar titles
         // This saves a lot of time, because we don't need to calculate and later compare fingerprints for files with different titles
 
-        if self.params.compare_fingerprints_only_with_similar_titles {
+        let entries_to_check: Vec<MusicEntry> = if self.params.compare_fingerprints_only_with_similar_titles {
             let grouped_by_title: BTreeMap<String, Vec<MusicEntry>> = Self::get_entries_grouped_by_title(mem::take(&mut self.music_entries));
-            self.music_to_check = grouped_by_title
+            grouped_by_title
                 .into_values()
                 .filter_map(|entries| if entries.len() >= 2 { Some(entries) } else { None })
                 .flatten()
-                .map(|e| (e.path.to_string_lossy().to_string(), e))
-                .collect();
+                .collect()
         } else {
-            self.music_to_check = mem::take(&mut self.music_entries).into_iter().map(|e| (e.path.to_string_lossy().to_string(), e)).collect();
+            mem::take(&mut self.music_entries)
+        };
+
+        // CUE tracks share audio file, so whole file is fingerprinted only once and later split into tracks
+        let (cue_track_entries, entries_to_check): (Vec<_>, Vec<_>) = entries_to_check.into_iter().partition(|e| e.cue_track.is_some());
+        self.music_to_check = entries_to_check.into_iter().map(|e| (e.path.to_string_lossy().to_string(), e)).collect();
+        for cue_track_entry in &cue_track_entries {
+            self.music_to_check.entry(cue_track_entry.path.to_string_lossy().to_string()).or_insert_with(|| MusicEntry {
+                cue_track: None,
+                ..cue_track_entry.clone()
+            });
         }
 
         let progress_handler = prepare_thread_handler_common(
@@ -110,6 +127,8 @@ impl SameMusic {
             0,
         );
 
+        // Fingerprint cache may contain outdated tags and quality info, so only fingerprints are taken from it
+        let mut current_entries = self.music_to_check.clone();
         let (loaded_hash_map, records_already_cached, non_cached_files_to_check) = self.load_cache(false);
 
         progress_handler.join_thread();
@@ -158,11 +177,17 @@ impl SameMusic {
 
         let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::SameMusic(mode, SameMusicStage::SavingFingerprintCache), 0, 0);
 
-        vec_file_entry.extend(records_already_cached.into_values());
+        vec_file_entry.extend(records_already_cached.into_iter().map(|(path, cached_entry)| match current_entries.remove(&path) {
+            Some(mut music_entry) => {
+                music_entry.fingerprint = cached_entry.fingerprint;
+                music_entry
+            }
+            None => cached_entry,
+        }));
 
         self.save_cache(&vec_file_entry, loaded_hash_map, false);
 
-        self.music_entries = vec_file_entry;
+        self.music_entries = join_cue_track_fingerprints(vec_file_entry, cue_track_entries, &self.hash_preset_config);
 
         progress_handler.join_thread();
         if check_if_stop_received(stop_flag) {
@@ -185,7 +210,22 @@ impl SameMusic {
 
         let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::SameMusic(mode, SameMusicStage::LoadingTagsCache(CacheLoadPhase::Loading)), 0, 0);
 
-        let (loaded_hash_map, records_already_cached, non_cached_files_to_check) = self.load_cache(true);
+        let (loaded_hash_map, mut records_already_cached, mut non_cached_files_to_check) = self.load_cache(true);
+
+        let detect_fake_lossless = self.params.detect_fake_lossless;
+        if detect_fake_lossless {
+            // Entries cached without spectrum analysis need to be checked again
+            let not_analysed_files: Vec<String> = records_already_cached
+                .iter()
+                .filter(|(_, music_entry)| music_entry.lossless && music_entry.spectral_cutoff == 0)
+                .map(|(path, _)| path.clone())
+                .collect();
+            for path in not_analysed_files {
+                if let Some(music_entry) = records_already_cached.remove(&path) {
+                    non_cached_files_to_check.insert(path, music_entry);
+                }
+            }
+        }
 
         progress_handler.join_thread();
         if check_if_stop_received(stop_flag) {
@@ -203,7 +243,12 @@ impl SameMusic {
                     return None;
                 }
 
-                let res = read_single_file_tags(&path, music_entry);
+                let res = read_single_file_tags(&path, music_entry).map(|mut music_entry| {
+                    if detect_fake_lossless && music_entry.lossless {
+                        check_fake_lossless(&mut music_entry, stop_flag);
+                    }
+                    music_entry
+                });
                 progress_handler.increase_items(1);
                 Some(res)
             })
@@ -258,7 +303,7 @@ impl SameMusic {
                     progress_handler.join_thread();
                     return WorkContinueStatus::Stop;
                 }
-                old_duplicates = self.check_music_item(old_duplicates, progress_handler.items_counter(), get_item, approximate_comparison);
+                old_duplicates = self.check_music_item(old_duplicates, progress_handler.items_counter(), flag, get_item, approximate_comparison);
             }
         }
         if (self.params.music_similarity & MusicSimilarity::BITRATE) == MusicSimilarity::BITRATE {
@@ -345,7 +390,7 @@ impl SameMusic {
                     #[expect(clippy::indexing_slicing)] // Validated that base_files/files_to_compare are not empty
                     if base_files.is_empty()
                         || files_to_compare.is_empty()
-                        || (base_files.len() == 1 && files_to_compare.len() == 1 && (base_files[0].path == files_to_compare[0].path))
+                        || (base_files.len() == 1 && files_to_compare.len() == 1 && (base_files[0].get_identifier() == files_to_compare[0].get_identifier()))
                     {
                         return None;
                     }
@@ -382,7 +427,7 @@ impl SameMusic {
                 return None;
             }
 
-            let f_string = f_entry.path.to_string_lossy().to_string();
+            let f_string = f_entry.get_identifier();
             if used_paths.contains(&f_string) {
                 continue;
             }
@@ -390,7 +435,7 @@ impl SameMusic {
             let (mut collected_similar_items, errors): (Vec<_>, Vec<_>) = files_to_compare
                 .par_iter()
                 .map(|e_entry| {
-                    let e_string = e_entry.path.to_string_lossy().to_string();
+                    let e_string = e_entry.get_identifier();
                     if used_paths.contains(&e_string) || e_string == f_string {
                         return None;
                     }
@@ -481,15 +526,24 @@ impl SameMusic {
         &self,
         old_duplicates: Vec<Vec<MusicEntry>>,
         items_counter: &Arc<AtomicUsize>,
+        flag: MusicSimilarity,
         get_item: fn(&MusicEntry) -> String,
         approximate_comparison: bool,
     ) -> Vec<Vec<MusicEntry>> {
+        // Only artist and title have many variants of writing the same thing
+        let is_artist = flag == MusicSimilarity::TRACK_ARTIST;
+        let (normalization, fuzzy_threshold) = if is_artist || flag == MusicSimilarity::TRACK_TITLE {
+            (self.params.tag_normalization, self.params.fuzzy_similarity_threshold)
+        } else {
+            (TagNormalization::NONE, None)
+        };
+
         let mut new_duplicates: Vec<_> = Default::default();
         let old_duplicates_len = old_duplicates.len();
         for vec_file_entry in old_duplicates {
             let mut hash_map: BTreeMap<String, Vec<MusicEntry>> = Default::default();
             for file_entry in vec_file_entry {
-                let mut thing = get_item(&file_entry).trim().to_lowercase();
+                let mut thing = normalize_tag(&get_item(&file_entry), normalization, is_artist);
                 if approximate_comparison {
                     thing = get_simplified_name(&thing);
                 }
@@ -497,7 +551,7 @@ impl SameMusic {
                     hash_map.entry(thing).or_default().push(file_entry);
                 }
             }
-            for (_title, vec_file_entry) in hash_map {
+            for vec_file_entry in group_by_similar_values(hash_map, fuzzy_threshold) {
                 if vec_file_entry.len() > 1 {
                     new_duplicates.push(vec_file_entry);
                 }
@@ -507,6 +561,75 @@ impl SameMusic {
 
         new_duplicates
     }
+
+    #[fun_time(message = "fix_files", level = "debug")]
+    pub(crate) fn fix_files(&mut self, stop_flag: &Arc<AtomicBool>, _progress_sender: Option<&Sender<ProgressData>>, fix_params: MusicTagsFixParams) {
+        // Files grouped by tags are the same only from tags point of view, so copying tags between them could merge different recordings
+        if self.params.check_type != CheckingMethod::AudioContent {
+            warn!("Tags can be copied only between files grouped by audio content, but {:?} was used", self.params.check_type);
+            self.common_data.text_messages.warnings.push(flc!("core_music_tags_copy_requires_audio_content"));
+            return;
+        }
+        let dry_run = self.common_data.dry_run;
+        let directories = &self.common_data.directories;
+
+        // In reference mode, tags are always taken from reference file, otherwise from best tagged file in group
+        // Tags of virtual CUE tracks cannot be read or written, because they belong to whole audio file
+        // Files from reference folders are never modified
+        let is_normal_file = |entry: &&MusicEntry| entry.cue_track.is_none() && !directories.is_in_referenced_directory(&entry.path);
+        let groups: Vec<(Option<&MusicEntry>, Vec<&MusicEntry>)> = if self.common_data.use_reference_folders {
+            self.duplicated_music_entries_referenced
+                .iter()
+                .filter(|(reference_entry, _)| reference_entry.cue_track.is_none())
+                .map(|(reference_entry, entries)| (Some(reference_entry), entries.iter().filter(is_normal_file).collect()))
+                .collect()
+        } else {
+            self.duplicated_music_entries
+                .iter()
+                .map(|entries| (None, entries.iter().filter(is_normal_file).collect()))
+                .collect()
+        };
+
+        let tag_copy_reports: Vec<TagCopyReport> = groups
+            .into_par_iter()
+            .map(|(reference_entry, entries)| {
+                if check_if_stop_received(stop_flag) {
+                    return None;
+                }
+                Some(copy_tags_in_group(reference_entry, &entries, fix_params.conflict_policy, dry_run))
+            })
+            .while_some()
+            .flatten()
+            .collect();
+
+        for report in &tag_copy_reports {
+            if let Some(error) = &report.error {
+                self.common_data
+                    .text_messages
+                    .warnings
+                    .push(flc!("core_failed_to_copy_tags", file = report.path.to_string_lossy(), reason = error));
+            }
+        }
+
+        // Results should show new tags of modified files
+        if !dry_run {
+            let changed_paths: HashSet<&Path> = tag_copy_reports
+                .iter()
+                .filter(|report| report.error.is_none() && !report.changed_tags.is_empty())
+                .map(|report| report.path.as_path())
+                .collect();
+            let reference_entries = self.duplicated_music_entries_referenced.iter_mut().map(|(_, entries)| entries);
+            for entry in self.duplicated_music_entries.iter_mut().chain(reference_entries).flatten() {
+                if changed_paths.contains(entry.path.as_path())
+                    && let Some(updated_entry) = read_single_file_tags(&entry.path.to_string_lossy(), entry.clone())
+                {
+                    *entry = updated_entry;
+                }
+            }
+        }
+
+        self.tag_copy_reports = tag_copy_reports;
+    }
 }
 
 fn read_single_file_tags(path: &str, mut music_entry: MusicEntry) -> Option<MusicEntry> {
@@ -530,6 +653,7 @@ fn read_single_file_tags(path: &str, mut music_entry: MusicEntry) -> Option<Musi
     let mut genre = String::new();
 
     let bitrate = properties.audio_bitrate().unwrap_or(0);
+    let (codec, lossless) = get_codec_info(tagged_file.file_type(), properties.bit_depth());
 
     if let Some(tag) = tagged_file.primary_tag() {
         track_title = tag.get_string(ItemKey::TrackTitle).unwrap_or_default().to_string();
@@ -575,6 +699,10 @@ fn read_single_file_tags(path: &str, mut music_entry: MusicEntry) -> Option<Musi
     music_entry.length = length_in_seconds;
     music_entry.genre = genre;
     music_entry.bitrate = bitrate;
+    music_entry.codec = codec;
+    music_entry.lossless = lossless;
+    music_entry.sample_rate = properties.sample_rate().unwrap_or(0);
+    music_entry.bit_depth = properties.bit_depth().unwrap_or(0);
 
     Some(music_entry)
 }
@@ -590,6 +718,21 @@ pub fn format_audio_duration(duration: u32) -> String {
     }
 }
 
+// e.g. "FLAC 44100Hz/16bit", "MP3 44100Hz" or "FLAC 44100Hz/16bit (fake, cutoff 16000Hz)"
+pub fn format_audio_quality(music_entry: &MusicEntry) -> String {
+    let mut quality = music_entry.codec.clone();
+    if music_entry.sample_rate != 0 {
+        quality.push_str(&format!(" {}Hz", music_entry.sample_rate));
+    }
+    if music_entry.bit_depth != 0 {
+        quality.push_str(&format!("/{}bit", music_entry.bit_depth));
+    }
+    if music_entry.fake_lossless {
+        quality.push_str(&format!(" (fake, cutoff {}Hz)", music_entry.spectral_cutoff));
+    }
+    quality
+}
+
 fn get_simplified_name_internal(what: &str, ignore_numbers: bool) -> String {
     let mut new_what = String::with_capacity(what.len());
     let mut tab_number = 0;
@@ -662,9 +805,9 @@ fn get_simplified_name(what: &str) -> String {
 
 pub fn get_similar_music_cache_file(checking_tags: bool) -> String {
     if checking_tags {
-        format!("cache_same_music_tags_{CACHE_VERSION}.bin")
+        format!("cache_same_music_tags_{CACHE_SAME_MUSIC_VERSION}.bin")
     } else {
-        format!("cache_same_music_fingerprints_{CACHE_VERSION}.bin")
+        format!("cache_same_music_fingerprints_{CACHE_SAME_MUSIC_VERSION}.bin")
     }
 }
 
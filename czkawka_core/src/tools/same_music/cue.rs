@@ -0,0 +1,275 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::debug;
+use rusty_chromaprint::Configuration;
+
+use crate::tools::same_music::{CueTrack, MusicEntry, SameMusic};
+
+// CUE sheets use frames as smallest time unit, there are 75 frames in each second
+const CUE_FRAMES_PER_SECOND: f32 = 75.0;
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct CueSheet {
+    pub performer: String,
+    pub year: String,
+    pub genre: String,
+    pub files: Vec<CueFile>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct CueFile {
+    pub path: PathBuf,
+    pub tracks: Vec<CueSheetTrack>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct CueSheetTrack {
+    pub index: u32,
+    pub title: String,
+    pub performer: String,
+    // Start of track in seconds, taken from INDEX 01
+    pub start: f32,
+}
+
+// Paths of audio files inside FILE commands are resolved relative to folder containing CUE sheet
+pub(crate) fn parse_cue_sheet(content: &str, cue_folder: &Path) -> CueSheet {
+    let mut cue_sheet = CueSheet::default();
+    // Commands after data track describe that track, so they cannot be assigned to previous audio track
+    let mut inside_data_track = false;
+
+    for line in content.trim_start_matches('\u{feff}').lines() {
+        let line = line.trim();
+        let (command, arguments) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let command = command.to_ascii_uppercase();
+        let arguments = arguments.trim();
+
+        match command.as_str() {
+            "FILE" => {
+                let file_name = unquote(arguments.rsplit_once(char::is_whitespace).map_or(arguments, |(name, _file_type)| name.trim()));
+                inside_data_track = false;
+                cue_sheet.files.push(CueFile {
+                    path: cue_folder.join(file_name),
+                    tracks: Vec::new(),
+                });
+            }
+            "TRACK" => {
+                let Some(cue_file) = cue_sheet.files.last_mut() else {
+                    continue;
+                };
+                let mut parts = arguments.split_whitespace();
+                let (Some(Ok(index)), Some(track_type)) = (parts.next().map(str::parse::<u32>), parts.next()) else {
+                    continue;
+                };
+                // Data tracks from mixed mode CDs are not music
+                inside_data_track = !track_type.eq_ignore_ascii_case("AUDIO");
+                if !inside_data_track {
+                    cue_file.tracks.push(CueSheetTrack {
+                        index,
+                        // Negative start marks track, for which INDEX 01 was not found yet
+                        start: -1.0,
+                        ..Default::default()
+                    });
+                }
+            }
+            "TITLE" | "PERFORMER" | "INDEX" if !inside_data_track => {
+                let current_track = cue_sheet.files.last_mut().and_then(|cue_file| cue_file.tracks.last_mut());
+                match (command.as_str(), current_track) {
+                    ("TITLE", Some(track)) => track.title = unquote(arguments).to_string(),
+                    ("PERFORMER", Some(track)) => track.performer = unquote(arguments).to_string(),
+                    ("PERFORMER", None) => cue_sheet.performer = unquote(arguments).to_string(),
+                    ("INDEX", Some(track)) => {
+                        if let Some(("01", time)) = arguments.split_once(char::is_whitespace)
+                            && let Some(start) = parse_cue_time(time.trim())
+                        {
+                            track.start = start;
+                        }
+                    }
+                    // Album title is not needed, because there is no album field in MusicEntry
+                    _ => {}
+                }
+            }
+            "REM" => {
+                let (rem_name, rem_value) = arguments.split_once(char::is_whitespace).unwrap_or((arguments, ""));
+                match rem_name.to_ascii_uppercase().as_str() {
+                    "DATE" => cue_sheet.year = unquote(rem_value.trim()).to_string(),
+                    "GENRE" => cue_sheet.genre = unquote(rem_value.trim()).to_string(),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Track without INDEX 01 has unknown position, so cannot be used
+    for cue_file in &mut cue_sheet.files {
+        cue_file.tracks.retain(|track| track.start >= 0.0);
+    }
+
+    cue_sheet
+}
+
+// Time in format mm:ss:ff, where minutes may be bigger than 59
+fn parse_cue_time(time: &str) -> Option<f32> {
+    let mut parts = time.split(':').map(str::parse::<u32>);
+    let (Some(Ok(minutes)), Some(Ok(seconds)), Some(Ok(frames)), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+        return None;
+    };
+    Some((minutes * 60 + seconds) as f32 + frames as f32 / CUE_FRAMES_PER_SECOND)
+}
+
+fn unquote(text: &str) -> &str {
+    text.strip_prefix('"').and_then(|text| text.strip_suffix('"')).unwrap_or(text)
+}
+
+fn read_cue_sheet(cue_path: &Path) -> Option<CueSheet> {
+    let content = match fs::read(cue_path) {
+        Ok(content) => content,
+        Err(e) => {
+            debug!("Failed to read CUE sheet \"{}\" - {e}", cue_path.to_string_lossy());
+            return None;
+        }
+    };
+    // Older CUE sheets are often saved in local encodings, so invalid characters are replaced instead of rejecting whole file
+    let content = String::from_utf8_lossy(&content);
+    Some(parse_cue_sheet(&content, cue_path.parent()?))
+}
+
+impl MusicEntry {
+    // Tracks from the same CUE image share path, so path alone is not enough to identify entry
+    pub(crate) fn get_identifier(&self) -> String {
+        match &self.cue_track {
+            Some(cue_track) => format!("{}#{}", self.path.to_string_lossy(), cue_track.index),
+            None => self.path.to_string_lossy().to_string(),
+        }
+    }
+}
+
+impl SameMusic {
+    // Replaces every audio file described by CUE sheet with virtual entries, one for each track
+    pub(crate) fn split_cue_images(&mut self) {
+        let music_folders: BTreeSet<&Path> = self.music_entries.iter().filter_map(|entry| entry.path.parent()).collect();
+        let cue_paths: Vec<PathBuf> = music_folders
+            .into_iter()
+            .filter_map(|folder| fs::read_dir(folder).ok())
+            .flat_map(|read_dir| read_dir.flatten().map(|dir_entry| dir_entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("cue")))
+            .collect();
+        if cue_paths.is_empty() {
+            return;
+        }
+
+        let mut image_paths: BTreeSet<PathBuf> = BTreeSet::new();
+        let mut cue_entries = Vec::new();
+        for cue_path in cue_paths {
+            let Some(cue_sheet) = read_cue_sheet(&cue_path) else {
+                continue;
+            };
+            for cue_file in &cue_sheet.files {
+                // Single track in file means that file is normal track, not whole album
+                if cue_file.tracks.len() < 2 || image_paths.contains(&cue_file.path) {
+                    continue;
+                }
+                let Some(image_entry) = self.music_entries.iter().find(|entry| entry.path == cue_file.path) else {
+                    continue;
+                };
+                cue_entries.extend(create_cue_track_entries(image_entry, &cue_sheet, cue_file, &cue_path));
+                image_paths.insert(cue_file.path.clone());
+            }
+        }
+
+        self.music_entries.retain(|entry| !image_paths.contains(&entry.path));
+        self.music_entries.extend(cue_entries);
+    }
+}
+
+fn create_cue_track_entries(image_entry: &MusicEntry, cue_sheet: &CueSheet, cue_file: &CueFile, cue_path: &Path) -> Vec<MusicEntry> {
+    let image_length = image_entry.length as f32;
+    cue_file
+        .tracks
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, track)| {
+            let end = cue_file.tracks.get(idx + 1).map_or(image_length, |next_track| next_track.start);
+            if end <= track.start {
+                return None;
+            }
+            let performer = if track.performer.is_empty() { &cue_sheet.performer } else { &track.performer };
+
+            let mut entry = image_entry.clone();
+            entry.track_title = track.title.clone();
+            entry.track_artist = performer.clone();
+            entry.year = cue_sheet.year.clone();
+            entry.genre = cue_sheet.genre.clone();
+            entry.length = (end - track.start).round() as u32;
+            entry.fingerprint = Vec::new();
+            entry.cue_track = Some(CueTrack {
+                cue_path: cue_path.to_path_buf(),
+                index: track.index,
+                start: track.start,
+                end,
+            });
+            Some(entry)
+        })
+        .collect()
+}
+
+// Image files are fingerprinted as a whole, and then each track takes its part of fingerprint
+pub(crate) fn join_cue_track_fingerprints(music_entries: Vec<MusicEntry>, cue_track_entries: Vec<MusicEntry>, configuration: &Configuration) -> Vec<MusicEntry> {
+    if cue_track_entries.is_empty() {
+        return music_entries;
+    }
+
+    let image_paths: BTreeSet<&Path> = cue_track_entries.iter().map(|entry| entry.path.as_path()).collect();
+    let (image_entries, mut music_entries): (Vec<_>, Vec<_>) = music_entries.into_iter().partition(|entry| image_paths.contains(entry.path.as_path()));
+    let image_fingerprints: BTreeMap<PathBuf, Vec<u32>> = image_entries.into_iter().map(|entry| (entry.path, entry.fingerprint)).collect();
+
+    for mut entry in cue_track_entries {
+        // Image without fingerprint failed to decode
+        if let Some(image_fingerprint) = image_fingerprints.get(&entry.path)
+            && let Some(cue_track) = &entry.cue_track
+        {
+            entry.fingerprint = slice_cue_track_fingerprint(image_fingerprint, cue_track, configuration);
+            music_entries.push(entry);
+        }
+    }
+    music_entries
+}
+
+fn slice_cue_track_fingerprint(image_fingerprint: &[u32], cue_track: &CueTrack, configuration: &Configuration) -> Vec<u32> {
+    let item_duration = configuration.item_duration_in_seconds();
+    let start_item = ((cue_track.start / item_duration) as usize).min(image_fingerprint.len());
+    let end_item = ((cue_track.end / item_duration).ceil() as usize).clamp(start_item, image_fingerprint.len());
+    image_fingerprint.get(start_item..end_item).unwrap_or_default().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cue_sheet() {
+        let content = "\u{feff}REM GENRE Rock\r\nREM DATE 1999\r\nPERFORMER \"Album Artist\"\r\nTITLE \"Album\"\r\nFILE \"Album Image.flac\" WAVE\r\n  TRACK 01 AUDIO\r\n    TITLE \"First\"\r\n    INDEX 01 00:00:00\r\n  TRACK 02 AUDIO\r\n    TITLE \"Second\"\r\n    PERFORMER \"Guest\"\r\n    INDEX 00 03:20:00\r\n    INDEX 01 03:21:37\r\n  TRACK 03 MODE1/2352\r\n    INDEX 01 10:00:00\r\n  TRACK 04 AUDIO\r\n    TITLE \"Without index\"\r\n";
+        let cue_sheet = parse_cue_sheet(content, Path::new("/music"));
+
+        assert_eq!(cue_sheet.performer, "Album Artist");
+        assert_eq!(cue_sheet.year, "1999");
+        assert_eq!(cue_sheet.genre, "Rock");
+        assert_eq!(cue_sheet.files.len(), 1);
+        assert_eq!(cue_sheet.files[0].path, Path::new("/music").join("Album Image.flac"));
+        let tracks = &cue_sheet.files[0].tracks;
+        assert_eq!(tracks.len(), 2);
+        assert_eq!((tracks[0].index, tracks[0].title.as_str(), tracks[0].performer.as_str()), (1, "First", ""));
+        assert_eq!((tracks[1].index, tracks[1].title.as_str(), tracks[1].performer.as_str()), (2, "Second", "Guest"));
+        assert!((tracks[1].start - (201.0 + 37.0 / 75.0)).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_parse_cue_time() {
+        assert_eq!(parse_cue_time("00:00:00"), Some(0.0));
+        assert_eq!(parse_cue_time("75:30:00"), Some(4530.0));
+        assert_eq!(parse_cue_time("01:02"), None);
+        assert_eq!(parse_cue_time("aa:00:00"), None);
+    }
+}
@@ -1,5 +1,10 @@
 use bitflags::bitflags;
 pub mod core;
+pub mod cue;
+pub mod normalization;
+pub mod quality;
+pub mod snippet;
+pub mod tag_copy;
 pub mod traits;
 
 #[cfg(test)]
@@ -30,6 +35,20 @@ bitflags! {
     }
 }
 
+bitflags! {
+    // Steps applied to artist and title tags before comparing them
+    #[derive(PartialEq, Copy, Clone, Debug)]
+    pub struct TagNormalization : u32 {
+        const NONE = 0;
+
+        const STRIP_FEATURING = 0b1;
+        const STRIP_VERSION_QUALIFIERS = 0b10;
+        const TRANSLITERATE = 0b100;
+        // Disabled by default, because it merges band names like "Simon & Garfunkel" with "Simon"
+        const SPLIT_ARTISTS = 0b1000;
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MusicEntry {
     pub size: u64,
@@ -44,6 +63,27 @@ pub struct MusicEntry {
     pub length: u32,
     pub genre: String,
     pub bitrate: u32,
+
+    pub codec: String,
+    pub lossless: bool,
+    pub sample_rate: u32,
+    pub bit_depth: u8,
+    // Highest frequency found in spectrum, 0 if not analysed
+    pub spectral_cutoff: u32,
+    // Lossless file created from lossy source, detected by spectral cutoff
+    pub fake_lossless: bool,
+    // Set for virtual entries, which are only part of bigger audio file described by CUE sheet
+    pub cue_track: Option<CueTrack>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CueTrack {
+    pub cue_path: PathBuf,
+    // Track number from CUE sheet
+    pub index: u32,
+    // Position of track in audio file, in seconds
+    pub start: f32,
+    pub end: f32,
 }
 
 impl ResultEntry for MusicEntry {
@@ -72,10 +112,67 @@ impl FileEntry {
             length: 0,
             genre: String::new(),
             bitrate: 0,
+
+            codec: String::new(),
+            lossless: false,
+            sample_rate: 0,
+            bit_depth: 0,
+            spectral_cutoff: 0,
+            fake_lossless: false,
+            cue_track: None,
         }
     }
 }
 
+// Library track which contains searched audio snippet
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SnippetMatch {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified_date: u64,
+    // Start of matched part in library track, in seconds
+    pub track_offset: f32,
+    // Start of matched part in snippet, in seconds
+    pub snippet_offset: f32,
+    pub duration: f32,
+    // 100% means that matched fingerprints are identical
+    pub similarity: f64,
+}
+
+impl ResultEntry for SnippetMatch {
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+    fn get_modified_date(&self) -> u64 {
+        self.modified_date
+    }
+    fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TagConflictPolicy {
+    // Only tags which are missing or empty in target file are set
+    FillEmpty,
+    Overwrite,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MusicTagsFixParams {
+    pub conflict_policy: TagConflictPolicy,
+}
+
+// Tags copied to single file - in dry run, tags which would be copied
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TagCopyReport {
+    pub path: PathBuf,
+    pub source_path: PathBuf,
+    // Names of changed tags e.g. "title" or "cover"
+    pub changed_tags: Vec<String>,
+    pub error: Option<String>,
+}
+
 struct GroupedFilesToCheck {
     pub base_files: Vec<MusicEntry>,
     pub files_to_compare: Vec<MusicEntry>,
@@ -96,6 +193,12 @@ pub struct SameMusicParameters {
     pub minimum_segment_duration: f32,
     pub maximum_difference: f64,
     pub compare_fingerprints_only_with_similar_titles: bool,
+    pub detect_fake_lossless: bool,
+    pub tag_normalization: TagNormalization,
+    // Minimal similarity (0.0 - 1.0) of normalized artist and title tags, when None tags must be equal
+    pub fuzzy_similarity_threshold: Option<f64>,
+    // Audio files described by CUE sheets are split into separate tracks
+    pub use_cue_sheets: bool,
 }
 
 impl SameMusicParameters {
@@ -106,9 +209,14 @@ impl SameMusicParameters {
         minimum_segment_duration: f32,
         maximum_difference: f64,
         compare_fingerprints_only_with_similar_titles: bool,
+        detect_fake_lossless: bool,
+        tag_normalization: TagNormalization,
+        fuzzy_similarity_threshold: Option<f64>,
+        use_cue_sheets: bool,
     ) -> Self {
         assert!(!music_similarity.is_empty());
         assert!([CheckingMethod::AudioTags, CheckingMethod::AudioContent].contains(&check_type));
+        assert!(fuzzy_similarity_threshold.is_none_or(|threshold| (0.0..=1.0).contains(&threshold)));
         Self {
             music_similarity,
             approximate_comparison,
@@ -116,6 +224,10 @@ impl SameMusicParameters {
             minimum_segment_duration,
             maximum_difference,
             compare_fingerprints_only_with_similar_titles,
+            detect_fake_lossless,
+            tag_normalization,
+            fuzzy_similarity_threshold,
+            use_cue_sheets,
         }
     }
 }
@@ -128,6 +240,9 @@ pub struct SameMusic {
     duplicated_music_entries: Vec<Vec<MusicEntry>>,
     duplicated_music_entries_referenced: Vec<(MusicEntry, Vec<MusicEntry>)>,
     hash_preset_config: Configuration,
+    snippet_path: Option<PathBuf>,
+    snippet_matches: Vec<SnippetMatch>,
+    tag_copy_reports: Vec<TagCopyReport>,
     params: SameMusicParameters,
 }
 
@@ -136,6 +251,14 @@ impl SameMusic {
         &self.duplicated_music_entries
     }
 
+    pub const fn get_snippet_matches(&self) -> &Vec<SnippetMatch> {
+        &self.snippet_matches
+    }
+
+    pub const fn get_tag_copy_reports(&self) -> &Vec<TagCopyReport> {
+        &self.tag_copy_reports
+    }
+
     pub fn get_params(&self) -> &SameMusicParameters {
         &self.params
     }
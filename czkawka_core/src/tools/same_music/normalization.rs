@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+
+use crate::tools::same_music::{MusicEntry, TagNormalization};
+
+// Words after which rest of artist or title is only list of guest artists
+const FEATURING_SEPARATORS: &[&str] = &[" feat. ", " feat ", " ft. ", " ft ", " featuring "];
+// Separator of multiple values in artist tag - words like "&" or "," are not used by default, because they are part of many band names e.g. "Simon & Garfunkel"
+const ARTIST_SEPARATOR: char = ';';
+// Used only with `TagNormalization::SPLIT_ARTISTS` - artists added after them are treated as guests
+const SECONDARY_ARTIST_SEPARATORS: &[&str] = &["&", ",", " and "];
+// Bracketed parts containing these words describe release, not recording itself
+const VERSION_QUALIFIERS: &[&str] = &[
+    "remaster",
+    "remastered",
+    "live",
+    "version",
+    "edit",
+    "mono",
+    "stereo",
+    "deluxe",
+    "bonus",
+    "demo",
+    "acoustic",
+    "explicit",
+    "clean",
+    "single",
+    "radio",
+    "album",
+    "feat",
+    "ft",
+    "featuring",
+];
+
+// Converts artist or title tag into form, which is the same for most variants of the same track
+pub(crate) fn normalize_tag(value: &str, normalization: TagNormalization, is_artist: bool) -> String {
+    let original_value = value.trim().to_lowercase();
+    if normalization.is_empty() {
+        return original_value;
+    }
+
+    let mut value = original_value.clone();
+    if normalization.contains(TagNormalization::TRANSLITERATE) {
+        value = deunicode::deunicode(&value).to_lowercase();
+    }
+    if normalization.contains(TagNormalization::STRIP_VERSION_QUALIFIERS) {
+        value = strip_version_qualifiers(&value);
+    }
+    if is_artist && normalization.contains(TagNormalization::SPLIT_ARTISTS) {
+        value = strip_secondary_artists(&value);
+    }
+    if normalization.contains(TagNormalization::STRIP_FEATURING) {
+        value = strip_featuring(&value, is_artist);
+    }
+
+    // Punctuation differs between releases a lot, e.g. "AC/DC" and "AC-DC"
+    let normalized = value
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // Tag containing only punctuation or qualifiers is better than empty one, which would be ignored
+    if normalized.is_empty() { original_value } else { normalized }
+}
+
+fn strip_featuring(value: &str, is_artist: bool) -> String {
+    let padded = format!(" {value} ");
+    let cut_position = FEATURING_SEPARATORS.iter().filter_map(|separator| padded.find(separator)).min();
+    let value = match cut_position {
+        // Separator at the beginning would remove whole value
+        Some(position) if position > 0 => padded.get(..position).unwrap_or(&padded).trim(),
+        _ => value,
+    };
+    if !is_artist {
+        return value.to_string();
+    }
+
+    // Order of artists in multi value tags differs between releases, so they are sorted to be compared as set
+    let mut artists: Vec<&str> = value.split(ARTIST_SEPARATOR).map(str::trim).filter(|artist| !artist.is_empty()).collect();
+    artists.sort_unstable();
+    artists.dedup();
+    if artists.is_empty() { value.to_string() } else { artists.join("; ") }
+}
+
+// Leaves only first artist from every value of multi value tag, e.g. "Artist & X; Other, Y" becomes "Artist; Other"
+fn strip_secondary_artists(value: &str) -> String {
+    value
+        .split(ARTIST_SEPARATOR)
+        .map(|artist| {
+            let cut_position = SECONDARY_ARTIST_SEPARATORS.iter().filter_map(|separator| artist.find(separator)).min();
+            match cut_position {
+                // Separator at the beginning would remove whole artist
+                Some(position) if position > 0 => artist.get(..position).unwrap_or(artist),
+                _ => artist,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn strip_version_qualifiers(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut bracket_content = String::new();
+    let mut closing_bracket = None;
+
+    for character in value.chars() {
+        match (closing_bracket, character) {
+            (None, '(') => closing_bracket = Some(')'),
+            (None, '[') => closing_bracket = Some(']'),
+            (None, _) => result.push(character),
+            (Some(closing), _) if closing == character => {
+                if !is_version_qualifier(&bracket_content) {
+                    result.push_str(&format!("({bracket_content})"));
+                }
+                bracket_content.clear();
+                closing_bracket = None;
+            }
+            (Some(_), _) => bracket_content.push(character),
+        }
+    }
+    // Not closed bracket is left as is
+    if closing_bracket.is_some() {
+        result.push('(');
+        result.push_str(&bracket_content);
+    }
+
+    // Qualifiers may be also added after dash e.g. "Song - Remastered 2011" or "Song - Live at Wembley"
+    if let Some(position) = result.rfind(" - ")
+        && let Some(suffix) = result.get(position + 3..)
+        && is_version_qualifier(suffix)
+    {
+        result.truncate(position);
+    }
+
+    result.trim().to_string()
+}
+
+fn is_version_qualifier(text: &str) -> bool {
+    text.split(|c: char| !c.is_alphanumeric()).any(|word| VERSION_QUALIFIERS.contains(&word))
+}
+
+// Groups entries by normalized tag values - with fuzzy threshold, values which are similar enough are placed in the same group
+pub(crate) fn group_by_similar_values(entries: BTreeMap<String, Vec<MusicEntry>>, fuzzy_threshold: Option<f64>) -> Vec<Vec<MusicEntry>> {
+    let Some(fuzzy_threshold) = fuzzy_threshold else {
+        return entries.into_values().collect();
+    };
+
+    let mut groups: Vec<(String, Vec<MusicEntry>)> = Vec::new();
+    for (value, music_entries) in entries {
+        match groups
+            .iter_mut()
+            .find(|(group_value, _)| strsim::normalized_levenshtein(group_value, &value) >= fuzzy_threshold)
+        {
+            Some((_, group_entries)) => group_entries.extend(music_entries),
+            None => groups.push((value, music_entries)),
+        }
+    }
+    groups.into_iter().map(|(_, music_entries)| music_entries).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tag() {
+        let all = TagNormalization::STRIP_FEATURING | TagNormalization::STRIP_VERSION_QUALIFIERS | TagNormalization::TRANSLITERATE;
+        let cases = [
+            ("Artist feat. Someone", all, "artist"),
+            ("Artist & Other", all, "artist other"),
+            ("Artist ft Other", all, "artist"),
+            ("Artist, Other & Another", all, "artist other another"),
+            ("Other; Artist", all, "artist other"),
+            ("Artist;Other; Artist feat. Someone", all, "artist other"),
+            ("Song (Remastered 2011)", all, "song"),
+            ("Song [Live]", all, "song"),
+            ("Song - Remastered 2011", all, "song"),
+            ("Song (feat. Someone)", all, "song"),
+            ("Song (Interlude)", all, "song interlude"),
+            ("Björk", all, "bjork"),
+            ("Björk", TagNormalization::STRIP_FEATURING, "björk"),
+            ("AC/DC", all, "ac dc"),
+            ("Song (Live)", TagNormalization::STRIP_FEATURING, "song live"),
+            ("Song (Live)", TagNormalization::NONE, "song (live)"),
+            ("(Live)", all, "(live)"),
+            ("  Simple  ", all, "simple"),
+        ];
+
+        for (input, normalization, expected) in cases {
+            let res = normalize_tag(input, normalization, true);
+            assert_eq!(res, expected, "Input: {input}, Expected: {expected}, Got: {res}");
+        }
+
+        // Band names are not split, but multiple artists are compared regardless of their order
+        assert_eq!(normalize_tag("Love & Hate (feat. Someone)", all, false), "love hate");
+        assert_eq!(normalize_tag("Simon & Garfunkel", all, true), "simon garfunkel");
+        assert_eq!(normalize_tag("Earth, Wind & Fire", all, true), "earth wind fire");
+        assert_eq!(normalize_tag("First; Second", all, true), normalize_tag("Second; First", all, true));
+    }
+
+    #[test]
+    fn test_normalize_tag_split_artists() {
+        let split = TagNormalization::STRIP_FEATURING | TagNormalization::SPLIT_ARTISTS;
+        let cases = [
+            ("Artist & X", "artist"),
+            ("Artist, X & Y", "artist"),
+            ("Artist and X", "artist"),
+            ("Artist & X; Other", "artist other"),
+            ("& Artist", "artist"),
+            ("Brandy", "brandy"),
+        ];
+
+        for (input, expected) in cases {
+            let res = normalize_tag(input, split, true);
+            assert_eq!(res, expected, "Input: {input}, Expected: {expected}, Got: {res}");
+        }
+
+        assert_eq!(normalize_tag("Artist & X", split, true), normalize_tag("Artist", split, true));
+        // Titles are never split
+        assert_eq!(normalize_tag("Love & Hate", split, false), "love hate");
+        // Without flag, duo names stay intact
+        assert_eq!(normalize_tag("Simon & Garfunkel", TagNormalization::STRIP_FEATURING, true), "simon garfunkel");
+    }
+}
@@ -0,0 +1,260 @@
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use lofty::file::FileType;
+use log::{debug, error};
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+use symphonia::core::codecs::CodecParameters;
+use symphonia::core::codecs::audio::AudioDecoderOptions;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::formats::probe::Hint;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+
+use crate::common::create_crash_message;
+use crate::common::progress_stop_handler::check_if_stop_received;
+use crate::tools::same_music::MusicEntry;
+
+const FFT_SIZE: usize = 4096;
+// Encoder lowpass is constant through whole track, so there is no need to decode everything
+const MAX_ANALYSED_SECONDS: u64 = 120;
+// Spectrum is averaged in bands of this width, so single tones do not look like cutoff
+const BAND_WIDTH_HZ: f32 = 250.0;
+// Lossy encoders remove everything above lowpass frequency, so level drops sharply, unlike natural roll-off
+const CUTOFF_DROP_DB: f32 = 30.0;
+// Windows quieter than this (RMS) contain no useful information about frequency content
+const SILENCE_RMS: f32 = 1e-4;
+// Even highest quality MP3/AAC encoders use lowpass around 20 kHz, while real CD audio reaches 22 kHz
+const FAKE_LOSSLESS_MAX_CUTOFF_HZ: u32 = 20_000;
+
+// Returns codec name and information if it is lossless
+pub(crate) fn get_codec_info(file_type: FileType, bit_depth: Option<u8>) -> (String, bool) {
+    let (codec, lossless) = match file_type {
+        // Lofty only reports bit depth for lossless codecs inside MP4 container
+        FileType::Mp4 if bit_depth.is_some() => ("ALAC", true),
+        FileType::Aac | FileType::Mp4 => ("AAC", false),
+        FileType::Aiff => ("AIFF", true),
+        FileType::Ape => ("APE", true),
+        FileType::Flac => ("FLAC", true),
+        FileType::Mpeg => ("MP3", false),
+        FileType::Mpc => ("Musepack", false),
+        FileType::Opus => ("Opus", false),
+        FileType::Vorbis => ("Vorbis", false),
+        FileType::Speex => ("Speex", false),
+        FileType::Wav => ("WAV", true),
+        FileType::WavPack => ("WavPack", true),
+        FileType::Custom(name) => (name, false),
+        _ => ("", false),
+    };
+    (codec.to_string(), lossless)
+}
+
+pub(crate) fn is_fake_lossless(sample_rate: u32, spectral_cutoff: u32) -> bool {
+    if spectral_cutoff == 0 || sample_rate == 0 {
+        return false;
+    }
+    // Cutoff close to Nyquist frequency is just anti-aliasing filter of genuine recording
+    let nyquist = sample_rate / 2;
+    spectral_cutoff < FAKE_LOSSLESS_MAX_CUTOFF_HZ.min(nyquist * 9 / 10)
+}
+
+// Approximate bitrate of lossy source, based on typical lowpass frequencies used by LAME
+fn estimate_lossy_bitrate(spectral_cutoff: u32) -> u32 {
+    match spectral_cutoff {
+        19_500.. => 256,
+        18_500.. => 192,
+        17_250.. => 160,
+        16_500.. => 128,
+        _ => 96,
+    }
+}
+
+impl MusicEntry {
+    // Bigger value means better quality - genuine lossless files always win with lossy ones,
+    // fake lossless files are compared with lossy files by bitrate of their probable source
+    pub fn quality_rank(&self) -> (bool, u8, u32, u32, u64) {
+        let genuine_lossless = self.lossless && !self.fake_lossless;
+        let bitrate = if self.fake_lossless {
+            estimate_lossy_bitrate(self.spectral_cutoff)
+        } else {
+            self.bitrate
+        };
+        if genuine_lossless {
+            (true, self.bit_depth, self.sample_rate, bitrate, self.size)
+        } else {
+            (false, 0, 0, bitrate, self.size)
+        }
+    }
+}
+
+pub(crate) fn check_fake_lossless(music_entry: &mut MusicEntry, stop_flag: &Arc<AtomicBool>) {
+    match calc_spectral_cutoff(&music_entry.path, stop_flag) {
+        Ok(Some(spectral_cutoff)) => {
+            music_entry.spectral_cutoff = spectral_cutoff;
+            music_entry.fake_lossless = is_fake_lossless(music_entry.sample_rate, spectral_cutoff);
+        }
+        Ok(None) => {}
+        Err(e) => debug!("Failed to analyse spectrum of \"{}\" - {e}", music_entry.path.to_string_lossy()),
+    }
+}
+
+// Returns highest frequency(in Hz) present in audio, Ok(None) when stop flag was set or when file is silent
+pub(crate) fn calc_spectral_cutoff(path: &Path, stop_flag: &Arc<AtomicBool>) -> Result<Option<u32>, String> {
+    std::panic::catch_unwind(|| {
+        let src = File::open(path).map_err(|_| "failed to open file".to_string())?;
+        let mss = MediaSourceStream::new(Box::new(src), Default::default());
+
+        let mut hint = Hint::new();
+        if let Some(ext) = path.extension().and_then(std::ffi::OsStr::to_str) {
+            hint.with_extension(ext);
+        }
+
+        let mut format = symphonia::default::get_probe()
+            .probe(&hint, mss, FormatOptions::default(), MetadataOptions::default())
+            .map_err(|_| "unsupported format".to_string())?;
+
+        let Some((track_id, audio_params)) = format.tracks().iter().find_map(|t| match t.codec_params.as_ref() {
+            Some(CodecParameters::Audio(p)) if p.sample_rate.is_some() => Some((t.id, p.clone())),
+            _ => None,
+        }) else {
+            return Err("no supported audio track".to_string());
+        };
+
+        let mut decoder = symphonia::default::get_codecs()
+            .make_audio_decoder(&audio_params, &AudioDecoderOptions::default())
+            .map_err(|_| "unsupported codec".to_string())?;
+
+        let mut analyzer: Option<SpectrumAnalyzer> = None;
+        let mut samples: Vec<f32> = Vec::new();
+
+        loop {
+            if check_if_stop_received(stop_flag) {
+                return Ok(None);
+            }
+
+            let packet = match format.next_packet() {
+                Ok(Some(packet)) => packet,
+                Ok(None) => break,
+                Err(symphonia::core::errors::Error::IoError(ref e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(format!("error while reading audio packet: {e}")),
+            };
+            if packet.track_id != track_id {
+                continue;
+            }
+
+            match decoder.decode(&packet) {
+                Ok(audio_buf) => {
+                    let spec = audio_buf.spec();
+                    let analyzer = analyzer.get_or_insert_with(|| SpectrumAnalyzer::new(spec.rate(), spec.channels().count()));
+
+                    samples.clear();
+                    audio_buf.copy_to_vec_interleaved(&mut samples);
+                    analyzer.consume(&samples);
+                    if analyzer.analysed_samples >= MAX_ANALYSED_SECONDS * u64::from(analyzer.sample_rate) {
+                        break;
+                    }
+                }
+                Err(symphonia::core::errors::Error::DecodeError(_)) => (),
+                Err(e) => return Err(format!("fatal error while decoding audio: {e}")),
+            }
+        }
+
+        let Some(analyzer) = analyzer else {
+            return Err("no audio frames decoded".to_string());
+        };
+        Ok(analyzer.find_cutoff())
+    })
+    .unwrap_or_else(|_| {
+        let message = create_crash_message("Symphonia", &path.to_string_lossy(), "https://github.com/pdeljanov/Symphonia");
+        error!("{message}");
+        Err(message)
+    })
+}
+
+struct SpectrumAnalyzer {
+    sample_rate: u32,
+    channels: usize,
+    analysed_samples: u64,
+    window: Vec<f32>,
+    mono_buffer: Vec<f32>,
+    power_sum: Vec<f64>,
+    analysed_windows: u32,
+    fft: Arc<dyn rustfft::Fft<f32>>,
+}
+
+impl SpectrumAnalyzer {
+    fn new(sample_rate: u32, channels: usize) -> Self {
+        // Hann window reduces spectral leakage, which could otherwise hide cutoff
+        let window = (0..FFT_SIZE)
+            .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (FFT_SIZE - 1) as f32).cos())
+            .collect();
+        Self {
+            sample_rate,
+            channels: channels.max(1),
+            analysed_samples: 0,
+            window,
+            mono_buffer: Vec::with_capacity(FFT_SIZE),
+            power_sum: vec![0.0; FFT_SIZE / 2],
+            analysed_windows: 0,
+            fft: FftPlanner::new().plan_fft_forward(FFT_SIZE),
+        }
+    }
+
+    fn consume(&mut self, interleaved_samples: &[f32]) {
+        for frame in interleaved_samples.chunks_exact(self.channels) {
+            self.mono_buffer.push(frame.iter().sum::<f32>() / self.channels as f32);
+            if self.mono_buffer.len() == FFT_SIZE {
+                self.analyse_window();
+                self.mono_buffer.clear();
+            }
+        }
+        self.analysed_samples += (interleaved_samples.len() / self.channels) as u64;
+    }
+
+    fn analyse_window(&mut self) {
+        let rms = (self.mono_buffer.iter().map(|s| s * s).sum::<f32>() / FFT_SIZE as f32).sqrt();
+        if rms < SILENCE_RMS {
+            return;
+        }
+
+        let mut buffer: Vec<Complex<f32>> = self.mono_buffer.iter().zip(&self.window).map(|(s, w)| Complex::new(s * w, 0.0)).collect();
+        self.fft.process(&mut buffer);
+        for (power, value) in self.power_sum.iter_mut().zip(&buffer) {
+            *power += f64::from(value.norm_sqr());
+        }
+        self.analysed_windows += 1;
+    }
+
+    fn find_cutoff(&self) -> Option<u32> {
+        if self.analysed_windows == 0 {
+            return None;
+        }
+
+        let bin_width = self.sample_rate as f32 / FFT_SIZE as f32;
+        let bins_in_band = ((BAND_WIDTH_HZ / bin_width).ceil() as usize).max(1);
+        let band_levels: Vec<f32> = self
+            .power_sum
+            .chunks(bins_in_band)
+            .map(|band| {
+                let average_power = band.iter().sum::<f64>() / (band.len() as f64 * f64::from(self.analysed_windows));
+                (10.0 * (average_power + 1e-20).log10()) as f32
+            })
+            .collect();
+
+        // Cutoff is highest place, where level of band is much higher than level of every band above it
+        let mut max_level_above: Option<f32> = None;
+        for (idx, &level) in band_levels.iter().enumerate().rev() {
+            if let Some(max_level) = max_level_above
+                && level - max_level >= CUTOFF_DROP_DB
+            {
+                return Some(((idx + 1) as f32 * bins_in_band as f32 * bin_width) as u32);
+            }
+            max_level_above = Some(max_level_above.map_or(level, |max_level| max_level.max(level)));
+        }
+
+        Some(self.sample_rate / 2)
+    }
+}
@@ -0,0 +1,160 @@
+use std::mem;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Instant;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use log::debug;
+use rayon::prelude::*;
+use rusty_chromaprint::{Configuration, match_fingerprints};
+
+use crate::common::audio_fingerprint::calc_fingerprint_and_duration;
+use crate::common::consts::AUDIO_FILES_CONTENT_EXTENSIONS;
+use crate::common::model::{CheckingMethod, WorkContinueStatus};
+use crate::common::progress_data::{ProgressData, SameMusicMode, SameMusicStage, ToolStage};
+use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
+use crate::common::tool_data::CommonData;
+use crate::common::traits::DebugPrint;
+use crate::flc;
+use crate::tools::same_music::{MusicEntry, SameMusic, SnippetMatch};
+
+// Fingerprint items contain 32 bits, so segment score is between 0 (identical) and 32 (completely different)
+const MAX_SEGMENT_SCORE: f64 = 32.0;
+
+impl SameMusic {
+    // Looks for library tracks which contain given audio clip
+    // Library fingerprints are shared with normal content search, so already cached files are not decoded again
+    #[fun_time(message = "search_snippet", level = "info")]
+    pub fn search_snippet(&mut self, snippet_path: &Path, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) {
+        let start_time = Instant::now();
+        self.snippet_path = Some(snippet_path.to_path_buf());
+
+        let () = (|| {
+            if self.prepare_items(Some(AUDIO_FILES_CONTENT_EXTENSIONS)).is_err() {
+                return;
+            }
+
+            let snippet_fingerprint = match calc_fingerprint_and_duration(snippet_path, &self.hash_preset_config, stop_flag) {
+                Ok(Some((fingerprint, _duration))) => fingerprint,
+                Ok(None) => {
+                    self.common_data.stopped_search = true;
+                    return;
+                }
+                Err(e) => {
+                    self.common_data.text_messages.critical = Some(flc!("core_failed_to_fingerprint_snippet", file = snippet_path.to_string_lossy(), reason = e));
+                    return;
+                }
+            };
+
+            if self.check_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+                return;
+            }
+
+            // Snippet has no tags, so every library file needs to have its fingerprint
+            self.params.compare_fingerprints_only_with_similar_titles = false;
+            self.music_entries = mem::take(&mut self.music_to_check).into_values().collect();
+            if self.calculate_fingerprint(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+                return;
+            }
+            if self.match_snippet_fingerprint(&snippet_fingerprint, stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+            }
+        })();
+
+        self.information.scanning_time = start_time.elapsed();
+
+        if !self.common_data.stopped_search {
+            self.debug_print();
+        }
+    }
+
+    #[fun_time(message = "match_snippet_fingerprint", level = "debug")]
+    fn match_snippet_fingerprint(&mut self, snippet_fingerprint: &[u32], stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        if self.music_entries.is_empty() {
+            return WorkContinueStatus::Continue;
+        }
+
+        let mode = match self.get_params().check_type {
+            CheckingMethod::AudioTags => SameMusicMode::AudioTags,
+            CheckingMethod::AudioContent => SameMusicMode::AudioContent,
+            _ => unreachable!("SameMusic only supports AudioTags and AudioContent"),
+        };
+        let progress_handler = prepare_thread_handler_common(
+            progress_sender,
+            ToolStage::SameMusic(mode, SameMusicStage::ComparingFingerprints),
+            self.music_entries.len(),
+            0,
+        );
+
+        let configuration = &self.hash_preset_config;
+        let minimum_segment_duration = self.params.minimum_segment_duration;
+        let maximum_difference = self.params.maximum_difference;
+
+        debug!("match_snippet_fingerprint - comparing snippet with {} files", self.music_entries.len());
+        let results: Vec<Result<SnippetMatch, String>> = self
+            .music_entries
+            .par_iter()
+            .map(|entry| {
+                if check_if_stop_received(stop_flag) {
+                    return None;
+                }
+                let result = find_snippet_in_entry(snippet_fingerprint, entry, configuration, minimum_segment_duration, maximum_difference);
+                progress_handler.increase_items(1);
+                Some(result)
+            })
+            .while_some()
+            .flatten()
+            .collect();
+
+        progress_handler.join_thread();
+        if check_if_stop_received(stop_flag) {
+            return WorkContinueStatus::Stop;
+        }
+
+        for result in results {
+            match result {
+                Ok(snippet_match) => self.snippet_matches.push(snippet_match),
+                Err(e) => self.common_data.text_messages.errors.push(e),
+            }
+        }
+        self.snippet_matches.sort_by(|a, b| b.similarity.total_cmp(&a.similarity).then_with(|| a.path.cmp(&b.path)));
+
+        // Clear unused data
+        self.music_entries.clear();
+
+        WorkContinueStatus::Continue
+    }
+}
+
+// Returns best segment of library track which matches snippet, if any
+fn find_snippet_in_entry(
+    snippet_fingerprint: &[u32],
+    entry: &MusicEntry,
+    configuration: &Configuration,
+    minimum_segment_duration: f32,
+    maximum_difference: f64,
+) -> Option<Result<SnippetMatch, String>> {
+    let segments = match match_fingerprints(snippet_fingerprint, &entry.fingerprint, configuration) {
+        Ok(segments) => segments,
+        Err(e) => return Some(Err(flc!("core_error_comparing_fingerprints", reason = e.to_string()))),
+    };
+
+    let best_segment = segments
+        .into_iter()
+        .filter(|s| s.duration(configuration) > minimum_segment_duration && s.score < maximum_difference)
+        .max_by(|a, b| a.duration(configuration).total_cmp(&b.duration(configuration)).then_with(|| b.score.total_cmp(&a.score)))?;
+
+    Some(Ok(SnippetMatch {
+        path: entry.path.clone(),
+        size: entry.size,
+        modified_date: entry.modified_date,
+        track_offset: best_segment.start2(configuration),
+        snippet_offset: best_segment.start1(configuration),
+        duration: best_segment.duration(configuration),
+        similarity: (1.0 - best_segment.score / MAX_SEGMENT_SCORE) * 100.0,
+    }))
+}
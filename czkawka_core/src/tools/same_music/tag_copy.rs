@@ -0,0 +1,180 @@
+use std::panic;
+use std::path::Path;
+
+use lofty::config::WriteOptions;
+use lofty::picture::{Picture, PictureType};
+use lofty::prelude::*;
+use lofty::read_from_path;
+use lofty::tag::Tag;
+use log::{debug, error};
+
+use crate::common::create_crash_message;
+use crate::tools::same_music::{MusicEntry, TagConflictPolicy, TagCopyReport};
+
+// Year is handled separately, because formats store it in different fields
+const COPIED_TEXT_TAGS: [(&str, ItemKey); 4] = [
+    ("title", ItemKey::TrackTitle),
+    ("artist", ItemKey::TrackArtist),
+    ("album", ItemKey::AlbumTitle),
+    ("genre", ItemKey::Genre),
+];
+
+#[derive(Default, Clone)]
+struct CopyableTags {
+    text_tags: Vec<(&'static str, ItemKey, String)>,
+    year: Option<String>,
+    cover: Option<Picture>,
+}
+
+impl CopyableTags {
+    fn filled_tags_count(&self) -> usize {
+        self.text_tags.len() + usize::from(self.year.is_some()) + usize::from(self.cover.is_some())
+    }
+}
+
+// Copies tags from reference file or, when it is not available, from file with most filled tags to rest of group
+pub(crate) fn copy_tags_in_group(reference_entry: Option<&MusicEntry>, entries: &[&MusicEntry], conflict_policy: TagConflictPolicy, dry_run: bool) -> Vec<TagCopyReport> {
+    let mut tagged_entries: Vec<(&MusicEntry, CopyableTags)> = reference_entry
+        .into_iter()
+        .chain(entries.iter().copied())
+        .map(|entry| {
+            let tags = read_copyable_tags(&entry.path).unwrap_or_else(|e| {
+                debug!("Failed to read tags of \"{}\" - {e}", entry.path.to_string_lossy());
+                CopyableTags::default()
+            });
+            (entry, tags)
+        })
+        .collect();
+
+    let source_idx = if reference_entry.is_some() {
+        Some(0)
+    } else {
+        tagged_entries
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, (entry, tags))| (tags.filled_tags_count(), entry.quality_rank()))
+            .map(|(idx, _)| idx)
+    };
+    let Some(source_idx) = source_idx.filter(|idx| *idx < tagged_entries.len()) else {
+        return Vec::new();
+    };
+    let (source_entry, source_tags) = tagged_entries.swap_remove(source_idx);
+    if source_tags.filled_tags_count() == 0 {
+        return Vec::new();
+    }
+
+    tagged_entries
+        .into_iter()
+        .filter_map(|(entry, _)| {
+            let (changed_tags, error) = match copy_tags_to_file(&source_tags, &entry.path, conflict_policy, dry_run) {
+                Ok(changed_tags) if changed_tags.is_empty() => return None,
+                Ok(changed_tags) => (changed_tags, None),
+                Err(e) => (Vec::new(), Some(e)),
+            };
+            Some(TagCopyReport {
+                path: entry.path.clone(),
+                source_path: source_entry.path.clone(),
+                changed_tags,
+                error,
+            })
+        })
+        .collect()
+}
+
+fn read_copyable_tags(path: &Path) -> Result<CopyableTags, String> {
+    catch_lofty_panic(path, || {
+        let tagged_file = read_from_path(path).map_err(|e| e.to_string())?;
+
+        // Primary tag is checked first, other tags only fill missing values
+        let primary_tag_type = tagged_file.primary_tag_type();
+        let mut tags: Vec<&Tag> = tagged_file.primary_tag().into_iter().collect();
+        tags.extend(tagged_file.tags().iter().filter(|tag| tag.tag_type() != primary_tag_type));
+
+        let mut copyable_tags = CopyableTags::default();
+        for (name, item_key) in COPIED_TEXT_TAGS {
+            if let Some(value) = tags.iter().find_map(|tag| get_non_empty_string(tag, item_key)) {
+                copyable_tags.text_tags.push((name, item_key, value));
+            }
+        }
+        copyable_tags.year = tags.iter().find_map(|tag| get_year(tag));
+        copyable_tags.cover = tags.iter().find_map(|tag| get_cover(tag).cloned());
+
+        Ok(copyable_tags)
+    })
+}
+
+// Returns names of changed tags - in dry run file is not modified, but returned names are the same
+fn copy_tags_to_file(source_tags: &CopyableTags, path: &Path, conflict_policy: TagConflictPolicy, dry_run: bool) -> Result<Vec<String>, String> {
+    catch_lofty_panic(path, || {
+        let mut tagged_file = read_from_path(path).map_err(|e| e.to_string())?;
+
+        if tagged_file.primary_tag().is_none() {
+            let tag_type = tagged_file.primary_tag_type();
+            tagged_file.insert_tag(Tag::new(tag_type));
+        }
+        let Some(tag) = tagged_file.primary_tag_mut() else {
+            return Err("file format does not support tags".to_string());
+        };
+
+        let mut changed_tags = Vec::new();
+        for (name, item_key, value) in &source_tags.text_tags {
+            if should_replace(get_non_empty_string(tag, *item_key).as_ref(), value, conflict_policy) {
+                tag.insert_text(*item_key, value.clone());
+                changed_tags.push((*name).to_string());
+            }
+        }
+        if let Some(year) = &source_tags.year
+            && should_replace(get_year(tag).as_ref(), year, conflict_policy)
+        {
+            // Some tag types, e.g. ID3v2.4, have only recording date field
+            if !tag.insert_text(ItemKey::Year, year.clone()) {
+                tag.insert_text(ItemKey::RecordingDate, year.clone());
+            }
+            changed_tags.push("year".to_string());
+        }
+        if let Some(cover) = &source_tags.cover
+            && should_replace(get_cover(tag).map(Picture::data), cover.data(), conflict_policy)
+        {
+            let mut cover = cover.clone();
+            cover.set_pic_type(PictureType::CoverFront);
+            tag.remove_picture_type(PictureType::CoverFront);
+            tag.push_picture(cover);
+            changed_tags.push("cover".to_string());
+        }
+
+        if !dry_run && !changed_tags.is_empty() {
+            tagged_file.save_to_path(path, WriteOptions::default()).map_err(|e| e.to_string())?;
+        }
+
+        Ok(changed_tags)
+    })
+}
+
+fn should_replace<T: PartialEq + ?Sized>(current: Option<&T>, new: &T, conflict_policy: TagConflictPolicy) -> bool {
+    match current {
+        None => true,
+        Some(current) => conflict_policy == TagConflictPolicy::Overwrite && current != new,
+    }
+}
+
+fn get_non_empty_string(tag: &Tag, item_key: ItemKey) -> Option<String> {
+    tag.get_string(item_key).map(str::trim).filter(|value| !value.is_empty()).map(str::to_string)
+}
+
+fn get_year(tag: &Tag) -> Option<String> {
+    get_non_empty_string(tag, ItemKey::Year).or_else(|| get_non_empty_string(tag, ItemKey::RecordingDate))
+}
+
+// Not every file marks its picture as front cover, so first picture is used as fallback
+fn get_cover(tag: &Tag) -> Option<&Picture> {
+    let pictures = tag.pictures();
+    pictures.iter().find(|picture| picture.pic_type() == PictureType::CoverFront).or_else(|| pictures.first())
+}
+
+fn catch_lofty_panic<T>(path: &Path, func: impl FnOnce() -> Result<T, String> + panic::UnwindSafe) -> Result<T, String> {
+    panic::catch_unwind(func).unwrap_or_else(|_| {
+        let message = create_crash_message("Lofty", &path.to_string_lossy(), "https://github.com/Serial-ATA/lofty-rs");
+        error!("{message}");
+        Err(message)
+    })
+}
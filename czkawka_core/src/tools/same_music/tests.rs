@@ -1,11 +1,19 @@
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 
-use crate::common::model::CheckingMethod;
-use crate::common::tool_data::CommonData;
-use crate::common::traits::Search;
-use crate::tools::same_music::{MusicSimilarity, SameMusic, SameMusicParameters};
+use lofty::config::WriteOptions;
+use lofty::picture::{MimeType, Picture, PictureType};
+use lofty::prelude::*;
+use lofty::tag::Tag;
+use tempfile::TempDir;
+
+use crate::common::consts::AUDIO_FILES_TAGS_EXTENSIONS;
+use crate::common::model::{CheckingMethod, FileEntry};
+use crate::common::tool_data::{CommonData, DeleteMethod};
+use crate::common::traits::{DeletingItems, FixingItems, Search};
+use crate::tools::same_music::{MusicEntry, MusicSimilarity, MusicTagsFixParams, SameMusic, SameMusicParameters, TagConflictPolicy, TagNormalization};
 
 fn get_test_resources_path() -> PathBuf {
     let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_resources").join("audio");
@@ -19,7 +27,18 @@ fn get_test_resources_path() -> PathBuf {
 fn test_same_music_by_content_high_similarity() {
     let test_path = get_test_resources_path();
 
-    let params = SameMusicParameters::new(MusicSimilarity::TRACK_TITLE, false, CheckingMethod::AudioContent, 10.0, 0.2, false);
+    let params = SameMusicParameters::new(
+        MusicSimilarity::TRACK_TITLE,
+        false,
+        CheckingMethod::AudioContent,
+        10.0,
+        0.2,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
 
     let mut finder = SameMusic::new(params);
     finder.set_included_paths(vec![test_path]);
@@ -42,7 +61,18 @@ fn test_same_music_by_content_high_similarity() {
 fn test_same_music_by_content_medium_similarity() {
     let test_path = get_test_resources_path();
 
-    let params = SameMusicParameters::new(MusicSimilarity::TRACK_TITLE, false, CheckingMethod::AudioContent, 10.0, 0.5, false);
+    let params = SameMusicParameters::new(
+        MusicSimilarity::TRACK_TITLE,
+        false,
+        CheckingMethod::AudioContent,
+        10.0,
+        0.5,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
 
     let mut finder = SameMusic::new(params);
     finder.set_included_paths(vec![test_path]);
@@ -65,7 +95,18 @@ fn test_same_music_by_content_medium_similarity() {
 fn test_same_music_by_content_low_similarity() {
     let test_path = get_test_resources_path();
 
-    let params = SameMusicParameters::new(MusicSimilarity::TRACK_TITLE, false, CheckingMethod::AudioContent, 10.0, 0.8, false);
+    let params = SameMusicParameters::new(
+        MusicSimilarity::TRACK_TITLE,
+        false,
+        CheckingMethod::AudioContent,
+        10.0,
+        0.8,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
 
     let mut finder = SameMusic::new(params);
     finder.set_included_paths(vec![test_path]);
@@ -95,6 +136,10 @@ fn test_same_music_by_tags_title_artist() {
         10.0,
         0.2,
         false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
     );
 
     let mut finder = SameMusic::new(params);
@@ -118,7 +163,18 @@ fn test_same_music_by_tags_title_artist() {
 fn test_same_music_by_tags_year() {
     let test_path = get_test_resources_path();
 
-    let params = SameMusicParameters::new(MusicSimilarity::YEAR, false, CheckingMethod::AudioTags, 10.0, 0.2, false);
+    let params = SameMusicParameters::new(
+        MusicSimilarity::YEAR,
+        false,
+        CheckingMethod::AudioTags,
+        10.0,
+        0.2,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
 
     let mut finder = SameMusic::new(params);
     finder.set_included_paths(vec![test_path]);
@@ -140,7 +196,18 @@ fn test_same_music_by_tags_year() {
 fn test_same_music_by_tags_genre() {
     let test_path = get_test_resources_path();
 
-    let params = SameMusicParameters::new(MusicSimilarity::GENRE, false, CheckingMethod::AudioTags, 10.0, 0.2, false);
+    let params = SameMusicParameters::new(
+        MusicSimilarity::GENRE,
+        false,
+        CheckingMethod::AudioTags,
+        10.0,
+        0.2,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
 
     let mut finder = SameMusic::new(params);
     finder.set_included_paths(vec![test_path]);
@@ -163,7 +230,18 @@ fn test_same_music_by_tags_genre() {
 fn test_same_music_by_tags_bitrate() {
     let test_path = get_test_resources_path();
 
-    let params = SameMusicParameters::new(MusicSimilarity::BITRATE, false, CheckingMethod::AudioTags, 10.0, 0.2, false);
+    let params = SameMusicParameters::new(
+        MusicSimilarity::BITRATE,
+        false,
+        CheckingMethod::AudioTags,
+        10.0,
+        0.2,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
 
     let mut finder = SameMusic::new(params);
     finder.set_included_paths(vec![test_path]);
@@ -193,6 +271,10 @@ fn test_same_music_by_tags_all_criteria() {
         10.0,
         0.2,
         false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
     );
 
     let mut finder = SameMusic::new(params);
@@ -222,6 +304,10 @@ fn test_same_music_approximate_comparison() {
         10.0,
         0.2,
         false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
     );
 
     let mut finder = SameMusic::new(params);
@@ -243,12 +329,21 @@ fn test_same_music_approximate_comparison() {
 
 #[test]
 fn test_same_music_empty_directory() {
-    use tempfile::TempDir;
-
     let temp_dir = TempDir::new().unwrap();
     let path = temp_dir.path();
 
-    let params = SameMusicParameters::new(MusicSimilarity::TRACK_TITLE, false, CheckingMethod::AudioTags, 10.0, 0.2, false);
+    let params = SameMusicParameters::new(
+        MusicSimilarity::TRACK_TITLE,
+        false,
+        CheckingMethod::AudioTags,
+        10.0,
+        0.2,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
 
     let mut finder = SameMusic::new(params);
     finder.set_included_paths(vec![path.to_path_buf()]);
@@ -268,15 +363,6 @@ fn test_same_music_empty_directory() {
 
 #[test]
 fn test_same_music_reference_mode_deletes_only_non_reference() {
-    use std::fs;
-    use std::path::Path;
-
-    use tempfile::TempDir;
-
-    use crate::common::tool_data::DeleteMethod;
-    use crate::common::traits::DeletingItems;
-    use crate::tools::same_music::MusicEntry;
-
     let temp_dir = TempDir::new().unwrap();
     let reference = temp_dir.path().join("reference.mp3");
     let duplicate = temp_dir.path().join("duplicate.mp3");
@@ -294,9 +380,27 @@ fn test_same_music_reference_mode_deletes_only_non_reference() {
         length: 0,
         genre: String::new(),
         bitrate: 0,
+        codec: String::new(),
+        lossless: false,
+        sample_rate: 0,
+        bit_depth: 0,
+        spectral_cutoff: 0,
+        fake_lossless: false,
+        cue_track: None,
     };
 
-    let params = SameMusicParameters::new(MusicSimilarity::TRACK_TITLE, false, CheckingMethod::AudioTags, 10.0, 0.2, false);
+    let params = SameMusicParameters::new(
+        MusicSimilarity::TRACK_TITLE,
+        false,
+        CheckingMethod::AudioTags,
+        10.0,
+        0.2,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
     let mut finder = SameMusic::new(params);
     finder.set_delete_method(DeleteMethod::Delete);
     finder.set_move_to_trash(false);
@@ -309,3 +413,486 @@ fn test_same_music_reference_mode_deletes_only_non_reference() {
     assert!(reference.exists(), "Reference track must be kept");
     assert!(!duplicate.exists(), "Non-reference duplicate must be deleted (#1643)");
 }
+
+fn run_snippet_search(snippet_name: &str) -> SameMusic {
+    let temp_dir = TempDir::new().unwrap();
+    fs::copy(get_test_resources_path().join("base.mp3"), temp_dir.path().join("base.mp3")).unwrap();
+
+    let params = SameMusicParameters::new(
+        MusicSimilarity::TRACK_TITLE,
+        false,
+        CheckingMethod::AudioContent,
+        5.0,
+        2.0,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
+    let mut finder = SameMusic::new(params);
+    finder.set_included_paths(vec![temp_dir.path().to_path_buf()]);
+    finder.set_use_cache(false);
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search_snippet(&get_test_resources_path().join(snippet_name), &stop_flag, None);
+    finder
+}
+
+#[test]
+fn test_snippet_search_finds_offset_of_clip() {
+    let start_finder = run_snippet_search("base_start.mp3");
+    let start_matches = start_finder.get_snippet_matches();
+    assert_eq!(start_matches.len(), 1, "{:?}", start_finder.get_text_messages());
+    assert!(start_finder.found_any_items());
+
+    let end_finder = run_snippet_search("base_end.mp3");
+    let end_matches = end_finder.get_snippet_matches();
+    assert_eq!(end_matches.len(), 1, "{:?}", end_finder.get_text_messages());
+
+    assert!(start_matches[0].path.ends_with("base.mp3"));
+    assert!(start_matches[0].similarity > 90.0);
+    assert!(start_matches[0].track_offset < 1.0, "Got offset {}", start_matches[0].track_offset);
+    assert!(end_matches[0].track_offset > 30.0, "Got offset {}", end_matches[0].track_offset);
+}
+
+#[test]
+fn test_snippet_search_with_missing_snippet() {
+    let finder = run_snippet_search("not_existing.mp3");
+
+    assert!(finder.get_snippet_matches().is_empty());
+    assert!(finder.get_text_messages().critical.is_some());
+    assert!(!finder.found_any_items());
+}
+
+// Mono 16 bit WAV, with tones every 250 Hz up to given frequency - lossy transcodes look similar, because everything above lowpass is removed
+fn write_test_wav(path: &Path, max_frequency: f32) {
+    const SAMPLE_RATE: u32 = 44_100;
+
+    let frequencies: Vec<f32> = (0..).map(|i| i as f32 * 250.0 + 60.0).take_while(|frequency| *frequency < max_frequency).collect();
+    let amplitude = 0.8 / frequencies.len() as f32;
+    let samples: Vec<i16> = (0..SAMPLE_RATE * 3)
+        .map(|idx| {
+            let time = idx as f32 / SAMPLE_RATE as f32;
+            let value: f32 = frequencies
+                .iter()
+                .enumerate()
+                .map(|(tone_idx, frequency)| amplitude * (std::f32::consts::TAU * frequency * time + tone_idx as f32).sin())
+                .sum();
+            (value * f32::from(i16::MAX)) as i16
+        })
+        .collect();
+
+    let data_len = samples.len() as u32 * 2;
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // Mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    std::fs::write(path, wav).unwrap();
+}
+
+#[test]
+fn test_fake_lossless_detection() {
+    let temp_dir = TempDir::new().unwrap();
+    let genuine_path = temp_dir.path().join("genuine.wav");
+    let transcoded_path = temp_dir.path().join("transcoded.wav");
+    write_test_wav(&genuine_path, 21_800.0);
+    write_test_wav(&transcoded_path, 16_000.0);
+
+    let params = SameMusicParameters::new(
+        MusicSimilarity::TRACK_TITLE,
+        false,
+        CheckingMethod::AudioTags,
+        10.0,
+        0.2,
+        false,
+        true,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
+    let mut finder = SameMusic::new(params);
+    finder.set_included_paths(vec![temp_dir.path().to_path_buf()]);
+    finder.set_use_cache(false);
+    finder.prepare_items(Some(AUDIO_FILES_TAGS_EXTENSIONS)).unwrap();
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.check_files(&stop_flag, None);
+    finder.read_tags(&stop_flag, None);
+
+    let genuine = finder.music_entries.iter().find(|e| e.path == genuine_path).unwrap();
+    assert_eq!(genuine.codec, "WAV");
+    assert!(genuine.lossless);
+    assert_eq!(genuine.sample_rate, 44_100);
+    assert_eq!(genuine.bit_depth, 16);
+    assert!(genuine.spectral_cutoff > 21_000, "Got cutoff {}", genuine.spectral_cutoff);
+    assert!(!genuine.fake_lossless);
+
+    let transcoded = finder.music_entries.iter().find(|e| e.path == transcoded_path).unwrap();
+    assert!(transcoded.lossless);
+    assert!((15_500..16_500).contains(&transcoded.spectral_cutoff), "Got cutoff {}", transcoded.spectral_cutoff);
+    assert!(transcoded.fake_lossless);
+}
+
+#[test]
+fn test_delete_all_except_best_quality() {
+    let temp_dir = TempDir::new().unwrap();
+    let mk = |name: &str, codec: &str, lossless: bool, bitrate: u32, spectral_cutoff: u32, fake_lossless: bool, size: u64| {
+        let path = temp_dir.path().join(name);
+        fs::write(&path, "music").unwrap();
+        MusicEntry {
+            size,
+            path,
+            modified_date: 0,
+            fingerprint: Vec::new(),
+            track_title: String::new(),
+            track_artist: String::new(),
+            year: String::new(),
+            length: 0,
+            genre: String::new(),
+            bitrate,
+            codec: codec.to_string(),
+            lossless,
+            sample_rate: 44_100,
+            bit_depth: if lossless { 16 } else { 0 },
+            spectral_cutoff,
+            fake_lossless,
+            cue_track: None,
+        }
+    };
+    let mp3_320 = mk("320.mp3", "MP3", false, 320, 0, false, 100);
+    let mp3_256 = mk("256.mp3", "MP3", false, 256, 0, false, 80);
+    let mp3_128 = mk("128.mp3", "MP3", false, 128, 0, false, 150);
+    let genuine_flac = mk("genuine.flac", "FLAC", true, 900, 22_050, false, 300);
+    let fake_flac = mk("fake.flac", "FLAC", true, 1000, 16_000, true, 400);
+
+    // Fake FLAC is ranked by bitrate of its probable lossy source
+    assert!(fake_flac.quality_rank() < mp3_320.quality_rank());
+    assert!(mp3_320.quality_rank() < genuine_flac.quality_rank());
+
+    let params = SameMusicParameters::new(
+        MusicSimilarity::TRACK_TITLE,
+        false,
+        CheckingMethod::AudioTags,
+        10.0,
+        0.2,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
+    let mut finder = SameMusic::new(params);
+    finder.set_delete_method(DeleteMethod::AllExceptBestQuality);
+    finder.set_move_to_trash(false);
+    finder.duplicated_music_entries = vec![vec![mp3_320.clone(), genuine_flac.clone(), fake_flac.clone()], vec![mp3_128.clone(), mp3_256.clone()]];
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let _ = finder.delete_files(&stop_flag, None);
+
+    assert!(genuine_flac.path.exists(), "Genuine lossless file must be kept");
+    assert!(!fake_flac.path.exists());
+    assert!(!mp3_320.path.exists());
+    assert!(mp3_256.path.exists(), "Higher bitrate must win, even with smaller file");
+    assert!(!mp3_128.path.exists());
+}
+
+#[test]
+fn test_delete_all_except_best_quality_keeps_reference_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let reference_dir = temp_dir.path().join("reference");
+    fs::create_dir(&reference_dir).unwrap();
+    let mk = |path: PathBuf, bitrate: u32| {
+        fs::write(&path, "music").unwrap();
+        let mut entry = FileEntry { path, size: 5, modified_date: 0 }.into_music_entry();
+        entry.codec = "MP3".to_string();
+        entry.bitrate = bitrate;
+        entry
+    };
+    let reference = mk(reference_dir.join("128.mp3"), 128);
+    let better = mk(temp_dir.path().join("320.mp3"), 320);
+    let worse = mk(temp_dir.path().join("96.mp3"), 96);
+
+    let params = SameMusicParameters::new(
+        MusicSimilarity::TRACK_TITLE,
+        false,
+        CheckingMethod::AudioTags,
+        10.0,
+        0.2,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
+    let mut finder = SameMusic::new(params);
+    finder.set_delete_method(DeleteMethod::AllExceptBestQuality);
+    finder.set_move_to_trash(false);
+    finder.set_use_reference_folders(true);
+    finder.set_included_paths(vec![temp_dir.path().to_path_buf()]);
+    finder.set_reference_paths(vec![reference_dir]);
+    finder.duplicated_music_entries_referenced = vec![(reference.clone(), vec![better.clone(), worse.clone()])];
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let _ = finder.delete_files(&stop_flag, None);
+
+    assert!(reference.path.exists(), "Reference file must never be deleted, even with lower quality");
+    assert!(better.path.exists(), "Best quality copy must be kept");
+    assert!(!worse.path.exists());
+}
+
+#[test]
+fn test_tag_normalization_and_fuzzy_matching() {
+    let mk = |name: &str, artist: &str, title: &str| {
+        let mut entry = FileEntry {
+            path: PathBuf::from(name),
+            size: 1,
+            modified_date: 0,
+        }
+        .into_music_entry();
+        entry.track_artist = artist.to_string();
+        entry.track_title = title.to_string();
+        entry
+    };
+    let entries = vec![
+        mk("original.mp3", "Björk", "Army of Me"),
+        mk("remaster.mp3", "Bjork feat. Someone", "Army of Me (Remastered 2011)"),
+        mk("live.mp3", "Björk ft. Band", "Army Of Me [Live]"),
+        mk("typo.mp3", "Bjork", "Army of Mee"),
+        mk("other.mp3", "Björk", "Hyperballad"),
+    ];
+
+    let run = |tag_normalization: TagNormalization, fuzzy_similarity_threshold: Option<f64>| {
+        let params = SameMusicParameters::new(
+            MusicSimilarity::TRACK_TITLE | MusicSimilarity::TRACK_ARTIST,
+            false,
+            CheckingMethod::AudioTags,
+            10.0,
+            0.2,
+            false,
+            false,
+            tag_normalization,
+            fuzzy_similarity_threshold,
+            false,
+        );
+        let mut finder = SameMusic::new(params);
+        finder.music_entries = entries.clone();
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        finder.check_for_duplicate_tags(&stop_flag, None);
+        finder.get_duplicated_music_entries().iter().map(Vec::len).collect::<Vec<_>>()
+    };
+
+    let all = TagNormalization::STRIP_FEATURING | TagNormalization::STRIP_VERSION_QUALIFIERS | TagNormalization::TRANSLITERATE;
+    assert!(run(TagNormalization::NONE, None).is_empty());
+    assert_eq!(run(all, None), vec![3]);
+    assert_eq!(run(all, Some(0.9)), vec![4]);
+}
+
+fn write_test_tags(path: &Path, tags: &[(ItemKey, &str)], cover: Option<&[u8]>) {
+    let mut tagged_file = lofty::read_from_path(path).unwrap();
+    let mut tag = Tag::new(tagged_file.primary_tag_type());
+    for (item_key, value) in tags {
+        tag.insert_text(*item_key, (*value).to_string());
+    }
+    if let Some(cover) = cover {
+        tag.push_picture(Picture::unchecked(cover.to_vec()).pic_type(PictureType::CoverFront).mime_type(MimeType::Png).build());
+    }
+    tagged_file.insert_tag(tag);
+    tagged_file.save_to_path(path, WriteOptions::default()).unwrap();
+}
+
+fn read_test_tag(path: &Path, item_key: ItemKey) -> Option<String> {
+    let tagged_file = lofty::read_from_path(path).unwrap();
+    tagged_file.primary_tag().and_then(|tag| tag.get_string(item_key)).map(str::to_string)
+}
+
+#[test]
+fn test_copy_missing_tags() {
+    let temp_dir = TempDir::new().unwrap();
+    let tagged_path = temp_dir.path().join("tagged.wav");
+    let untagged_path = temp_dir.path().join("untagged.wav");
+
+    let run = |conflict_policy: TagConflictPolicy, dry_run: bool| {
+        write_test_wav(&tagged_path, 1000.0);
+        write_test_wav(&untagged_path, 1000.0);
+        write_test_tags(
+            &tagged_path,
+            &[
+                (ItemKey::TrackTitle, "Title"),
+                (ItemKey::TrackArtist, "Artist"),
+                (ItemKey::AlbumTitle, "Album"),
+                (ItemKey::RecordingDate, "1999"),
+                (ItemKey::Genre, "Rock"),
+            ],
+            Some(&[1, 2, 3]),
+        );
+        write_test_tags(&untagged_path, &[(ItemKey::TrackTitle, "Other title")], None);
+
+        let mk = |path: &Path| {
+            FileEntry {
+                path: path.to_path_buf(),
+                size: 1,
+                modified_date: 0,
+            }
+            .into_music_entry()
+        };
+        let params = SameMusicParameters::new(
+            MusicSimilarity::TRACK_TITLE,
+            false,
+            CheckingMethod::AudioContent,
+            10.0,
+            2.0,
+            false,
+            false,
+            TagNormalization::NONE,
+            None,
+            false,
+        );
+        let mut finder = SameMusic::new(params);
+        finder.set_dry_run(dry_run);
+        finder.duplicated_music_entries = vec![vec![mk(&untagged_path), mk(&tagged_path)]];
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        finder.fix_items(&stop_flag, None, MusicTagsFixParams { conflict_policy });
+        finder
+    };
+
+    let finder = run(TagConflictPolicy::FillEmpty, true);
+    let reports = finder.get_tag_copy_reports();
+    assert_eq!(reports.len(), 1, "{:?}", finder.get_text_messages());
+    assert_eq!(reports[0].path, untagged_path);
+    assert_eq!(reports[0].source_path, tagged_path);
+    assert_eq!(reports[0].changed_tags, vec!["artist", "album", "genre", "year", "cover"]);
+    assert_eq!(read_test_tag(&untagged_path, ItemKey::TrackArtist), None, "Dry run must not modify file");
+
+    let finder = run(TagConflictPolicy::FillEmpty, false);
+    assert_eq!(finder.get_tag_copy_reports().len(), 1, "{:?}", finder.get_text_messages());
+    assert_eq!(read_test_tag(&untagged_path, ItemKey::TrackTitle).as_deref(), Some("Other title"));
+    assert_eq!(read_test_tag(&untagged_path, ItemKey::TrackArtist).as_deref(), Some("Artist"));
+    assert_eq!(read_test_tag(&untagged_path, ItemKey::AlbumTitle).as_deref(), Some("Album"));
+    assert_eq!(read_test_tag(&untagged_path, ItemKey::RecordingDate).as_deref(), Some("1999"));
+    let cover_data = lofty::read_from_path(&untagged_path).unwrap().primary_tag().unwrap().pictures()[0].data().to_vec();
+    assert_eq!(cover_data, vec![1, 2, 3]);
+    let updated_entry = finder.get_duplicated_music_entries()[0].iter().find(|entry| entry.path == untagged_path).unwrap();
+    assert_eq!(updated_entry.track_artist, "Artist");
+
+    let finder = run(TagConflictPolicy::Overwrite, false);
+    assert_eq!(finder.get_tag_copy_reports()[0].changed_tags, vec!["title", "artist", "album", "genre", "year", "cover"]);
+    assert_eq!(read_test_tag(&untagged_path, ItemKey::TrackTitle).as_deref(), Some("Title"));
+}
+
+#[test]
+fn test_copy_tags_requires_audio_content_mode() {
+    let temp_dir = TempDir::new().unwrap();
+    let tagged_path = temp_dir.path().join("tagged.wav");
+    let untagged_path = temp_dir.path().join("untagged.wav");
+    write_test_wav(&tagged_path, 1000.0);
+    write_test_wav(&untagged_path, 1000.0);
+    write_test_tags(&tagged_path, &[(ItemKey::TrackTitle, "Title"), (ItemKey::TrackArtist, "Artist")], None);
+    write_test_tags(&untagged_path, &[(ItemKey::TrackTitle, "Title")], None);
+    let untagged_content = fs::read(&untagged_path).unwrap();
+
+    let mk = |path: &Path| {
+        FileEntry {
+            path: path.to_path_buf(),
+            size: 1,
+            modified_date: 0,
+        }
+        .into_music_entry()
+    };
+    let params = SameMusicParameters::new(
+        MusicSimilarity::TRACK_TITLE,
+        false,
+        CheckingMethod::AudioTags,
+        10.0,
+        2.0,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        false,
+    );
+    let mut finder = SameMusic::new(params);
+    finder.duplicated_music_entries = vec![vec![mk(&untagged_path), mk(&tagged_path)]];
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.fix_items(
+        &stop_flag,
+        None,
+        MusicTagsFixParams {
+            conflict_policy: TagConflictPolicy::Overwrite,
+        },
+    );
+
+    assert!(finder.get_tag_copy_reports().is_empty());
+    assert_eq!(finder.get_text_messages().warnings.len(), 1);
+    assert_eq!(fs::read(&untagged_path).unwrap(), untagged_content, "Files grouped by tags must not be modified");
+}
+
+#[test]
+fn test_cue_sheet_tracks_are_compared_with_separate_files() {
+    let temp_dir = TempDir::new().unwrap();
+    for file_name in ["base.mp3", "base_start.mp3", "base_end.mp3"] {
+        fs::copy(get_test_resources_path().join(file_name), temp_dir.path().join(file_name)).unwrap();
+    }
+    fs::write(
+        temp_dir.path().join("album.cue"),
+        "PERFORMER \"Artist\"\nFILE \"base.mp3\" MP3\n  TRACK 01 AUDIO\n    TITLE \"Start\"\n    INDEX 01 00:00:00\n  TRACK 02 AUDIO\n    TITLE \"End\"\n    INDEX 01 00:30:00\n",
+    )
+    .unwrap();
+
+    let params = SameMusicParameters::new(
+        MusicSimilarity::TRACK_TITLE,
+        false,
+        CheckingMethod::AudioContent,
+        5.0,
+        2.0,
+        false,
+        false,
+        TagNormalization::NONE,
+        None,
+        true,
+    );
+    let mut finder = SameMusic::new(params);
+    finder.set_included_paths(vec![temp_dir.path().to_path_buf()]);
+    finder.set_use_cache(false);
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+
+    let mut matched_tracks: Vec<(String, u32, String)> = finder
+        .get_duplicated_music_entries()
+        .iter()
+        .map(|group| {
+            assert_eq!(group.len(), 2, "{group:?}");
+            let cue_entry = group.iter().find(|entry| entry.cue_track.is_some()).unwrap();
+            let file_entry = group.iter().find(|entry| entry.cue_track.is_none()).unwrap();
+            let cue_track = cue_entry.cue_track.as_ref().unwrap();
+            assert!(cue_entry.path.ends_with("base.mp3"));
+            assert!(cue_track.cue_path.ends_with("album.cue"));
+            (
+                cue_entry.track_title.clone(),
+                cue_track.index,
+                file_entry.path.file_name().unwrap().to_string_lossy().to_string(),
+            )
+        })
+        .collect();
+    matched_tracks.sort();
+
+    assert_eq!(
+        matched_tracks,
+        vec![("End".to_string(), 2, "base_end.mp3".to_string()), ("Start".to_string(), 1, "base_start.mp3".to_string())]
+    );
+}
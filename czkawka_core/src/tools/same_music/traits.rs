@@ -1,4 +1,5 @@
 use std::io::prelude::*;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::time::Instant;
@@ -10,10 +11,10 @@ use crate::common::consts::{AUDIO_FILES_CONTENT_EXTENSIONS, AUDIO_FILES_TAGS_EXT
 use crate::common::model::{CheckingMethod, WorkContinueStatus};
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
-use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
+use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, FixingItems, PrintResults, Search};
 use crate::flc;
-use crate::tools::same_music::core::format_audio_duration;
-use crate::tools::same_music::{Info, MusicEntry, MusicSimilarity, SameMusic, SameMusicParameters};
+use crate::tools::same_music::core::{format_audio_duration, format_audio_quality};
+use crate::tools::same_music::{Info, MusicEntry, MusicSimilarity, MusicTagsFixParams, SameMusic, SameMusicParameters, SnippetMatch, TagCopyReport};
 
 impl AllTraits for SameMusic {}
 
@@ -47,6 +48,9 @@ impl Search for SameMusic {
                         self.common_data.stopped_search = true;
                         return;
                     }
+                    if self.params.use_cue_sheets {
+                        self.split_cue_images();
+                    }
                     if self.check_for_duplicate_tags(stop_flag, progress_sender) == WorkContinueStatus::Stop {
                         self.common_data.stopped_search = true;
                         return;
@@ -57,6 +61,9 @@ impl Search for SameMusic {
                         self.common_data.stopped_search = true;
                         return;
                     }
+                    if self.params.use_cue_sheets {
+                        self.split_cue_images();
+                    }
                     if self.calculate_fingerprint(stop_flag, progress_sender) == WorkContinueStatus::Stop {
                         self.common_data.stopped_search = true;
                         return;
@@ -98,6 +105,9 @@ impl DebugPrint for SameMusic {
 impl PrintResults for SameMusic {
     fn write_results<T: Write>(&self, writer: &mut T) -> std::io::Result<()> {
         self.write_base_search_paths(writer)?;
+        if let Some(snippet_path) = &self.snippet_path {
+            return write_snippet_matches(writer, snippet_path, &self.snippet_matches);
+        }
         if !self.duplicated_music_entries.is_empty() {
             writeln!(writer, "{} music files which have similar friends\n\n.", self.duplicated_music_entries.len())?;
 
@@ -123,11 +133,13 @@ impl PrintResults for SameMusic {
             write!(writer, "Not found any similar music files.")?;
         }
 
-        Ok(())
+        write_tag_copy_reports(writer, &self.tag_copy_reports, self.common_data.dry_run)
     }
 
     fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
-        if self.get_use_reference() {
+        if self.snippet_path.is_some() {
+            self.save_results_to_file_as_json_internal(file_name, &self.snippet_matches, pretty_print)
+        } else if self.get_use_reference() {
             self.save_results_to_file_as_json_internal(file_name, &self.duplicated_music_entries_referenced, pretty_print)
         } else {
             self.save_results_to_file_as_json_internal(file_name, &self.duplicated_music_entries, pretty_print)
@@ -138,15 +150,74 @@ impl PrintResults for SameMusic {
 fn write_music_entry<T: Write>(writer: &mut T, file_entry: &MusicEntry) -> std::io::Result<()> {
     writeln!(
         writer,
-        "TT: {}  -  TA: {}  -  Y: {}  -  L: {}  -  G: {}  -  B: {}  -  P: \"{}\"",
+        "TT: {}  -  TA: {}  -  Y: {}  -  L: {}  -  G: {}  -  B: {}  -  Q: {}  -  P: \"{}\"",
         file_entry.track_title,
         file_entry.track_artist,
         file_entry.year,
         format_audio_duration(file_entry.length),
         file_entry.genre,
         file_entry.bitrate,
+        format_audio_quality(file_entry),
         file_entry.path.to_string_lossy()
-    )
+    )?;
+    if let Some(cue_track) = &file_entry.cue_track {
+        writeln!(
+            writer,
+            "    CUE track {} ({} - {}) from \"{}\"",
+            cue_track.index,
+            format_audio_duration(cue_track.start as u32),
+            format_audio_duration(cue_track.end as u32),
+            cue_track.cue_path.to_string_lossy()
+        )?;
+    }
+    Ok(())
+}
+
+fn write_tag_copy_reports<T: Write>(writer: &mut T, tag_copy_reports: &[TagCopyReport], dry_run: bool) -> std::io::Result<()> {
+    if tag_copy_reports.is_empty() {
+        return Ok(());
+    }
+
+    let copied_files = tag_copy_reports.iter().filter(|report| report.error.is_none()).count();
+    if dry_run {
+        writeln!(writer, "\n\nTags would be copied to {copied_files} files (dry run).")?;
+    } else {
+        writeln!(writer, "\n\nCopied tags to {copied_files} files.")?;
+    }
+    for report in tag_copy_reports {
+        match &report.error {
+            None => writeln!(
+                writer,
+                "\"{}\" - {} - from \"{}\"",
+                report.path.to_string_lossy(),
+                report.changed_tags.join(", "),
+                report.source_path.to_string_lossy()
+            )?,
+            Some(error) => writeln!(writer, "\"{}\" - failed: {error}", report.path.to_string_lossy())?,
+        }
+    }
+    Ok(())
+}
+
+fn write_snippet_matches<T: Write>(writer: &mut T, snippet_path: &Path, snippet_matches: &[SnippetMatch]) -> std::io::Result<()> {
+    if snippet_matches.is_empty() {
+        write!(writer, "Not found any music files containing \"{}\".", snippet_path.to_string_lossy())?;
+        return Ok(());
+    }
+
+    writeln!(writer, "Found {} music files containing \"{}\".\n", snippet_matches.len(), snippet_path.to_string_lossy())?;
+    for snippet_match in snippet_matches {
+        writeln!(
+            writer,
+            "\"{}\" - at {:.1}s (snippet from {:.1}s) - duration {:.1}s - similarity {:.1}%",
+            snippet_match.path.to_string_lossy(),
+            snippet_match.track_offset,
+            snippet_match.snippet_offset,
+            snippet_match.duration,
+            snippet_match.similarity
+        )?;
+    }
+    Ok(())
 }
 
 impl CommonData for SameMusic {
@@ -169,7 +240,15 @@ impl CommonData for SameMusic {
         self.get_params().check_type
     }
     fn found_any_items(&self) -> bool {
-        self.information.number_of_duplicates > 0
+        self.information.number_of_duplicates > 0 || !self.snippet_matches.is_empty()
+    }
+}
+
+impl FixingItems for SameMusic {
+    type FixParams = MusicTagsFixParams;
+    #[fun_time(message = "fix_items", level = "debug")]
+    fn fix_items(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>, fix_params: Self::FixParams) {
+        self.fix_files(stop_flag, progress_sender, fix_params);
     }
 }
 
@@ -179,11 +258,44 @@ impl DeletingItems for SameMusic {
         if self.get_cd().delete_method == DeleteMethod::None {
             return WorkContinueStatus::Continue;
         }
+        // Virtual CUE tracks are only parts of bigger audio files, so they are never deleted
+        if self.get_cd().delete_method == DeleteMethod::AllExceptBestQuality {
+            // Reference files take part in choosing the best copy, but are never deleted
+            let groups: Vec<Vec<MusicEntry>> = if self.get_use_reference_folders() {
+                self.duplicated_music_entries_referenced
+                    .iter()
+                    .map(|(reference, files)| files.iter().chain(std::iter::once(reference)).cloned().collect())
+                    .collect()
+            } else {
+                self.duplicated_music_entries.clone()
+            };
+            let directories = &self.common_data.directories;
+            let files_to_delete = groups
+                .into_iter()
+                .flat_map(|mut group| {
+                    group.sort_by_key(MusicEntry::quality_rank);
+                    group.pop();
+                    group
+                        .into_iter()
+                        .filter(|entry| entry.cue_track.is_none() && !directories.is_in_referenced_directory(&entry.path))
+                })
+                .collect();
+            return self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFiles(files_to_delete));
+        }
         if self.get_use_reference_folders() {
-            let files_to_delete: Vec<_> = self.duplicated_music_entries_referenced.iter().flat_map(|(_, files)| files.iter().cloned()).collect();
+            let files_to_delete: Vec<_> = self
+                .duplicated_music_entries_referenced
+                .iter()
+                .flat_map(|(_, files)| files.iter().filter(|entry| entry.cue_track.is_none()).cloned())
+                .collect();
             return self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFiles(files_to_delete));
         }
-        let files_to_delete = self.duplicated_music_entries.clone();
+        let files_to_delete = self
+            .duplicated_music_entries
+            .iter()
+            .map(|group| group.iter().filter(|entry| entry.cue_track.is_none()).cloned().collect::<Vec<_>>())
+            .filter(|group| group.len() > 1)
+            .collect();
         self.delete_advanced_elements_and_add_to_messages(stop_flag, progress_sender, files_to_delete)
     }
 }
@@ -1,4 +1,5 @@
 use std::io::Write;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::time::Instant;
@@ -8,12 +9,13 @@ use fun_time::fun_time;
 use humansize::{BINARY, format_size};
 
 use crate::common::consts::{HEIC_EXTENSIONS, IMAGE_RS_SIMILAR_IMAGES_EXTENSIONS, RAW_IMAGE_EXTENSIONS};
+use crate::common::html_report::{HtmlReportGroup, HtmlReportItem, HtmlReportPreview, write_html_report};
 use crate::common::model::WorkContinueStatus;
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
-use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
+use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, HtmlReport, PrintResults, Search};
 use crate::tools::similar_images::core::get_string_from_similarity;
-use crate::tools::similar_images::{Info, SimilarImages, SimilarImagesParameters};
+use crate::tools::similar_images::{ImagesEntry, Info, SimilarImages, SimilarImagesParameters};
 
 impl AllTraits for SimilarImages {}
 
@@ -135,6 +137,46 @@ impl PrintResults for SimilarImages {
         }
     }
 }
+impl HtmlReport for SimilarImages {
+    #[fun_time(message = "save_results_to_html_report", level = "debug")]
+    fn save_results_to_html_report(&self, folder: &Path) -> std::io::Result<()> {
+        let hash_size = self.get_params().hash_size;
+        let to_report_item = |file_entry: &ImagesEntry, is_reference: bool| HtmlReportItem {
+            path: file_entry.path.clone(),
+            preview: Some(HtmlReportPreview::Image(file_entry.path.clone())),
+            columns: vec![
+                format_size(file_entry.size, BINARY),
+                format!("{}x{}", file_entry.width, file_entry.height),
+                get_string_from_similarity(file_entry.difference, hash_size),
+            ],
+            is_reference,
+        };
+
+        let groups: Vec<HtmlReportGroup> = if self.get_use_reference() {
+            self.similar_referenced_vectors
+                .iter()
+                .map(|(reference_entry, entries)| HtmlReportGroup {
+                    items: [to_report_item(reference_entry, true)]
+                        .into_iter()
+                        .chain(entries.iter().map(|entry| to_report_item(entry, false)))
+                        .collect(),
+                    notes: Vec::new(),
+                })
+                .collect()
+        } else {
+            self.similar_vectors
+                .iter()
+                .map(|entries| HtmlReportGroup {
+                    items: entries.iter().map(|entry| to_report_item(entry, false)).collect(),
+                    notes: Vec::new(),
+                })
+                .collect()
+        };
+
+        write_html_report(folder, "Similar images", &["Size", "Dimensions", "Similarity"], &groups)
+    }
+}
+
 impl CommonData for SimilarImages {
     type Info = Info;
     type Parameters = SimilarImagesParameters;
@@ -1,5 +1,6 @@
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::mem;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 
@@ -24,7 +25,8 @@ use crate::common::tool_data::{CommonData, CommonToolData};
 use crate::common::video_utils::{VIDEO_THUMBNAILS_SUBFOLDER, VideoMetadata, generate_thumbnail};
 use crate::flc;
 use crate::helpers::long_operation_watcher::run_with_long_operation_warnings;
-use crate::tools::similar_videos::{SimilarVideos, SimilarVideosParameters, VideoAudioEntry, VideosEntry};
+use crate::tools::similar_videos::match_ranges::{find_audio_match_range, find_visual_match_range};
+use crate::tools::similar_videos::{SimilarVideos, SimilarVideosParameters, VideoAudioEntry, VideoMatchRange, VideosEntry};
 
 impl SimilarVideos {
     pub fn new(params: SimilarVideosParameters) -> Self {
@@ -35,6 +37,7 @@ impl SimilarVideos {
             videos_to_check: Default::default(),
             audio_to_check: Default::default(),
             similar_referenced_vectors: Vec::new(),
+            match_ranges: Vec::new(),
             audio_config: Configuration::preset_test1(),
             params,
         }
@@ -153,6 +156,8 @@ impl SimilarVideos {
                 file_entry.width = metadata.width;
                 file_entry.height = metadata.height;
                 file_entry.duration = metadata.duration;
+                file_entry.audio_streams = metadata.audio_streams;
+                file_entry.subtitle_streams = metadata.subtitle_streams;
             }
             Err(e) => {
                 let path = file_entry.path.to_string_lossy();
@@ -237,6 +242,7 @@ impl SimilarVideos {
         }
 
         self.remove_from_reference_folders();
+        self.remove_match_ranges_of_not_found_videos();
 
         if self.common_data.use_reference_folders {
             for (_fe, vector) in &self.similar_referenced_vectors {
@@ -357,6 +363,7 @@ impl SimilarVideos {
         let exclude_same_size = self.get_params().exclude_videos_with_same_size;
         let exclude_same_resolution = self.get_params().exclude_videos_with_same_resolution;
         let mut collected_similar_videos: Vec<Vec<VideosEntry>> = Default::default();
+        let mut match_ranges: Vec<VideoMatchRange> = Vec::new();
         for group in groups {
             let mut temp_vector: Vec<VideosEntry> = Vec::new();
             let mut bt_size: BTreeSet<u64> = Default::default();
@@ -379,11 +386,13 @@ impl SimilarVideos {
             }
             if temp_vector.len() > 1 {
                 temp_vector.sort_unstable_by(|a, b| a.modified_date.cmp(&b.modified_date).then(a.path.cmp(&b.path)));
+                match_ranges.extend(visual_match_ranges_in_group(&temp_vector, &cmp_config));
                 collected_similar_videos.push(temp_vector);
             }
         }
 
         self.similar_vectors = collected_similar_videos;
+        self.match_ranges = match_ranges;
     }
 
     #[fun_time(message = "remove_from_reference_folders", level = "debug")]
@@ -392,6 +401,17 @@ impl SimilarVideos {
             self.similar_referenced_vectors = self.common_data.directories.filter_reference_folders(mem::take(&mut self.similar_vectors));
         }
     }
+
+    // Videos may be removed from groups by filters and reference folders, so their ranges would point to not visible results
+    fn remove_match_ranges_of_not_found_videos(&mut self) {
+        let referenced_entries = self
+            .similar_referenced_vectors
+            .iter()
+            .flat_map(|(reference_entry, entries)| entries.iter().chain([reference_entry]));
+        let found_paths: HashSet<&Path> = self.similar_vectors.iter().flatten().chain(referenced_entries).map(|entry| entry.path.as_path()).collect();
+        self.match_ranges
+            .retain(|match_range| found_paths.contains(match_range.container_path.as_path()) && found_paths.contains(match_range.contained_path.as_path()));
+    }
     #[fun_time(message = "calculate_audio_fingerprints", level = "debug")]
     pub(crate) fn calculate_audio_fingerprints(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
         if self.audio_to_check.is_empty() {
@@ -504,6 +524,7 @@ impl SimilarVideos {
         let configuration = &self.audio_config;
 
         let mut similar_vectors: Vec<Vec<VideosEntry>> = Vec::new();
+        let mut match_ranges: Vec<VideoMatchRange> = Vec::new();
         let mut used_paths: IndexSet<String> = Default::default();
 
         // Compute each entry's path string once instead of re-allocating it for every pair on every
@@ -526,7 +547,7 @@ impl SimilarVideos {
 
             let f_duration = f64::from(f_entry.audio_duration_seconds);
 
-            let (similar_entries, errors): (Vec<&VideoAudioEntry>, Vec<_>) = entries
+            let (similar_entries, errors): (Vec<(&VideoAudioEntry, Option<VideoMatchRange>)>, Vec<_>) = entries
                 .par_iter()
                 .zip(path_strings.par_iter())
                 .map(|(e_entry, e_string)| {
@@ -548,7 +569,19 @@ impl SimilarVideos {
                     segments.retain(|s| s.score < maximum_difference);
                     let matched_duration: f32 = segments.iter().map(|s| s.duration(configuration)).sum();
                     let threshold = shorter as f32 * (audio_similarity_percent / 100.0) as f32;
-                    if matched_duration >= threshold { Some(Ok(e_entry)) } else { None }
+                    if matched_duration >= threshold {
+                        let match_range = find_audio_match_range(
+                            &f_entry.path,
+                            f_entry.audio_duration_seconds,
+                            &e_entry.path,
+                            e_entry.audio_duration_seconds,
+                            &segments,
+                            configuration,
+                        );
+                        Some(Ok((e_entry, match_range)))
+                    } else {
+                        None
+                    }
                 })
                 .flatten()
                 .partition_map(|res| match res {
@@ -559,13 +592,12 @@ impl SimilarVideos {
             self.common_data.text_messages.errors.extend(errors);
 
             if !similar_entries.is_empty() {
-                let mut result_group: Vec<VideosEntry> = similar_entries
-                    .iter()
-                    .map(|e_entry| {
-                        used_paths.insert(e_entry.path.to_string_lossy().to_string());
-                        audio_entry_to_videos_entry(e_entry)
-                    })
-                    .collect();
+                let mut result_group: Vec<VideosEntry> = Vec::new();
+                for (e_entry, match_range) in similar_entries {
+                    used_paths.insert(e_entry.path.to_string_lossy().to_string());
+                    result_group.push(audio_entry_to_videos_entry(e_entry));
+                    match_ranges.extend(match_range);
+                }
                 used_paths.insert(f_string.clone());
                 result_group.push(audio_entry_to_videos_entry(f_entry));
                 similar_vectors.push(result_group);
@@ -581,8 +613,10 @@ impl SimilarVideos {
         }
 
         self.similar_vectors = similar_vectors;
+        self.match_ranges = match_ranges;
 
         self.remove_from_reference_folders();
+        self.remove_match_ranges_of_not_found_videos();
 
         if self.common_data.use_reference_folders {
             for (_fe, vector) in &self.similar_referenced_vectors {
@@ -600,6 +634,27 @@ impl SimilarVideos {
     }
 }
 
+// Every video in group is compared with the longest one, which most likely contains the rest of them
+fn visual_match_ranges_in_group(group: &[VideosEntry], cmp_config: &CompareConfig) -> Vec<VideoMatchRange> {
+    let signatures: Vec<&VideoSignature> = group.iter().filter_map(|entry| entry.signature.as_ref()).collect();
+    let Some(longest_signature) = signatures.iter().max_by_key(|signature| signature.duration_ms) else {
+        return Vec::new();
+    };
+    signatures
+        .iter()
+        .filter(|signature| signature.path != longest_signature.path)
+        .filter_map(|signature| {
+            find_visual_match_range(
+                longest_signature,
+                signature,
+                cmp_config.tolerance,
+                cmp_config.min_matching_windows,
+                cmp_config.subclip_min_match,
+            )
+        })
+        .collect()
+}
+
 fn exclude_same_size_and_resolution(similar_vectors: Vec<Vec<VideosEntry>>, exclude_same_size: bool, exclude_same_resolution: bool) -> Vec<Vec<VideosEntry>> {
     similar_vectors
         .into_par_iter()
@@ -654,6 +709,8 @@ fn audio_entry_to_videos_entry(ae: &VideoAudioEntry) -> VideosEntry {
         width: None,
         height: None,
         duration: Some(f64::from(ae.audio_duration_seconds)),
+        audio_streams: Vec::new(),
+        subtitle_streams: Vec::new(),
         thumbnail_path: None,
     }
 }
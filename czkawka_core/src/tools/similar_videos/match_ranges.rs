@@ -0,0 +1,209 @@
+use std::path::Path;
+
+use rusty_chromaprint::{Configuration, Segment};
+use similario_core::visual::{TemporalHash, VideoSignature};
+
+use crate::tools::similar_videos::VideoMatchRange;
+use crate::tools::similar_videos::core::format_duration_opt;
+
+// Shorter video is aligned with longer one in the same way as when grouping videos, and matched part is taken from windows which are close enough
+// Videos with the same number of windows are first compared as a whole, because then any window may be shifted
+pub(crate) fn find_visual_match_range(
+    first: &VideoSignature,
+    second: &VideoSignature,
+    tolerance: f32,
+    min_matching_windows: f32,
+    subclip_min_match: f32,
+) -> Option<VideoMatchRange> {
+    let (contained, container) = if first.duration_ms <= second.duration_ms { (first, second) } else { (second, first) };
+    let clip_hashes = &contained.visual_hashes;
+    let source_hashes = &container.visual_hashes;
+
+    let offset = if clip_hashes.len() == source_hashes.len() && matching_ratio(clip_hashes, source_hashes, tolerance) >= min_matching_windows {
+        0
+    } else {
+        best_alignment_offset(clip_hashes, source_hashes, tolerance, subclip_min_match)?
+    };
+
+    let matched_windows: Vec<(&TemporalHash, &TemporalHash)> = clip_hashes
+        .iter()
+        .zip(source_hashes.iter().skip(offset))
+        .filter(|(clip, source)| clip.normalized_distance(source) <= tolerance)
+        .collect();
+
+    let container_start = matched_windows.iter().map(|(_, source)| source.start_ms).min()?;
+    let container_end = matched_windows.iter().map(|(_, source)| source.end_ms).max()?;
+    let contained_start = matched_windows.iter().map(|(clip, _)| clip.start_ms).min()?;
+    let contained_end = matched_windows.iter().map(|(clip, _)| clip.end_ms).max()?;
+
+    Some(VideoMatchRange {
+        container_path: container.path.clone(),
+        contained_path: contained.path.clone(),
+        container_start: ms_to_seconds(container_start),
+        container_end: ms_to_seconds(container_end),
+        contained_start: ms_to_seconds(contained_start),
+        contained_end: ms_to_seconds(contained_end),
+    })
+}
+
+fn best_alignment_offset(clip_hashes: &[TemporalHash], source_hashes: &[TemporalHash], tolerance: f32, min_match: f32) -> Option<usize> {
+    if clip_hashes.is_empty() {
+        return None;
+    }
+    source_hashes
+        .windows(clip_hashes.len())
+        .enumerate()
+        .filter(|(_, source_window)| matching_ratio(clip_hashes, source_window, tolerance) >= min_match)
+        .map(|(offset, source_window)| (offset, average_similarity(clip_hashes, source_window)))
+        .max_by(|(_, first_score), (_, second_score)| first_score.total_cmp(second_score))
+        .map(|(offset, _)| offset)
+}
+
+fn matching_ratio(clip_hashes: &[TemporalHash], source_hashes: &[TemporalHash], tolerance: f32) -> f32 {
+    let matching = clip_hashes
+        .iter()
+        .zip(source_hashes)
+        .filter(|(clip, source)| clip.normalized_distance(source) <= tolerance)
+        .count();
+    matching as f32 / clip_hashes.len().max(1) as f32
+}
+
+fn average_similarity(clip_hashes: &[TemporalHash], source_hashes: &[TemporalHash]) -> f32 {
+    clip_hashes
+        .iter()
+        .zip(source_hashes)
+        .map(|(clip, source)| 1.0 - clip.normalized_distance(source))
+        .sum::<f32>()
+        / clip_hashes.len().max(1) as f32
+}
+
+// Matched part is the span between first and last matching audio segment
+pub(crate) fn find_audio_match_range(
+    first_path: &Path,
+    first_duration: u32,
+    second_path: &Path,
+    second_duration: u32,
+    segments: &[Segment],
+    configuration: &Configuration,
+) -> Option<VideoMatchRange> {
+    let first_start = segments.iter().map(|segment| segment.start1(configuration)).min_by(f32::total_cmp)?;
+    let first_end = segments.iter().map(|segment| segment.end1(configuration)).max_by(f32::total_cmp)?;
+    let second_start = segments.iter().map(|segment| segment.start2(configuration)).min_by(f32::total_cmp)?;
+    let second_end = segments.iter().map(|segment| segment.end2(configuration)).max_by(f32::total_cmp)?;
+
+    let ((container_path, container_start, container_end), (contained_path, contained_start, contained_end)) = if first_duration >= second_duration {
+        ((first_path, first_start, first_end), (second_path, second_start, second_end))
+    } else {
+        ((second_path, second_start, second_end), (first_path, first_start, first_end))
+    };
+    Some(VideoMatchRange {
+        container_path: container_path.to_path_buf(),
+        contained_path: contained_path.to_path_buf(),
+        container_start: f64::from(container_start),
+        container_end: f64::from(container_end),
+        contained_start: f64::from(contained_start),
+        contained_end: f64::from(contained_end),
+    })
+}
+
+fn ms_to_seconds(ms: u64) -> f64 {
+    ms as f64 / 1000.0
+}
+
+impl VideoMatchRange {
+    pub fn format_description(&self) -> String {
+        format!(
+            "\"{}\" is contained in \"{}\" at {} - {} (matched part of contained video {} - {})",
+            self.contained_path.to_string_lossy(),
+            self.container_path.to_string_lossy(),
+            format_duration_opt(Some(self.container_start)),
+            format_duration_opt(Some(self.container_end)),
+            format_duration_opt(Some(self.contained_start)),
+            format_duration_opt(Some(self.contained_end)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+
+    use similario_core::visual::HashBits;
+
+    use super::*;
+    use crate::common::audio_fingerprint::calc_fingerprint_and_duration;
+
+    fn make_hash(start_ms: u64, seed: usize) -> TemporalHash {
+        let mut bits = HashBits::ZERO;
+        for idx in (seed..1000).step_by(7) {
+            bits.set(idx, true);
+        }
+        TemporalHash {
+            start_ms,
+            end_ms: start_ms + 10_000,
+            bits,
+        }
+    }
+
+    fn make_signature(path: &str, duration_ms: u64, visual_hashes: Vec<TemporalHash>) -> VideoSignature {
+        VideoSignature {
+            path: PathBuf::from(path),
+            duration_ms,
+            aspect_ratio: 1.0,
+            visual_hashes,
+            audio_fingerprint: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_visual_match_range_of_subclip() {
+        let episode = make_signature("episode.mp4", 3_600_000, (0..6).map(|idx| make_hash(idx as u64 * 600_000, idx)).collect());
+        let clip = make_signature("clip.mp4", 1_200_000, vec![make_hash(0, 3), make_hash(600_000, 4)]);
+
+        let range = find_visual_match_range(&clip, &episode, 0.1, 0.6, 0.5).unwrap();
+        assert_eq!(range.container_path, PathBuf::from("episode.mp4"));
+        assert_eq!(range.contained_path, PathBuf::from("clip.mp4"));
+        assert!((range.container_start - 1800.0).abs() < 0.001);
+        assert!((range.container_end - 2410.0).abs() < 0.001);
+        assert!((range.contained_start - 0.0).abs() < 0.001);
+        assert!((range.contained_end - 610.0).abs() < 0.001);
+
+        // Only matching windows are part of range
+        let partial_clip = make_signature("partial.mp4", 1_200_000, vec![make_hash(0, 0), make_hash(600_000, 6)]);
+        let range = find_visual_match_range(&partial_clip, &episode, 0.1, 0.6, 0.5).unwrap();
+        assert!((range.container_start - 0.0).abs() < 0.001);
+        assert!((range.container_end - 10.0).abs() < 0.001);
+
+        let unrelated_clip = make_signature("unrelated.mp4", 1_200_000, vec![make_hash(0, 6), make_hash(600_000, 6)]);
+        assert!(find_visual_match_range(&unrelated_clip, &episode, 0.1, 0.6, 0.5).is_none());
+    }
+
+    #[test]
+    fn test_audio_match_range_of_ending() {
+        let resources = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_resources").join("audio");
+        let configuration = Configuration::preset_test1();
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let (base_fingerprint, base_duration) = calc_fingerprint_and_duration(resources.join("base.mp3"), &configuration, &stop_flag).unwrap().unwrap();
+        let (end_fingerprint, end_duration) = calc_fingerprint_and_duration(resources.join("base_end.mp3"), &configuration, &stop_flag).unwrap().unwrap();
+
+        let segments = rusty_chromaprint::match_fingerprints(&end_fingerprint, &base_fingerprint, &configuration).unwrap();
+        let range = find_audio_match_range(
+            &resources.join("base_end.mp3"),
+            end_duration,
+            &resources.join("base.mp3"),
+            base_duration,
+            &segments,
+            &configuration,
+        )
+        .unwrap();
+
+        assert_eq!(range.container_path, resources.join("base.mp3"));
+        assert_eq!(range.contained_path, resources.join("base_end.mp3"));
+        assert!(range.container_start > range.contained_start, "Ending should be found in later part of full file");
+        assert!(range.container_end <= base_duration as f64 + 1.0);
+        assert!(range.contained_end <= end_duration as f64 + 1.0);
+    }
+}
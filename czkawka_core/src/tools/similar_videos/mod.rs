@@ -1,4 +1,6 @@
 pub mod core;
+pub mod match_ranges;
+pub mod quality;
 pub mod traits;
 
 #[cfg(test)]
@@ -16,6 +18,7 @@ use similario_core::VideoSignature;
 use crate::common::model::FileEntry;
 use crate::common::tool_data::CommonToolData;
 use crate::common::traits::ResultEntry;
+use crate::common::video_utils::MediaStream;
 
 pub const MAX_TOLERANCE: i32 = 20;
 
@@ -63,6 +66,8 @@ pub struct VideosEntry {
     pub width: Option<u32>,
     pub height: Option<u32>,
     pub duration: Option<f64>,
+    pub audio_streams: Vec<MediaStream>,
+    pub subtitle_streams: Vec<MediaStream>,
 
     #[serde(skip)] // Saving it to cache is bad idea, because cache can be moved to another locations
     pub thumbnail_path: Option<PathBuf>,
@@ -79,6 +84,18 @@ pub struct VideoAudioEntry {
     pub audio_duration_seconds: u32,
 }
 
+/// Part of longer video, which was matched with part of shorter video, e.g. trailer found inside full episode.
+/// All times are in seconds from start of video.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VideoMatchRange {
+    pub container_path: PathBuf,
+    pub contained_path: PathBuf,
+    pub container_start: f64,
+    pub container_end: f64,
+    pub contained_start: f64,
+    pub contained_end: f64,
+}
+
 impl ResultEntry for VideoAudioEntry {
     fn get_path(&self) -> &Path {
         &self.path
@@ -118,6 +135,8 @@ impl FileEntry {
             width: None,
             height: None,
             duration: None,
+            audio_streams: Vec::new(),
+            subtitle_streams: Vec::new(),
             thumbnail_path: None,
         }
     }
@@ -217,6 +236,8 @@ pub struct SimilarVideos {
     pub(crate) information: Info,
     pub(crate) similar_vectors: Vec<Vec<VideosEntry>>,
     pub(crate) similar_referenced_vectors: Vec<(VideosEntry, Vec<VideosEntry>)>,
+    /// Matched time ranges between videos in found groups, kept outside `VideosEntry` so the cache format is not affected.
+    pub(crate) match_ranges: Vec<VideoMatchRange>,
     pub(crate) videos_to_check: BTreeMap<String, VideosEntry>,
     /// Entries for the audio fingerprint pass, keyed by path string.
     pub(crate) audio_to_check: BTreeMap<String, VideoAudioEntry>,
@@ -248,6 +269,10 @@ impl SimilarVideos {
         &self.similar_referenced_vectors
     }
 
+    pub fn get_match_ranges(&self) -> &Vec<VideoMatchRange> {
+        &self.match_ranges
+    }
+
     pub fn get_number_of_base_duplicated_files(&self) -> usize {
         if self.common_data.use_reference_folders {
             self.similar_referenced_vectors.len()
@@ -0,0 +1,111 @@
+use std::collections::BTreeSet;
+
+use crate::common::video_utils::MediaStream;
+use crate::tools::similar_videos::VideosEntry;
+
+// Aspect ratios closer than this are treated as equal, because encoders round dimensions to multiples of 2, 8 or 16
+const ASPECT_RATIO_TOLERANCE: f64 = 0.05;
+// Bits per pixel of H.264 video, above which most content looks the same as source
+const TRANSPARENT_BITS_PER_PIXEL: f64 = 0.1;
+const MAX_BITRATE_POINTS: f64 = 40.0;
+const RESOLUTION_POINTS_MULTIPLIER: f64 = 10.0;
+const LETTERBOX_OR_CROP_PENALTY: f64 = 10.0;
+// Used when fps cannot be read, most videos have between 24 and 30 frames per second
+const DEFAULT_FPS: f64 = 25.0;
+
+// Multiplier of bitrate, so the same bitrate of more efficient codec gives better quality
+fn codec_efficiency(codec: &str) -> f64 {
+    match codec.to_ascii_lowercase().as_str() {
+        "av1" => 2.0,
+        "hevc" | "h265" | "vp9" => 1.6,
+        "h264" | "vp8" => 1.0,
+        "mpeg4" | "msmpeg4v3" | "wmv3" | "vc1" | "theora" => 0.7,
+        _ => 0.5,
+    }
+}
+
+// Widest picture in group is treated as original one, narrower ones either have black bars or are cropped (e.g. pan and scan)
+pub fn get_widest_aspect_ratio<'a>(entries: impl IntoIterator<Item = &'a VideosEntry>) -> Option<f64> {
+    entries.into_iter().filter_map(VideosEntry::aspect_ratio).max_by(f64::total_cmp)
+}
+
+// Keeps the best version as last item of group
+pub(crate) fn sort_by_quality(group: &mut [VideosEntry]) {
+    let widest_aspect_ratio = get_widest_aspect_ratio(group.iter());
+    group.sort_by(|a, b| {
+        a.quality_score(widest_aspect_ratio)
+            .total_cmp(&b.quality_score(widest_aspect_ratio))
+            .then(a.size.cmp(&b.size))
+    });
+}
+
+// Keeps version with most audio and subtitle languages as last item of group, quality decides between versions with same tracks
+pub(crate) fn sort_by_tracks(group: &mut [VideosEntry]) {
+    let widest_aspect_ratio = get_widest_aspect_ratio(group.iter());
+    group.sort_by(|a, b| {
+        a.languages_count()
+            .cmp(&b.languages_count())
+            .then((a.audio_streams.len() + a.subtitle_streams.len()).cmp(&(b.audio_streams.len() + b.subtitle_streams.len())))
+            .then(a.quality_score(widest_aspect_ratio).total_cmp(&b.quality_score(widest_aspect_ratio)))
+            .then(a.size.cmp(&b.size))
+    });
+}
+
+fn count_languages(streams: &[MediaStream]) -> usize {
+    streams.iter().filter_map(|stream| stream.language.as_deref()).collect::<BTreeSet<_>>().len()
+}
+
+impl VideosEntry {
+    // Different audio languages and different subtitle languages are counted separately
+    pub fn languages_count(&self) -> usize {
+        count_languages(&self.audio_streams) + count_languages(&self.subtitle_streams)
+    }
+
+    fn aspect_ratio(&self) -> Option<f64> {
+        match (self.width, self.height) {
+            (Some(width), Some(height)) if width > 0 && height > 0 => Some(f64::from(width) / f64::from(height)),
+            _ => None,
+        }
+    }
+
+    pub fn is_letterboxed_or_cropped(&self, widest_aspect_ratio: Option<f64>) -> bool {
+        matches!((self.aspect_ratio(), widest_aspect_ratio), (Some(aspect_ratio), Some(widest_aspect_ratio)) if aspect_ratio < widest_aspect_ratio * (1.0 - ASPECT_RATIO_TOLERANCE))
+    }
+
+    // Bigger value means better quality, 0 is used when video properties are unknown
+    // Resolution is most important, then bitrate per pixel normalized by codec efficiency - starved high resolution encodes lose with well encoded smaller ones
+    pub fn quality_score(&self, widest_aspect_ratio: Option<f64>) -> f64 {
+        let (Some(width), Some(height)) = (self.width, self.height) else {
+            return 0.0;
+        };
+        if width == 0 || height == 0 {
+            return 0.0;
+        }
+        let width = f64::from(width);
+        let height = f64::from(height);
+
+        let letterboxed_or_cropped = self.is_letterboxed_or_cropped(widest_aspect_ratio);
+        // Only part of frame with same aspect ratio as widest version contains picture, which can be compared
+        let picture_height = match widest_aspect_ratio {
+            Some(widest_aspect_ratio) if letterboxed_or_cropped => (width / widest_aspect_ratio).min(height),
+            _ => height,
+        };
+        let picture_pixels = (width * picture_height).max(1.0);
+        let resolution_points = picture_pixels.log2() * RESOLUTION_POINTS_MULTIPLIER;
+
+        let bitrate_points = match self.bitrate {
+            Some(bitrate) if bitrate > 0 => {
+                let efficiency = codec_efficiency(self.codec.as_deref().unwrap_or_default());
+                let fps = self.fps.filter(|fps| *fps > 0.0).unwrap_or(DEFAULT_FPS);
+                // Black bars are almost free to encode, so whole bitrate is used by picture
+                let bits_per_pixel = bitrate as f64 * efficiency / (picture_pixels * fps);
+                (bits_per_pixel / TRANSPARENT_BITS_PER_PIXEL * MAX_BITRATE_POINTS / 2.0).min(MAX_BITRATE_POINTS)
+            }
+            _ => 0.0,
+        };
+
+        let penalty = if letterboxed_or_cropped { LETTERBOX_OR_CROP_PENALTY } else { 0.0 };
+
+        resolution_points + bitrate_points - penalty
+    }
+}
@@ -1,3 +1,4 @@
+use std::fs;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Once};
@@ -5,11 +6,14 @@ use std::sync::{Arc, Once};
 use tempfile::TempDir;
 
 use crate::common::config_cache_path::set_config_cache_path_test;
-use crate::common::tool_data::CommonData;
-use crate::common::traits::Search;
+use crate::common::tool_data::{CommonData, DeleteMethod};
+use crate::common::traits::{DeletingItems, PrintResults, Search};
+use crate::common::video_utils::MediaStream;
+use crate::tools::similar_videos::quality::get_widest_aspect_ratio;
 use crate::tools::similar_videos::{
     DEFAULT_AUDIO_LENGTH_RATIO, DEFAULT_AUDIO_MAXIMUM_DIFFERENCE, DEFAULT_AUDIO_MIN_DURATION_SECONDS, DEFAULT_AUDIO_SIMILARITY_PERCENT, DEFAULT_CROP_DETECT,
-    DEFAULT_DURATION_TOLERANCE_PCT, DEFAULT_MIN_MATCHING_WINDOWS, DEFAULT_SUBCLIP_MIN_MATCH, DEFAULT_WINDOW_COUNT, SimilarVideos, SimilarVideosParameters,
+    DEFAULT_DURATION_TOLERANCE_PCT, DEFAULT_MIN_MATCHING_WINDOWS, DEFAULT_SUBCLIP_MIN_MATCH, DEFAULT_WINDOW_COUNT, SimilarVideos, SimilarVideosParameters, VideoMatchRange,
+    VideosEntry,
 };
 
 static INIT: Once = Once::new();
@@ -218,6 +222,8 @@ fn test_similar_videos_reference_mode_deletes_only_non_reference() {
         width: None,
         height: None,
         duration: None,
+        audio_streams: Vec::new(),
+        subtitle_streams: Vec::new(),
         thumbnail_path: None,
     };
 
@@ -233,3 +239,239 @@ fn test_similar_videos_reference_mode_deletes_only_non_reference() {
     assert!(reference.exists(), "Reference video must be kept");
     assert!(!duplicate.exists(), "Non-reference duplicate must be deleted (#1643)");
 }
+
+#[test]
+fn test_delete_all_except_best_quality() {
+    let temp_dir = TempDir::new().unwrap();
+    let mk = |name: &str, codec: &str, width: u32, height: u32, bitrate: u64, size: u64| {
+        let path = temp_dir.path().join(name);
+        fs::write(&path, "video").unwrap();
+        VideosEntry {
+            path,
+            size,
+            modified_date: 0,
+            signature: None,
+            error: String::new(),
+            fps: Some(24.0),
+            codec: Some(codec.to_string()),
+            bitrate: Some(bitrate),
+            width: Some(width),
+            height: Some(height),
+            duration: Some(7200.0),
+            audio_streams: Vec::new(),
+            subtitle_streams: Vec::new(),
+            thumbnail_path: None,
+        }
+    };
+    // Movie in 2.40:1 aspect ratio
+    let hevc_cropped = mk("hevc_cropped.mkv", "hevc", 1920, 800, 4_000_000, 3_600);
+    let h264_letterboxed = mk("h264_letterboxed.mp4", "h264", 1920, 1080, 4_000_000, 3_600);
+    let h264_starved = mk("h264_starved.mp4", "h264", 1920, 800, 300_000, 270);
+    let mpeg2_dvd = mk("mpeg2_dvd.mpg", "mpeg2video", 720, 300, 6_000_000, 5_400);
+
+    let group = [hevc_cropped.clone(), h264_letterboxed.clone(), h264_starved.clone(), mpeg2_dvd.clone()];
+    let widest_aspect_ratio = get_widest_aspect_ratio(&group);
+    assert!(h264_letterboxed.is_letterboxed_or_cropped(widest_aspect_ratio));
+    assert!(!hevc_cropped.is_letterboxed_or_cropped(widest_aspect_ratio));
+    // Black bars do not add any picture, so letterboxed copy loses with the same bitrate
+    assert!(h264_letterboxed.quality_score(widest_aspect_ratio) < hevc_cropped.quality_score(widest_aspect_ratio));
+    assert!(h264_starved.quality_score(widest_aspect_ratio) < h264_letterboxed.quality_score(widest_aspect_ratio));
+    assert!(mpeg2_dvd.quality_score(widest_aspect_ratio) < hevc_cropped.quality_score(widest_aspect_ratio));
+
+    let mut finder = SimilarVideos::new(make_params_visual());
+    finder.set_delete_method(DeleteMethod::AllExceptBestQuality);
+    finder.set_move_to_trash(false);
+    finder.similar_vectors = vec![group.to_vec()];
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let _ = finder.delete_files(&stop_flag, None);
+
+    assert!(hevc_cropped.path.exists(), "Best version must be kept");
+    assert!(!h264_letterboxed.path.exists());
+    assert!(!h264_starved.path.exists());
+    assert!(!mpeg2_dvd.path.exists(), "Bigger file with old codec must be deleted");
+}
+
+#[test]
+fn test_delete_all_except_best_quality_keeps_reference_videos() {
+    let temp_dir = TempDir::new().unwrap();
+    let reference_dir = temp_dir.path().join("reference");
+    fs::create_dir(&reference_dir).unwrap();
+    let mk = |path: PathBuf, width: u32| {
+        fs::write(&path, "video").unwrap();
+        VideosEntry {
+            path,
+            size: 1000,
+            modified_date: 0,
+            signature: None,
+            error: String::new(),
+            fps: Some(24.0),
+            codec: Some("h264".to_string()),
+            bitrate: Some(u64::from(width) * 2_000),
+            width: Some(width),
+            height: Some(width / 2),
+            duration: Some(7200.0),
+            audio_streams: Vec::new(),
+            subtitle_streams: Vec::new(),
+            thumbnail_path: None,
+        }
+    };
+    let reference = mk(reference_dir.join("reference.mkv"), 1280);
+    let better = mk(temp_dir.path().join("better.mkv"), 3840);
+    let worse = mk(temp_dir.path().join("worse.mkv"), 640);
+
+    let mut finder = SimilarVideos::new(make_params_visual());
+    finder.set_delete_method(DeleteMethod::AllExceptBestQuality);
+    finder.set_move_to_trash(false);
+    finder.set_use_reference_folders(true);
+    finder.set_included_paths(vec![temp_dir.path().to_path_buf()]);
+    finder.set_reference_paths(vec![reference_dir]);
+    finder.similar_referenced_vectors = vec![(reference.clone(), vec![better.clone(), worse.clone()])];
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let _ = finder.delete_files(&stop_flag, None);
+
+    assert!(reference.path.exists(), "Reference video must never be deleted, even with lower quality");
+    assert!(better.path.exists(), "Best version must be kept");
+    assert!(!worse.path.exists());
+}
+
+#[test]
+fn test_delete_all_except_most_tracks() {
+    let temp_dir = TempDir::new().unwrap();
+    let stream = |language: Option<&str>| MediaStream {
+        codec: Some("aac".to_string()),
+        language: language.map(str::to_string),
+    };
+    let mk = |name: &str, width: u32, audio_streams: Vec<MediaStream>, subtitle_streams: Vec<MediaStream>| {
+        let path = temp_dir.path().join(name);
+        fs::write(&path, "video").unwrap();
+        VideosEntry {
+            path,
+            size: 1000,
+            modified_date: 0,
+            signature: None,
+            error: String::new(),
+            fps: Some(24.0),
+            codec: Some("h264".to_string()),
+            bitrate: Some(4_000_000),
+            width: Some(width),
+            height: Some(width / 2),
+            duration: Some(7200.0),
+            audio_streams,
+            subtitle_streams,
+            thumbnail_path: None,
+        }
+    };
+    let best_quality = mk("best_quality.mkv", 3840, vec![stream(Some("eng"))], vec![]);
+    // Two english tracks (e.g. with commentary) give only one language
+    let commentary = mk("commentary.mkv", 1920, vec![stream(Some("eng")), stream(Some("eng")), stream(None)], vec![]);
+    let multi_language = mk("multi_language.mkv", 1280, vec![stream(Some("eng")), stream(Some("pol"))], vec![stream(Some("eng"))]);
+    let multi_language_worse = mk("multi_language_worse.mkv", 640, vec![stream(Some("pol")), stream(Some("eng"))], vec![stream(Some("pol"))]);
+
+    assert_eq!(commentary.languages_count(), 1);
+    assert_eq!(multi_language.languages_count(), 3);
+    assert_eq!(MediaStream::format_streams(&commentary.audio_streams), "eng (aac), eng (aac), ? (aac)");
+
+    let mut finder = SimilarVideos::new(make_params_visual());
+    finder.set_delete_method(DeleteMethod::AllExceptMostTracks);
+    finder.set_move_to_trash(false);
+    finder.similar_vectors = vec![vec![best_quality.clone(), commentary.clone(), multi_language.clone(), multi_language_worse.clone()]];
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let _ = finder.delete_files(&stop_flag, None);
+
+    assert!(multi_language.path.exists(), "Version with most languages must be kept");
+    assert!(!multi_language_worse.path.exists(), "Quality decides between versions with the same languages");
+    assert!(!best_quality.path.exists());
+    assert!(!commentary.path.exists());
+}
+
+#[test]
+fn test_delete_all_except_most_tracks_keeps_reference_videos() {
+    let temp_dir = TempDir::new().unwrap();
+    let reference_dir = temp_dir.path().join("reference");
+    fs::create_dir(&reference_dir).unwrap();
+    let stream = |language: &str| MediaStream {
+        codec: Some("aac".to_string()),
+        language: Some(language.to_string()),
+    };
+    let mk = |path: PathBuf, audio_streams: Vec<MediaStream>| {
+        fs::write(&path, "video").unwrap();
+        VideosEntry {
+            path,
+            size: 1000,
+            modified_date: 0,
+            signature: None,
+            error: String::new(),
+            fps: Some(24.0),
+            codec: Some("h264".to_string()),
+            bitrate: Some(4_000_000),
+            width: Some(1920),
+            height: Some(1080),
+            duration: Some(7200.0),
+            audio_streams,
+            subtitle_streams: Vec::new(),
+            thumbnail_path: None,
+        }
+    };
+    let reference = mk(reference_dir.join("reference.mkv"), vec![stream("eng")]);
+    let most_tracks = mk(temp_dir.path().join("most_tracks.mkv"), vec![stream("eng"), stream("pol"), stream("ger")]);
+    let fewer_tracks = mk(temp_dir.path().join("fewer_tracks.mkv"), vec![stream("eng"), stream("pol")]);
+
+    let mut finder = SimilarVideos::new(make_params_visual());
+    finder.set_delete_method(DeleteMethod::AllExceptMostTracks);
+    finder.set_move_to_trash(false);
+    finder.set_use_reference_folders(true);
+    finder.set_included_paths(vec![temp_dir.path().to_path_buf()]);
+    finder.set_reference_paths(vec![reference_dir]);
+    finder.similar_referenced_vectors = vec![(reference.clone(), vec![most_tracks.clone(), fewer_tracks.clone()])];
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let _ = finder.delete_files(&stop_flag, None);
+
+    assert!(reference.path.exists(), "Reference video must never be deleted, even with fewer tracks");
+    assert!(most_tracks.path.exists(), "Version with most languages must be kept");
+    assert!(!fewer_tracks.path.exists());
+}
+
+#[test]
+fn test_json_export_contains_match_ranges() {
+    let temp_dir = TempDir::new().unwrap();
+    let mk = |name: &str| VideosEntry {
+        path: PathBuf::from(name),
+        size: 1,
+        modified_date: 0,
+        signature: None,
+        error: String::new(),
+        fps: None,
+        codec: None,
+        bitrate: None,
+        width: None,
+        height: None,
+        duration: None,
+        audio_streams: Vec::new(),
+        subtitle_streams: Vec::new(),
+        thumbnail_path: None,
+    };
+
+    let mut finder = SimilarVideos::new(make_params_visual());
+    finder.similar_vectors = vec![vec![mk("/episode.mp4"), mk("/trailer.mp4")], vec![mk("/a.mp4"), mk("/b.mp4")]];
+    finder.match_ranges = vec![VideoMatchRange {
+        container_path: PathBuf::from("/episode.mp4"),
+        contained_path: PathBuf::from("/trailer.mp4"),
+        container_start: 60.0,
+        container_end: 90.0,
+        contained_start: 0.0,
+        contained_end: 30.0,
+    }];
+
+    let json_path = temp_dir.path().join("results.json");
+    finder.save_results_to_file_as_json(&json_path.to_string_lossy(), false).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+
+    assert_eq!(json[0][0]["path"], "/episode.mp4");
+    assert_eq!(json[0][0]["match_ranges"][0]["container_start"], 60.0);
+    assert_eq!(json[0][1]["match_ranges"][0]["contained_path"], "/trailer.mp4");
+    assert_eq!(json[1][0]["match_ranges"].as_array().unwrap().len(), 0);
+}
@@ -1,4 +1,5 @@
 use std::io::Write;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::time::Instant;
@@ -6,16 +7,20 @@ use std::time::Instant;
 use crossbeam_channel::Sender;
 use fun_time::fun_time;
 use humansize::{BINARY, format_size};
+use serde::Serialize;
 
 use crate::common::consts::VIDEO_FILES_EXTENSIONS;
 use crate::common::ffmpeg_utils::check_if_ffprobe_ffmpeg_exists;
+use crate::common::html_report::{HtmlReportGroup, HtmlReportItem, HtmlReportPreview, write_html_report};
 use crate::common::model::{CheckingMethod, WorkContinueStatus};
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
-use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
+use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, HtmlReport, PrintResults, Search};
+use crate::common::video_utils::MediaStream;
 use crate::flc;
 use crate::tools::similar_videos::core::{format_bitrate_opt, format_duration_opt};
-use crate::tools::similar_videos::{Info, SimilarVideos, SimilarVideosParameters};
+use crate::tools::similar_videos::quality::{get_widest_aspect_ratio, sort_by_quality, sort_by_tracks};
+use crate::tools::similar_videos::{Info, SimilarVideos, SimilarVideosParameters, VideoMatchRange, VideosEntry};
 
 impl AllTraits for SimilarVideos {}
 
@@ -88,6 +93,32 @@ impl DeletingItems for SimilarVideos {
         if self.get_cd().delete_method == DeleteMethod::None {
             return WorkContinueStatus::Continue;
         }
+        let delete_method = self.get_cd().delete_method;
+        if matches!(delete_method, DeleteMethod::AllExceptBestQuality | DeleteMethod::AllExceptMostTracks) {
+            // Reference videos take part in choosing the best version, but are never deleted
+            let groups: Vec<Vec<VideosEntry>> = if self.get_use_reference_folders() {
+                self.similar_referenced_vectors
+                    .iter()
+                    .map(|(reference, files)| files.iter().chain(std::iter::once(reference)).cloned().collect())
+                    .collect()
+            } else {
+                self.similar_vectors.clone()
+            };
+            let directories = &self.common_data.directories;
+            let files_to_delete = groups
+                .into_iter()
+                .flat_map(|mut group| {
+                    if delete_method == DeleteMethod::AllExceptBestQuality {
+                        sort_by_quality(&mut group);
+                    } else {
+                        sort_by_tracks(&mut group);
+                    }
+                    group.pop();
+                    group.into_iter().filter(|entry| !directories.is_in_referenced_directory(&entry.path))
+                })
+                .collect();
+            return self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFiles(files_to_delete));
+        }
         if self.get_use_reference_folders() {
             let files_to_delete: Vec<_> = self.similar_referenced_vectors.iter().flat_map(|(_, files)| files.iter().cloned()).collect();
             return self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFiles(files_to_delete));
@@ -114,7 +145,7 @@ impl PrintResults for SimilarVideos {
     fn write_results<T: Write>(&self, writer: &mut T) -> std::io::Result<()> {
         self.write_base_search_paths(writer)?;
 
-        fn write_video_entry<T: Write>(writer: &mut T, file_entry: &crate::tools::similar_videos::VideosEntry) -> std::io::Result<()> {
+        fn write_video_entry<T: Write>(writer: &mut T, file_entry: &crate::tools::similar_videos::VideosEntry, widest_aspect_ratio: Option<f64>) -> std::io::Result<()> {
             let bitrate = format_bitrate_opt(file_entry.bitrate);
             let fps = file_entry.fps.map(|e| format!("{e:.2}")).unwrap_or_default();
             let codec = file_entry.codec.clone().unwrap_or_default();
@@ -124,48 +155,71 @@ impl PrintResults for SimilarVideos {
                 "".to_string()
             };
             let duration = format_duration_opt(file_entry.duration);
+            let quality = format!("{:.1}", file_entry.quality_score(widest_aspect_ratio));
+            let letterboxed_or_cropped = if file_entry.is_letterboxed_or_cropped(widest_aspect_ratio) {
+                " (letterboxed or cropped)"
+            } else {
+                ""
+            };
 
             writeln!(
                 writer,
-                "\"{}\" - {} - {} - {} - {} - {} - {}",
+                "\"{}\" - {} - {} - {} - {} - {} - {} - {}{} - {} - {}",
                 file_entry.path.to_string_lossy(),
                 format_size(file_entry.size, BINARY),
                 bitrate,
                 fps,
                 codec,
                 dimensions,
-                duration
+                duration,
+                quality,
+                letterboxed_or_cropped,
+                MediaStream::format_streams(&file_entry.audio_streams),
+                MediaStream::format_streams(&file_entry.subtitle_streams)
             )
         }
 
+        let write_match_ranges = |writer: &mut T, group: &[&crate::tools::similar_videos::VideosEntry]| -> std::io::Result<()> {
+            for match_range in &self.match_ranges {
+                if group.iter().any(|file_entry| file_entry.path == match_range.contained_path) {
+                    writeln!(writer, "{}", match_range.format_description())?;
+                }
+            }
+            Ok(())
+        };
+
         if !self.similar_vectors.is_empty() {
             write!(writer, "{} videos which have similar friends\n\n", self.similar_vectors.len())?;
 
             for struct_similar in &self.similar_vectors {
                 writeln!(
                     writer,
-                    "Found {} videos which have similar friends (path, size, bitrate, fps, codec, dimensions, duration)",
+                    "Found {} videos which have similar friends (path, size, bitrate, fps, codec, dimensions, duration, quality, audio, subtitles)",
                     struct_similar.len()
                 )?;
+                let widest_aspect_ratio = get_widest_aspect_ratio(struct_similar);
                 for file_entry in struct_similar {
-                    write_video_entry(writer, file_entry)?;
+                    write_video_entry(writer, file_entry, widest_aspect_ratio)?;
                 }
+                write_match_ranges(writer, &struct_similar.iter().collect::<Vec<_>>())?;
                 writeln!(writer)?;
             }
         } else if !self.similar_referenced_vectors.is_empty() {
             write!(
                 writer,
-                "{} videos which have similar friends (path, size, bitrate, fps, codec, dimensions, duration)\n\n",
+                "{} videos which have similar friends (path, size, bitrate, fps, codec, dimensions, duration, quality, audio, subtitles)\n\n",
                 self.similar_referenced_vectors.len()
             )?;
 
             for (fe, struct_similar) in &self.similar_referenced_vectors {
                 writeln!(writer, "Found {} videos which have similar friends", struct_similar.len())?;
                 writeln!(writer)?;
-                write_video_entry(writer, fe)?;
+                let widest_aspect_ratio = get_widest_aspect_ratio(struct_similar.iter().chain([fe]));
+                write_video_entry(writer, fe, widest_aspect_ratio)?;
                 for file_entry in struct_similar {
-                    write_video_entry(writer, file_entry)?;
+                    write_video_entry(writer, file_entry, widest_aspect_ratio)?;
                 }
+                write_match_ranges(writer, &struct_similar.iter().chain([fe]).collect::<Vec<_>>())?;
                 writeln!(writer)?;
             }
         } else {
@@ -176,14 +230,99 @@ impl PrintResults for SimilarVideos {
     }
 
     fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+        #[derive(Serialize, Debug)]
+        struct VideosEntryWithMatchRanges<'a> {
+            #[serde(flatten)]
+            entry: &'a VideosEntry,
+            // Parts of this video, which were found in other video or which contain other video
+            match_ranges: Vec<&'a VideoMatchRange>,
+        }
+        fn with_match_ranges<'a>(entry: &'a VideosEntry, match_ranges: &'a [VideoMatchRange]) -> VideosEntryWithMatchRanges<'a> {
+            VideosEntryWithMatchRanges {
+                entry,
+                match_ranges: match_ranges
+                    .iter()
+                    .filter(|match_range| match_range.container_path == entry.path || match_range.contained_path == entry.path)
+                    .collect(),
+            }
+        }
+        let with_match_ranges = |entry| with_match_ranges(entry, &self.match_ranges);
+
         if self.get_use_reference() {
-            self.save_results_to_file_as_json_internal(file_name, &self.similar_referenced_vectors, pretty_print)
+            let results: Vec<_> = self
+                .similar_referenced_vectors
+                .iter()
+                .map(|(reference, entries)| (with_match_ranges(reference), entries.iter().map(with_match_ranges).collect::<Vec<_>>()))
+                .collect();
+            self.save_results_to_file_as_json_internal(file_name, &results, pretty_print)
         } else {
-            self.save_results_to_file_as_json_internal(file_name, &self.similar_vectors, pretty_print)
+            let results: Vec<Vec<_>> = self.similar_vectors.iter().map(|entries| entries.iter().map(with_match_ranges).collect()).collect();
+            self.save_results_to_file_as_json_internal(file_name, &results, pretty_print)
         }
     }
 }
 
+impl HtmlReport for SimilarVideos {
+    #[fun_time(message = "save_results_to_html_report", level = "debug")]
+    fn save_results_to_html_report(&self, folder: &Path) -> std::io::Result<()> {
+        let to_report_item = |file_entry: &VideosEntry, is_reference: bool, widest_aspect_ratio: Option<f64>| HtmlReportItem {
+            path: file_entry.path.clone(),
+            // Thumbnails exist only when they were generated during scan
+            preview: file_entry.thumbnail_path.clone().map(HtmlReportPreview::Thumbnail),
+            columns: vec![
+                format_size(file_entry.size, BINARY),
+                format_bitrate_opt(file_entry.bitrate),
+                file_entry.fps.map(|fps| format!("{fps:.2}")).unwrap_or_default(),
+                file_entry.codec.clone().unwrap_or_default(),
+                match (file_entry.width, file_entry.height) {
+                    (Some(width), Some(height)) => format!("{width}x{height}"),
+                    _ => String::new(),
+                },
+                format_duration_opt(file_entry.duration),
+                format!("{:.1}", file_entry.quality_score(widest_aspect_ratio)),
+                MediaStream::format_streams(&file_entry.audio_streams),
+                MediaStream::format_streams(&file_entry.subtitle_streams),
+            ],
+            is_reference,
+        };
+        let to_report_group = |entries: Vec<(&VideosEntry, bool)>| {
+            let widest_aspect_ratio = get_widest_aspect_ratio(entries.iter().map(|(entry, _)| *entry));
+            let notes = self
+                .match_ranges
+                .iter()
+                .filter(|match_range| entries.iter().any(|(entry, _)| entry.path == match_range.contained_path))
+                .map(|match_range| match_range.format_description())
+                .collect();
+            HtmlReportGroup {
+                items: entries
+                    .iter()
+                    .map(|(entry, is_reference)| to_report_item(entry, *is_reference, widest_aspect_ratio))
+                    .collect(),
+                notes,
+            }
+        };
+
+        let groups: Vec<HtmlReportGroup> = if self.get_use_reference() {
+            self.similar_referenced_vectors
+                .iter()
+                .map(|(reference_entry, entries)| to_report_group([(reference_entry, true)].into_iter().chain(entries.iter().map(|entry| (entry, false))).collect()))
+                .collect()
+        } else {
+            self.similar_vectors
+                .iter()
+                .map(|entries| to_report_group(entries.iter().map(|entry| (entry, false)).collect()))
+                .collect()
+        };
+
+        write_html_report(
+            folder,
+            "Similar videos",
+            &["Size", "Bitrate", "FPS", "Codec", "Dimensions", "Duration", "Quality", "Audio", "Subtitles"],
+            &groups,
+        )
+    }
+}
+
 impl CommonData for SimilarVideos {
     type Info = Info;
     type Parameters = SimilarVideosParameters;
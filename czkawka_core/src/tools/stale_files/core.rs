@@ -0,0 +1,254 @@
+use std::collections::BTreeSet;
+use std::fs::Metadata;
+use std::path::Path;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use log::debug;
+use rayon::prelude::*;
+
+use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
+use crate::common::model::{FileEntry, ToolType, WorkContinueStatus};
+use crate::common::progress_data::ProgressData;
+use crate::common::tool_data::CommonToolData;
+use crate::flc;
+use crate::tools::stale_files::{Info, SECONDS_IN_DAY, StaleFileEntry, StaleFiles, StaleFilesParameters};
+
+impl StaleFiles {
+    pub fn new(params: StaleFilesParameters) -> Self {
+        Self {
+            common_data: CommonToolData::new(ToolType::StaleFiles),
+            information: Info::default(),
+            stale_files: Default::default(),
+            params,
+        }
+    }
+
+    #[fun_time(message = "look_for_stale_files", level = "debug")]
+    pub(crate) fn look_for_stale_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        let result = DirTraversalBuilder::new()
+            .group_by(|_fe| ())
+            .stop_flag(stop_flag)
+            .progress_sender(progress_sender)
+            .common_data(&self.common_data)
+            .build()
+            .run();
+
+        match result {
+            DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } => {
+                self.common_data.text_messages.warnings.extend(warnings);
+
+                let traversed_directories: BTreeSet<&Path> = grouped_file_entries.values().flatten().filter_map(|fe| fe.path.parent()).collect();
+                self.check_access_time_reliability(&traversed_directories);
+
+                let current_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+                let params = &self.params;
+                let mut stale_files: Vec<StaleFileEntry> = grouped_file_entries
+                    .into_values()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .into_par_iter()
+                    .filter_map(|fe| {
+                        // Traversal provides only modification date, so other times need to be read again
+                        let metadata = fe.path.symlink_metadata().ok()?;
+                        let entry = create_stale_file_entry(fe, &metadata);
+                        is_stale(&entry, params, current_time).then_some(entry)
+                    })
+                    .collect();
+
+                // The oldest files first
+                stale_files.par_sort_unstable_by(|a, b| a.modified_date.cmp(&b.modified_date).then_with(|| a.path.cmp(&b.path)));
+
+                self.stale_files = stale_files;
+                self.information.number_of_stale_files = self.stale_files.len();
+                debug!("look_for_stale_files - Found {} stale files.", self.stale_files.len());
+                WorkContinueStatus::Continue
+            }
+
+            DirTraversalResult::Stopped => WorkContinueStatus::Stop,
+        }
+    }
+
+    // Access times may be not updated at all or only sometimes, depending on mount options, so user should know that results may be wrong.
+    // Other filesystems may be mounted below included directories, so directories of found files are checked too
+    #[fun_time(message = "check_access_time_reliability", level = "debug")]
+    pub(crate) fn check_access_time_reliability(&mut self, traversed_directories: &BTreeSet<&Path>) {
+        if self.params.min_days_since_access.is_none() {
+            return;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            let Ok(content) = std::fs::read_to_string("/proc/mounts") else {
+                debug!("check_access_time_reliability - cannot read /proc/mounts");
+                return;
+            };
+            let mounts = parse_mounts(&content);
+
+            for (path, mount) in get_used_mounts(&mounts, &self.common_data.directories.included_directories, traversed_directories) {
+                let path = path.to_string_lossy().to_string();
+                let mount_point = mount.mount_point.to_string_lossy().to_string();
+                match mount.access_time_mode() {
+                    AccessTimeMode::NoAtime => {
+                        self.common_data
+                            .text_messages
+                            .warnings
+                            .push(flc!("core_stale_files_noatime_mount", path = path, mount = mount_point));
+                    }
+                    AccessTimeMode::RelAtime => {
+                        self.common_data
+                            .text_messages
+                            .messages
+                            .push(flc!("core_stale_files_relatime_mount", path = path, mount = mount_point));
+                    }
+                    AccessTimeMode::StrictAtime => {}
+                }
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = traversed_directories;
+    }
+}
+
+fn system_time_to_secs(time: std::io::Result<SystemTime>) -> u64 {
+    time.ok().and_then(|t| t.duration_since(UNIX_EPOCH).ok()).map_or(0, |d| d.as_secs())
+}
+
+#[cfg(unix)]
+fn get_changed_time(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    u64::try_from(metadata.ctime()).unwrap_or(0)
+}
+
+#[cfg(not(unix))]
+fn get_changed_time(metadata: &Metadata) -> u64 {
+    system_time_to_secs(metadata.created())
+}
+
+fn create_stale_file_entry(fe: FileEntry, metadata: &Metadata) -> StaleFileEntry {
+    StaleFileEntry {
+        accessed_date: system_time_to_secs(metadata.accessed()),
+        changed_date: get_changed_time(metadata),
+        path: fe.path,
+        size: fe.size,
+        modified_date: fe.modified_date,
+    }
+}
+
+// Unknown time(0) never passes check, to not treat files with unreadable times as stale
+pub(crate) fn is_stale(entry: &StaleFileEntry, params: &StaleFilesParameters, current_time: u64) -> bool {
+    let limits = [
+        (params.min_days_since_modification, entry.modified_date),
+        (params.min_days_since_access, entry.accessed_date),
+        (params.min_days_since_change, entry.changed_date),
+    ];
+
+    limits.iter().all(|(min_days, time)| match min_days {
+        Some(min_days) => *time != 0 && current_time.saturating_sub(*time) >= min_days.saturating_mul(SECONDS_IN_DAY),
+        None => true,
+    })
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AccessTimeMode {
+    NoAtime,
+    RelAtime,
+    StrictAtime,
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone)]
+pub(crate) struct MountEntry {
+    pub(crate) mount_point: PathBuf,
+    pub(crate) options: Vec<String>,
+}
+
+#[cfg(target_os = "linux")]
+impl MountEntry {
+    pub(crate) fn access_time_mode(&self) -> AccessTimeMode {
+        if self.options.iter().any(|o| o == "noatime") {
+            AccessTimeMode::NoAtime
+        } else if self.options.iter().any(|o| o == "relatime") {
+            AccessTimeMode::RelAtime
+        } else {
+            AccessTimeMode::StrictAtime
+        }
+    }
+}
+
+// Format of /proc/mounts - "device mount_point fs_type options dump pass", where spaces and other special characters in paths are escaped as octal e.g. \040
+#[cfg(target_os = "linux")]
+pub(crate) fn parse_mounts(content: &str) -> Vec<MountEntry> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount_point = fields.next()?;
+            let _fs_type = fields.next()?;
+            let options = fields.next()?;
+            Some(MountEntry {
+                mount_point: PathBuf::from(decode_mount_path(mount_point)),
+                options: options.split(',').map(str::to_string).collect(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn decode_mount_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let escaped = bytes
+            .get(idx + 1..idx + 4)
+            .filter(|_| bytes.get(idx) == Some(&b'\\'))
+            .and_then(|octal| std::str::from_utf8(octal).ok())
+            .and_then(|octal| u8::from_str_radix(octal, 8).ok());
+        if let Some(byte) = escaped {
+            decoded.push(byte);
+            idx += 4;
+        } else {
+            decoded.extend(bytes.get(idx));
+            idx += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+// Later entries hide earlier ones mounted at the same place, so the last of the longest matching mount points is used
+#[cfg(target_os = "linux")]
+pub(crate) fn find_mount<'a>(mounts: &'a [MountEntry], path: &Path) -> Option<&'a MountEntry> {
+    mounts
+        .iter()
+        .filter(|mount| path.starts_with(&mount.mount_point))
+        .max_by_key(|mount| mount.mount_point.components().count())
+}
+
+// Returns every mount used by scanned files, with path shown to user - included directory or mount point placed inside it.
+// Included directories are checked first, so mounts found later from traversed directories are always placed below them
+#[cfg(target_os = "linux")]
+pub(crate) fn get_used_mounts<'a>(mounts: &'a [MountEntry], included_directories: &[PathBuf], traversed_directories: &BTreeSet<&Path>) -> Vec<(PathBuf, &'a MountEntry)> {
+    let mut checked_mount_points = BTreeSet::new();
+    let mut used_mounts = Vec::new();
+
+    let directories = included_directories.iter().map(|directory| (directory.as_path(), true));
+    for (directory, is_included) in directories.chain(traversed_directories.iter().map(|directory| (*directory, false))) {
+        let Some(mount) = find_mount(mounts, directory) else {
+            continue;
+        };
+        if !checked_mount_points.insert(&mount.mount_point) {
+            continue;
+        }
+        let path = if is_included { directory } else { mount.mount_point.as_path() };
+        used_mounts.push((path.to_path_buf(), mount));
+    }
+    used_mounts
+}
@@ -0,0 +1,80 @@
+pub mod core;
+#[cfg(test)]
+mod tests;
+pub mod traits;
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::common::tool_data::CommonToolData;
+use crate::common::traits::ResultEntry;
+
+pub const SECONDS_IN_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Default, Clone)]
+pub struct Info {
+    pub number_of_stale_files: usize,
+    pub scanning_time: Duration,
+}
+
+/// File is stale only when it is older than every enabled limit, disabled limits(`None`) are ignored
+#[derive(Clone, Debug)]
+pub struct StaleFilesParameters {
+    pub min_days_since_modification: Option<u64>,
+    pub min_days_since_access: Option<u64>,
+    // On Windows, creation time is used instead of inode change time
+    pub min_days_since_change: Option<u64>,
+}
+
+impl StaleFilesParameters {
+    pub fn new(min_days_since_modification: Option<u64>, min_days_since_access: Option<u64>, min_days_since_change: Option<u64>) -> Self {
+        Self {
+            min_days_since_modification,
+            min_days_since_access,
+            min_days_since_change,
+        }
+    }
+}
+
+impl Default for StaleFilesParameters {
+    fn default() -> Self {
+        Self::new(Some(365), None, None)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct StaleFileEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified_date: u64,
+    // 0 when time cannot be read
+    pub accessed_date: u64,
+    pub changed_date: u64,
+}
+
+impl ResultEntry for StaleFileEntry {
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+    fn get_modified_date(&self) -> u64 {
+        self.modified_date
+    }
+    fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+pub struct StaleFiles {
+    common_data: CommonToolData,
+    information: Info,
+    stale_files: Vec<StaleFileEntry>,
+    params: StaleFilesParameters,
+}
+
+impl StaleFiles {
+    pub const fn get_stale_files(&self) -> &Vec<StaleFileEntry> {
+        &self.stale_files
+    }
+}
@@ -0,0 +1,176 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use filetime::FileTime;
+use tempfile::TempDir;
+
+use crate::common::tool_data::{CommonData, DeleteMethod};
+use crate::common::traits::Search;
+use crate::tools::stale_files::core::is_stale;
+use crate::tools::stale_files::{SECONDS_IN_DAY, StaleFileEntry, StaleFiles, StaleFilesParameters};
+
+fn current_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+fn create_file_with_times(path: &Path, days_since_access: u64, days_since_modification: u64) {
+    fs::write(path, b"content").unwrap();
+    let now = current_time();
+    let accessed = FileTime::from_unix_time((now - days_since_access * SECONDS_IN_DAY) as i64, 0);
+    let modified = FileTime::from_unix_time((now - days_since_modification * SECONDS_IN_DAY) as i64, 0);
+    filetime::set_file_times(path, accessed, modified).unwrap();
+}
+
+fn search(path: &Path, params: StaleFilesParameters) -> StaleFiles {
+    let mut finder = StaleFiles::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+    finder
+}
+
+fn found_names(finder: &StaleFiles) -> Vec<String> {
+    finder
+        .get_stale_files()
+        .iter()
+        .map(|fe| fe.path.file_name().unwrap().to_string_lossy().to_string())
+        .collect()
+}
+
+#[test]
+fn test_stale_by_modification() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    create_file_with_times(&path.join("old.txt"), 0, 400);
+    create_file_with_times(&path.join("older.txt"), 0, 800);
+    create_file_with_times(&path.join("new.txt"), 0, 10);
+
+    let finder = search(path, StaleFilesParameters::default());
+
+    // The oldest files are first
+    assert_eq!(found_names(&finder), vec!["older.txt", "old.txt"]);
+    assert_eq!(finder.get_information().number_of_stale_files, 2);
+}
+
+#[test]
+fn test_stale_by_access_and_modification() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    create_file_with_times(&path.join("both_old.txt"), 100, 100);
+    create_file_with_times(&path.join("recently_read.txt"), 1, 100);
+    create_file_with_times(&path.join("recently_modified.txt"), 100, 1);
+
+    let access_only = search(path, StaleFilesParameters::new(None, Some(30), None));
+    let mut names = found_names(&access_only);
+    names.sort();
+    assert_eq!(names, vec!["both_old.txt", "recently_modified.txt"]);
+
+    let both = search(path, StaleFilesParameters::new(Some(30), Some(30), None));
+    assert_eq!(found_names(&both), vec!["both_old.txt"]);
+}
+
+#[test]
+fn test_is_stale_limits() {
+    let now = current_time();
+    let entry = StaleFileEntry {
+        path: PathBuf::from("/tmp/file.txt"),
+        size: 1,
+        modified_date: now - 50 * SECONDS_IN_DAY,
+        accessed_date: 0,
+        changed_date: now - 5 * SECONDS_IN_DAY,
+    };
+
+    assert!(is_stale(&entry, &StaleFilesParameters::new(Some(50), None, None), now));
+    assert!(!is_stale(&entry, &StaleFilesParameters::new(Some(51), None, None), now));
+    assert!(!is_stale(&entry, &StaleFilesParameters::new(Some(10), None, Some(10)), now));
+    assert!(is_stale(&entry, &StaleFilesParameters::new(Some(10), None, Some(5)), now));
+    // Unknown access time is never treated as old
+    assert!(!is_stale(&entry, &StaleFilesParameters::new(None, Some(0), None), now));
+    assert!(is_stale(&entry, &StaleFilesParameters::new(None, None, None), now));
+}
+
+#[test]
+fn test_delete_stale_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    create_file_with_times(&path.join("old.txt"), 0, 400);
+    create_file_with_times(&path.join("new.txt"), 0, 10);
+
+    let mut finder = StaleFiles::new(StaleFilesParameters::default());
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    finder.set_delete_method(DeleteMethod::Delete);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+
+    assert!(!path.join("old.txt").exists());
+    assert!(path.join("new.txt").exists());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_parse_mounts() {
+    use crate::tools::stale_files::core::{AccessTimeMode, find_mount, parse_mounts};
+
+    let content = "/dev/sda1 / ext4 rw,relatime 0 0\n\
+                   /dev/sdb1 /mnt/my\\040disk ext4 rw,noatime 0 0\n\
+                   tmpfs /tmp tmpfs rw,nosuid 0 0\n\
+                   broken line\n\
+                   /dev/sdc1 /tmp ext4 rw,strictatime 0 0\n";
+    let mounts = parse_mounts(content);
+    assert_eq!(mounts.len(), 4);
+    assert_eq!(mounts[1].mount_point, PathBuf::from("/mnt/my disk"));
+
+    let root = find_mount(&mounts, Path::new("/home/user")).unwrap();
+    assert_eq!(root.mount_point, PathBuf::from("/"));
+    assert_eq!(root.access_time_mode(), AccessTimeMode::RelAtime);
+
+    let disk = find_mount(&mounts, Path::new("/mnt/my disk/files")).unwrap();
+    assert_eq!(disk.access_time_mode(), AccessTimeMode::NoAtime);
+
+    // Last mount at the same place hides previous ones
+    let tmp = find_mount(&mounts, Path::new("/tmp/a")).unwrap();
+    assert_eq!(tmp.options, vec!["rw", "strictatime"]);
+    assert_eq!(tmp.access_time_mode(), AccessTimeMode::StrictAtime);
+
+    assert!(find_mount(&mounts, Path::new("relative/path")).is_none());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_used_mounts_below_included_directories() {
+    use std::collections::BTreeSet;
+
+    use crate::tools::stale_files::core::{get_used_mounts, parse_mounts};
+
+    let content = "/dev/sda1 / ext4 rw,strictatime 0 0\n\
+                   /dev/sdb1 /data ext4 rw,relatime 0 0\n\
+                   /dev/sdc1 /data/archive ext4 rw,noatime 0 0\n\
+                   /dev/sdd1 /data/unused ext4 rw,noatime 0 0\n";
+    let mounts = parse_mounts(content);
+
+    let included_directories = vec![PathBuf::from("/data")];
+    let traversed_directories: BTreeSet<&Path> = [Path::new("/data/docs"), Path::new("/data/archive/2020"), Path::new("/data/archive/2021")]
+        .into_iter()
+        .collect();
+    let used_mounts: Vec<_> = get_used_mounts(&mounts, &included_directories, &traversed_directories)
+        .into_iter()
+        .map(|(path, mount)| (path, mount.mount_point.clone()))
+        .collect();
+
+    // Mount without any found files is not reported
+    assert_eq!(
+        used_mounts,
+        vec![
+            (PathBuf::from("/data"), PathBuf::from("/data")),
+            (PathBuf::from("/data/archive"), PathBuf::from("/data/archive")),
+        ]
+    );
+}
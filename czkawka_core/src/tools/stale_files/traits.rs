@@ -0,0 +1,127 @@
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use humansize::{BINARY, format_size};
+
+use crate::common::model::WorkContinueStatus;
+use crate::common::progress_data::ProgressData;
+use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
+use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
+use crate::tools::stale_files::{Info, SECONDS_IN_DAY, StaleFiles, StaleFilesParameters};
+
+impl AllTraits for StaleFiles {}
+
+impl DeletingItems for StaleFiles {
+    #[fun_time(message = "delete_files", level = "debug")]
+    fn delete_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        match self.common_data.delete_method {
+            DeleteMethod::Delete => self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFiles(self.stale_files.clone())),
+            DeleteMethod::None => WorkContinueStatus::Continue,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl DebugPrint for StaleFiles {
+    #[expect(clippy::print_stdout)]
+    fn debug_print(&self) {
+        if !cfg!(debug_assertions) || cfg!(test) {
+            return;
+        }
+
+        println!("### INDIVIDUAL DEBUG PRINT ###");
+        println!("Info: {:?}", self.information);
+        println!("Params: {:?}", self.params);
+        self.debug_print_common();
+        println!("-----------------------------------------");
+    }
+}
+
+fn format_age(current_time: u64, time: u64) -> String {
+    if time == 0 {
+        "unknown".to_string()
+    } else {
+        format!("{} days", current_time.saturating_sub(time) / SECONDS_IN_DAY)
+    }
+}
+
+impl PrintResults for StaleFiles {
+    fn write_results<T: Write>(&self, writer: &mut T) -> std::io::Result<()> {
+        self.write_base_search_paths(writer)?;
+
+        if self.information.number_of_stale_files != 0 {
+            writeln!(writer, "Found {} stale files.\n", self.information.number_of_stale_files)?;
+            let current_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            for file_entry in &self.stale_files {
+                writeln!(
+                    writer,
+                    "\"{}\" - {} - modified {}, accessed {}, changed {} ago",
+                    file_entry.path.to_string_lossy(),
+                    format_size(file_entry.size, BINARY),
+                    format_age(current_time, file_entry.modified_date),
+                    format_age(current_time, file_entry.accessed_date),
+                    format_age(current_time, file_entry.changed_date),
+                )?;
+            }
+        } else {
+            writeln!(writer, "Not found any stale files.")?;
+        }
+
+        Ok(())
+    }
+
+    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+        self.save_results_to_file_as_json_internal(file_name, &self.stale_files, pretty_print)
+    }
+}
+
+impl Search for StaleFiles {
+    #[fun_time(message = "find_stale_files", level = "info")]
+    fn search(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) {
+        let start_time = Instant::now();
+
+        let () = (|| {
+            if self.prepare_items(None).is_err() {
+                return;
+            }
+            if self.look_for_stale_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+                return;
+            }
+            if self.delete_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+            }
+        })();
+
+        self.information.scanning_time = start_time.elapsed();
+
+        if !self.common_data.stopped_search {
+            self.debug_print();
+        }
+    }
+}
+
+impl CommonData for StaleFiles {
+    type Info = Info;
+    type Parameters = StaleFilesParameters;
+
+    fn get_information(&self) -> Self::Info {
+        self.information.clone()
+    }
+    fn get_params(&self) -> Self::Parameters {
+        self.params.clone()
+    }
+    fn get_cd(&self) -> &CommonToolData {
+        &self.common_data
+    }
+    fn get_cd_mut(&mut self) -> &mut CommonToolData {
+        &mut self.common_data
+    }
+    fn found_any_items(&self) -> bool {
+        self.information.number_of_stale_files > 0
+    }
+}
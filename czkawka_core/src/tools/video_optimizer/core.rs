@@ -20,6 +20,8 @@ use crate::tools::video_optimizer::{
     Info, VideoCropEntry, VideoCropParams, VideoCropSingleFixParams, VideoOptimizer, VideoOptimizerFixParams, VideoOptimizerParameters, VideoTranscodeEntry, VideoTranscodeParams,
 };
 
+mod job_journal;
+mod metadata_preserver;
 mod video_converter;
 mod video_cropper;
 
@@ -27,8 +29,11 @@ pub use video_converter::process_video;
 pub use video_cropper::fix_video_crop;
 
 use crate::common::cache::CACHE_VIDEO_OPTIMIZE_VERSION;
+use crate::common::tool_data::CommonData;
 use crate::common::traits::ResultEntry;
 use crate::flc;
+use crate::tools::video_optimizer::VideoOptimizerJobState;
+use crate::tools::video_optimizer::core::job_journal::{JobJournal, get_params_hash};
 
 impl VideoOptimizer {
     pub fn new(params: VideoOptimizerParameters) -> Self {
@@ -368,37 +373,119 @@ impl VideoOptimizer {
     }
 
     #[fun_time(message = "fix_files", level = "debug")]
-    pub(crate) fn fix_files(&mut self, stop_flag: &Arc<AtomicBool>, _progress_sender: Option<&Sender<ProgressData>>, fix_params: VideoOptimizerFixParams) {
+    pub(crate) fn fix_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>, fix_params: VideoOptimizerFixParams) {
         match self.params.clone() {
             VideoOptimizerParameters::VideoTranscode(_) => {
                 let VideoOptimizerFixParams::VideoTranscode(video_transcode_params) = fix_params else {
                     unreachable!("VideoTranscode mode should have VideoTranscode fix_params(caller is responsible for that)");
                 };
 
-                let transcode_warnings: Vec<_> = mem::take(&mut self.video_transcode_result_entries)
-                    .into_par_iter()
-                    .map(|entry| {
-                        if check_if_stop_received(stop_flag) {
-                            return None;
+                let journal = self.load_job_journal(get_video_transcode_journal_file(), get_params_hash(&video_transcode_params));
+                let entries = mem::take(&mut self.video_transcode_result_entries);
+                let mut entries = self.skip_processed_videos(journal.as_ref(), entries);
+                let mut transcode_warnings = Vec::new();
+
+                if video_converter::uses_two_pass(&video_transcode_params) {
+                    let progress_handler = prepare_thread_handler_common(
+                        progress_sender,
+                        ToolStage::VideoOptimizer(VideoOptimizerStage::EncodingFirstPass),
+                        entries.len(),
+                        entries.iter().map(|entry| entry.size).sum(),
+                    );
+                    let first_pass_results: Vec<_> = entries
+                        .into_par_iter()
+                        .map(|entry| {
+                            if check_if_stop_received(stop_flag) {
+                                return None;
+                            }
+
+                            if let Some(journal) = &journal {
+                                journal.mark_pending(&entry);
+                            }
+                            let result = video_converter::run_first_pass(stop_flag, &entry.path.to_string_lossy(), &video_transcode_params);
+                            if let (Some(journal), Err(_)) = (&journal, &result) {
+                                journal.mark_finished(&entry, &result, entry.path.clone(), stop_flag);
+                            }
+                            progress_handler.increase_items(1);
+                            progress_handler.increase_size(entry.size);
+                            Some((entry, result))
+                        })
+                        .while_some()
+                        .collect();
+                    progress_handler.join_thread();
+
+                    entries = Vec::new();
+                    for (entry, result) in first_pass_results {
+                        match result {
+                            Ok(()) => entries.push(entry),
+                            Err(e) => transcode_warnings.push(flc!("core_failed_to_optimize_video", file = entry.path.to_string_lossy(), reason = e)),
                         }
+                    }
+                }
+                // Statistics of first pass are removed by final pass, which is not started after stop
+                let two_pass_paths: Vec<String> = if video_converter::uses_two_pass(&video_transcode_params) {
+                    entries.iter().map(|entry| entry.path.to_string_lossy().to_string()).collect()
+                } else {
+                    Vec::new()
+                };
 
-                        match process_video(stop_flag, &entry.path.to_string_lossy(), entry.size, &video_transcode_params) {
-                            Ok(_new_size) => Some(None),
-                            Err(e) => Some(Some(flc!("core_failed_to_optimize_video", file = entry.path.to_string_lossy(), reason = e))),
-                        }
-                    })
-                    .while_some()
-                    .flatten()
-                    .collect();
+                let progress_handler = prepare_thread_handler_common(
+                    progress_sender,
+                    ToolStage::VideoOptimizer(VideoOptimizerStage::EncodingVideos),
+                    entries.len(),
+                    entries.iter().map(|entry| entry.size).sum(),
+                );
+                transcode_warnings.extend(
+                    entries
+                        .into_par_iter()
+                        .map(|entry| {
+                            if check_if_stop_received(stop_flag) {
+                                return None;
+                            }
+
+                            let video_path = entry.path.to_string_lossy().to_string();
+                            if let Some(journal) = &journal {
+                                journal.mark_pending(&entry);
+                            }
+                            let result = video_converter::process_video_final_pass(stop_flag, &video_path, entry.size, &video_transcode_params);
+                            if let Some(journal) = &journal {
+                                journal.mark_finished(&entry, &result, video_converter::get_output_path(&video_path, &video_transcode_params), stop_flag);
+                            }
+                            progress_handler.increase_items(1);
+                            progress_handler.increase_size(entry.size);
+                            match result {
+                                Ok(warning) => Some(warning),
+                                Err(e) => Some(Some(flc!("core_failed_to_optimize_video", file = entry.path.to_string_lossy(), reason = e))),
+                            }
+                        })
+                        .while_some()
+                        .flatten()
+                        .collect::<Vec<_>>(),
+                );
+                progress_handler.join_thread();
+
+                if check_if_stop_received(stop_flag) {
+                    for path in &two_pass_paths {
+                        video_converter::remove_pass_log_files(path);
+                    }
+                }
 
                 self.common_data.text_messages.warnings.extend(transcode_warnings);
+                if let Some(journal) = journal {
+                    journal.save();
+                    self.common_data.text_messages.warnings.extend(journal.take_warnings());
+                }
             }
             VideoOptimizerParameters::VideoCrop(_) => {
                 let VideoOptimizerFixParams::VideoCrop(video_crop_params) = fix_params else {
                     unreachable!("VideoCrop mode should have VideoCrop fix_params(caller is responsible for that)");
                 };
 
-                let crop_warnings: Vec<_> = mem::take(&mut self.video_crop_result_entries)
+                let journal = self.load_job_journal(get_video_crop_journal_file(), get_params_hash(&video_crop_params));
+                let entries = mem::take(&mut self.video_crop_result_entries);
+                let entries = self.skip_processed_videos(journal.as_ref(), entries);
+
+                let crop_warnings: Vec<_> = entries
                     .into_par_iter()
                     .map(|entry| {
                         if check_if_stop_received(stop_flag) {
@@ -412,10 +499,19 @@ impl VideoOptimizer {
                             quality: video_crop_params.quality,
                             crop_rectangle: (left, top, right, bottom),
                             crop_mechanism: video_crop_params.crop_mechanism,
+                            metadata_preservation: video_crop_params.metadata_preservation,
                         };
 
-                        match fix_video_crop(&entry.path, &entry_crop_params, stop_flag, &entry.codec) {
-                            Ok(()) => Some(None),
+                        if let Some(journal) = &journal {
+                            journal.mark_pending(&entry);
+                        }
+                        let result = video_cropper::crop_video(&entry.path, &entry_crop_params, stop_flag, &entry.codec);
+                        if let Some(journal) = &journal {
+                            journal.mark_finished(&entry, &result, video_cropper::get_output_path(&entry.path, &entry_crop_params), stop_flag);
+                        }
+
+                        match result {
+                            Ok(warning) => Some(warning),
                             Err(e) => Some(Some(flc!("core_failed_to_crop_video", file = entry.path.to_string_lossy(), reason = e))),
                         }
                     })
@@ -424,15 +520,74 @@ impl VideoOptimizer {
                     .collect();
 
                 self.common_data.text_messages.warnings.extend(crop_warnings);
+                if let Some(journal) = journal {
+                    journal.save();
+                    self.common_data.text_messages.warnings.extend(journal.take_warnings());
+                }
             }
         }
     }
+
+    // Journal is stored in cache folder, so it is used only when cache is enabled
+    fn load_job_journal(&mut self, journal_file_name: String, params_hash: u64) -> Option<JobJournal> {
+        if !self.get_use_cache() {
+            return None;
+        }
+
+        let (journal, messages) = JobJournal::load(journal_file_name, params_hash, self.get_delete_outdated_cache(), self.get_save_also_as_json());
+        self.common_data.text_messages.extend_with_another_messages(messages);
+        Some(journal)
+    }
+
+    fn skip_processed_videos<T: ResultEntry>(&mut self, journal: Option<&JobJournal>, entries: Vec<T>) -> Vec<T> {
+        let Some(journal) = journal else {
+            return entries;
+        };
+
+        // Failures are often caused by missing encoder or lack of disk space, so failed videos are processed again
+        let mut done = 0;
+        let mut failed = 0;
+        let entries: Vec<T> = entries
+            .into_iter()
+            .filter(|entry| match journal.get_processed_state(entry) {
+                Some(VideoOptimizerJobState::Done) => {
+                    done += 1;
+                    false
+                }
+                Some(VideoOptimizerJobState::Failed(_)) => {
+                    failed += 1;
+                    true
+                }
+                Some(VideoOptimizerJobState::Pending) | None => true,
+            })
+            .collect();
+
+        if failed > 0 {
+            debug!("Processing again {failed} videos which failed in previous runs");
+        }
+        if done > 0 {
+            debug!("Skipped {done} videos already optimized");
+            self.common_data
+                .text_messages
+                .messages
+                .push(flc!("core_video_optimizer_skipped_processed_videos", done = done));
+        }
+        entries
+    }
 }
 
 pub fn get_video_transcode_cache_file() -> String {
     format!("cache_video_transcode_{CACHE_VIDEO_OPTIMIZE_VERSION}.bin")
 }
 
+pub fn get_video_transcode_journal_file() -> String {
+    format!("cache_video_transcode_journal_{CACHE_VIDEO_OPTIMIZE_VERSION}.bin")
+}
+
+pub fn get_video_crop_journal_file() -> String {
+    format!("cache_video_crop_journal_{CACHE_VIDEO_OPTIMIZE_VERSION}.bin")
+}
+
 pub fn get_video_crop_cache_file(params: &VideoCropParams) -> String {
     format!(
         "cache_video_crop_{CACHE_VIDEO_OPTIMIZE_VERSION}_{:?}_t{}_p{}_s{}_c{}.bin",
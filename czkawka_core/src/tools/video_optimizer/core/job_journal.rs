@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use log::debug;
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::common::cache::{load_cache_from_file_generalized_by_path, save_cache_to_file_generalized};
+use crate::common::progress_stop_handler::check_if_stop_received;
+use crate::common::traits::ResultEntry;
+use crate::helpers::messages::Messages;
+use crate::tools::video_optimizer::{VideoOptimizerJobEntry, VideoOptimizerJobState};
+
+// Whole journal is rewritten on every save, so with many short videos, saving after each of them would take more time than processing
+const JOURNAL_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+struct JournalJobs {
+    jobs: BTreeMap<String, VideoOptimizerJobEntry>,
+    last_save: Instant,
+}
+
+// Journal is saved periodically and at the end of processing, so after crash or stop, only videos processed since last save are processed again
+pub(crate) struct JobJournal {
+    journal_file_name: String,
+    save_also_as_json: bool,
+    // Videos processed with other settings are processed again
+    params_hash: u64,
+    jobs: Mutex<JournalJobs>,
+    warnings: Mutex<Vec<String>>,
+}
+
+// Debug representation contains all fields, so is used instead of requiring hashing from every parameter type
+pub(crate) fn get_params_hash(params: &impl std::fmt::Debug) -> u64 {
+    xxh3_64(format!("{params:?}").as_bytes())
+}
+
+impl JobJournal {
+    pub(crate) fn load(journal_file_name: String, params_hash: u64, delete_outdated_cache: bool, save_also_as_json: bool) -> (Self, Messages) {
+        let (messages, loaded_jobs) = load_cache_from_file_generalized_by_path::<VideoOptimizerJobEntry>(&journal_file_name, delete_outdated_cache, &BTreeMap::new());
+        debug!("Loaded {} jobs from video optimizer journal", loaded_jobs.as_ref().map_or(0, BTreeMap::len));
+
+        let journal = Self {
+            journal_file_name,
+            save_also_as_json,
+            params_hash,
+            jobs: Mutex::new(JournalJobs {
+                jobs: loaded_jobs.unwrap_or_default(),
+                last_save: Instant::now(),
+            }),
+            warnings: Mutex::new(Vec::new()),
+        };
+        (journal, messages)
+    }
+
+    // Returns state of video, only if it was already processed with the same settings and not modified since then
+    pub(crate) fn get_processed_state(&self, entry: &impl ResultEntry) -> Option<VideoOptimizerJobState> {
+        let jobs = self.jobs.lock().expect("Mutex poisoned");
+        let job = jobs.jobs.get(entry.get_path().to_string_lossy().as_ref())?;
+
+        if job.state == VideoOptimizerJobState::Pending || job.params_hash != self.params_hash || job.size != entry.get_size() || job.modified_date != entry.get_modified_date() {
+            return None;
+        }
+        Some(job.state.clone())
+    }
+
+    pub(crate) fn mark_pending(&self, entry: &impl ResultEntry) {
+        let job = VideoOptimizerJobEntry {
+            path: entry.get_path().to_path_buf(),
+            size: entry.get_size(),
+            modified_date: entry.get_modified_date(),
+            params_hash: self.params_hash,
+            state: VideoOptimizerJobState::Pending,
+            output_path: None,
+        };
+        self.update_job(job);
+    }
+
+    // Videos interrupted by stop request are left as pending, so they will be processed again in next run
+    pub(crate) fn mark_finished<T>(&self, entry: &impl ResultEntry, result: &Result<T, String>, output_path: PathBuf, stop_flag: &Arc<AtomicBool>) {
+        if result.is_err() && check_if_stop_received(stop_flag) {
+            return;
+        }
+
+        // Overwritten original has different size and modification date than scanned file
+        let (size, modified_date) = fs::metadata(entry.get_path()).map_or((entry.get_size(), entry.get_modified_date()), |metadata| {
+            let modified_date = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map_or(entry.get_modified_date(), |modified| modified.as_secs());
+            (metadata.len(), modified_date)
+        });
+
+        let (state, output_path) = match result {
+            Ok(_) => (VideoOptimizerJobState::Done, Some(output_path)),
+            Err(e) => (VideoOptimizerJobState::Failed(e.clone()), None),
+        };
+        let job = VideoOptimizerJobEntry {
+            path: entry.get_path().to_path_buf(),
+            size,
+            modified_date,
+            params_hash: self.params_hash,
+            state,
+            output_path,
+        };
+        self.update_job(job);
+    }
+
+    // Must be called after processing all videos, to save changes made since last periodic save
+    pub(crate) fn save(&self) {
+        let mut jobs = self.jobs.lock().expect("Mutex poisoned");
+        self.save_locked(&mut jobs);
+    }
+
+    pub(crate) fn take_warnings(&self) -> Vec<String> {
+        let mut warnings = std::mem::take(&mut *self.warnings.lock().expect("Mutex poisoned"));
+        // The same problem with saving journal is usually repeated for every save
+        warnings.dedup();
+        warnings
+    }
+
+    fn update_job(&self, job: VideoOptimizerJobEntry) {
+        let mut jobs = self.jobs.lock().expect("Mutex poisoned");
+        jobs.jobs.insert(job.path.to_string_lossy().to_string(), job);
+
+        if jobs.last_save.elapsed() >= JOURNAL_SAVE_INTERVAL {
+            self.save_locked(&mut jobs);
+        }
+    }
+
+    // Saving is done under lock, to not write the same file from multiple threads
+    fn save_locked(&self, jobs: &mut JournalJobs) {
+        let messages = save_cache_to_file_generalized(&self.journal_file_name, &jobs.jobs, self.save_also_as_json, 0);
+        self.warnings.lock().expect("Mutex poisoned").extend(messages.warnings);
+        jobs.last_save = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Once;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::common::config_cache_path::set_config_cache_path_test;
+    use crate::tools::video_optimizer::VideoTranscodeEntry;
+
+    static INIT: Once = Once::new();
+
+    fn setup_cache_path() {
+        INIT.call_once(|| {
+            let temp_cache = TempDir::new().expect("Failed to create temp cache dir");
+            let temp_config = TempDir::new().expect("Failed to create temp config dir");
+            set_config_cache_path_test(temp_cache.path().to_path_buf(), temp_config.path().to_path_buf());
+            std::mem::forget(temp_cache);
+            std::mem::forget(temp_config);
+        });
+    }
+
+    fn transcode_entry(path: &Path, size: u64, modified_date: u64) -> VideoTranscodeEntry {
+        VideoTranscodeEntry {
+            path: path.to_path_buf(),
+            size,
+            modified_date,
+            error: None,
+            codec: "h264".to_string(),
+            width: 1920,
+            height: 1080,
+            duration: 60.0,
+            thumbnail_path: None,
+        }
+    }
+
+    #[test]
+    fn test_journal_resumes_after_restart() {
+        setup_cache_path();
+        let temp_dir = TempDir::new().unwrap();
+        let journal_file_name = "test_video_optimizer_journal.bin".to_string();
+        let stop_flag = Arc::new(AtomicBool::new(false));
+
+        let done = temp_dir.path().join("done.mp4");
+        let failed = temp_dir.path().join("failed.mp4");
+        let interrupted = temp_dir.path().join("interrupted.mp4");
+        for path in [&done, &failed, &interrupted] {
+            fs::write(path, b"video").unwrap();
+        }
+        let modified_date = fs::metadata(&done).unwrap().modified().unwrap().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let done_entry = transcode_entry(&done, 5, modified_date);
+        let failed_entry = transcode_entry(&failed, 5, modified_date);
+        let interrupted_entry = transcode_entry(&interrupted, 5, modified_date);
+
+        {
+            let (journal, _messages) = JobJournal::load(journal_file_name.clone(), 1, false, false);
+            for entry in [&done_entry, &failed_entry, &interrupted_entry] {
+                journal.mark_pending(entry);
+            }
+            journal.mark_finished(&done_entry, &Ok(()), done.with_extension("czkawka_optimized.mp4"), &stop_flag);
+            journal.mark_finished(&failed_entry, &Err::<(), _>("broken".to_string()), failed, &stop_flag);
+            journal.save();
+            assert!(journal.take_warnings().is_empty());
+        }
+
+        let (journal, _messages) = JobJournal::load(journal_file_name.clone(), 1, false, false);
+        assert_eq!(journal.get_processed_state(&done_entry), Some(VideoOptimizerJobState::Done));
+        assert_eq!(journal.get_processed_state(&failed_entry), Some(VideoOptimizerJobState::Failed("broken".to_string())));
+        assert_eq!(journal.get_processed_state(&interrupted_entry), None);
+        // Video replaced by user after processing must be processed again
+        assert_eq!(journal.get_processed_state(&transcode_entry(&done, 10, modified_date)), None);
+
+        // Other settings give other result, so even already processed videos are processed again
+        let (journal, _messages) = JobJournal::load(journal_file_name, 2, false, false);
+        assert_eq!(journal.get_processed_state(&done_entry), None);
+        assert_eq!(journal.get_processed_state(&failed_entry), None);
+    }
+
+    #[test]
+    fn test_journal_is_saved_only_periodically() {
+        setup_cache_path();
+        let temp_dir = TempDir::new().unwrap();
+        let video = temp_dir.path().join("video.mp4");
+        fs::write(&video, b"video").unwrap();
+        let entry = transcode_entry(&video, 5, 0);
+        let journal_file_name = "test_video_optimizer_journal_periodic.bin".to_string();
+        let stop_flag = Arc::new(AtomicBool::new(false));
+
+        let (journal, _messages) = JobJournal::load(journal_file_name.clone(), 1, false, false);
+        journal.mark_pending(&entry);
+        journal.mark_finished(&entry, &Err::<(), _>("broken".to_string()), video, &stop_flag);
+        let (not_saved_journal, _messages) = JobJournal::load(journal_file_name.clone(), 1, false, false);
+        assert!(not_saved_journal.jobs.lock().unwrap().jobs.is_empty());
+
+        journal.save();
+        let (saved_journal, _messages) = JobJournal::load(journal_file_name, 1, false, false);
+        assert_eq!(saved_journal.jobs.lock().unwrap().jobs.len(), 1);
+    }
+
+    #[test]
+    fn test_journal_keeps_pending_after_stop() {
+        setup_cache_path();
+        let temp_dir = TempDir::new().unwrap();
+        let video = temp_dir.path().join("video.mp4");
+        fs::write(&video, b"video").unwrap();
+        let entry = transcode_entry(&video, 5, 0);
+        let stop_flag = Arc::new(AtomicBool::new(true));
+
+        let (journal, _messages) = JobJournal::load("test_video_optimizer_journal_stop.bin".to_string(), 1, false, false);
+        journal.mark_pending(&entry);
+        journal.mark_finished(&entry, &Err::<(), _>("Video processing was stopped by user".to_string()), video.clone(), &stop_flag);
+
+        let jobs = journal.jobs.lock().unwrap();
+        assert_eq!(jobs.jobs.get(video.to_string_lossy().as_ref()).unwrap().state, VideoOptimizerJobState::Pending);
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use filetime::FileTime;
+
+use crate::flc;
+use crate::helpers::ffprobe::{FfProbe, ffprobe};
+use crate::tools::video_optimizer::MetadataPreservation;
+
+// Tags checked after writing file - other tags are copied too, but these are needed to organise files by date and place
+const VERIFIED_TAGS: &[&str] = &["creation_time", "location", "com.apple.quicktime.location.ISO6709", "com.apple.quicktime.creationdate"];
+// These containers save only standard tags, unless additional flag is used
+const MOV_EXTENSIONS: &[&str] = &["mp4", "m4v", "mov"];
+
+pub(crate) struct OriginalMetadata {
+    preservation: MetadataPreservation,
+    accessed: FileTime,
+    modified: FileTime,
+    tags: BTreeMap<String, String>,
+    xattrs: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl OriginalMetadata {
+    pub(crate) fn read(path: &Path, preservation: MetadataPreservation) -> Result<Self, String> {
+        let read_error = |reason: String| flc!("core_failed_to_read_original_metadata", file = path.to_string_lossy(), reason = reason);
+
+        let file_metadata = fs::metadata(path).map_err(|e| read_error(e.to_string()))?;
+        let tags = if preservation.copy_metadata {
+            get_verified_tags(&ffprobe(path).map_err(|e| read_error(e.to_string()))?)
+        } else {
+            BTreeMap::new()
+        };
+        let xattrs = if preservation.copy_xattrs { read_xattrs(path).map_err(read_error)? } else { Vec::new() };
+
+        Ok(Self {
+            preservation,
+            accessed: FileTime::from_last_access_time(&file_metadata),
+            modified: FileTime::from_last_modification_time(&file_metadata),
+            tags,
+            xattrs,
+        })
+    }
+
+    // Must be called before replacing original file, because later there is no source to copy metadata from
+    pub(crate) fn verify_container_metadata(&self, output: &Path) -> Result<(), String> {
+        if self.tags.is_empty() {
+            return Ok(());
+        }
+
+        let info = ffprobe(output).map_err(|e| flc!("core_failed_to_read_original_metadata", file = output.to_string_lossy(), reason = e.to_string()))?;
+        let output_tags = get_verified_tags(&info);
+        let not_preserved: Vec<&str> = self
+            .tags
+            .iter()
+            .filter(|(name, value)| output_tags.get(*name) != Some(*value))
+            .map(|(name, _value)| name.as_str())
+            .collect();
+
+        if not_preserved.is_empty() {
+            Ok(())
+        } else {
+            Err(flc!("core_metadata_not_preserved", file = output.to_string_lossy(), items = not_preserved.join(", ")))
+        }
+    }
+
+    // Restores timestamps and extended attributes of final file and checks that they were really set
+    pub(crate) fn apply_to_file(&self, path: &Path) -> Result<(), String> {
+        let restore_error = |reason: String| flc!("core_failed_to_restore_metadata", file = path.to_string_lossy(), reason = reason);
+        let mut not_preserved = Vec::new();
+
+        if self.preservation.copy_xattrs {
+            write_xattrs(path, &self.xattrs).map_err(restore_error)?;
+            let written_xattrs = read_xattrs(path).map_err(restore_error)?;
+            not_preserved.extend(
+                self.xattrs
+                    .iter()
+                    .filter(|xattr| !written_xattrs.contains(xattr))
+                    .map(|(name, _value)| String::from_utf8_lossy(name).to_string()),
+            );
+        }
+
+        if self.preservation.restore_timestamps {
+            filetime::set_file_times(path, self.accessed, self.modified).map_err(|e| restore_error(e.to_string()))?;
+            let file_metadata = fs::metadata(path).map_err(|e| restore_error(e.to_string()))?;
+            // Some file systems store time with lower precision, so only seconds are compared
+            // Access time is not verified, because it may be changed by any read or ignored due to noatime mount option
+            if FileTime::from_last_modification_time(&file_metadata).unix_seconds() != self.modified.unix_seconds() {
+                not_preserved.push("mtime".to_string());
+            }
+        }
+
+        if not_preserved.is_empty() {
+            Ok(())
+        } else {
+            Err(flc!("core_metadata_not_preserved", file = path.to_string_lossy(), items = not_preserved.join(", ")))
+        }
+    }
+}
+
+// Rotation is not copied as metadata, because ffmpeg already rotates frames when re-encoding or cropping video
+pub(crate) fn add_ffmpeg_metadata_args(command: &mut Command, output: &Path, preservation: MetadataPreservation) {
+    if !preservation.copy_metadata {
+        return;
+    }
+
+    command
+        .arg("-map_metadata")
+        .arg("0")
+        .arg("-map_metadata:s:v")
+        .arg("0:s:v")
+        .arg("-map_metadata:s:a")
+        .arg("0:s:a");
+
+    let extension = output.extension().map(|extension| extension.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if MOV_EXTENSIONS.contains(&extension.as_str()) {
+        command.arg("-movflags").arg("use_metadata_tags");
+    }
+}
+
+fn get_verified_tags(info: &FfProbe) -> BTreeMap<String, String> {
+    let mut tags = BTreeMap::new();
+    let Some(format_tags) = &info.format.tags else {
+        return tags;
+    };
+
+    if let Some(creation_time) = &format_tags.creation_time {
+        tags.insert("creation_time".to_string(), creation_time.clone());
+    }
+    for tag_name in VERIFIED_TAGS {
+        if let Some(value) = format_tags.extra.get(*tag_name).and_then(serde_json::Value::as_str) {
+            tags.insert((*tag_name).to_string(), value.to_string());
+        }
+    }
+    tags
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn read_xattrs(path: &Path) -> Result<Vec<(Vec<u8>, Vec<u8>)>, String> {
+    use rustix::io::Errno;
+
+    let names_size = match rustix::fs::listxattr(path, &mut [0_u8; 0][..]) {
+        Ok(names_size) => names_size,
+        // File system without xattrs support
+        Err(e) if e == Errno::NOTSUP || e == Errno::OPNOTSUPP => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+    let mut names = vec![0; names_size];
+    let names_len = rustix::fs::listxattr(path, &mut names[..]).map_err(|e| e.to_string())?;
+    names.truncate(names_len);
+
+    let mut xattrs = Vec::new();
+    for name in names.split(|character| *character == 0).filter(|name| is_copyable_xattr(name)) {
+        let mut value = vec![0; rustix::fs::getxattr(path, name, &mut [0_u8; 0][..]).map_err(|e| e.to_string())?];
+        let value_len = rustix::fs::getxattr(path, name, &mut value[..]).map_err(|e| e.to_string())?;
+        value.truncate(value_len);
+        xattrs.push((name.to_vec(), value));
+    }
+    Ok(xattrs)
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn write_xattrs(path: &Path, xattrs: &[(Vec<u8>, Vec<u8>)]) -> Result<(), String> {
+    for (name, value) in xattrs {
+        rustix::fs::setxattr(path, name.as_slice(), value, rustix::fs::XattrFlags::empty()).map_err(|e| format!("{} - {e}", String::from_utf8_lossy(name)))?;
+    }
+    Ok(())
+}
+
+// On Linux other namespaces than "user" are managed by system or require root privileges
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn is_copyable_xattr(name: &[u8]) -> bool {
+    !name.is_empty() && (cfg!(target_os = "macos") || name.starts_with(b"user."))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn read_xattrs(_path: &Path) -> Result<Vec<(Vec<u8>, Vec<u8>)>, String> {
+    Ok(Vec::new())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn write_xattrs(_path: &Path, _xattrs: &[(Vec<u8>, Vec<u8>)]) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_restore_timestamps_and_xattrs() {
+        let temp_dir = TempDir::new().unwrap();
+        let original = temp_dir.path().join("original.mp4");
+        let output = temp_dir.path().join("output.mp4");
+        fs::write(&original, b"original").unwrap();
+        fs::write(&output, b"output").unwrap();
+
+        let modified = FileTime::from_unix_time(1_500_000_000, 0);
+        filetime::set_file_mtime(&original, modified).unwrap();
+        // Not every file system used for temporary files supports user xattrs
+        let xattrs_supported = cfg!(any(target_os = "linux", target_os = "macos")) && write_xattrs(&original, &[(b"user.czkawka".to_vec(), b"test".to_vec())]).is_ok();
+
+        let preservation = MetadataPreservation {
+            copy_metadata: false,
+            restore_timestamps: true,
+            copy_xattrs: true,
+        };
+        let original_metadata = OriginalMetadata::read(&original, preservation).unwrap();
+        original_metadata.verify_container_metadata(&output).unwrap();
+        original_metadata.apply_to_file(&output).unwrap();
+
+        let output_metadata = fs::metadata(&output).unwrap();
+        assert_eq!(FileTime::from_last_modification_time(&output_metadata), modified);
+        if xattrs_supported {
+            assert_eq!(read_xattrs(&output).unwrap(), vec![(b"user.czkawka".to_vec(), b"test".to_vec())]);
+        }
+    }
+
+    #[test]
+    fn test_add_ffmpeg_metadata_args() {
+        let enabled = MetadataPreservation {
+            copy_metadata: true,
+            ..Default::default()
+        };
+        let args = |output: &str, preservation: MetadataPreservation| {
+            let mut command = Command::new("ffmpeg");
+            add_ffmpeg_metadata_args(&mut command, Path::new(output), preservation);
+            command.get_args().map(|arg| arg.to_string_lossy().to_string()).collect::<Vec<_>>()
+        };
+
+        assert!(args("video.mp4", MetadataPreservation::default()).is_empty());
+        assert_eq!(
+            args("video.mkv", enabled),
+            vec!["-map_metadata", "0", "-map_metadata:s:v", "0:s:v", "-map_metadata:s:a", "0:s:a"]
+        );
+        assert!(args("video.MOV", enabled).ends_with(&["-movflags".to_string(), "use_metadata_tags".to_string()]));
+    }
+}
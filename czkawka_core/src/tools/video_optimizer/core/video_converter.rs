@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
@@ -10,7 +10,9 @@ use crate::common::ffmpeg_utils::find_vaapi_device;
 use crate::common::process_utils::run_command_interruptible;
 use crate::common::video_utils::VideoMetadata;
 use crate::flc;
-use crate::tools::video_optimizer::{HardwareEncoder, VideoTranscodeEntry, VideoTranscodeFixParams};
+use crate::helpers::ffprobe::ffprobe;
+use crate::tools::video_optimizer::core::metadata_preserver::{OriginalMetadata, add_ffmpeg_metadata_args};
+use crate::tools::video_optimizer::{HardwareEncoder, VideoRateControl, VideoTranscodeEntry, VideoTranscodeFixParams};
 
 pub fn check_video(mut entry: VideoTranscodeEntry) -> VideoTranscodeEntry {
     let metadata = match VideoMetadata::from_path(&entry.path) {
@@ -47,13 +49,64 @@ pub fn check_video(mut entry: VideoTranscodeEntry) -> VideoTranscodeEntry {
     entry
 }
 
+// Part of target file size reserved for container overhead
+const CONTAINER_OVERHEAD_RATIO: f64 = 0.02;
+// Video encoded with lower bitrate is unwatchable, so such target is most likely a mistake
+const MIN_TARGET_VIDEO_BITRATE: u64 = 50_000;
+// Bitrate assumed for copied audio stream, when container does not store it (e.g. most of mkv files)
+const DEFAULT_AUDIO_BITRATE: u64 = 256_000;
+// Files which may be created by ffmpeg and encoders during two-pass encoding
+const PASS_LOG_SUFFIXES: &[&str] = &["-0.log", "-0.log.temp", "-0.log.mbtree", "-0.log.mbtree.temp", "-0.log.cutree", "-0.log.cutree.temp"];
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum EncodingPass {
+    Single,
+    First,
+    Second,
+}
+
 pub fn process_video(stop_flag: &Arc<AtomicBool>, video_path: &str, original_size: u64, params: &VideoTranscodeFixParams) -> Result<(), String> {
-    let temp_output = Path::new(video_path).with_extension("czkawka_optimized.mp4");
+    if uses_two_pass(params) {
+        run_first_pass(stop_flag, video_path, params)?;
+    }
+    if let Some(warning) = process_video_final_pass(stop_flag, video_path, original_size, params)? {
+        warn!("{warning}");
+    }
+    Ok(())
+}
+
+pub(crate) fn uses_two_pass(params: &VideoTranscodeFixParams) -> bool {
+    params.custom_ffmpeg_command.is_none() && params.rate_control != VideoRateControl::ConstantQuality && params.codec.supports_two_pass()
+}
+
+// Only analyses video and saves statistics, which are used by final pass, so must be followed by `process_video_final_pass`
+pub(crate) fn run_first_pass(stop_flag: &Arc<AtomicBool>, video_path: &str, params: &VideoTranscodeFixParams) -> Result<(), String> {
+    let temp_output = get_temp_output_path(video_path);
+    run_standard_command(params, video_path, &temp_output, stop_flag, EncodingPass::First).inspect_err(|_| remove_pass_log_files(video_path))
+}
+
+// Returns warning when video was converted, but metadata of replaced original could not be restored
+pub(crate) fn process_video_final_pass(stop_flag: &Arc<AtomicBool>, video_path: &str, original_size: u64, params: &VideoTranscodeFixParams) -> Result<Option<String>, String> {
+    let temp_output = get_temp_output_path(video_path);
+    let two_pass = uses_two_pass(params);
+
+    let mut metadata_preservation = params.metadata_preservation;
+    // Custom command decides itself which metadata is copied
+    metadata_preservation.copy_metadata &= params.custom_ffmpeg_command.is_none();
+    let original_metadata = OriginalMetadata::read(Path::new(video_path), metadata_preservation).inspect_err(|_| {
+        if two_pass {
+            remove_pass_log_files(video_path);
+        }
+    })?;
 
     if let Some(ref cmd) = params.custom_ffmpeg_command {
         run_custom_command(cmd, video_path, &temp_output, stop_flag)?;
+    } else if two_pass {
+        let result = run_standard_command(params, video_path, &temp_output, stop_flag, EncodingPass::Second);
+        remove_pass_log_files(video_path);
+        result?;
     } else {
-        run_standard_command(params, video_path, &temp_output, stop_flag)?;
+        run_standard_command(params, video_path, &temp_output, stop_flag, EncodingPass::Single)?;
     }
 
     let metadata = fs::metadata(&temp_output).map_err(|e| {
@@ -78,21 +131,104 @@ pub fn process_video(stop_flag: &Arc<AtomicBool>, video_path: &str, original_siz
         ));
     }
 
+    original_metadata.verify_container_metadata(&temp_output).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_output);
+    })?;
+
     if params.overwrite_original {
         fs::rename(&temp_output, video_path).map_err(|e| {
             let _ = fs::remove_file(&temp_output);
             flc!("core_failed_to_replace_with_optimized", file = video_path, reason = e.to_string())
         })?;
+        // Original is already replaced, so conversion cannot be treated as failed
+        Ok(original_metadata.apply_to_file(Path::new(video_path)).err())
+    } else {
+        original_metadata.apply_to_file(&temp_output)?;
+        Ok(None)
     }
+}
 
-    Ok(())
+// Path of file with final result, used to record outputs in job journal
+pub(crate) fn get_output_path(video_path: &str, params: &VideoTranscodeFixParams) -> PathBuf {
+    if params.overwrite_original {
+        PathBuf::from(video_path)
+    } else {
+        get_temp_output_path(video_path)
+    }
+}
+
+fn get_temp_output_path(video_path: &str) -> PathBuf {
+    Path::new(video_path).with_extension("czkawka_optimized.mp4")
+}
+
+// Whole file name is kept, so videos which differ only by extension(e.g. movie.mkv and movie.mp4) do not share statistics,
+// and process id separates logs of other app instances which may optimize the same video
+fn get_pass_log_prefix(video_path: &str) -> PathBuf {
+    PathBuf::from(format!("{video_path}.czkawka_passlog_{}", std::process::id()))
+}
+
+pub(crate) fn remove_pass_log_files(video_path: &str) {
+    let log_prefix = get_pass_log_prefix(video_path).to_string_lossy().to_string();
+    for suffix in PASS_LOG_SUFFIXES {
+        let _ = fs::remove_file(format!("{log_prefix}{suffix}"));
+    }
+}
+
+// Returns None when video should be encoded with constant quality
+fn get_target_video_bitrate(rate_control: VideoRateControl, video_path: &str) -> Result<Option<u64>, String> {
+    let video_bitrate = match rate_control {
+        VideoRateControl::ConstantQuality => return Ok(None),
+        VideoRateControl::TargetBitrate(bitrate) => bitrate,
+        VideoRateControl::TargetFileSize(target_file_size) => {
+            let info = ffprobe(video_path).map_err(|e| flc!("core_failed_to_read_video_properties", reason = e.to_string()))?;
+            let Some(duration) = info.format.get_duration().map(|duration| duration.as_secs_f64()).filter(|duration| *duration > 0.0) else {
+                return Err(flc!("core_failed_to_get_video_duration", file = video_path));
+            };
+            // Audio streams are copied, so they take the same space as in original file
+            let audio_bitrate = info
+                .streams
+                .iter()
+                .filter(|stream| stream.codec_type.as_deref() == Some("audio"))
+                .map(|stream| get_audio_stream_bitrate(stream.bit_rate.as_deref()))
+                .sum();
+            calculate_video_bitrate_for_file_size(target_file_size, duration, audio_bitrate)
+        }
+    };
+
+    if video_bitrate < MIN_TARGET_VIDEO_BITRATE {
+        return Err(flc!("core_target_video_bitrate_too_low", bitrate = video_bitrate, minimum = MIN_TARGET_VIDEO_BITRATE));
+    }
+    Ok(Some(video_bitrate))
 }
 
-fn run_standard_command(params: &VideoTranscodeFixParams, video_path: &str, temp_output: &Path, stop_flag: &Arc<AtomicBool>) -> Result<(), String> {
+fn get_audio_stream_bitrate(bit_rate: Option<&str>) -> u64 {
+    bit_rate
+        .and_then(|bit_rate| bit_rate.parse::<u64>().ok())
+        .filter(|bit_rate| *bit_rate > 0)
+        .unwrap_or(DEFAULT_AUDIO_BITRATE)
+}
+
+pub(crate) fn calculate_video_bitrate_for_file_size(target_file_size: u64, duration: f64, audio_bitrate: u64) -> u64 {
+    let total_bitrate = target_file_size as f64 * 8.0 * (1.0 - CONTAINER_OVERHEAD_RATIO) / duration;
+    (total_bitrate - audio_bitrate as f64).max(0.0) as u64
+}
+
+fn run_standard_command(params: &VideoTranscodeFixParams, video_path: &str, temp_output: &Path, stop_flag: &Arc<AtomicBool>, pass: EncodingPass) -> Result<(), String> {
+    let target_video_bitrate = get_target_video_bitrate(params.rate_control, video_path)?;
+
     let mut command = Command::new("ffmpeg");
 
     // Determine whether to use a hardware encoder or the software codec.
-    let hw = params.hardware_encoder;
+    // Hardware encoders have different rate control mechanisms, so only software ones are used to reach target bitrate
+    let hw = if target_video_bitrate.is_some() && params.hardware_encoder != HardwareEncoder::None {
+        warn!(
+            "Hardware encoder '{}' is not used when encoding to target bitrate or size, falling back to software encoder",
+            params.hardware_encoder.as_config_name()
+        );
+        HardwareEncoder::None
+    } else {
+        params.hardware_encoder
+    };
     let hw_encoder_name = if hw == HardwareEncoder::None { None } else { hw.encoder_name_for_codec(params.codec) };
 
     if hw != HardwareEncoder::None && hw_encoder_name.is_none() {
@@ -116,7 +252,16 @@ fn run_standard_command(params: &VideoTranscodeFixParams, video_path: &str, temp
         command.arg("-c:v").arg(hw_name);
         command.args(hw.quality_args(params.quality));
     } else {
-        command.arg("-c:v").arg(params.codec.as_str()).arg("-crf").arg(params.quality.to_string());
+        command.arg("-c:v").arg(params.codec.as_str());
+        if let Some(bitrate) = target_video_bitrate {
+            command.arg("-b:v").arg(bitrate.to_string());
+            if pass != EncodingPass::Single {
+                let pass_number = if pass == EncodingPass::First { 1 } else { 2 };
+                command.args(params.codec.two_pass_args(pass_number, &get_pass_log_prefix(video_path)));
+            }
+        } else {
+            command.arg("-crf").arg(params.quality.to_string());
+        }
     }
 
     let mut filters: Vec<String> = Vec::new();
@@ -141,7 +286,13 @@ fn run_standard_command(params: &VideoTranscodeFixParams, video_path: &str, temp
         command.arg("-vf").arg(filters.join(","));
     }
 
-    command.arg("-c:a").arg("copy").arg("-y").arg(temp_output);
+    if pass == EncodingPass::First {
+        // First pass only collects statistics, so encoded video is not needed
+        command.arg("-an").arg("-f").arg("null").arg("-");
+    } else {
+        add_ffmpeg_metadata_args(&mut command, temp_output, params.metadata_preservation);
+        command.arg("-c:a").arg("copy").arg("-y").arg(temp_output);
+    }
 
     let codec_label = hw_encoder_name.unwrap_or_else(|| params.codec.as_ffprobe_codec_name());
     run_ffmpeg_command(command, video_path, codec_label, stop_flag, temp_output)
@@ -194,3 +345,84 @@ fn run_ffmpeg_command(command: Command, video_path: &str, codec_name: &str, stop
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::video_optimizer::{MetadataPreservation, NoiseReductionMethod, VideoCodec};
+
+    fn transcode_params(codec: VideoCodec, rate_control: VideoRateControl) -> VideoTranscodeFixParams {
+        VideoTranscodeFixParams {
+            codec,
+            hardware_encoder: HardwareEncoder::None,
+            quality: 23,
+            rate_control,
+            fail_if_not_smaller: false,
+            overwrite_original: false,
+            limit_video_size: false,
+            max_width: 1920,
+            max_height: 1080,
+            noise_reduction: NoiseReductionMethod::None,
+            noise_reduction_strength: 5,
+            custom_ffmpeg_command: None,
+            metadata_preservation: MetadataPreservation::default(),
+        }
+    }
+
+    #[test]
+    fn test_calculate_video_bitrate_for_file_size() {
+        // 700 MiB movie with 2 hours and 192 kbit/s audio
+        let bitrate = calculate_video_bitrate_for_file_size(700 * 1024 * 1024, 7200.0, 192_000);
+        assert!((600_000..620_000).contains(&bitrate), "Unexpected bitrate {bitrate}");
+
+        // Audio alone is bigger than target size
+        assert_eq!(calculate_video_bitrate_for_file_size(1024, 60.0, 128_000), 0);
+
+        assert_eq!(get_audio_stream_bitrate(Some("128000")), 128_000);
+        assert_eq!(get_audio_stream_bitrate(None), DEFAULT_AUDIO_BITRATE);
+        assert_eq!(get_audio_stream_bitrate(Some("N/A")), DEFAULT_AUDIO_BITRATE);
+    }
+
+    #[test]
+    fn test_target_video_bitrate() {
+        assert_eq!(get_target_video_bitrate(VideoRateControl::ConstantQuality, "not_existing.mp4"), Ok(None));
+        assert_eq!(
+            get_target_video_bitrate(VideoRateControl::TargetBitrate(2_000_000), "not_existing.mp4"),
+            Ok(Some(2_000_000))
+        );
+        get_target_video_bitrate(VideoRateControl::TargetBitrate(1000), "not_existing.mp4").unwrap_err();
+    }
+
+    #[test]
+    fn test_uses_two_pass() {
+        assert!(!uses_two_pass(&transcode_params(VideoCodec::H264, VideoRateControl::ConstantQuality)));
+        assert!(uses_two_pass(&transcode_params(VideoCodec::H264, VideoRateControl::TargetBitrate(2_000_000))));
+        assert!(uses_two_pass(&transcode_params(VideoCodec::H265, VideoRateControl::TargetFileSize(1_000_000))));
+        assert!(!uses_two_pass(&transcode_params(VideoCodec::Av1, VideoRateControl::TargetBitrate(2_000_000))));
+
+        let mut custom_command_params = transcode_params(VideoCodec::H264, VideoRateControl::TargetBitrate(2_000_000));
+        custom_command_params.custom_ffmpeg_command = Some("ffmpeg -i {PATH}".to_string());
+        assert!(!uses_two_pass(&custom_command_params));
+
+        let log_prefix = get_pass_log_prefix("/tmp/movie.mkv");
+        let log_prefix_text = format!("/tmp/movie.mkv.czkawka_passlog_{}", std::process::id());
+        assert_eq!(VideoCodec::H264.two_pass_args(1, &log_prefix), vec!["-pass", "1", "-passlogfile", &log_prefix_text]);
+        assert_eq!(
+            VideoCodec::H265.two_pass_args(2, &log_prefix),
+            vec!["-x265-params", &format!("pass=2:stats={log_prefix_text}-0.log")]
+        );
+        assert_ne!(log_prefix, get_pass_log_prefix("/tmp/movie.mp4"));
+    }
+
+    #[test]
+    fn test_pass_logs_removed_when_original_metadata_cannot_be_read() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let video_path = temp_dir.path().join("removed_after_first_pass.mkv").to_string_lossy().to_string();
+        let pass_log = format!("{}-0.log", get_pass_log_prefix(&video_path).to_string_lossy());
+        fs::write(&pass_log, "statistics").unwrap();
+
+        let params = transcode_params(VideoCodec::H264, VideoRateControl::TargetBitrate(2_000_000));
+        process_video_final_pass(&Arc::new(AtomicBool::new(false)), &video_path, 1000, &params).unwrap_err();
+        assert!(!Path::new(&pass_log).exists());
+    }
+}
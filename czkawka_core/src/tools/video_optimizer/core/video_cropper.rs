@@ -1,15 +1,16 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use image::RgbImage;
-use log::error;
+use log::{error, warn};
 
 use crate::common::consts::VIDEO_RESOLUTION_LIMIT;
 use crate::common::process_utils::run_command_interruptible;
 use crate::common::video_utils::{VideoMetadata, extract_frame_ffmpeg};
 use crate::flc;
+use crate::tools::video_optimizer::core::metadata_preserver::{OriginalMetadata, add_ffmpeg_metadata_args};
 use crate::tools::video_optimizer::{VideoCropEntry, VideoCropParams, VideoCropSingleFixParams, VideoCroppingMechanism};
 
 const MIN_SAMPLES: usize = 3;
@@ -415,6 +416,14 @@ pub fn check_video_crop(mut entry: VideoCropEntry, params: &VideoCropParams, sto
 }
 
 pub fn fix_video_crop(video_path: &Path, params: &VideoCropSingleFixParams, stop_flag: &Arc<AtomicBool>, current_codec: &str) -> Result<(), String> {
+    if let Some(warning) = crop_video(video_path, params, stop_flag, current_codec)? {
+        warn!("{warning}");
+    }
+    Ok(())
+}
+
+// Returns warning when video was cropped, but metadata of replaced original could not be restored
+pub(crate) fn crop_video(video_path: &Path, params: &VideoCropSingleFixParams, stop_flag: &Arc<AtomicBool>, current_codec: &str) -> Result<Option<String>, String> {
     if stop_flag.load(Ordering::Relaxed) {
         return Err("Video processing was stopped by user".to_string());
     }
@@ -428,13 +437,9 @@ pub fn fix_video_crop(video_path: &Path, params: &VideoCropSingleFixParams, stop
     let crop_width = right - left;
     let crop_height = bottom - top;
 
-    let crop_type_suffix = match params.crop_mechanism {
-        VideoCroppingMechanism::BlackBars => "blackbars",
-        VideoCroppingMechanism::StaticContent => "staticcontent",
-    };
+    let temp_output = get_temp_output_path(video_path, params.crop_mechanism);
 
-    let extension = video_path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
-    let temp_output = video_path.with_extension(format!("czkawka_cropped_{crop_type_suffix}.{extension}"));
+    let original_metadata = OriginalMetadata::read(video_path, params.metadata_preservation)?;
 
     let mut command = Command::new("ffmpeg");
     command.arg("-i").arg(video_path).arg("-vf").arg(format!("crop={crop_width}:{crop_height}:{left}:{top}"));
@@ -451,6 +456,7 @@ pub fn fix_video_crop(video_path: &Path, params: &VideoCropSingleFixParams, stop
         }
     }
 
+    add_ffmpeg_metadata_args(&mut command, &temp_output, params.metadata_preservation);
     command.arg("-c:a").arg("copy");
     command.arg("-y").arg(&temp_output);
 
@@ -495,11 +501,40 @@ pub fn fix_video_crop(video_path: &Path, params: &VideoCropSingleFixParams, stop
         return Err(flc!("core_cropped_video_not_created", temp = format!("{:?}", temp_output)));
     }
 
+    original_metadata.verify_container_metadata(&temp_output).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp_output);
+    })?;
+
     if params.overwrite_original {
-        std::fs::rename(&temp_output, video_path).map_err(|e| format!("Failed to replace original file: {e}"))?;
+        std::fs::rename(&temp_output, video_path).map_err(|e| {
+            let _ = std::fs::remove_file(&temp_output);
+            flc!("core_failed_to_replace_with_optimized", file = video_path.to_string_lossy(), reason = e.to_string())
+        })?;
+        // Original is already replaced, so cropping cannot be treated as failed
+        Ok(original_metadata.apply_to_file(video_path).err())
+    } else {
+        original_metadata.apply_to_file(&temp_output)?;
+        Ok(None)
     }
+}
 
-    Ok(())
+// Path of file with final result, used to record outputs in job journal
+pub(crate) fn get_output_path(video_path: &Path, params: &VideoCropSingleFixParams) -> PathBuf {
+    if params.overwrite_original {
+        video_path.to_path_buf()
+    } else {
+        get_temp_output_path(video_path, params.crop_mechanism)
+    }
+}
+
+fn get_temp_output_path(video_path: &Path, crop_mechanism: VideoCroppingMechanism) -> PathBuf {
+    let crop_type_suffix = match crop_mechanism {
+        VideoCroppingMechanism::BlackBars => "blackbars",
+        VideoCroppingMechanism::StaticContent => "staticcontent",
+    };
+
+    let extension = video_path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    video_path.with_extension(format!("czkawka_cropped_{crop_type_suffix}.{extension}"))
 }
 
 #[cfg(test)]
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use crate::flc;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -26,6 +28,24 @@ impl VideoCodec {
             Self::Vp9 => "vp9",
         }
     }
+
+    /// Returns arguments of given pass (1 or 2) of two-pass encoding, or empty list if codec is encoded in single pass.
+    ///
+    /// Statistics of first pass are saved into files starting with `log_prefix`.
+    /// SVT-AV1 wrapper in ffmpeg does not support two-pass encoding, so AV1 uses single pass with average bitrate.
+    pub fn two_pass_args(self, pass: u8, log_prefix: &Path) -> Vec<String> {
+        let log_prefix = log_prefix.to_string_lossy();
+        match self {
+            Self::H264 | Self::Vp9 => vec!["-pass".into(), pass.to_string(), "-passlogfile".into(), log_prefix.to_string()],
+            // libx265 ignores generic pass options, so statistics file needs to be set via its own parameters
+            Self::H265 => vec!["-x265-params".into(), format!("pass={pass}:stats={log_prefix}-0.log")],
+            Self::Av1 => Vec::new(),
+        }
+    }
+
+    pub const fn supports_two_pass(self) -> bool {
+        !matches!(self, Self::Av1)
+    }
 }
 
 impl std::str::FromStr for VideoCodec {
@@ -42,6 +62,17 @@ impl std::str::FromStr for VideoCodec {
     }
 }
 
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum VideoRateControl {
+    /// Constant quality, set by `quality` field of transcode parameters
+    #[default]
+    ConstantQuality,
+    /// Average video bitrate in bits per second
+    TargetBitrate(u64),
+    /// Size of whole output file in bytes, audio is copied without changes, so only video bitrate is adjusted
+    TargetFileSize(u64),
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
 pub enum HardwareEncoder {
     #[default]
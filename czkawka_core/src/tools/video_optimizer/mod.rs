@@ -10,7 +10,7 @@ use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
-pub use self::encoding::{HardwareEncoder, NoiseReductionMethod, VideoCodec};
+pub use self::encoding::{HardwareEncoder, NoiseReductionMethod, VideoCodec, VideoRateControl};
 use crate::common::model::FileEntry;
 use crate::common::tool_data::CommonToolData;
 use crate::common::traits::ResultEntry;
@@ -50,6 +50,7 @@ pub struct VideoTranscodeFixParams {
     pub codec: VideoCodec,
     pub hardware_encoder: HardwareEncoder,
     pub quality: u32,
+    pub rate_control: VideoRateControl,
     pub fail_if_not_smaller: bool,
     pub overwrite_original: bool,
     pub limit_video_size: bool,
@@ -58,6 +59,7 @@ pub struct VideoTranscodeFixParams {
     pub noise_reduction: NoiseReductionMethod,
     pub noise_reduction_strength: u32,
     pub custom_ffmpeg_command: Option<String>,
+    pub metadata_preservation: MetadataPreservation,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -67,6 +69,7 @@ pub struct VideoCropSingleFixParams {
     pub quality: Option<u32>,
     pub crop_rectangle: (u32, u32, u32, u32),
     pub crop_mechanism: VideoCroppingMechanism,
+    pub metadata_preservation: MetadataPreservation,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -75,6 +78,18 @@ pub struct VideoCropFixParams {
     pub target_codec: Option<VideoCodec>,
     pub quality: Option<u32>,
     pub crop_mechanism: VideoCroppingMechanism,
+    pub metadata_preservation: MetadataPreservation,
+}
+
+// What is copied from original video to transcoded or cropped one, result is verified after writing
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct MetadataPreservation {
+    // Global and per-stream container metadata, e.g. creation time or GPS location
+    pub copy_metadata: bool,
+    // Modification and access time
+    pub restore_timestamps: bool,
+    // Extended attributes, supported only on Linux and macOS
+    pub copy_xattrs: bool,
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -242,6 +257,38 @@ impl ResultEntry for VideoCropEntry {
     }
 }
 
+// State of single video in job journal, which allows to resume long optimization after crash or stop
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum VideoOptimizerJobState {
+    Pending,
+    Done,
+    Failed(String),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VideoOptimizerJobEntry {
+    pub path: PathBuf,
+    // Size and modification date of file after processing, so overwritten videos are also recognized in next run
+    pub size: u64,
+    pub modified_date: u64,
+    // Hash of parameters used to process video
+    pub params_hash: u64,
+    pub state: VideoOptimizerJobState,
+    pub output_path: Option<PathBuf>,
+}
+
+impl ResultEntry for VideoOptimizerJobEntry {
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+    fn get_modified_date(&self) -> u64 {
+        self.modified_date
+    }
+    fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
 impl FileEntry {
     fn into_video_transcode_entry(self) -> VideoTranscodeEntry {
         VideoTranscodeEntry {
@@ -14,9 +14,9 @@ use czkawka_core::tools::big_file::{BigFile, BigFileParameters};
 use czkawka_core::tools::broken_files::{BrokenFiles, BrokenFilesParameters, CheckedTypes};
 use czkawka_core::tools::duplicate::{DuplicateFinder, DuplicateFinderParameters};
 use czkawka_core::tools::empty_files::EmptyFiles;
-use czkawka_core::tools::empty_folder::EmptyFolder;
+use czkawka_core::tools::empty_folder::{EmptyFolder, EmptyFolderParameters};
 use czkawka_core::tools::invalid_symlinks::InvalidSymlinks;
-use czkawka_core::tools::same_music::{MusicSimilarity, SameMusic, SameMusicParameters};
+use czkawka_core::tools::same_music::{MusicSimilarity, SameMusic, SameMusicParameters, TagNormalization};
 use czkawka_core::tools::similar_images::{SimilarImages, SimilarImagesParameters};
 use czkawka_core::tools::similar_videos::{
     DEFAULT_CROP_DETECT, DEFAULT_DURATION_TOLERANCE_PCT, DEFAULT_MIN_MATCHING_WINDOWS, DEFAULT_SKIP_FORWARD_AMOUNT, DEFAULT_SUBCLIP_MIN_MATCH, DEFAULT_VID_HASH_DURATION,
@@ -319,7 +319,7 @@ fn empty_dirs_search(
     thread::Builder::new()
         .stack_size(DEFAULT_THREAD_SIZE)
         .spawn(move || {
-            let mut tool = EmptyFolder::new();
+            let mut tool = EmptyFolder::new(EmptyFolderParameters::default());
 
             set_common_settings(&mut tool, &loaded_commons);
             tool.search(&stop_flag, Some(&progress_data_sender));
@@ -450,6 +450,10 @@ fn same_music_search(
                     minimum_segment_duration,
                     maximum_difference,
                     comparison_only_in_title_group,
+                    false,
+                    TagNormalization::NONE,
+                    None,
+                    false,
                 );
                 let mut tool = SameMusic::new(params);
 
@@ -18,6 +18,7 @@ rust_rename_single_invalid_name = New name must not be empty or contain path sep
 rust_rename_single_target_exists = A file with that name already exists in this folder.
 rust_rename_single_same_name = New name is the same as the current one.
 rust_renamed_file = Renamed { $old_name } to { $new_name }
+rust_rename_not_finished = File { $path } was not renamed, because renaming was stopped
 rust_no_included_paths = Cannot start scan when no included paths are set.
 rust_all_paths_referenced = Cannot start scan when all included paths are set as referenced paths, you need to disable reference checkbox next to input path.
 rust_found_empty_folders = Found { $items_found } empty folders in { $time }
@@ -36,6 +37,7 @@ rust_found_duplicate_files = Found { $items_found } duplicate files in { $groups
 rust_found_duplicate_files_no_lost_space = Found { $items_found } duplicate files in { $groups } groups in { $time }
 rust_found_big_files = Found { $items_found } big files with size { $size } in { $time }
 rust_found_exif_files = Found { $items_found } files with exif data in { $time }
+rust_found_stale_files = Found { $items_found } stale files with size { $size } in { $time }
 rust_cannot_load_preset = Cannot change and load preset { $preset_idx } - reason { $reason }, using default settings instead
 rust_saved_preset = Saved preset { $preset_idx }
 rust_cannot_save_preset = Cannot save preset { $preset_idx } - reason { $reason }
@@ -123,6 +125,8 @@ column_duration = Duration
 column_exif_tags = EXIF Tags
 column_new_name = New Name
 column_full_path = Full Path
+column_access_date = Access Date
+column_change_date = Change Date
 
 # Slint translations
 ok_button = OK
@@ -183,6 +187,7 @@ tool_bad_extensions = Bad Extensions
 tool_bad_names = Bad Names
 tool_video_optimizer = Video Optimizer
 tool_exif_remover = Exif Remover
+tool_stale_files = Stale Files
 sort_by_full_name = Sort by full name
 sort_by_focus = Sort by focus
 sort_reverse = Reverse order
@@ -440,6 +445,10 @@ subsettings_exif_ignored_tags_text = Ignored tags:
 subsettings_exif_ignored_tags_hint_text = Comma-separated list of tags to exclude from scanning (e.g. GPS, Thumbnail). Some tags, such as ImageWidth in TIFF files, are hidden to prevent breaking the image.
 subsettings_temporary_files_extensions_text = Extensions:
 subsettings_temporary_files_extensions_hint_text = Comma-separated list of extensions/suffixes treated as temporary (e.g. .tmp,.bak,~). Reset restores the built-in defaults.
+subsettings_stale_files_modified_days = Days since modification
+subsettings_stale_files_accessed_days = Days since access
+subsettings_stale_files_changed_days = Days since status change
+subsettings_stale_files_days_hint = Only files older than every filled limit are found, empty fields are ignored. Access times are not reliable on filesystems mounted with noatime or relatime options. On Windows creation time is used instead of status change time.
 clean_button_text = Clean
 clean_text = Clean EXIF data
 clean_confirmation_text = Are you sure you want to remove EXIF data from the selected items?
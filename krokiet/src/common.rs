@@ -338,6 +338,33 @@ pub enum StrDataVideoOptimizer {
 }
 pub const MAX_STR_DATA_VIDEO_OPTIMIZER: usize = StrDataVideoOptimizer::PreviewPath as usize + 1;
 
+// Stale Files
+#[repr(u8)]
+#[derive(Debug, Eq, PartialEq, TryFromPrimitive)]
+pub enum IntDataStaleFiles {
+    ModificationDatePart1,
+    ModificationDatePart2,
+    SizePart1,
+    SizePart2,
+    AccessDatePart1,
+    AccessDatePart2,
+    ChangeDatePart1,
+    ChangeDatePart2,
+}
+pub const MAX_INT_DATA_STALE_FILES: usize = IntDataStaleFiles::ChangeDatePart2 as usize + 1;
+
+#[repr(u8)]
+#[derive(Debug, Eq, PartialEq, TryFromPrimitive)]
+pub enum StrDataStaleFiles {
+    Size,
+    Name,
+    Path,
+    ModificationDate,
+    AccessDate,
+    ChangeDate,
+}
+pub const MAX_STR_DATA_STALE_FILES: usize = StrDataStaleFiles::ChangeDate as usize + 1;
+
 pub(crate) enum SortIdx {
     StrIdx(i32),
     IntIdx(i32),
@@ -452,6 +479,13 @@ impl ActiveTab {
                 StrDataVideoOptimizer::Dimensions => SortIdx::IntIdx(IntDataVideoOptimizer::PixelCount as i32),
                 StrDataVideoOptimizer::NewDimensions => SortIdx::IntIdx(IntDataVideoOptimizer::DiffInPixels as i32),
             },
+            Self::StaleFiles => match StrDataStaleFiles::try_from(str_idx as u8).unwrap_or_else(|_| panic!("Invalid str idx {str_idx} for StaleFiles")) {
+                StrDataStaleFiles::Name | StrDataStaleFiles::Path => SortIdx::StrIdx(str_idx),
+                StrDataStaleFiles::ModificationDate => SortIdx::IntIdxPair(IntDataStaleFiles::ModificationDatePart1 as i32, IntDataStaleFiles::ModificationDatePart2 as i32),
+                StrDataStaleFiles::AccessDate => SortIdx::IntIdxPair(IntDataStaleFiles::AccessDatePart1 as i32, IntDataStaleFiles::AccessDatePart2 as i32),
+                StrDataStaleFiles::ChangeDate => SortIdx::IntIdxPair(IntDataStaleFiles::ChangeDatePart1 as i32, IntDataStaleFiles::ChangeDatePart2 as i32),
+                StrDataStaleFiles::Size => SortIdx::IntIdxPair(IntDataStaleFiles::SizePart1 as i32, IntDataStaleFiles::SizePart2 as i32),
+            },
             Self::Settings | Self::About => panic!("Button should be disabled"),
         }
     }
@@ -473,6 +507,7 @@ impl ActiveTab {
             Self::BadNames => StrDataBadNames::Path as usize,
             Self::ExifRemover => StrDataExifRemover::Path as usize,
             Self::VideoOptimizer => StrDataVideoOptimizer::Path as usize,
+            Self::StaleFiles => StrDataStaleFiles::Path as usize,
             Self::Settings | Self::About => panic!("Button should be disabled"),
         }
     }
@@ -493,6 +528,7 @@ impl ActiveTab {
             Self::BadNames => StrDataBadNames::Name as usize,
             Self::ExifRemover => StrDataExifRemover::Name as usize,
             Self::VideoOptimizer => StrDataVideoOptimizer::Name as usize,
+            Self::StaleFiles => StrDataStaleFiles::Name as usize,
             Self::Settings | Self::About => panic!("Button should be disabled"),
         }
     }
@@ -520,6 +556,7 @@ impl ActiveTab {
             Self::BadNames => IntDataBadNames::ModificationDatePart1 as usize,
             Self::ExifRemover => IntDataExifRemover::ModificationDatePart1 as usize,
             Self::VideoOptimizer => IntDataVideoOptimizer::ModificationDatePart1 as usize,
+            Self::StaleFiles => IntDataStaleFiles::ModificationDatePart1 as usize,
             Self::Settings | Self::About => panic!("Button should be disabled"),
         }
     }
@@ -537,6 +574,7 @@ impl ActiveTab {
             Self::BadNames => IntDataBadNames::SizePart1 as usize,
             Self::ExifRemover => IntDataExifRemover::SizePart1 as usize,
             Self::VideoOptimizer => IntDataVideoOptimizer::SizePart1 as usize,
+            Self::StaleFiles => IntDataStaleFiles::SizePart1 as usize,
             Self::Settings | Self::About | Self::EmptyFolders | Self::InvalidSymlinks => return None,
         };
         Some(res)
@@ -612,7 +650,8 @@ impl ActiveTab {
             | Self::BadExtensions
             | Self::BadNames
             | Self::ExifRemover
-            | Self::VideoOptimizer => false,
+            | Self::VideoOptimizer
+            | Self::StaleFiles => false,
             Self::SimilarImages | Self::DuplicateFiles | Self::SimilarVideos | Self::SimilarMusic => true,
             Self::Settings | Self::About => panic!("Button should be disabled"),
         }
@@ -633,6 +672,7 @@ impl ActiveTab {
             Self::BadNames => app.get_bad_names_model(),
             Self::ExifRemover => app.get_exif_remover_model(),
             Self::VideoOptimizer => app.get_video_optimizer_model(),
+            Self::StaleFiles => app.get_stale_files_model(),
             Self::Settings | Self::About => panic!("Button should be disabled"),
         }
     }
@@ -653,6 +693,7 @@ impl ActiveTab {
             Self::BadNames => app.set_bad_names_model(model),
             Self::ExifRemover => app.set_exif_remover_model(model),
             Self::VideoOptimizer => app.set_video_optimizer_model(model),
+            Self::StaleFiles => app.set_stale_files_model(model),
             Self::Settings | Self::About => panic!("Button should be disabled"),
         }
     }
@@ -74,6 +74,10 @@ pub(crate) fn set_number_of_enabled_items(app: &MainWindow, active_tab: ActiveTa
             app.global::<GuiState>().set_selected_results_video_optimizer(it1);
             app.global::<GuiState>().set_selected_results_video_optimizer2(it2);
         }
+        ActiveTab::StaleFiles => {
+            app.global::<GuiState>().set_selected_results_stale_files(it1);
+            app.global::<GuiState>().set_selected_results_stale_files2(it2);
+        }
         _ => unreachable!("Current tab is not a tool that has enabled items"),
     }
 }
@@ -144,6 +148,10 @@ pub(crate) fn get_number_of_enabled_items(app: &MainWindow, active_tab: ActiveTa
             app.global::<GuiState>().get_selected_results_video_optimizer(),
             app.global::<GuiState>().get_selected_results_video_optimizer2(),
         ),
+        ActiveTab::StaleFiles => (
+            app.global::<GuiState>().get_selected_results_stale_files(),
+            app.global::<GuiState>().get_selected_results_stale_files2(),
+        ),
         _ => unreachable!("Current tab is not a tool that has enabled items"),
     };
     connect_i32_into_u64(it1, it2)
@@ -12,6 +12,7 @@ mod invalid_symlinks;
 mod same_music;
 mod similar_images;
 mod similar_videos;
+mod stale_files;
 mod temporary_files;
 mod video_optimizer;
 
@@ -42,6 +43,7 @@ use crate::connect_scan::invalid_symlinks::scan_invalid_symlinks;
 use crate::connect_scan::same_music::scan_similar_music;
 use crate::connect_scan::similar_images::scan_similar_images;
 use crate::connect_scan::similar_videos::scan_similar_videos;
+use crate::connect_scan::stale_files::scan_stale_files;
 use crate::connect_scan::temporary_files::scan_temporary_files;
 use crate::connect_scan::video_optimizer::scan_video_optimizer;
 use crate::settings::model::{BasicSettings, ComboBoxItems, SettingsCustom};
@@ -131,6 +133,7 @@ pub(crate) fn connect_scan_button(
             ActiveTab::TemporaryFiles => scan_temporary_files(a, scan_data),
             ActiveTab::ExifRemover => scan_exif_remover(a, scan_data),
             ActiveTab::VideoOptimizer => scan_video_optimizer(a, scan_data),
+            ActiveTab::StaleFiles => scan_stale_files(a, scan_data),
             ActiveTab::Settings | ActiveTab::About => panic!("Button should be disabled"),
         }
     });
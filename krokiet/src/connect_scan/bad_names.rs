@@ -29,6 +29,7 @@ pub(crate) fn scan_bad_names(a: Weak<MainWindow>, sd: ScanData) {
                     None
                 },
                 remove_duplicated_non_alphanumeric: sd.custom_settings.bad_names_sub_remove_duplicated,
+                ..Default::default()
             };
             let params = BadNamesParameters::new(checked_issues);
             let mut tool = BadNames::new(params);
@@ -6,7 +6,7 @@ use czkawka_core::common::tool_data::CommonData;
 use czkawka_core::common::traits::{ResultEntry, Search};
 use czkawka_core::common::{format_time, split_path, split_path_compare};
 use czkawka_core::tools::empty_folder;
-use czkawka_core::tools::empty_folder::{EmptyFolder, FolderEntry};
+use czkawka_core::tools::empty_folder::{EmptyFolder, EmptyFolderParameters, FolderEntry};
 use rayon::prelude::*;
 use slint::{ComponentHandle, ModelRc, SharedString, VecModel, Weak};
 
@@ -18,7 +18,7 @@ pub(crate) fn scan_empty_folders(a: Weak<MainWindow>, sd: ScanData) {
     thread::Builder::new()
         .stack_size(DEFAULT_THREAD_SIZE)
         .spawn(move || {
-            let mut tool = EmptyFolder::new();
+            let mut tool = EmptyFolder::new(EmptyFolderParameters::default());
             set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
             tool.search(&sd.stop_flag, Some(&sd.progress_sender));
 
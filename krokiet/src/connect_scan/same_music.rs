@@ -7,7 +7,7 @@ use czkawka_core::common::traits::{ResultEntry, Search};
 use czkawka_core::common::{format_time, split_path};
 use czkawka_core::tools::same_music;
 use czkawka_core::tools::same_music::core::format_audio_duration;
-use czkawka_core::tools::same_music::{MusicEntry, MusicSimilarity, SameMusic, SameMusicParameters};
+use czkawka_core::tools::same_music::{MusicEntry, MusicSimilarity, SameMusic, SameMusicParameters, TagNormalization};
 use humansize::{BINARY, format_size};
 use slint::{ComponentHandle, ModelRc, SharedString, VecModel, Weak};
 
@@ -46,6 +46,10 @@ pub(crate) fn scan_similar_music(a: Weak<MainWindow>, sd: ScanData) {
                 sd.custom_settings.similar_music_sub_minimal_fragment_duration_value,
                 sd.custom_settings.similar_music_sub_maximum_difference_value as f64,
                 sd.custom_settings.similar_music_compare_fingerprints_only_with_similar_titles,
+                false,
+                TagNormalization::NONE,
+                None,
+                false,
             );
             let mut tool = SameMusic::new(params);
             set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
@@ -0,0 +1,116 @@
+use std::rc::Rc;
+use std::thread;
+
+use czkawka_core::common::consts::DEFAULT_THREAD_SIZE;
+use czkawka_core::common::tool_data::CommonData;
+use czkawka_core::common::traits::{ResultEntry, Search};
+use czkawka_core::common::{format_time, split_path, split_path_compare};
+use czkawka_core::tools::stale_files;
+use czkawka_core::tools::stale_files::{StaleFileEntry, StaleFiles, StaleFilesParameters};
+use humansize::{BINARY, format_size};
+use rayon::prelude::*;
+use slint::{ComponentHandle, ModelRc, SharedString, VecModel, Weak};
+
+use crate::common::{MAX_INT_DATA_STALE_FILES, MAX_STR_DATA_STALE_FILES, split_u64_into_i32s};
+use crate::connect_scan::{MessagesData, ScanData, get_dt_timestamp_string, get_text_messages, insert_data_to_model, reset_selection_at_end, set_common_settings};
+use crate::{ActiveTab, GuiState, MainWindow, flk};
+
+pub(crate) fn scan_stale_files(a: Weak<MainWindow>, sd: ScanData) {
+    thread::Builder::new()
+        .stack_size(DEFAULT_THREAD_SIZE)
+        .spawn(move || {
+            let params = StaleFilesParameters::new(
+                sd.custom_settings.stale_files_sub_modified_days,
+                sd.custom_settings.stale_files_sub_accessed_days,
+                sd.custom_settings.stale_files_sub_changed_days,
+            );
+            let mut tool = StaleFiles::new(params);
+            set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
+
+            tool.search(&sd.stop_flag, Some(&sd.progress_sender));
+
+            let mut vector = tool.get_stale_files().clone();
+            let (critical, messages) = get_text_messages(&tool, &sd.basic_settings);
+
+            vector.par_sort_unstable_by(|a, b| split_path_compare(a.path.as_path(), b.path.as_path()));
+
+            let info = tool.get_information();
+            let stopped_search = tool.get_stopped_search();
+            let files_size = vector.iter().map(|f| f.size).sum::<u64>();
+            sd.shared_models.lock().expect("Mutex poisoned").shared_stale_files_state = Some(tool);
+
+            let messages_data = MessagesData { critical, messages };
+
+            a.upgrade_in_event_loop(move |app| {
+                write_stale_files_results(&app, vector, messages_data, info, sd, stopped_search, files_size);
+            })
+        })
+        .expect("Cannot start thread - not much we can do here");
+}
+fn write_stale_files_results(
+    app: &MainWindow,
+    vector: Vec<StaleFileEntry>,
+    messages_data: MessagesData,
+    info: stale_files::Info,
+    sd: ScanData,
+    stopped_search: bool,
+    files_size: u64,
+) {
+    let scanning_time_str = format_time(info.scanning_time);
+    let items_found = info.number_of_stale_files;
+
+    let items = Rc::new(VecModel::default());
+    for fe in vector {
+        let (data_model_str, data_model_int) = prepare_data_model_stale_files(&fe);
+        insert_data_to_model(&items, data_model_str, data_model_int, None);
+    }
+    app.set_stale_files_model(items.into());
+    if let Some(critical) = messages_data.critical {
+        app.invoke_scan_ended(critical.into());
+    } else {
+        if !stopped_search && sd.basic_settings.play_audio_on_scan_completion {
+            sd.audio_player.play_scan_completed();
+        }
+        let result_message = flk!(
+            "rust_found_stale_files",
+            items_found = items_found,
+            time = scanning_time_str,
+            size = format_size(files_size, BINARY)
+        );
+        if !stopped_search && sd.basic_settings.show_notification_on_scan_completion {
+            crate::notification_manager::send_scan_completed_notification("Stale Files", &result_message);
+        }
+        app.invoke_scan_ended(result_message.into());
+    }
+    app.global::<GuiState>().set_info_text(messages_data.messages.into());
+    reset_selection_at_end(app, ActiveTab::StaleFiles);
+}
+
+fn prepare_data_model_stale_files(fe: &StaleFileEntry) -> (ModelRc<SharedString>, ModelRc<i32>) {
+    let (directory, file) = split_path(fe.get_path());
+    let data_model_str_arr: [SharedString; MAX_STR_DATA_STALE_FILES] = [
+        format_size(fe.size, BINARY).into(),
+        file.into(),
+        directory.into(),
+        get_dt_timestamp_string(fe.modified_date).into(),
+        get_dt_timestamp_string(fe.accessed_date).into(),
+        get_dt_timestamp_string(fe.changed_date).into(),
+    ];
+    let data_model_str = VecModel::from_slice(&data_model_str_arr);
+    let modification_split = split_u64_into_i32s(fe.get_modified_date());
+    let size_split = split_u64_into_i32s(fe.size);
+    let access_split = split_u64_into_i32s(fe.accessed_date);
+    let change_split = split_u64_into_i32s(fe.changed_date);
+    let data_model_int_arr: [i32; MAX_INT_DATA_STALE_FILES] = [
+        modification_split.0,
+        modification_split.1,
+        size_split.0,
+        size_split.1,
+        access_split.0,
+        access_split.1,
+        change_split.0,
+        change_split.1,
+    ];
+    let data_model_int = VecModel::from_slice(&data_model_int_arr);
+    (data_model_str, data_model_int)
+}
@@ -3,9 +3,9 @@ use slint::{Model, ModelRc, SharedString, VecModel};
 
 use crate::common::{
     IntDataBigFiles, IntDataBrokenFiles, IntDataDuplicateFiles, IntDataEmptyFiles, IntDataEmptyFolders, IntDataExifRemover, IntDataInvalidSymlinks, IntDataSimilarImages,
-    IntDataSimilarMusic, IntDataSimilarVideos, IntDataTemporaryFiles, IntDataVideoOptimizer, StrDataBadExtensions, StrDataBadNames, StrDataBigFiles, StrDataBrokenFiles,
-    StrDataDuplicateFiles, StrDataEmptyFiles, StrDataEmptyFolders, StrDataExifRemover, StrDataInvalidSymlinks, StrDataSimilarImages, StrDataSimilarMusic, StrDataSimilarVideos,
-    StrDataTemporaryFiles, StrDataVideoOptimizer, connect_i32_into_u64,
+    IntDataSimilarMusic, IntDataSimilarVideos, IntDataStaleFiles, IntDataTemporaryFiles, IntDataVideoOptimizer, StrDataBadExtensions, StrDataBadNames, StrDataBigFiles,
+    StrDataBrokenFiles, StrDataDuplicateFiles, StrDataEmptyFiles, StrDataEmptyFolders, StrDataExifRemover, StrDataInvalidSymlinks, StrDataSimilarImages, StrDataSimilarMusic,
+    StrDataSimilarVideos, StrDataStaleFiles, StrDataTemporaryFiles, StrDataVideoOptimizer, connect_i32_into_u64,
 };
 use crate::{ActiveTab, ColumnType, CustomSelectColumnModel, SingleMainListModel, flk};
 pub(super) type SelectionResult = (u64, u64, ModelRc<SingleMainListModel>);
@@ -98,6 +98,8 @@ pub(super) fn build_custom_select_columns(active_tab: ActiveTab) -> Vec<CustomSe
     let exif_tags = flk!("column_exif_tags");
     let new_name = flk!("column_new_name");
     let full_path = flk!("column_full_path");
+    let access_date = flk!("column_access_date");
+    let change_date = flk!("column_change_date");
 
     match active_tab {
         ActiveTab::DuplicateFiles => vec![
@@ -212,6 +214,15 @@ pub(super) fn build_custom_select_columns(active_tab: ActiveTab) -> Vec<CustomSe
             col_int_pair!(format!("{} [KB]", size), IntDataVideoOptimizer::SizePart1),
             col_date!(&mod_date, IntDataVideoOptimizer::ModificationDatePart1),
         ],
+        ActiveTab::StaleFiles => vec![
+            col_full_path!(&full_path),
+            col_str!(&file_name, StrDataStaleFiles::Name),
+            col_str!(&path, StrDataStaleFiles::Path),
+            col_int_pair!(format!("{} [KB]", size), IntDataStaleFiles::SizePart1),
+            col_date!(&mod_date, IntDataStaleFiles::ModificationDatePart1),
+            col_date!(&access_date, IntDataStaleFiles::AccessDatePart1),
+            col_date!(&change_date, IntDataStaleFiles::ChangeDatePart1),
+        ],
         ActiveTab::Settings | ActiveTab::About => Vec::new(),
     }
 }
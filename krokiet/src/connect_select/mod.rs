@@ -217,6 +217,7 @@ pub(crate) fn set_select_buttons(app: &MainWindow) {
         | ActiveTab::BadNames
         | ActiveTab::ExifRemover
         | ActiveTab::VideoOptimizer
+        | ActiveTab::StaleFiles
         | ActiveTab::Settings
         | ActiveTab::About => Vec::new(),
     };
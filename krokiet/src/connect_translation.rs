@@ -126,6 +126,7 @@ fn translate_items(app: &MainWindow) {
     translation.set_tool_exif_remover_text(flk!("tool_exif_remover").into());
     translation.set_tool_video_optimizer_text(flk!("tool_video_optimizer").into());
     translation.set_tool_bad_names_text(flk!("tool_bad_names").into());
+    translation.set_tool_stale_files_text(flk!("tool_stale_files").into());
     translation.set_sort_by_full_name_text(flk!("sort_by_full_name").into());
     translation.set_sort_by_focus_text(flk!("sort_by_focus").into());
     translation.set_sort_reverse_text(flk!("sort_reverse").into());
@@ -257,6 +258,10 @@ fn translate_items(app: &MainWindow) {
     translation.set_subsettings_exif_ignored_tags_hint_text(flk!("subsettings_exif_ignored_tags_hint_text").into());
     translation.set_subsettings_temporary_files_extensions_text(flk!("subsettings_temporary_files_extensions_text").into());
     translation.set_subsettings_temporary_files_extensions_hint_text(flk!("subsettings_temporary_files_extensions_hint_text").into());
+    translation.set_subsettings_stale_files_modified_days_text(flk!("subsettings_stale_files_modified_days").into());
+    translation.set_subsettings_stale_files_accessed_days_text(flk!("subsettings_stale_files_accessed_days").into());
+    translation.set_subsettings_stale_files_changed_days_text(flk!("subsettings_stale_files_changed_days").into());
+    translation.set_subsettings_stale_files_days_hint_text(flk!("subsettings_stale_files_days_hint").into());
     translation.set_clean_button_text(flk!("clean_button_text").into());
     translation.set_clean_text(flk!("clean_text").into());
     translation.set_clean_confirmation_text(flk!("clean_confirmation_text").into());
@@ -457,6 +462,7 @@ fn translate_items(app: &MainWindow) {
         (flk!("tool_bad_names").into(), ActiveTab::BadNames),
         (flk!("tool_exif_remover").into(), ActiveTab::ExifRemover),
         (flk!("tool_video_optimizer").into(), ActiveTab::VideoOptimizer),
+        (flk!("tool_stale_files").into(), ActiveTab::StaleFiles),
     ];
     let gui_state = app.global::<GuiState>();
     gui_state.set_tools_model(ModelRc::new(VecModel::from(tools_model.to_vec())));
@@ -503,6 +509,8 @@ fn translate_items(app: &MainWindow) {
     let exif_tags = flk!("column_exif_tags");
     let new_dimensions = flk!("column_new_dimensions");
     let new_name = flk!("column_new_name");
+    let access_date = flk!("column_access_date");
+    let change_date = flk!("column_change_date");
 
     let fnm = |model: &[&str]| {
         let shared_string = model.iter().map(|s| (*s).into()).collect::<Vec<SharedString>>();
@@ -523,6 +531,7 @@ fn translate_items(app: &MainWindow) {
     settings.set_exif_remover_column_name(fnm(&[&selection, &size, &file_name, &path, &exif_tags, &mod_date]));
     settings.set_video_optimizer_column_name(fnm(&[&selection, &size, &file_name, &path, &codec, &dimensions, &new_dimensions, &mod_date]));
     settings.set_bad_names_column_name(fnm(&[&selection, &file_name, &new_name, &path]));
+    settings.set_stale_files_column_name(fnm(&[&selection, &size, &file_name, &path, &mod_date, &access_date, &change_date]));
 
     crate::set_initial_gui_info::apply_combo_box_translations(app);
 }
@@ -5,7 +5,9 @@ use std::thread;
 
 use crossbeam_channel::Sender;
 use czkawka_core::common::progress_data::ProgressData;
-use czkawka_core::tools::video_optimizer::{HardwareEncoder, NoiseReductionMethod, VideoCodec, VideoCropSingleFixParams, VideoCroppingMechanism, VideoTranscodeFixParams};
+use czkawka_core::tools::video_optimizer::{
+    HardwareEncoder, MetadataPreservation, NoiseReductionMethod, VideoCodec, VideoCropSingleFixParams, VideoCroppingMechanism, VideoRateControl, VideoTranscodeFixParams,
+};
 use slint::{ComponentHandle, Weak};
 
 use crate::common::IntDataVideoOptimizer;
@@ -188,6 +190,7 @@ impl ModelProcessor {
                         codec: requested_video_codec,
                         hardware_encoder,
                         quality: target_quality,
+                        rate_control: VideoRateControl::ConstantQuality,
                         fail_if_not_smaller: fail_if_bigger,
                         overwrite_original: overwrite_files,
                         limit_video_size,
@@ -196,6 +199,7 @@ impl ModelProcessor {
                         noise_reduction: noise_reduction.clone(),
                         noise_reduction_strength,
                         custom_ffmpeg_command: custom_ffmpeg_command.clone(),
+                        metadata_preservation: MetadataPreservation::default(),
                     },
                 )
             };
@@ -259,6 +263,7 @@ impl ModelProcessor {
                         quality,
                         crop_rectangle: (left, top, right, bottom),
                         crop_mechanism: video_crop_mechanism,
+                        metadata_preservation: MetadataPreservation::default(),
                     },
                     codec,
                 )
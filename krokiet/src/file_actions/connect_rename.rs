@@ -1,10 +1,12 @@
-use std::path::{MAIN_SEPARATOR, Path};
+use std::collections::{HashMap, HashSet};
+use std::path::{MAIN_SEPARATOR, Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 
 use crossbeam_channel::Sender;
 use czkawka_core::common::progress_data::ProgressData;
+use czkawka_core::common::rename::{PlannedRename, RenameIssue, RenamePlan, RenameResult, is_valid_file_name, rename_file};
 use log::{error, info, warn};
 use slint::{ComponentHandle, Model, ModelRc, SharedString, VecModel, Weak};
 
@@ -86,11 +88,11 @@ fn connect_rename_single_file(app: &MainWindow) {
             report_failure(&app, crate::flk!("rust_rename_single_target_exists"));
             return;
         }
-        if let Err(e) = std::fs::rename(&old_full_path, &new_full_path) {
+        if let Err(e) = rename_file(Path::new(&old_full_path), Path::new(&new_full_path)) {
             error!("Failed to rename {old_full_path:?} to {new_full_path:?}: {e}");
             report_failure(
                 &app,
-                crate::flk!("rust_failed_to_rename_file", old_path = old_full_path, new_path = new_full_path, error = e.to_string()),
+                crate::flk!("rust_failed_to_rename_file", old_path = old_full_path, new_path = new_full_path, error = e),
             );
             return;
         }
@@ -141,7 +143,7 @@ fn connect_rename_single_file(app: &MainWindow) {
 }
 
 fn is_valid_rename_target_name(new_name: &str) -> bool {
-    !new_name.is_empty() && !new_name.contains('/') && !new_name.contains('\\')
+    is_valid_file_name(new_name)
 }
 
 fn build_full_path(folder: &str, name: &str) -> String {
@@ -161,7 +163,9 @@ impl ModelProcessor {
             let name_idx = self.active_tab.get_str_name_idx();
             let ext_idx = self.active_tab.get_str_proper_extension();
 
-            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_extension_item(data, path_idx, name_idx, ext_idx);
+            let get_rename = move |data: &SimplerSingleMainListModel| get_extension_rename(data, path_idx, name_idx, ext_idx);
+            let outcomes = rename_checked_items(&simpler_model, &stop_flag, get_rename);
+            let rm_fnc = move |data: &SimplerSingleMainListModel| get_rename_outcome(&outcomes, &get_rename(data).0);
 
             self.process_and_update_gui_state(
                 &weak_app,
@@ -182,7 +186,9 @@ impl ModelProcessor {
             let name_idx = self.active_tab.get_str_name_idx();
             let new_name_idx = StrDataBadNames::NewName as usize;
 
-            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_file_name_item(data, path_idx, name_idx, new_name_idx);
+            let get_rename = move |data: &SimplerSingleMainListModel| get_file_name_rename(data, path_idx, name_idx, new_name_idx);
+            let outcomes = rename_checked_items(&simpler_model, &stop_flag, get_rename);
+            let rm_fnc = move |data: &SimplerSingleMainListModel| get_rename_outcome(&outcomes, &get_rename(data).0);
 
             self.process_and_update_gui_state(
                 &weak_app,
@@ -197,62 +203,69 @@ impl ModelProcessor {
     }
 }
 
-#[cfg(not(test))]
-fn rename_single_file_name_item(data: &SimplerSingleMainListModel, path_idx: usize, name_idx: usize, new_file_name_idx: usize) -> Result<(), String> {
-    use std::path::MAIN_SEPARATOR;
+fn get_file_name_rename(data: &SimplerSingleMainListModel, path_idx: usize, name_idx: usize, new_file_name_idx: usize) -> (PathBuf, String) {
     let folder = &data.val_str[path_idx];
     let file_name = &data.val_str[name_idx];
     let new_file_name = &data.val_str[new_file_name_idx];
 
-    let new_full_path = format!("{folder}{MAIN_SEPARATOR}{new_file_name}");
-    let old_full_path = format!("{folder}{MAIN_SEPARATOR}{file_name}");
-
-    if let Err(e) = std::fs::rename(&old_full_path, &new_full_path) {
-        Err(crate::flk!(
-            "rust_failed_to_rename_file",
-            old_path = old_full_path,
-            new_path = new_full_path,
-            error = e.to_string()
-        ))
-    } else {
-        Ok(())
-    }
+    (PathBuf::from(build_full_path(folder, file_name)), new_file_name.clone())
 }
 
-#[cfg(not(test))]
-fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usize, name_idx: usize, ext_idx: usize) -> Result<(), String> {
-    use std::path::MAIN_SEPARATOR;
+fn get_extension_rename(data: &SimplerSingleMainListModel, path_idx: usize, name_idx: usize, ext_idx: usize) -> (PathBuf, String) {
     let folder = &data.val_str[path_idx];
     let file_name = &data.val_str[name_idx];
     let new_extension = &data.val_str[ext_idx];
 
-    let file_stem = std::path::Path::new(&file_name).file_stem().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
-    let new_full_path = format!("{folder}{MAIN_SEPARATOR}{file_stem}.{new_extension}");
-    let old_full_path = format!("{folder}{MAIN_SEPARATOR}{file_name}");
-
-    if let Err(e) = std::fs::rename(&old_full_path, &new_full_path) {
-        Err(crate::flk!(
-            "rust_failed_to_rename_file",
-            old_path = old_full_path,
-            new_path = new_full_path,
-            error = e.to_string()
-        ))
-    } else {
-        Ok(())
-    }
+    let file_stem = Path::new(&file_name).file_stem().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+    (PathBuf::from(build_full_path(folder, file_name)), format!("{file_stem}.{new_extension}"))
 }
 
-#[cfg(test)]
-fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usize, _name_idx: usize, _ext_idx: usize) -> Result<(), String> {
-    let full_path = &data.val_str[path_idx];
-    if full_path.contains("test_error") {
-        return Err(format!("Test error for item: {full_path}"));
-    }
-    Ok(())
+// All checked items are renamed as one batch, so collisions between them and with files on disk
+// are found before anything is touched, results are then reported per row by `get_rename_outcome`
+fn rename_checked_items(
+    simpler_model: &[(usize, SimplerSingleMainListModel)],
+    stop_flag: &Arc<AtomicBool>,
+    get_rename: impl Fn(&SimplerSingleMainListModel) -> (PathBuf, String),
+) -> HashMap<PathBuf, Result<(), String>> {
+    let plan = RenamePlan::from_names(
+        simpler_model
+            .iter()
+            .filter(|(_idx, data)| data.checked && !data.header_row)
+            .map(|(_idx, data)| get_rename(data)),
+    );
+    let entries = plan.get_entries().to_vec();
+    let result = plan.execute(stop_flag, None);
+    collect_rename_outcomes(&entries, result)
+}
+
+fn collect_rename_outcomes(entries: &[PlannedRename], result: RenameResult) -> HashMap<PathBuf, Result<(), String>> {
+    let renamed: HashSet<PathBuf> = result.renamed.into_iter().map(|(source, _target)| source).collect();
+    let mut failed: HashMap<PathBuf, String> = result.failed.into_iter().collect();
+
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let outcome = match &entry.issue {
+                Some(RenameIssue::Unchanged) => Ok(()),
+                Some(_) => Err(entry.get_issue_message().unwrap_or_default()),
+                None if renamed.contains(&entry.source) => Ok(()),
+                // Items neither renamed nor failed were skipped, because renaming was stopped
+                None => Err(failed.remove(&entry.source)?),
+            };
+            Some((entry.source.clone(), outcome))
+        })
+        .collect()
+}
+
+fn get_rename_outcome(outcomes: &HashMap<PathBuf, Result<(), String>>, source: &Path) -> Result<(), String> {
+    outcomes
+        .get(source)
+        .cloned()
+        .unwrap_or_else(|| Err(crate::flk!("rust_rename_not_finished", path = source.to_string_lossy().to_string())))
 }
 
 #[cfg(test)]
-fn rename_single_file_name_item(data: &SimplerSingleMainListModel, path_idx: usize, _name_idx: usize, _file_name: usize) -> Result<(), String> {
+fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usize, _name_idx: usize, _ext_idx: usize) -> Result<(), String> {
     let full_path = &data.val_str[path_idx];
     if full_path.contains("test_error") {
         return Err(format!("Test error for item: {full_path}"));
@@ -340,6 +353,36 @@ mod tests {
         assert!(new_model.iter().skip(2).all(|model| !model.checked));
     }
 
+    #[test]
+    fn test_collect_rename_outcomes() {
+        let planned = |source: &str, issue: Option<RenameIssue>| PlannedRename {
+            source: PathBuf::from(source),
+            new_name: format!("{source}_new"),
+            target: PathBuf::from(format!("{source}_new")),
+            issue,
+        };
+        let entries = vec![
+            planned("renamed", None),
+            planned("failed", None),
+            planned("stopped", None),
+            planned("unchanged", Some(RenameIssue::Unchanged)),
+            planned("duplicate", Some(RenameIssue::DuplicateTarget)),
+        ];
+        let result = RenameResult {
+            renamed: vec![(PathBuf::from("renamed"), PathBuf::from("renamed_new"))],
+            failed: vec![(PathBuf::from("failed"), "Failed error".to_string())],
+            errors: vec!["Failed error".to_string()],
+        };
+
+        let outcomes = collect_rename_outcomes(&entries, result);
+        assert_eq!(outcomes.len(), 4);
+        assert_eq!(get_rename_outcome(&outcomes, Path::new("renamed")), Ok(()));
+        assert_eq!(get_rename_outcome(&outcomes, Path::new("unchanged")), Ok(()));
+        assert_eq!(get_rename_outcome(&outcomes, Path::new("failed")), Err("Failed error".to_string()));
+        get_rename_outcome(&outcomes, Path::new("duplicate")).unwrap_err();
+        get_rename_outcome(&outcomes, Path::new("stopped")).unwrap_err();
+    }
+
     #[test]
     fn test_is_valid_rename_target_name() {
         assert!(is_valid_rename_target_name("new_name.txt"));
@@ -214,6 +214,7 @@ pub(crate) fn zeroing_all_models(app: &MainWindow) {
     app.set_invalid_symlinks_model(Rc::new(VecModel::default()).into());
     app.set_temporary_files_model(Rc::new(VecModel::default()).into());
     app.set_video_optimizer_model(Rc::new(VecModel::default()).into());
+    app.set_stale_files_model(Rc::new(VecModel::default()).into());
 }
 
 #[allow(clippy::allow_attributes)]
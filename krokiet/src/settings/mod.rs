@@ -577,6 +577,11 @@ pub(crate) fn set_settings_to_gui(app: &MainWindow, custom_settings: &SettingsCu
 
     settings.set_ignored_exif_tags(custom_settings.ignored_exif_tags.clone().into());
     settings.set_temporary_files_sub_extensions(custom_settings.temporary_files_extensions.clone().into());
+    // Empty field means that limit is disabled
+    let days_to_string = |days: Option<u64>| days.map(|days| days.to_string()).unwrap_or_default();
+    settings.set_stale_files_sub_modified_days(days_to_string(custom_settings.stale_files_sub_modified_days).into());
+    settings.set_stale_files_sub_accessed_days(days_to_string(custom_settings.stale_files_sub_accessed_days).into());
+    settings.set_stale_files_sub_changed_days(days_to_string(custom_settings.stale_files_sub_changed_days).into());
 
     // Popup-specific settings
     settings.set_popup_move_preserve_folder_structure(custom_settings.popup_move_preserve_folder_structure);
@@ -626,6 +631,7 @@ pub(crate) fn set_settings_to_gui(app: &MainWindow, custom_settings: &SettingsCu
         settings.set_exif_remover_column_size(fnm(&[sel_px, size_px, name_px, path_px, 300.0, mod_px], "exif_remover"));
         settings.set_video_optimizer_column_size(fnm(&[sel_px, size_px, name_px, path_px, 100.0, 120.0, 160.0, mod_px], "video_optimizer"));
         settings.set_bad_names_column_size(fnm(&[sel_px, name_px, 250.0, path_px], "bad_names"));
+        settings.set_stale_files_column_size(fnm(&[sel_px, size_px, name_px, path_px, mod_px, mod_px, mod_px], "stale_files"));
     }
 
     // Clear text
@@ -787,6 +793,9 @@ pub(crate) fn collect_settings(app: &MainWindow) -> SettingsCustom {
 
     let ignored_exif_tags = settings.get_ignored_exif_tags().to_string();
     let temporary_files_extensions = settings.get_temporary_files_sub_extensions().to_string();
+    let stale_files_sub_modified_days = settings.get_stale_files_sub_modified_days().trim().parse().ok();
+    let stale_files_sub_accessed_days = settings.get_stale_files_sub_accessed_days().trim().parse().ok();
+    let stale_files_sub_changed_days = settings.get_stale_files_sub_changed_days().trim().parse().ok();
 
     let column_sizes = BTreeMap::from([
         ("duplicates".to_string(), settings.get_duplicates_column_size().iter().collect::<Vec<_>>()),
@@ -803,6 +812,7 @@ pub(crate) fn collect_settings(app: &MainWindow) -> SettingsCustom {
         ("exif_remover".to_string(), settings.get_exif_remover_column_size().iter().collect::<Vec<_>>()),
         ("video_optimizer".to_string(), settings.get_video_optimizer_column_size().iter().collect::<Vec<_>>()),
         ("bad_names".to_string(), settings.get_bad_names_column_size().iter().collect::<Vec<_>>()),
+        ("stale_files".to_string(), settings.get_stale_files_column_size().iter().collect::<Vec<_>>()),
     ]);
     assert_eq!(column_sizes.len(), TOOLS_NUMBER);
 
@@ -912,6 +922,9 @@ pub(crate) fn collect_settings(app: &MainWindow) -> SettingsCustom {
         video_optimizer_hardware_encoder,
         ignored_exif_tags,
         temporary_files_extensions,
+        stale_files_sub_modified_days,
+        stale_files_sub_accessed_days,
+        stale_files_sub_changed_days,
         column_sizes,
         popup_move_preserve_folder_structure: settings.get_popup_move_preserve_folder_structure(),
         popup_move_copy_mode: settings.get_popup_move_copy_mode(),
@@ -13,6 +13,7 @@ use czkawka_core::tools::similar_videos::{
     DEFAULT_DURATION_TOLERANCE_PCT, DEFAULT_MIN_MATCHING_WINDOWS, DEFAULT_SKIP_FORWARD_AMOUNT, DEFAULT_SUBCLIP_MIN_MATCH, DEFAULT_VID_HASH_DURATION,
     DEFAULT_VIDEO_PERCENTAGE_FOR_THUMBNAIL, DEFAULT_WINDOW_COUNT,
 };
+use czkawka_core::tools::stale_files::StaleFilesParameters;
 use czkawka_core::tools::temporary::DEFAULT_TEMP_EXTENSIONS_STR;
 use czkawka_core::tools::video_optimizer::{NoiseReductionMethod, VideoCodec, VideoCroppingMechanism, VideoOptimizerMode};
 use home::home_dir;
@@ -254,6 +255,12 @@ pub struct SettingsCustom {
     pub ignored_exif_tags: String,
     #[serde(default = "default_temporary_files_extensions")]
     pub temporary_files_extensions: String,
+    #[serde(default = "default_stale_files_modified_days")]
+    pub stale_files_sub_modified_days: Option<u64>,
+    #[serde(default)]
+    pub stale_files_sub_accessed_days: Option<u64>,
+    #[serde(default)]
+    pub stale_files_sub_changed_days: Option<u64>,
     #[serde(default)]
     pub column_sizes: BTreeMap<String, Vec<f32>>,
 
@@ -595,6 +602,9 @@ pub(crate) fn default_ignored_exif_tags() -> String {
 pub(crate) fn default_temporary_files_extensions() -> String {
     DEFAULT_TEMP_EXTENSIONS_STR.to_string()
 }
+fn default_stale_files_modified_days() -> Option<u64> {
+    StaleFilesParameters::default().min_days_since_modification
+}
 pub(crate) fn default_video_optimizer_hardware_encoder() -> String {
     "none".to_string()
 }